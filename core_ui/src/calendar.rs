@@ -0,0 +1,198 @@
+use std::collections::HashSet;
+
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use tui::{layout::{Constraint, Rect}, widgets};
+
+use crate::{
+    theme::{UiCalendarTheme, UiTheme},
+    component::{Component, EventComponent},
+    render::RenderFrame,
+    event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
+};
+
+const DAYS_PER_WEEK: usize = 7;
+const WEEKDAYS: [&str; DAYS_PER_WEEK] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+/// Month grid with a selected day, weeks start on Monday
+///
+/// Arrows move the selection by a day or a week, PageUp/PageDown by a month.
+pub struct UiCalendar {
+    theme: UiCalendarTheme,
+    today: NaiveDate,
+    selected: NaiveDate,
+    marked: HashSet<NaiveDate>,
+    is_focused: bool,
+}
+
+impl UiCalendar {
+    pub fn new(today: NaiveDate) -> Self {
+        Self {
+            theme: UiCalendarTheme::default(),
+            today,
+            selected: today,
+            marked: HashSet::new(),
+            is_focused: false,
+        }
+    }
+
+    pub fn theme(&self) -> &UiCalendarTheme {
+        &self.theme
+    }
+
+    pub fn selected(&self) -> NaiveDate {
+        self.selected
+    }
+
+    pub fn select(&mut self, date: NaiveDate) {
+        self.selected = date;
+    }
+
+    pub fn set_today(&mut self, today: NaiveDate) {
+        self.today = today;
+    }
+
+    pub fn go_today(&mut self) {
+        self.selected = self.today;
+    }
+
+    /// Days highlighted as having events
+    pub fn set_marked<I: IntoIterator<Item = NaiveDate>>(&mut self, days: I) {
+        self.marked = days.into_iter().collect();
+    }
+
+    pub fn is_marked(&self, date: NaiveDate) -> bool {
+        self.marked.contains(&date)
+    }
+
+    /// Moves the selection by `days`, may be negative
+    pub fn move_days(&mut self, days: i64) {
+        if let Some(date) = self.selected.checked_add_signed(Duration::days(days)) {
+            self.selected = date;
+        }
+    }
+
+    /// Same day of the next month, the last day if the month is shorter
+    pub fn next_month(&mut self) {
+        if let Some(date) = self.selected.checked_add_months(Months::new(1)) {
+            self.selected = date;
+        }
+    }
+
+    pub fn previous_month(&mut self) {
+        if let Some(date) = self.selected.checked_sub_months(Months::new(1)) {
+            self.selected = date;
+        }
+    }
+
+    /// Weeks of the selected month, `None` for the days of the adjacent months
+    fn weeks(&self) -> Vec<[Option<NaiveDate>; DAYS_PER_WEEK]> {
+        let first = self.selected.with_day(1).unwrap_or(self.selected);
+        let offset = first.weekday().num_days_from_monday() as usize;
+        let mut weeks = vec![];
+        let mut week = [None; DAYS_PER_WEEK];
+        let mut date = first;
+        let mut column = offset;
+        while date.month() == first.month() {
+            week[column] = Some(date);
+            column += 1;
+            if column == DAYS_PER_WEEK {
+                weeks.push(week);
+                week = [None; DAYS_PER_WEEK];
+                column = 0;
+            }
+            date = match date.succ_opt() {
+                Some(date) => date,
+                None => break,
+            };
+        }
+        if column > 0 {
+            weeks.push(week);
+        }
+        weeks
+    }
+
+    fn day_style(&self, date: NaiveDate) -> tui::style::Style {
+        let mut style = if self.is_marked(date) { self.theme.marked_style } else { self.theme.day_style };
+        if date == self.today {
+            style = style.patch(self.theme.today_style);
+        }
+        if date == self.selected {
+            style = style.patch(self.theme.selected_style);
+        }
+        style
+    }
+}
+
+impl EventComponent for UiCalendar {
+    fn focus(&mut self, value: bool) {
+        self.is_focused = value;
+    }
+
+    fn on_focus(&self) -> bool {
+        self.is_focused
+    }
+}
+
+impl Component for UiCalendar {
+    fn apply_theme(&mut self, theme: &UiTheme) {
+        self.theme = theme.calendar;
+    }
+
+    fn draw(&self, f: &mut RenderFrame, area: Rect) {
+        let rows: Vec<widgets::Row> = self.weeks()
+            .into_iter()
+            .map(|week| widgets::Row::new(week.map(|day| match day {
+                Some(date) => widgets::Cell::from(format!("{:>2}", date.day())).style(self.day_style(date)),
+                None => widgets::Cell::from(""),
+            })))
+            .collect();
+        let table = widgets::Table::new(rows)
+            .header(widgets::Row::new(WEEKDAYS).style(self.theme.header_style))
+            .block(
+                widgets::Block::default()
+                .borders(widgets::Borders::ALL)
+                .title(self.selected.format("%B %Y").to_string()),
+                )
+            .widths(&[Constraint::Length(3); DAYS_PER_WEEK]);
+        f.render_widget(table, area);
+    }
+}
+
+impl TermEventDispatcher for UiCalendar {}
+
+impl KeyEventDispatcher for UiCalendar {
+    fn on_left(&mut self) -> bool {
+        self.move_days(-1);
+        true
+    }
+
+    fn on_right(&mut self) -> bool {
+        self.move_days(1);
+        true
+    }
+
+    fn on_up(&mut self) -> bool {
+        self.move_days(-(DAYS_PER_WEEK as i64));
+        true
+    }
+
+    fn on_down(&mut self) -> bool {
+        self.move_days(DAYS_PER_WEEK as i64);
+        true
+    }
+
+    fn on_page_up(&mut self) -> bool {
+        self.previous_month();
+        true
+    }
+
+    fn on_page_down(&mut self) -> bool {
+        self.next_month();
+        true
+    }
+
+    fn on_home(&mut self) -> bool {
+        self.go_today();
+        true
+    }
+}
@@ -1,3 +1,6 @@
+use crossterm::event::KeyModifiers;
+use sport_core::keys::{Action, Context, KeyMap};
+
 use crate::component::TerminalEvent;
 
 pub type KeyEvent = crossterm::event::KeyCode;
@@ -63,10 +66,22 @@ pub trait KeyEventDispatcher {
 
 pub trait TermEventDispatcher: KeyEventDispatcher {
     fn dispatch_term_event(&mut self, event: &TerminalEvent) -> bool {
-        return match event {
+        match event {
             TerminalEvent::FocusGained => todo!(),
             TerminalEvent::FocusLost => todo!(),
-            TerminalEvent::Key(key_event) => self.dispatch_key_event(&key_event.code),
+            TerminalEvent::Key(key_event) => {
+                let action = self.key_bindings().and_then(|(key_map, context)| key_map.action(context, key_event));
+                if let Some(action) = action {
+                    if self.on_action(action) {
+                        return true;
+                    }
+                }
+                // ctrl+s is no plain `s`, the shift is in the case of a char
+                if key_event.modifiers.difference(KeyModifiers::SHIFT).is_empty() {
+                    return self.dispatch_key_event(&key_event.code);
+                }
+                false
+            }
             TerminalEvent::Mouse(_) => todo!(),
             TerminalEvent::Paste(s) => self.on_paste(s),
             TerminalEvent::Resize(_, _) => todo!(),
@@ -74,4 +89,8 @@ pub trait TermEventDispatcher: KeyEventDispatcher {
     }
 
     fn on_paste(&mut self, _s: &str) -> bool { false }
+
+    /// Keys of the named actions and where they are pressed, the actions are tried before the plain keys
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> { None }
+    fn on_action(&mut self, _action: Action) -> bool { false }
 }
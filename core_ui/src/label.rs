@@ -10,7 +10,7 @@ pub struct UiLabel<'a> {
 
 impl<'a> components::Component for UiLabel<'a> {
     fn draw(&self, f: &mut render::RenderFrame, area: layout::Rect) {
-         f.render_widget(self.clone(), area); // FIXME clone
+         f.render_widget(*self, area);
     }
 }
 
@@ -1,6 +1,7 @@
 use crossterm::event::Event;
 use tui::layout;
 use std::collections::VecDeque;
+use sport_core::keys::KeyMap;
 
 use crate::{
     component::{Component, EventComponent},
@@ -14,6 +15,12 @@ pub trait Layer: Component + EventComponent {
     fn set_modal(&mut self, _is_modal: bool) {}
 
     fn is_remove_requested(&self) -> bool { false }
+
+    /// Called on every application tick
+    fn on_tick(&mut self) {}
+
+    /// Keys in use, for the layers without a controller
+    fn set_key_map(&mut self, _key_map: &KeyMap) {}
 }
 
 pub trait LayerItemComponent: Component + EventComponent {}
@@ -26,6 +33,12 @@ pub struct UiLayer
     is_modal: bool,
 }
 
+impl Default for UiLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl UiLayer
 {
     pub fn new() -> Self {
@@ -48,6 +61,10 @@ impl UiLayer
     pub fn len(&self) -> usize {
         self.components.len()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
 }
 
 impl Layer for UiLayer 
@@ -115,6 +132,12 @@ pub struct UiLayers
     layers: VecDeque<Box<dyn Layer>>,
 }
 
+impl Default for UiLayers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl UiLayers 
 {
     pub fn new() -> Self {
@@ -125,6 +148,10 @@ impl UiLayers
         self.layers.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
     pub fn push(&mut self, layer: Box<dyn Layer>) {
         self.layers.push_back(layer);
     }
@@ -133,13 +160,13 @@ impl UiLayers
         self.layers.pop_back();
     }
 
-    pub fn top<'a>(&'a mut self) -> Option<&'a mut Box<dyn Layer>> {
+    pub fn top(&mut self) -> Option<&mut Box<dyn Layer>> {
         let index = self.layers.len() - 1;
         self.layers.get_mut(index)
     }
 
-    pub fn get(&self, index: usize) -> Option<&Box<dyn Layer>> {
-        self.layers.get(index)
+    pub fn get(&self, index: usize) -> Option<&dyn Layer> {
+        self.layers.get(index).map(|l| l.as_ref())
     }
 
     pub fn get_all<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Box<dyn Layer>> + 'a> {
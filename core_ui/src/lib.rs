@@ -4,6 +4,7 @@ pub mod components {
       text_edit::TextEdit,
       label::UiLabel,
       tabel::UiTable,
+      calendar::UiCalendar,
       message_box::UiMessageBox
   };
 }
@@ -17,5 +18,6 @@ pub mod label;
 pub mod text_edit;
 pub mod message_box;
 pub mod tabel;
+pub mod calendar;
 pub mod event_dispatcher;
 pub mod validators;
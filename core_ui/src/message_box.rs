@@ -1,3 +1,4 @@
+use sport_core::keys::{Action, Context, KeyMap};
 use tui::{widgets::{self, Wrap, Paragraph}, layout::{Layout, Direction, Constraint, self}, style::{Color, Style}};
 
 use crate::{
@@ -18,7 +19,8 @@ pub struct UiMessageBox
     is_visible: bool,
     is_modal: bool,
     is_removing_needed: bool,
-    on_accept: Option<Box<dyn FnOnce()>>
+    on_accept: Option<Box<dyn FnOnce()>>,
+    key_map: KeyMap,
 }
 
 impl UiMessageBox {
@@ -32,6 +34,7 @@ impl UiMessageBox {
             is_modal: true,
             is_removing_needed: false,
             on_accept: None,
+            key_map: KeyMap::default(),
         } 
     }
 
@@ -70,24 +73,26 @@ impl UiMessageBox {
     }
 }
 
-impl TermEventDispatcher for UiMessageBox {}
-
-impl KeyEventDispatcher for UiMessageBox { 
-    fn on_esc(&mut self) -> bool {
-        self.close();
-        true
+impl TermEventDispatcher for UiMessageBox {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((&self.key_map, Context::Dialog))
     }
 
-    fn on_enter(&mut self) -> bool {
-        self.accept();
-        self.close();
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Close | Action::Reject => self.close(),
+            Action::Accept => {
+                self.accept();
+                self.close();
+            }
+            _ => return false,
+        }
         true
     }
+}
 
-    fn on_char(&mut self, c: &char) -> bool {
-        if *c == 'q' {
-            self.close()
-        }
+impl KeyEventDispatcher for UiMessageBox { 
+    fn on_char(&mut self, _c: &char) -> bool {
         true
     }
 }
@@ -101,7 +106,11 @@ impl Component for UiMessageBox {
         };
         let area = utils::centered_rect(50, 50, area);
         let block = widgets::Block::default()
-            .title(format!("{} message: `{}`; -> Commands(Close: [q] | [ESC] | [ENTER])", title, self.title))
+            .title(format!(
+                "{} message: `{}`; -> Commands(Close: [{}] | [{}] | [{}])",
+                title, self.title,
+                self.key_map.binding(Action::Close), self.key_map.binding(Action::Reject), self.key_map.binding(Action::Accept),
+            ))
             .border_style(Style::default().fg(color))
             .borders(widgets::Borders::ALL);
         f.render_widget(tui::widgets::Clear, area);
@@ -135,6 +144,10 @@ impl Layer for UiMessageBox {
     fn set_modal(&mut self, is_modal: bool) { self.is_modal = is_modal; }
 
     fn is_remove_requested(&self) -> bool { self.is_removing_needed }
+
+    fn set_key_map(&mut self, key_map: &KeyMap) {
+        self.key_map = key_map.clone();
+    }
 }
 
 pub enum UiMessageBoxType {
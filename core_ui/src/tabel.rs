@@ -112,7 +112,7 @@ impl<V> EventComponent for UiTable<V> {
 
 impl<V> Component for UiTable<V> {
     fn apply_theme(&mut self, theme: &UiTheme) {
-        self.theme = theme.table.clone();
+        self.theme = theme.table;
     }
 
     fn draw(&self, _f: &mut RenderFrame, _area: tui::layout::Rect) {}
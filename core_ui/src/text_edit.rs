@@ -1,10 +1,11 @@
+use crossterm::event::KeyModifiers;
 use tui::{layout, text::{Spans, Span}, widgets, style::{Style, Color}};
 
 use crate::{
     render::RenderFrame,
     theme::{UiTextEditTheme, UiTheme},
     event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
-    component::{Component, EventComponent}, validators::{Validator, EmptyStrValidator}, 
+    component::{Component, EventComponent, TerminalEvent}, validators::{Validator, EmptyStrValidator}, 
 };
 
 pub struct TextEdit<V: Validator<str> = EmptyStrValidator>
@@ -37,16 +38,16 @@ impl<V: Validator<str>> TextEdit<V> {
     }
 
     pub fn is_valid(&self) -> bool {
-        if let Ok(_) = self.validator.validate(&self.text) {
-            true
-        } else {
-            false
-        }
+        self.validator.validate(&self.text).is_ok()
     }
 
     pub fn validate(&self) -> Result<(), String> {
         self.validator.validate(&self.text).map_err(|e| e.to_string())
     }
+
+    pub fn validator(&self) -> &V {
+        &self.validator
+    }
 }
 
 impl<V: Validator<str>> TermEventDispatcher for TextEdit<V> {
@@ -117,6 +118,19 @@ impl<V: Validator<str>> EventComponent for TextEdit<V> {
     fn focus(&mut self, value: bool) { self.is_focused = value; }
 
     fn on_focus(&self) -> bool { self.is_focused }
+
+    fn on_term_event(&mut self, event: &TerminalEvent) -> bool {
+        // keys with ctrl or alt are commands, not text
+        if let TerminalEvent::Key(key) = event {
+            if key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) {
+                return false;
+            }
+        }
+        if self.on_focus() {
+            return self.dispatch_term_event(event);
+        }
+        false
+    }
 }
 
 
@@ -1,19 +1,12 @@
 use tui::style;
 
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Default)]
 pub struct UiTheme {
     pub table: UiTableTheme,
     pub text_edit: UiTextEditTheme,
-}
-
-impl Default for UiTheme {
-    fn default() -> Self {
-        Self {
-            table: UiTableTheme::default(),
-            text_edit: UiTextEditTheme::default(),
-        }
-    }
+    pub calendar: UiCalendarTheme,
+    pub status: UiStatusTheme,
 }
 
 #[derive(Clone, Copy)]
@@ -45,3 +38,43 @@ impl Default for UiTableTheme {
         }
     }
 }
+
+#[derive(Clone, Copy)]
+pub struct UiCalendarTheme {
+    pub header_style: style::Style,
+    pub day_style: style::Style,
+    /// Days with an event
+    pub marked_style: style::Style,
+    pub today_style: style::Style,
+    pub selected_style: style::Style,
+}
+
+impl Default for UiCalendarTheme {
+    fn default() -> Self {
+        Self {
+            header_style: style::Style::default().fg(style::Color::Red),
+            day_style: style::Style::default().fg(style::Color::Gray),
+            marked_style: style::Style::default().fg(style::Color::Green).add_modifier(style::Modifier::BOLD),
+            today_style: style::Style::default().add_modifier(style::Modifier::UNDERLINED),
+            selected_style: style::Style::default().add_modifier(style::Modifier::REVERSED),
+        }
+    }
+}
+
+/// Styles of values by their severity
+#[derive(Clone, Copy)]
+pub struct UiStatusTheme {
+    pub ok_style: style::Style,
+    pub warning_style: style::Style,
+    pub danger_style: style::Style,
+}
+
+impl Default for UiStatusTheme {
+    fn default() -> Self {
+        Self {
+            ok_style: style::Style::default().fg(style::Color::Green),
+            warning_style: style::Style::default().fg(style::Color::Yellow),
+            danger_style: style::Style::default().fg(style::Color::Red).add_modifier(style::Modifier::BOLD),
+        }
+    }
+}
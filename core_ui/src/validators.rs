@@ -1,3 +1,7 @@
+use std::marker::PhantomData;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use sport_core_db::units::{Quantity, UnitSystem, ParseQuantityError};
 use thiserror::Error;
 
 pub trait Validator<T: ?Sized> {
@@ -12,12 +16,8 @@ pub enum EmptyStrValidatorError {
     Empty,
 }
 
+#[derive(Default)]
 pub struct EmptyStrValidator;
-impl Default for EmptyStrValidator {
-    fn default() -> Self {
-        Self {}
-    }
-}
 
 impl Validator<str> for EmptyStrValidator {
     type Error = EmptyStrValidatorError;
@@ -62,3 +62,132 @@ impl Validator<str> for StrValidator {
 }
 
 
+
+/// Validates text as a quantity in the user's unit system
+pub struct QuantityValidator<Q: Quantity> {
+    pub system: UnitSystem,
+    pub is_optional: bool,
+    quantity: PhantomData<Q>,
+}
+
+impl<Q: Quantity> QuantityValidator<Q> {
+    pub fn new(system: UnitSystem) -> Self { Self { system, is_optional: false, quantity: PhantomData } }
+
+    pub fn optional(system: UnitSystem) -> Self { Self { system, is_optional: true, quantity: PhantomData } }
+}
+
+impl<Q: Quantity> Default for QuantityValidator<Q> {
+    fn default() -> Self {
+        Self::new(UnitSystem::default())
+    }
+}
+
+impl<Q: Quantity> Validator<str> for QuantityValidator<Q> {
+    type Error = ParseQuantityError;
+
+    fn validate<'a>(&'a self, value: &'a str) -> Result<(), Self::Error> {
+        if self.is_optional && value.trim().is_empty() {
+            return Ok(());
+        }
+        Q::parse(value, self.system).map(|_| ())
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum NumberValidatorError {
+    #[error("Not a number")]
+    NotANumber,
+    #[error("Max limit")]
+    MaxLimit,
+    #[error("Min limit")]
+    MinLimit,
+}
+
+pub struct NumberValidator {
+    pub min: f64,
+    pub max: f64,
+    pub is_integer: bool,
+    pub is_optional: bool,
+}
+
+impl NumberValidator {
+    pub fn new(min: f64, max: f64) -> Self { Self { min, max, is_integer: false, is_optional: false } }
+
+    pub fn integer(min: f64, max: f64) -> Self { Self { is_integer: true, ..Self::new(min, max) } }
+
+    pub fn optional(mut self) -> Self {
+        self.is_optional = true;
+        self
+    }
+}
+
+impl Default for NumberValidator {
+    fn default() -> Self {
+        Self::new(f64::MIN, f64::MAX)
+    }
+}
+
+impl Validator<str> for NumberValidator {
+    type Error = NumberValidatorError;
+
+    fn validate<'a>(&'a self, value: &'a str) -> Result<(), Self::Error> {
+        let value = value.trim();
+        if self.is_optional && value.is_empty() {
+            return Ok(());
+        }
+        let number: f64 = if self.is_integer {
+            value.parse::<i64>().map(|n| n as f64).map_err(|_| NumberValidatorError::NotANumber)?
+        } else {
+            value.replace(',', ".").parse().map_err(|_| NumberValidatorError::NotANumber)?
+        };
+        if number > self.max {
+            return Err(NumberValidatorError::MaxLimit);
+        }
+        if number < self.min {
+            return Err(NumberValidatorError::MinLimit);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum DateTimeValidatorError {
+    #[error("Expected format `{0}`")]
+    Format(&'static str),
+}
+
+/// Validates date or date with time in the `chrono` format
+pub struct DateTimeValidator {
+    pub format: &'static str,
+}
+
+impl DateTimeValidator {
+    pub const DATE: &'static str = "%Y-%m-%d";
+    pub const DATE_TIME: &'static str = "%Y-%m-%d %H:%M";
+
+    pub fn new(format: &'static str) -> Self { Self { format } }
+
+    pub fn date() -> Self { Self::new(Self::DATE) }
+
+    pub fn date_time() -> Self { Self::new(Self::DATE_TIME) }
+}
+
+impl Default for DateTimeValidator {
+    fn default() -> Self {
+        Self::date()
+    }
+}
+
+impl Validator<str> for DateTimeValidator {
+    type Error = DateTimeValidatorError;
+
+    fn validate<'a>(&'a self, value: &'a str) -> Result<(), Self::Error> {
+        let value = value.trim();
+        let is_valid = if self.format.contains("%H") {
+            NaiveDateTime::parse_from_str(value, self.format).is_ok()
+        } else {
+            NaiveDate::parse_from_str(value, self.format).is_ok()
+        };
+        if is_valid { Ok(()) } else { Err(DateTimeValidatorError::Format(self.format)) }
+    }
+}
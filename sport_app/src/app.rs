@@ -3,9 +3,9 @@ use std::{
     rc::Rc,
 };
 
-use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
 use core_ui::{render, theme::UiTheme, component::TerminalEvent};
-use sport_core::{events, controller::Controller, config::{KeyMap, Config}};
+use sport_core::{events, controller::Controller, config::Config, keys::Action};
 use sport_ui::ui;
 
 // For current moment app working with server only as one instance at time 
@@ -14,19 +14,17 @@ pub struct App {
     need_quit: bool,
     is_focused: bool,
     ui: ui::Ui,
-    keymap: KeyMap,
 }
 
 #[allow(dead_code)]
 impl App {
-    pub fn new(cfg: Config, keymap: KeyMap) -> Self {
-        let host = format!("http://{}:{}", cfg.server.host.to_string(), cfg.server.port);
+    pub fn new(cfg: Config) -> Self {
+        let host = cfg.server().url();
         let events_manager = Rc::new(events::Events::default());
-        let controller = Rc::new(Controller::new(&host, cfg, keymap.clone()));
+        let controller = Rc::new(Controller::new(&host, cfg));
         Self {
             need_quit: false,
             is_focused: true,
-            keymap,
             ui: ui::Ui::new(controller),
             events_manager,
         }
@@ -56,6 +54,7 @@ impl App {
                 break;
             }
             if last_tick.elapsed() >= tick_rate {
+                self.on_tick();
                 last_tick = Instant::now();
             }
         }
@@ -65,13 +64,15 @@ impl App {
         self.ui.apply_theme(theme);
     }
 
-    fn on_key(&mut self, c: char) {
-        if c == self.keymap.quit {
+    fn on_key(&mut self, key: &KeyEvent) {
+        if self.ui.key_map().is(Action::Quit, key) {
             self.need_quit = true;
         }
     }
 
-    async fn on_tick(&mut self) {}
+    fn on_tick(&mut self) {
+        self.ui.on_tick();
+    }
 
     fn process_events(&mut self) {
         for event in self.events_manager.get_all() {
@@ -82,10 +83,7 @@ impl App {
             }
             if !self.ui.send_term_event(&event) {
                 if let crossterm::event::Event::Key(key) = event {
-                    match key.code {
-                        KeyCode::Char(c) => self.on_key(c),
-                        _ => {}
-                    }
+                    self.on_key(&key);
                 }
             }
         }
@@ -1,15 +1,60 @@
-use std::time::Duration;
+use std::{env, fs::File, io::{self, Write}, path::Path, process, time::Duration};
 use app::App;
-use log::info;
-use sport_core::config::{self, Config, KeyMap};
+use log::{info, LevelFilter};
+use simplelog::{ConfigBuilder, SimpleLogger, WriteLogger};
+use sport_core::{
+    cli::{self, Cli, CliError, Command},
+    controller::Controller,
+};
 mod app;
 
+fn exit(err: CliError) -> ! {
+    eprintln!("{}", err);
+    process::exit(err.exit_code());
+}
+
+/// The terminal UI owns the screen, so it logs into a file next to the config
+fn init_logger(level: LevelFilter, tui: bool, config_path: &Path) {
+    if level == LevelFilter::Off {
+        return;
+    }
+    let log_cfg = ConfigBuilder::new().build();
+    let result = match tui {
+        true => File::create(config_path.with_file_name("sport_helper.log"))
+            .map_err(|err| err.to_string())
+            .and_then(|file| WriteLogger::init(level, log_cfg, file).map_err(|err| err.to_string())),
+        false => SimpleLogger::init(level, log_cfg).map_err(|err| err.to_string()),
+    };
+    if let Err(err) = result {
+        eprintln!("Can`t start logging: {}", err);
+    }
+}
+
 fn main() {
-    info!("Client started");
+    let cli = Cli::parse(env::args().skip(1)).unwrap_or_else(|err| exit(err));
+
+    if cli.command == Command::Help {
+        // a closed pipe is no reason to fail
+        let _ = writeln!(io::stdout(), "{}", cli::USAGE);
+        return;
+    }
+
+    let overrides = cli::overrides(&cli, |var| env::var(var).ok()).unwrap_or_else(|err| exit(err));
+    let cfg = cli::load_config(&overrides).unwrap_or_else(|err| exit(err));
+    let config_path = cfg.path.clone().unwrap_or_default();
+    init_logger(overrides.log_level.unwrap_or(LevelFilter::Off), cli.command == Command::Tui, &config_path);
+    info!("Config loaded from {}", config_path.display());
 
-    let cfg: Config = config::Config::load().unwrap();
-    let keymap = KeyMap::default();
+    if cli.command == Command::Tui {
+        info!("Client started");
+        let mut app = App::new(cfg);
+        app.run(Duration::from_millis(500));
+        return;
+    }
 
-    let mut app = App::new(cfg, keymap);
-    app.run(Duration::from_millis(500));
+    let host = cfg.server().url();
+    let controller = Controller::new(&host, cfg);
+    if let Err(err) = cli::run(&cli, &controller, &mut io::stdout()) {
+        exit(err);
+    }
 }
@@ -0,0 +1,429 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::{Add, AddAssign},
+};
+
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sport_core_db::{
+    entity::{MuscleGroup, Workout, WorkoutSet, ID},
+    model::{Db, Table},
+    units::Mass,
+};
+
+use crate::goals::MetricPoint;
+
+/// Brzycki formula is unreliable above this number of reps
+const BRZYCKI_MAX_REPS: u32 = 12;
+
+/// Estimation formula of the one rep max
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OneRepMaxFormula {
+    #[default]
+    Epley,
+    Brzycki,
+    Lombardi,
+}
+
+impl OneRepMaxFormula {
+    pub const ALL: [OneRepMaxFormula; 3] = [OneRepMaxFormula::Epley, OneRepMaxFormula::Brzycki, OneRepMaxFormula::Lombardi];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OneRepMaxFormula::Epley => "Epley",
+            OneRepMaxFormula::Brzycki => "Brzycki",
+            OneRepMaxFormula::Lombardi => "Lombardi",
+        }
+    }
+
+    /// Estimated one rep max of `reps` with `weight`, the exact weight for a single
+    ///
+    /// `None` if the formula is unreliable for that many reps.
+    pub fn estimate(&self, weight: Mass, reps: u32) -> Option<Mass> {
+        match reps {
+            0 => Some(Mass::default()),
+            1 => Some(weight),
+            reps => match self {
+                OneRepMaxFormula::Epley => Some(weight * (1.0 + reps as f64 / 30.0)),
+                OneRepMaxFormula::Brzycki => {
+                    (reps <= BRZYCKI_MAX_REPS).then(|| weight * (36.0 / (37.0 - reps as f64)))
+                }
+                OneRepMaxFormula::Lombardi => Some(weight * (reps as f64).powf(0.1)),
+            },
+        }
+    }
+
+    pub fn estimate_set(&self, set: &WorkoutSet) -> Option<Mass> {
+        self.estimate(set.weight, set.reps)
+    }
+}
+
+/// Inclusive range of dates
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Period {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl Period {
+    pub fn new(start: NaiveDate, end: NaiveDate) -> Self { Self { start, end } }
+
+    /// Week from Monday to Sunday containing `date`
+    pub fn week_of(date: NaiveDate) -> Self {
+        let start = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+        Self::new(start, start + Duration::days(6))
+    }
+
+    /// Calendar month containing `date`
+    pub fn month_of(date: NaiveDate) -> Self {
+        let start = date.with_day(1).unwrap_or(date);
+        let next = if start.month() == 12 {
+            NaiveDate::from_ymd_opt(start.year() + 1, 1, 1)
+        } else {
+            NaiveDate::from_ymd_opt(start.year(), start.month() + 1, 1)
+        };
+        let end = next.and_then(|d| d.pred_opt()).unwrap_or(start);
+        Self::new(start, end)
+    }
+
+    /// `days` days ending at `today`
+    pub fn last_days(days: u32, today: NaiveDate) -> Self {
+        Self::new(today - Duration::days(days.saturating_sub(1) as i64), today)
+    }
+
+    /// Everything up to `today`
+    pub fn until(today: NaiveDate) -> Self {
+        Self::new(NaiveDate::MIN, today)
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+
+    pub fn days(&self) -> i64 {
+        (self.end - self.start).num_days() + 1
+    }
+
+    /// Period of the same length right before this one
+    pub fn previous(&self) -> Self {
+        let days = Duration::days(self.days());
+        Self::new(self.start - days, self.end - days)
+    }
+}
+
+/// Amount of work done by a group of sets
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Volume {
+    pub sets: u32,
+    pub reps: u32,
+    /// Weight multiplied by reps
+    pub tonnage: Mass,
+}
+
+impl Volume {
+    pub fn of_set(set: &WorkoutSet) -> Self {
+        Self { sets: 1, reps: set.reps, tonnage: set.tonnage() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sets == 0
+    }
+}
+
+impl Add for Volume {
+    type Output = Volume;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Volume { sets: self.sets + rhs.sets, reps: self.reps + rhs.reps, tonnage: self.tonnage + rhs.tonnage }
+    }
+}
+
+impl AddAssign for Volume {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<'a> FromIterator<&'a WorkoutSet> for Volume {
+    fn from_iter<T: IntoIterator<Item = &'a WorkoutSet>>(iter: T) -> Self {
+        iter.into_iter().fold(Volume::default(), |volume, set| volume + Volume::of_set(set))
+    }
+}
+
+/// Volume of the sessions in a week
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WeeklyVolume {
+    /// Monday of the week
+    pub week_start: NaiveDate,
+    pub sessions: u32,
+    pub volume: Volume,
+}
+
+/// Workouts of the person within the period, oldest first
+pub fn workouts_in(db: &Db, person_id: ID, period: Period) -> Vec<&Workout> {
+    let mut workouts: Vec<&Workout> = db.workouts()
+        .get_all()
+        .filter(|w| w.person_id == person_id && period.contains(w.date()))
+        .collect();
+    workouts.sort_by_key(|w| (w.started_at, w.id));
+    workouts
+}
+
+/// Sets of the person within the period together with their workouts, in the logging order
+pub fn sets_in(db: &Db, person_id: ID, period: Period) -> Vec<(&Workout, &WorkoutSet)> {
+    let workouts: HashMap<ID, &Workout> = workouts_in(db, person_id, period).into_iter().map(|w| (w.id, w)).collect();
+    let mut sets: Vec<(&Workout, &WorkoutSet)> = db.sets()
+        .get_all()
+        .filter_map(|s| workouts.get(&s.workout_id).map(|w| (*w, s)))
+        .collect();
+    sets.sort_by_key(|(w, s)| (w.started_at, s.order, s.id));
+    sets
+}
+
+pub fn workout_volume(db: &Db, workout_id: ID) -> Volume {
+    db.sets().get_all().filter(|s| s.workout_id == workout_id).collect()
+}
+
+pub fn exercise_volume(db: &Db, person_id: ID, exercise_id: ID, period: Period) -> Volume {
+    sets_in(db, person_id, period)
+        .into_iter()
+        .filter(|(_, s)| s.exercise_id == exercise_id)
+        .map(|(_, s)| s)
+        .collect()
+}
+
+pub fn volume_by_exercise(db: &Db, person_id: ID, period: Period) -> BTreeMap<ID, Volume> {
+    let mut volumes: BTreeMap<ID, Volume> = BTreeMap::new();
+    for (_, set) in sets_in(db, person_id, period) {
+        *volumes.entry(set.exercise_id).or_default() += Volume::of_set(set);
+    }
+    volumes
+}
+
+/// Volume per trained muscle group, a set counts fully for every group of its exercise
+pub fn volume_by_muscle_group(db: &Db, person_id: ID, period: Period) -> BTreeMap<MuscleGroup, Volume> {
+    let mut volumes: BTreeMap<MuscleGroup, Volume> = BTreeMap::new();
+    for (exercise_id, volume) in volume_by_exercise(db, person_id, period) {
+        let groups = db.exercises().get_one(exercise_id).map(|e| e.muscle_groups.as_slice()).unwrap_or_default();
+        for group in groups {
+            *volumes.entry(*group).or_default() += volume;
+        }
+    }
+    volumes
+}
+
+/// Sessions and volume of every week overlapping the period, oldest first, including empty weeks
+pub fn weekly_volume(db: &Db, person_id: ID, period: Period) -> Vec<WeeklyVolume> {
+    let workouts = workouts_in(db, person_id, period);
+    let sets = sets_in(db, person_id, period);
+    let first = if period.start == NaiveDate::MIN {
+        match workouts.first() {
+            Some(w) => w.date(),
+            None => return vec![],
+        }
+    } else {
+        period.start
+    };
+    let mut weeks = vec![];
+    let mut week = Period::week_of(first);
+    while week.start <= period.end {
+        weeks.push(WeeklyVolume {
+            week_start: week.start,
+            sessions: workouts.iter().filter(|w| week.contains(w.date())).count() as u32,
+            volume: sets.iter().filter(|(w, _)| week.contains(w.date())).map(|(_, s)| *s).collect(),
+        });
+        week = Period::new(week.start + Duration::days(7), week.end + Duration::days(7));
+    }
+    weeks
+}
+
+/// Best estimated one rep max of the exercise within the period
+pub fn best_one_rep_max(db: &Db, person_id: ID, exercise_id: ID, period: Period, formula: OneRepMaxFormula) -> Option<Mass> {
+    sets_in(db, person_id, period)
+        .into_iter()
+        .filter(|(_, s)| s.exercise_id == exercise_id && s.reps > 0 && !s.weight.is_zero())
+        .filter_map(|(_, s)| formula.estimate_set(s))
+        .max_by(|a, b| a.kilograms().total_cmp(&b.kilograms()))
+}
+
+/// Weight of the set relative to the one rep max, `1.0` is the one rep max
+pub fn relative_intensity(set: &WorkoutSet, one_rep_max: Mass) -> Option<f64> {
+    (!one_rep_max.is_zero() && !set.weight.is_zero()).then(|| set.weight / one_rep_max)
+}
+
+/// Average relative intensity of the exercise sets within the period
+///
+/// Each set is compared with the best estimated one rep max reached up to its date.
+pub fn average_intensity(db: &Db, person_id: ID, exercise_id: ID, period: Period, formula: OneRepMaxFormula) -> Option<f64> {
+    let intensities: Vec<f64> = sets_in(db, person_id, period)
+        .into_iter()
+        .filter(|(_, s)| s.exercise_id == exercise_id)
+        .filter_map(|(w, s)| {
+            let one_rep_max = best_one_rep_max(db, person_id, exercise_id, Period::until(w.date()), formula)?;
+            relative_intensity(s, one_rep_max)
+        })
+        .collect();
+    (!intensities.is_empty()).then(|| intensities.iter().sum::<f64>() / intensities.len() as f64)
+}
+
+/// Best estimated one rep max of the exercise per workout day in kilograms, oldest first
+pub fn one_rep_max_history(db: &Db, person_id: ID, exercise_id: ID, period: Period, formula: OneRepMaxFormula) -> Vec<MetricPoint> {
+    let mut best: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+    for (workout, set) in sets_in(db, person_id, period) {
+        if set.exercise_id != exercise_id || set.reps == 0 || set.weight.is_zero() {
+            continue;
+        }
+        let Some(value) = formula.estimate_set(set) else {
+            continue;
+        };
+        let value = value.kilograms();
+        let entry = best.entry(workout.date()).or_insert(value);
+        *entry = entry.max(value);
+    }
+    best.into_iter().map(|(date, value)| MetricPoint::new(date, value)).collect()
+}
+
+/// Tonnage per workout day in kilograms, of one exercise or of all of them, oldest first
+pub fn tonnage_history(db: &Db, person_id: ID, exercise_id: Option<ID>, period: Period) -> Vec<MetricPoint> {
+    let mut days: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+    for (workout, set) in sets_in(db, person_id, period) {
+        if exercise_id.is_none_or(|id| set.exercise_id == id) {
+            *days.entry(workout.date()).or_default() += set.tonnage().kilograms();
+        }
+    }
+    days.into_iter().map(|(date, value)| MetricPoint::new(date, value)).collect()
+}
+
+/// Measured body weight in kilograms, oldest first
+pub fn body_weight_history(db: &Db, person_id: ID, period: Period) -> Vec<MetricPoint> {
+    let mut points: Vec<MetricPoint> = db.measurements()
+        .get_all()
+        .filter(|m| m.person_id == person_id && period.contains(m.date))
+        .map(|m| MetricPoint::new(m.date, m.body_weight.kilograms()))
+        .collect();
+    points.sort_by_key(|p| p.date);
+    points
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StreakUnit {
+    Day,
+    #[default]
+    Week,
+}
+
+impl StreakUnit {
+    pub fn name(&self, count: u32) -> &'static str {
+        match (self, count) {
+            (StreakUnit::Day, 1) => "day",
+            (StreakUnit::Day, _) => "days",
+            (StreakUnit::Week, 1) => "week",
+            (StreakUnit::Week, _) => "weeks",
+        }
+    }
+
+    /// Day or week containing `date`
+    pub fn period(&self, date: NaiveDate) -> Period {
+        match self {
+            StreakUnit::Day => Period::new(date, date),
+            StreakUnit::Week => Period::week_of(date),
+        }
+    }
+}
+
+/// Days or weeks with at least `min_sessions` sessions continue a streak
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct StreakRule {
+    pub unit: StreakUnit,
+    pub min_sessions: u32,
+}
+
+impl Default for StreakRule {
+    fn default() -> Self {
+        Self { unit: StreakUnit::Week, min_sessions: 1 }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Streaks {
+    /// Streak reaching the current day or week, which doesn't break it while in progress
+    pub current: u32,
+    pub longest: u32,
+}
+
+/// Current and longest streaks of the person up to `today`
+pub fn streaks(db: &Db, person_id: ID, rule: StreakRule, today: NaiveDate) -> Streaks {
+    let workouts = workouts_in(db, person_id, Period::until(today));
+    let first = match workouts.first() {
+        Some(w) => rule.unit.period(w.date()),
+        None => return Streaks::default(),
+    };
+    let mut sessions: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+    for workout in &workouts {
+        *sessions.entry(rule.unit.period(workout.date()).start).or_default() += 1;
+    }
+    let is_met = |period: Period| sessions.get(&period.start).copied().unwrap_or_default() >= rule.min_sessions.max(1);
+
+    let current_period = rule.unit.period(today);
+    let mut streaks = Streaks::default();
+    let mut run = 0;
+    let mut period = first;
+    while period.start <= current_period.start {
+        if is_met(period) {
+            run += 1;
+            streaks.longest = streaks.longest.max(run);
+        } else if period != current_period {
+            run = 0;
+        }
+        period = rule.unit.period(period.end + Duration::days(1));
+    }
+    streaks.current = run;
+    streaks
+}
+
+/// Planned number of sessions
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct Schedule {
+    pub sessions_per_week: u32,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self { sessions_per_week: 3 }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Adherence {
+    pub planned: u32,
+    /// Done sessions counted against the plan, extra sessions of a week are not counted
+    pub done: u32,
+    pub missed: u32,
+    /// Done part of the planned sessions in percents, `100` if nothing was planned
+    pub percent: f64,
+}
+
+/// Adherence to the schedule during the weeks overlapping the period
+pub fn adherence(db: &Db, person_id: ID, schedule: Schedule, period: Period) -> Adherence {
+    let mut adherence = Adherence::default();
+    for week in weekly_volume(db, person_id, Period::new(Period::week_of(period.start).start, period.end)) {
+        adherence.planned += schedule.sessions_per_week;
+        adherence.done += week.sessions.min(schedule.sessions_per_week);
+    }
+    adherence.missed = adherence.planned - adherence.done;
+    adherence.percent = match adherence.planned {
+        0 => 100.0,
+        planned => adherence.done as f64 / planned as f64 * 100.0,
+    };
+    adherence
+}
+
+/// The `weeks` complete weeks before the week of `today`
+pub fn last_complete_weeks(weeks: u32, today: NaiveDate) -> Period {
+    let current = Period::week_of(today);
+    Period::new(current.start - Duration::days(7 * weeks as i64), current.start - Duration::days(1))
+}
@@ -0,0 +1,419 @@
+use std::{collections::HashMap, fs, io, path::{Path, PathBuf}};
+
+use chrono::NaiveDateTime;
+use serde_derive::{Deserialize, Serialize};
+use sport_core_db::{
+    entity::{Exercise, Goal, GoalMetric, Measurement, Person, Routine, Workout, WorkoutSet, ID},
+    model::{Db, Table},
+};
+use thiserror::Error;
+
+use crate::{
+    config::Config,
+    controller::{self, Controller},
+    rest::RestClientError,
+};
+
+/// Format version written into new backups, older ones are read as well
+pub const VERSION: u32 = 1;
+/// Config sections with the server connection and not the user preferences
+const SKIPPED_SETTINGS: [&str; 4] = ["profile", "server", "account", "profiles"];
+
+#[derive(Error, Debug)]
+pub enum BackupError {
+    #[error("Invalid backup: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Backup version {0} is newer than the supported {VERSION}")]
+    Version(u32),
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error("Can`t restore {table} `{name}`: {err}")]
+    Rest { table: &'static str, name: String, err: RestClientError },
+}
+
+/// Every table of the client database with the user preferences of the config,
+/// personal records are left out as they are rebuilt from the sets
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Backup {
+    pub version: u32,
+    pub created_at: NaiveDateTime,
+    #[serde(default)]
+    pub persons: Vec<Person>,
+    #[serde(default)]
+    pub exercises: Vec<Exercise>,
+    #[serde(default)]
+    pub workouts: Vec<Workout>,
+    #[serde(default)]
+    pub sets: Vec<WorkoutSet>,
+    #[serde(default)]
+    pub measurements: Vec<Measurement>,
+    #[serde(default)]
+    pub goals: Vec<Goal>,
+    #[serde(default)]
+    pub routines: Vec<Routine>,
+    /// Config without the server and account sections
+    #[serde(default)]
+    pub settings: serde_json::Map<String, serde_json::Value>,
+}
+
+fn sorted<'a, T: Clone + 'a>(items: Box<dyn Iterator<Item = &'a T> + 'a>, id: fn(&T) -> ID) -> Vec<T> {
+    let mut items: Vec<T> = items.cloned().collect();
+    items.sort_by_key(id);
+    items
+}
+
+impl Backup {
+    pub fn new(db: &Db, cfg: &Config, created_at: NaiveDateTime) -> Self {
+        let mut settings = match serde_json::to_value(cfg) {
+            Ok(serde_json::Value::Object(settings)) => settings,
+            _ => serde_json::Map::new(),
+        };
+        SKIPPED_SETTINGS.iter().for_each(|s| { settings.remove(*s); });
+        Self {
+            version: VERSION,
+            created_at,
+            persons: sorted(db.persons().get_all(), |e| e.id),
+            exercises: sorted(db.exercises().get_all(), |e| e.id),
+            workouts: sorted(db.workouts().get_all(), |e| e.id),
+            sets: sorted(db.sets().get_all(), |e| e.id),
+            measurements: sorted(db.measurements().get_all(), |e| e.id),
+            goals: sorted(db.goals().get_all(), |e| e.id),
+            routines: sorted(db.routines().get_all(), |e| e.id),
+            settings,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, BackupError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, BackupError> {
+        let backup: Self = serde_json::from_str(text)?;
+        if backup.version > VERSION {
+            return Err(BackupError::Version(backup.version));
+        }
+        Ok(backup)
+    }
+
+    /// Default file name of the backup
+    pub fn file_name(&self) -> String {
+        format!("sport_helper-backup-{}.json", self.created_at.format("%Y-%m-%d"))
+    }
+
+    /// Writes the backup into `dir` under its default file name, returns the file path
+    pub fn export(&self, dir: &Path) -> Result<PathBuf, BackupError> {
+        let path = dir.join(self.file_name());
+        fs::write(&path, self.to_json()?)?;
+        Ok(path)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, BackupError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// `cfg` with the preferences of the backup, server and account kept
+    pub fn apply_settings(&self, cfg: &Config) -> Result<Config, BackupError> {
+        let mut value = serde_json::to_value(cfg)?;
+        if let serde_json::Value::Object(current) = &mut value {
+            for (key, setting) in &self.settings {
+                if !SKIPPED_SETTINGS.contains(&key.as_str()) {
+                    current.insert(key.clone(), setting.clone());
+                }
+            }
+        }
+        let mut restored: Config = serde_json::from_value(value)?;
+        restored.path = cfg.path.clone();
+        Ok(restored)
+    }
+}
+
+/// Where restored entries are looked up and created
+pub trait RestoreTarget {
+    /// Runs `f` on the current entries, without holding them during the inserts
+    fn lookup<R>(&self, f: impl FnOnce(&Db) -> R) -> R;
+    fn insert_person(&mut self, data: &Person) -> controller::Result<ID>;
+    fn insert_exercise(&mut self, data: &Exercise) -> controller::Result<ID>;
+    fn insert_workout(&mut self, data: &Workout) -> controller::Result<ID>;
+    fn insert_set(&mut self, data: &WorkoutSet) -> controller::Result<ID>;
+    fn insert_measurement(&mut self, data: &Measurement) -> controller::Result<ID>;
+    fn insert_goal(&mut self, data: &Goal) -> controller::Result<ID>;
+    fn insert_routine(&mut self, data: &Routine) -> controller::Result<ID>;
+}
+
+/// Creates the entries on the server
+impl RestoreTarget for &Controller {
+    fn lookup<R>(&self, f: impl FnOnce(&Db) -> R) -> R {
+        f(&self.db())
+    }
+
+    fn insert_person(&mut self, data: &Person) -> controller::Result<ID> {
+        self.person().insert(data)
+    }
+
+    fn insert_exercise(&mut self, data: &Exercise) -> controller::Result<ID> {
+        self.exercises().insert(data)
+    }
+
+    fn insert_workout(&mut self, data: &Workout) -> controller::Result<ID> {
+        self.workouts().insert(data)
+    }
+
+    fn insert_set(&mut self, data: &WorkoutSet) -> controller::Result<ID> {
+        self.sets().insert(data).map(|(id, _)| id)
+    }
+
+    fn insert_measurement(&mut self, data: &Measurement) -> controller::Result<ID> {
+        self.measurements().insert(data)
+    }
+
+    fn insert_goal(&mut self, data: &Goal) -> controller::Result<ID> {
+        self.goals().insert(data)
+    }
+
+    fn insert_routine(&mut self, data: &Routine) -> controller::Result<ID> {
+        self.routines().insert(data)
+    }
+}
+
+/// Creates nothing, hands out IDs after the existing ones to report what a restore would do
+pub struct DryRun<'a> {
+    db: &'a Db,
+    next_id: ID,
+}
+
+impl<'a> DryRun<'a> {
+    pub fn new(db: &'a Db) -> Self {
+        let max = [
+            db.persons().get_all().map(|e| e.id).max(),
+            db.exercises().get_all().map(|e| e.id).max(),
+            db.workouts().get_all().map(|e| e.id).max(),
+            db.sets().get_all().map(|e| e.id).max(),
+            db.measurements().get_all().map(|e| e.id).max(),
+            db.goals().get_all().map(|e| e.id).max(),
+            db.routines().get_all().map(|e| e.id).max(),
+        ];
+        Self { db, next_id: max.into_iter().flatten().max().unwrap_or_default() + 1 }
+    }
+
+    fn next(&mut self) -> controller::Result<ID> {
+        self.next_id += 1;
+        Ok(self.next_id - 1)
+    }
+}
+
+impl RestoreTarget for DryRun<'_> {
+    fn lookup<R>(&self, f: impl FnOnce(&Db) -> R) -> R {
+        f(self.db)
+    }
+
+    fn insert_person(&mut self, _: &Person) -> controller::Result<ID> { self.next() }
+    fn insert_exercise(&mut self, _: &Exercise) -> controller::Result<ID> { self.next() }
+    fn insert_workout(&mut self, _: &Workout) -> controller::Result<ID> { self.next() }
+    fn insert_set(&mut self, _: &WorkoutSet) -> controller::Result<ID> { self.next() }
+    fn insert_measurement(&mut self, _: &Measurement) -> controller::Result<ID> { self.next() }
+    fn insert_goal(&mut self, _: &Goal) -> controller::Result<ID> { self.next() }
+    fn insert_routine(&mut self, _: &Routine) -> controller::Result<ID> { self.next() }
+}
+
+/// Created and already existing entries of one table
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TableReport {
+    pub table: &'static str,
+    pub created: usize,
+    pub existing: usize,
+    /// Entries referencing something missing from the backup
+    pub skipped: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RestoreReport {
+    pub tables: Vec<TableReport>,
+}
+
+impl RestoreReport {
+    pub fn created(&self) -> usize {
+        self.tables.iter().map(|t| t.created).sum()
+    }
+
+    /// One line per table
+    pub fn describe(&self) -> String {
+        self.tables
+            .iter()
+            .map(|t| format!("{}: {} new, {} existing, {} skipped", t.table, t.created, t.existing, t.skipped))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+/// Old to new IDs of one table
+type IdMap = HashMap<ID, ID>;
+
+/// Backup entry with a name for the error messages
+trait Entry: Clone {
+    fn id(&self) -> ID;
+    fn label(&self) -> String;
+}
+
+macro_rules! entry {
+    ($type:ty, |$e:ident| $label:expr) => {
+        impl Entry for $type {
+            fn id(&self) -> ID { self.id }
+            fn label(&self) -> String { let $e = self; $label }
+        }
+    };
+}
+
+entry!(Person, |e| format!("{} {}", e.first_name, e.last_name));
+entry!(Exercise, |e| e.name.clone());
+entry!(Workout, |e| e.name.clone());
+entry!(WorkoutSet, |e| format!("#{}", e.order));
+entry!(Measurement, |e| e.date.to_string());
+entry!(Goal, |e| e.deadline.to_string());
+entry!(Routine, |e| e.name.clone());
+
+/// Maps the references of each entry and creates it unless the target has a match
+struct Restore<T: RestoreTarget> {
+    target: T,
+    report: RestoreReport,
+}
+
+impl<T: RestoreTarget> Restore<T> {
+    fn table<E: Entry>(
+        &mut self,
+        table: &'static str,
+        items: &[E],
+        map: impl Fn(&E) -> Option<E>,
+        existing: impl Fn(&Db, &E) -> Option<ID>,
+        insert: fn(&mut T, &E) -> controller::Result<ID>,
+    ) -> Result<IdMap, BackupError> {
+        let mut ids = IdMap::new();
+        let mut report = TableReport { table, ..TableReport::default() };
+        for item in items {
+            let Some(mapped) = map(item) else {
+                report.skipped += 1;
+                continue;
+            };
+            let new_id = match self.target.lookup(|db| existing(db, &mapped)) {
+                Some(found) => {
+                    report.existing += 1;
+                    found
+                }
+                None => {
+                    report.created += 1;
+                    insert(&mut self.target, &mapped).map_err(|err| BackupError::Rest { table, name: item.label(), err })?
+                }
+            };
+            ids.insert(item.id(), new_id);
+        }
+        self.report.tables.push(report);
+        Ok(ids)
+    }
+}
+
+fn map_metric(metric: GoalMetric, exercises: &IdMap) -> Option<GoalMetric> {
+    Some(match metric {
+        GoalMetric::OneRepMax { exercise_id } => GoalMetric::OneRepMax { exercise_id: *exercises.get(&exercise_id)? },
+        GoalMetric::WeeklyVolume { exercise_id: Some(id) } => GoalMetric::WeeklyVolume { exercise_id: Some(*exercises.get(&id)?) },
+        metric => metric,
+    })
+}
+
+/// Creates the entries of `backup` missing from `target`, matching persons by name and birth date,
+/// exercises by name, workouts by start and name, and other entries by their content
+pub fn restore<T: RestoreTarget>(backup: &Backup, target: T) -> Result<RestoreReport, BackupError> {
+    let mut restore = Restore { target, report: RestoreReport::default() };
+
+    let persons = restore.table(
+        "persons",
+        &backup.persons,
+        |e| Some(Person { id: 0, ..e.clone() }),
+        |db, e| db.persons()
+            .get_all()
+            .find(|p| p.first_name == e.first_name && p.last_name == e.last_name && p.birth_date == e.birth_date)
+            .map(|p| p.id),
+        T::insert_person,
+    )?;
+    let exercises = restore.table(
+        "exercises",
+        &backup.exercises,
+        |e| Some(Exercise { id: 0, ..e.clone() }),
+        |db, e| db.exercises()
+            .get_all()
+            .find(|x| x.name.trim().eq_ignore_ascii_case(e.name.trim()))
+            .map(|x| x.id),
+        T::insert_exercise,
+    )?;
+    let routines = restore.table(
+        "routines",
+        &backup.routines,
+        |e| {
+            let mut routine = Routine { id: 0, person_id: *persons.get(&e.person_id)?, ..e.clone() };
+            for exercise in &mut routine.exercises {
+                exercise.exercise_id = *exercises.get(&exercise.exercise_id)?;
+            }
+            Some(routine)
+        },
+        |db, e| db.routines()
+            .get_all()
+            .find(|r| r.person_id == e.person_id && r.name == e.name)
+            .map(|r| r.id),
+        T::insert_routine,
+    )?;
+    let workouts = restore.table(
+        "workouts",
+        &backup.workouts,
+        |e| Some(Workout {
+            id: 0,
+            person_id: *persons.get(&e.person_id)?,
+            routine_id: e.routine_id.and_then(|id| routines.get(&id).copied()),
+            ..e.clone()
+        }),
+        |db, e| db.workouts()
+            .get_all()
+            .find(|w| w.person_id == e.person_id && w.started_at == e.started_at && w.name == e.name)
+            .map(|w| w.id),
+        T::insert_workout,
+    )?;
+    restore.table(
+        "sets",
+        &backup.sets,
+        |e| Some(WorkoutSet {
+            id: 0,
+            workout_id: *workouts.get(&e.workout_id)?,
+            exercise_id: *exercises.get(&e.exercise_id)?,
+            ..e.clone()
+        }),
+        |db, e| db.sets()
+            .get_all()
+            .find(|s| s.workout_id == e.workout_id && s.order == e.order && s.exercise_id == e.exercise_id)
+            .map(|s| s.id),
+        T::insert_set,
+    )?;
+    restore.table(
+        "measurements",
+        &backup.measurements,
+        |e| Some(Measurement { id: 0, person_id: *persons.get(&e.person_id)?, ..e.clone() }),
+        |db, e| db.measurements()
+            .get_all()
+            .find(|m| m.person_id == e.person_id && m.date == e.date)
+            .map(|m| m.id),
+        T::insert_measurement,
+    )?;
+    restore.table(
+        "goals",
+        &backup.goals,
+        |e| Some(Goal {
+            id: 0,
+            person_id: *persons.get(&e.person_id)?,
+            metric: map_metric(e.metric, &exercises)?,
+            ..e.clone()
+        }),
+        |db, e| db.goals()
+            .get_all()
+            .find(|g| g.person_id == e.person_id && g.metric == e.metric && g.deadline == e.deadline)
+            .map(|g| g.id),
+        T::insert_goal,
+    )?;
+
+    Ok(restore.report)
+}
@@ -0,0 +1,156 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use chrono::{Duration, NaiveDate};
+use sport_core_db::{
+    entity::{MuscleGroup, ID},
+    model::{Db, Table},
+};
+
+use crate::analytics::{self, Period};
+
+/// Acute window of the workload ratio in days
+const ACUTE_DAYS: u32 = 7;
+/// Chronic window right before the acute one, in weeks
+const CHRONIC_WEEKS: u32 = 3;
+/// Acute:chronic workload ratios from these values are load spikes
+const ACWR_WARNING: f64 = 1.3;
+const ACWR_DANGER: f64 = 1.5;
+/// Push/pull and upper/lower ratios farther from `1.0` than these factors are imbalances
+const RATIO_WARNING: f64 = 1.5;
+const RATIO_DANGER: f64 = 2.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Status {
+    #[default]
+    Ok,
+    Warning,
+    Danger,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GroupBalance {
+    pub group: MuscleGroup,
+    /// Sets within the last week
+    pub weekly_sets: u32,
+    /// Average weekly sets of the weeks before
+    pub chronic_sets: f64,
+    /// Acute:chronic workload ratio, `None` without earlier training of the group
+    pub acwr: Option<f64>,
+    pub status: Status,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BalanceWarning {
+    LoadSpike { group: MuscleGroup, acwr: f64 },
+    /// Trained in the previous weeks but not within the last one
+    Skipped(MuscleGroup),
+    PushPull(f64),
+    UpperLower(f64),
+}
+
+impl BalanceWarning {
+    pub fn status(&self) -> Status {
+        match self {
+            BalanceWarning::LoadSpike { acwr, .. } => acwr_status(*acwr),
+            BalanceWarning::Skipped(_) => Status::Warning,
+            BalanceWarning::PushPull(ratio) | BalanceWarning::UpperLower(ratio) => ratio_status(*ratio),
+        }
+    }
+}
+
+impl Display for BalanceWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BalanceWarning::LoadSpike { group, acwr } => write!(f, "Load spike on {}: {:.2}x the usual weekly sets", group, acwr),
+            BalanceWarning::Skipped(group) => write!(f, "No {} sets this week", group),
+            BalanceWarning::PushPull(ratio) => write!(f, "Push/pull imbalance {:.2}", ratio),
+            BalanceWarning::UpperLower(ratio) => write!(f, "Upper/lower imbalance {:.2}", ratio),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Balance {
+    /// Groups trained within the acute or chronic windows
+    pub groups: Vec<GroupBalance>,
+    /// Weekly sets ratios by the primary muscle group, `None` if one side has no sets
+    pub push_pull: Option<f64>,
+    pub upper_lower: Option<f64>,
+    pub warnings: Vec<BalanceWarning>,
+}
+
+pub fn acwr_status(acwr: f64) -> Status {
+    if acwr >= ACWR_DANGER {
+        Status::Danger
+    } else if acwr >= ACWR_WARNING {
+        Status::Warning
+    } else {
+        Status::Ok
+    }
+}
+
+pub fn ratio_status(ratio: f64) -> Status {
+    let factor = if ratio < 1.0 { 1.0 / ratio } else { ratio };
+    if factor >= RATIO_DANGER {
+        Status::Danger
+    } else if factor >= RATIO_WARNING {
+        Status::Warning
+    } else {
+        Status::Ok
+    }
+}
+
+fn ratio(a: u32, b: u32) -> Option<f64> {
+    (a > 0 && b > 0).then(|| a as f64 / b as f64)
+}
+
+/// Sets of the primary muscle group of every exercise
+fn primary_group_sets(db: &Db, person_id: ID, period: Period) -> BTreeMap<MuscleGroup, u32> {
+    let mut sets: BTreeMap<MuscleGroup, u32> = BTreeMap::new();
+    for (exercise_id, volume) in analytics::volume_by_exercise(db, person_id, period) {
+        if let Some(group) = db.exercises().get_one(exercise_id).and_then(|e| e.muscle_groups.first()) {
+            *sets.entry(*group).or_default() += volume.sets;
+        }
+    }
+    sets
+}
+
+/// Muscle group balance of the week ending at `today` against the weeks before
+pub fn balance(db: &Db, person_id: ID, today: NaiveDate) -> Balance {
+    let acute = Period::last_days(ACUTE_DAYS, today);
+    let chronic = Period::new(acute.start - Duration::days(7 * CHRONIC_WEEKS as i64), acute.start - Duration::days(1));
+    let acute_volumes = analytics::volume_by_muscle_group(db, person_id, acute);
+    let chronic_volumes = analytics::volume_by_muscle_group(db, person_id, chronic);
+
+    let mut balance = Balance::default();
+    for group in MuscleGroup::ALL {
+        let weekly_sets = acute_volumes.get(&group).map(|v| v.sets).unwrap_or_default();
+        let chronic_sets = chronic_volumes.get(&group).map(|v| v.sets).unwrap_or_default() as f64 / CHRONIC_WEEKS as f64;
+        if weekly_sets == 0 && chronic_sets == 0.0 {
+            continue;
+        }
+        let acwr = (chronic_sets > 0.0).then(|| weekly_sets as f64 / chronic_sets);
+        let mut status = acwr.map(acwr_status).unwrap_or_default();
+        if let Some(acwr) = acwr.filter(|acwr| acwr_status(*acwr) != Status::Ok) {
+            balance.warnings.push(BalanceWarning::LoadSpike { group, acwr });
+        }
+        if weekly_sets == 0 {
+            status = Status::Warning;
+            balance.warnings.push(BalanceWarning::Skipped(group));
+        }
+        balance.groups.push(GroupBalance { group, weekly_sets, chronic_sets, acwr, status });
+    }
+
+    let primary = primary_group_sets(db, person_id, acute);
+    let sum = |f: fn(&MuscleGroup) -> bool| primary.iter().filter(|(g, _)| f(g)).map(|(_, sets)| *sets).sum::<u32>();
+    balance.push_pull = ratio(sum(MuscleGroup::is_push), sum(MuscleGroup::is_pull));
+    balance.upper_lower = ratio(sum(MuscleGroup::is_upper), sum(MuscleGroup::is_lower));
+    if let Some(push_pull) = balance.push_pull.filter(|r| ratio_status(*r) != Status::Ok) {
+        balance.warnings.push(BalanceWarning::PushPull(push_pull));
+    }
+    if let Some(upper_lower) = balance.upper_lower.filter(|r| ratio_status(*r) != Status::Ok) {
+        balance.warnings.push(BalanceWarning::UpperLower(upper_lower));
+    }
+    balance.warnings.sort_by_key(|w| std::cmp::Reverse(w.status()));
+    balance
+}
@@ -0,0 +1,240 @@
+use std::fmt::Display;
+
+use chrono::NaiveDate;
+use serde_derive::{Deserialize, Serialize};
+use sport_core_db::{
+    entity::{Gender, Measurement, ID},
+    model::{Db, Table},
+    units::{Length, Mass},
+};
+
+/// Healthy body mass index range
+const NORMAL_BMI: (f64, f64) = (18.5, 25.0);
+const OBESE_BMI: f64 = 30.0;
+/// Percentages of the max or reserve heart rate bounding the five training zones
+const ZONE_BOUNDS: [f64; 6] = [0.5, 0.6, 0.7, 0.8, 0.9, 1.0];
+const ZONE_NAMES: [&str; 5] = ["Recovery", "Endurance", "Tempo", "Threshold", "Maximum"];
+
+pub fn bmi(weight: Mass, height: Length) -> Option<f64> {
+    let meters = height.meters();
+    (meters > 0.0 && !weight.is_zero()).then(|| weight.kilograms() / (meters * meters))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BmiCategory {
+    Underweight,
+    Normal,
+    Overweight,
+    Obese,
+}
+
+impl BmiCategory {
+    pub fn of(bmi: f64) -> Self {
+        if bmi < NORMAL_BMI.0 {
+            BmiCategory::Underweight
+        } else if bmi < NORMAL_BMI.1 {
+            BmiCategory::Normal
+        } else if bmi < OBESE_BMI {
+            BmiCategory::Overweight
+        } else {
+            BmiCategory::Obese
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BmiCategory::Underweight => "underweight",
+            BmiCategory::Normal => "normal",
+            BmiCategory::Overweight => "overweight",
+            BmiCategory::Obese => "obese",
+        }
+    }
+}
+
+impl Display for BmiCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Weights with a normal body mass index at the height
+pub fn ideal_weight_range(height: Length) -> (Mass, Mass) {
+    let square = height.meters() * height.meters();
+    (Mass::from_kilograms(NORMAL_BMI.0 * square), Mass::from_kilograms(NORMAL_BMI.1 * square))
+}
+
+/// Basal metabolic rate equation
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BmrFormula {
+    #[default]
+    MifflinStJeor,
+    /// Revised by Roza and Shizgal
+    HarrisBenedict,
+}
+
+impl BmrFormula {
+    pub const ALL: [BmrFormula; 2] = [BmrFormula::MifflinStJeor, BmrFormula::HarrisBenedict];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BmrFormula::MifflinStJeor => "Mifflin-St Jeor",
+            BmrFormula::HarrisBenedict => "Harris-Benedict",
+        }
+    }
+
+    /// Calories a day at rest, the average of both sexes for `Gender::Other`
+    pub fn bmr(&self, weight: Mass, height: Length, age: u32, gender: Gender) -> f64 {
+        let (w, h, a) = (weight.kilograms(), height.centimeters(), age as f64);
+        let male = || match self {
+            BmrFormula::MifflinStJeor => 10.0 * w + 6.25 * h - 5.0 * a + 5.0,
+            BmrFormula::HarrisBenedict => 88.362 + 13.397 * w + 4.799 * h - 5.677 * a,
+        };
+        let female = || match self {
+            BmrFormula::MifflinStJeor => 10.0 * w + 6.25 * h - 5.0 * a - 161.0,
+            BmrFormula::HarrisBenedict => 447.593 + 9.247 * w + 3.098 * h - 4.330 * a,
+        };
+        match gender {
+            Gender::Male => male(),
+            Gender::Female => female(),
+            Gender::Other => (male() + female()) / 2.0,
+        }
+    }
+}
+
+impl Display for BmrFormula {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Daily activity multiplying the basal metabolic rate
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityLevel {
+    Sedentary,
+    Light,
+    #[default]
+    Moderate,
+    Active,
+    VeryActive,
+}
+
+impl ActivityLevel {
+    pub const ALL: [ActivityLevel; 5] = [
+        ActivityLevel::Sedentary,
+        ActivityLevel::Light,
+        ActivityLevel::Moderate,
+        ActivityLevel::Active,
+        ActivityLevel::VeryActive,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ActivityLevel::Sedentary => "sedentary",
+            ActivityLevel::Light => "light",
+            ActivityLevel::Moderate => "moderate",
+            ActivityLevel::Active => "active",
+            ActivityLevel::VeryActive => "very active",
+        }
+    }
+
+    pub fn factor(&self) -> f64 {
+        match self {
+            ActivityLevel::Sedentary => 1.2,
+            ActivityLevel::Light => 1.375,
+            ActivityLevel::Moderate => 1.55,
+            ActivityLevel::Active => 1.725,
+            ActivityLevel::VeryActive => 1.9,
+        }
+    }
+}
+
+impl Display for ActivityLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Total daily energy expenditure in calories
+pub fn tdee(bmr: f64, activity: ActivityLevel) -> f64 {
+    bmr * activity.factor()
+}
+
+/// Age-predicted maximum heart rate
+pub fn max_heart_rate(age: u32) -> u32 {
+    220u32.saturating_sub(age)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HeartRateZone {
+    pub number: u32,
+    pub name: &'static str,
+    /// Beats per minute
+    pub low: u32,
+    pub high: u32,
+}
+
+/// Five training zones as percentages of the max heart rate,
+/// of the heart rate reserve (Karvonen) with a resting heart rate
+pub fn heart_rate_zones(age: u32, resting: Option<u32>) -> Vec<HeartRateZone> {
+    let max = max_heart_rate(age) as f64;
+    let rest = resting.map(|r| r as f64).filter(|r| *r < max).unwrap_or_default();
+    let bpm = |percent: f64| ((max - rest) * percent + rest).round() as u32;
+    ZONE_NAMES
+        .iter()
+        .enumerate()
+        .map(|(i, name)| HeartRateZone {
+            number: i as u32 + 1,
+            name,
+            low: bpm(ZONE_BOUNDS[i]),
+            high: bpm(ZONE_BOUNDS[i + 1]),
+        })
+        .collect()
+}
+
+/// Calculator results for a person's latest measurements
+#[derive(Clone, Debug, PartialEq)]
+pub struct Health {
+    pub age: u32,
+    /// Latest body weight, zero without measurements
+    pub weight: Mass,
+    pub bmi: Option<f64>,
+    pub bmr: Option<f64>,
+    pub tdee: Option<f64>,
+    pub resting_heart_rate: Option<u32>,
+    pub max_heart_rate: u32,
+    pub zones: Vec<HeartRateZone>,
+    pub ideal_weight: (Mass, Mass),
+}
+
+/// Latest measurement of the person with a value picked by `f`
+fn latest<T>(db: &Db, person_id: ID, f: impl Fn(&Measurement) -> Option<T>) -> Option<T> {
+    db.measurements()
+        .get_all()
+        .filter(|m| m.person_id == person_id)
+        .filter_map(|m| f(m).map(|value| (m.date, value)))
+        .max_by_key(|(date, _)| *date)
+        .map(|(_, value)| value)
+}
+
+/// `None` if the person isn't loaded or isn't born at `today`
+pub fn health(db: &Db, person_id: ID, today: NaiveDate, formula: BmrFormula, activity: ActivityLevel) -> Option<Health> {
+    let person = db.persons().get_one(person_id)?;
+    let age = person.age_at(today)?;
+    let weight = latest(db, person_id, |m| (!m.body_weight.is_zero()).then_some(m.body_weight)).unwrap_or_default();
+    let resting_heart_rate = latest(db, person_id, |m| m.resting_heart_rate);
+    let bmi = bmi(weight, person.height);
+    let bmr = bmi.map(|_| formula.bmr(weight, person.height, age, person.gender));
+    Some(Health {
+        age,
+        weight,
+        bmi,
+        bmr,
+        tdee: bmr.map(|bmr| tdee(bmr, activity)),
+        resting_heart_rate,
+        max_heart_rate: max_heart_rate(age),
+        zones: heart_rate_zones(age, resting_heart_rate),
+        ideal_weight: ideal_weight_range(person.height),
+    })
+}
@@ -0,0 +1,504 @@
+use std::{io::{self, Write}, path::{Path, PathBuf}};
+
+use chrono::{Local, NaiveDate};
+use serde::Serialize;
+use sport_core_db::{
+    entity::{Exercise, MuscleGroup, Person, ID},
+    model::Table,
+    units::{Length, Quantity, UnitSystem},
+};
+use thiserror::Error;
+
+use crate::{
+    backup::{self, Backup, BackupError, DryRun},
+    config::{Config, ConfigError, Overrides, ProfileError, OVERRIDES},
+    controller::{self, Controller},
+    csv,
+    rest::RestClientError,
+};
+
+pub const USAGE: &str = "\
+Usage: sport_helper [OPTIONS] [COMMAND]
+
+Without a command the terminal UI starts.
+
+Commands:
+  exercises list
+  exercises get <ID>
+  exercises add --name <NAME> [--description <TEXT>] [--muscle-groups <LIST>]
+  exercises update <ID> [--name <NAME>] [--description <TEXT>] [--muscle-groups <LIST>]
+  exercises rm <ID>
+  persons list
+  persons get <ID>
+  persons add --first-name <NAME> --last-name <NAME> --birth-date <YYYY-MM-DD> [--gender <GENDER>] [--height <HEIGHT>]
+  persons update <ID> [--first-name <NAME>] [--last-name <NAME>] [--birth-date <DATE>] [--gender <GENDER>] [--height <HEIGHT>]
+  persons rm <ID>
+  export [--dir <DIR>]         write a JSON backup of everything
+  import <FILE> [--dry-run]    restore a JSON backup
+  help
+
+Output:
+  --json    JSON documents
+  --csv     CSV with a header row
+            a table otherwise
+
+Options:                      environment variable
+  --config <FILE>             SPORT_HELPER_CONFIG    config file to use
+  --profile <NAME>            SPORT_HELPER_PROFILE   server and account of [profiles.<NAME>], `default` for [server] and [account]
+  --scheme <http|https>       SPORT_HELPER_SCHEME
+  --host <HOST>               SPORT_HELPER_HOST
+  --port <PORT>               SPORT_HELPER_PORT
+  --log-level <LEVEL>         SPORT_HELPER_LOG       off, error, warn, info, debug or trace
+
+Options win over environment variables, which win over the config file.
+--scheme, --host and --port change the server of the profile. The terminal UI logs into sport_helper.log
+next to the config file, the commands to stderr.";
+
+/// Exit codes of `sysexits.h`
+pub mod exit_code {
+    pub const USAGE: i32 = 64;
+    pub const DATA: i32 = 65;
+    pub const NOT_FOUND: i32 = 66;
+    pub const UNAVAILABLE: i32 = 69;
+    pub const SOFTWARE: i32 = 70;
+    pub const IO: i32 = 74;
+    pub const PROTOCOL: i32 = 76;
+    pub const CONFIG: i32 = 78;
+}
+
+#[derive(Error, Debug)]
+pub enum CliError {
+    #[error("{0}\n\n{USAGE}")]
+    Usage(String),
+    #[error("Invalid {field}: {err}")]
+    Invalid { field: String, err: String },
+    #[error("No {0} with id {1}")]
+    NotFound(&'static str, ID),
+    #[error("{0}")]
+    Rest(#[from] RestClientError),
+    #[error("{0}")]
+    Backup(#[from] BackupError),
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error("Can`t load the config file {}:\n{}\nFix or remove it, or pick another one with --config", path.display(), config_error(err))]
+    Config { path: PathBuf, err: ConfigError },
+    #[error("{0}")]
+    Profile(#[from] ProfileError),
+}
+
+impl CliError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Usage(_) => exit_code::USAGE,
+            CliError::Invalid { .. } => exit_code::DATA,
+            CliError::NotFound(..) => exit_code::NOT_FOUND,
+            CliError::Rest(err) | CliError::Backup(BackupError::Rest { err, .. }) => rest_exit_code(err),
+            CliError::Backup(BackupError::Json(_) | BackupError::Version(_)) => exit_code::DATA,
+            CliError::Backup(BackupError::Io(_)) | CliError::Io(_) => exit_code::IO,
+            CliError::Config { .. } | CliError::Profile(_) => exit_code::CONFIG,
+        }
+    }
+}
+
+/// Unreachable servers and rejected requests apart, so scripts can retry the first ones
+pub fn rest_exit_code(err: &RestClientError) -> i32 {
+    match err {
+        RestClientError::Request(_) => exit_code::UNAVAILABLE,
+        RestClientError::Parse(_) => exit_code::PROTOCOL,
+        RestClientError::ServerMessage(_) => exit_code::DATA,
+        RestClientError::ServerError(_) => exit_code::SOFTWARE,
+    }
+}
+
+/// The confy error names the kind only, its source says what is wrong
+fn config_error(err: &ConfigError) -> String {
+    match std::error::Error::source(err) {
+        Some(source) => format!("{}: {}", err, source.to_string().trim_end()),
+        None => err.to_string(),
+    }
+}
+
+fn usage(msg: impl Into<String>) -> CliError {
+    CliError::Usage(msg.into())
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+/// `--name value` options of add and update in the given order
+pub type Fields = Vec<(String, String)>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EntityCommand {
+    List,
+    Get(ID),
+    Add(Fields),
+    Update(ID, Fields),
+    Remove(ID),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Command {
+    /// The terminal UI
+    #[default]
+    Tui,
+    Help,
+    Exercises(EntityCommand),
+    Persons(EntityCommand),
+    Export { dir: PathBuf },
+    Import { path: PathBuf, dry_run: bool },
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cli {
+    pub format: OutputFormat,
+    pub command: Command,
+    pub overrides: Overrides,
+}
+
+const EXERCISE_FIELDS: [&str; 3] = ["name", "description", "muscle-groups"];
+const PERSON_FIELDS: [&str; 5] = ["first-name", "last-name", "birth-date", "gender", "height"];
+
+fn parse_id(arg: Option<String>) -> Result<ID, CliError> {
+    let arg = arg.ok_or_else(|| usage("Missing <ID>"))?;
+    arg.parse().map_err(|_| usage(format!("Invalid id `{}`", arg)))
+}
+
+fn parse_fields(args: &mut impl Iterator<Item = String>, known: &[&str]) -> Result<Fields, CliError> {
+    let mut fields = Fields::new();
+    while let Some(arg) = args.next() {
+        let name = arg.strip_prefix("--").filter(|name| known.contains(name)).ok_or_else(|| usage(format!("Unexpected `{}`", arg)))?;
+        let value = args.next().ok_or_else(|| usage(format!("Missing value of `{}`", arg)))?;
+        fields.push((name.to_owned(), value));
+    }
+    Ok(fields)
+}
+
+fn parse_entity(args: &mut impl Iterator<Item = String>, known: &[&str]) -> Result<EntityCommand, CliError> {
+    let action = args.next().ok_or_else(|| usage("Missing action, one of list, get, add, update, rm"))?;
+    let command = match action.as_str() {
+        "list" => EntityCommand::List,
+        "get" => EntityCommand::Get(parse_id(args.next())?),
+        "add" => EntityCommand::Add(parse_fields(args, known)?),
+        "update" => EntityCommand::Update(parse_id(args.next())?, parse_fields(args, known)?),
+        "rm" => EntityCommand::Remove(parse_id(args.next())?),
+        _ => return Err(usage(format!("Unknown action `{}`", action))),
+    };
+    match args.next() {
+        Some(arg) => Err(usage(format!("Unexpected `{}`", arg))),
+        None => Ok(command),
+    }
+}
+
+impl Cli {
+    /// Arguments without the program name, output and config options are taken anywhere
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, CliError> {
+        let mut cli = Cli::default();
+        let mut rest = vec![];
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--json" => cli.format = OutputFormat::Json,
+                "--csv" => cli.format = OutputFormat::Csv,
+                "-h" | "--help" => cli.command = Command::Help,
+                _ => {
+                    let (option, value) = match arg.split_once('=') {
+                        Some((option, value)) => (option, Some(value.to_owned())),
+                        None => (arg.as_str(), None),
+                    };
+                    match option.strip_prefix("--").filter(|name| OVERRIDES.iter().any(|(n, _)| n == name)) {
+                        Some(name) => {
+                            let value = value.or_else(|| args.next()).ok_or_else(|| usage(format!("Missing value of `{}`", option)))?;
+                            cli.overrides.set(name, value).map_err(|err| usage(format!("Invalid `{}`: {}", option, err)))?;
+                        }
+                        None => rest.push(arg),
+                    }
+                }
+            }
+        }
+        if cli.command == Command::Help {
+            return Ok(cli);
+        }
+
+        let mut args = rest.into_iter();
+        let Some(command) = args.next() else {
+            return Ok(cli);
+        };
+        cli.command = match command.as_str() {
+            "help" => Command::Help,
+            "exercises" => Command::Exercises(parse_entity(&mut args, &EXERCISE_FIELDS)?),
+            "persons" => Command::Persons(parse_entity(&mut args, &PERSON_FIELDS)?),
+            "export" => {
+                let dir = match args.next().as_deref() {
+                    Some("--dir") => args.next().ok_or_else(|| usage("Missing value of `--dir`"))?.into(),
+                    Some(arg) => return Err(usage(format!("Unexpected `{}`", arg))),
+                    None => PathBuf::from("."),
+                };
+                Command::Export { dir }
+            }
+            "import" => {
+                let mut path = None;
+                let mut dry_run = false;
+                for arg in args.by_ref() {
+                    match arg.as_str() {
+                        "--dry-run" => dry_run = true,
+                        _ if path.is_none() && !arg.starts_with("--") => path = Some(PathBuf::from(arg)),
+                        _ => return Err(usage(format!("Unexpected `{}`", arg))),
+                    }
+                }
+                Command::Import { path: path.ok_or_else(|| usage("Missing <FILE>"))?, dry_run }
+            }
+            _ => return Err(usage(format!("Unknown command `{}`", command))),
+        };
+        match args.next() {
+            Some(arg) => Err(usage(format!("Unexpected `{}`", arg))),
+            None => Ok(cli),
+        }
+    }
+}
+
+/// Options of the command line first, then the environment variables
+pub fn overrides(cli: &Cli, var: impl Fn(&str) -> Option<String>) -> Result<Overrides, CliError> {
+    let env = Overrides::from_env(var).map_err(|(var, err)| CliError::Invalid { field: var.to_owned(), err })?;
+    Ok(cli.overrides.clone().or(env))
+}
+
+/// The config file of the overrides with their profile selected
+pub fn load_config(overrides: &Overrides) -> Result<Config, CliError> {
+    let path = overrides.config_path().map_err(|err| CliError::Config { path: PathBuf::new(), err })?;
+    let mut cfg = Config::load_path(path.clone()).map_err(|err| CliError::Config { path, err })?;
+    overrides.apply(&mut cfg)?;
+    Ok(cfg)
+}
+
+/// Rows for the table and CSV output, the entities themselves for JSON
+pub struct Output {
+    pub header: Vec<&'static str>,
+    pub rows: Vec<Vec<String>>,
+    pub json: serde_json::Value,
+}
+
+impl Output {
+    pub fn new<T: Serialize>(header: Vec<&'static str>, rows: Vec<Vec<String>>, entities: &T) -> Self {
+        Self { header, rows, json: serde_json::to_value(entities).unwrap_or_default() }
+    }
+
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Json => serde_json::to_string_pretty(&self.json).unwrap_or_default() + "\n",
+            OutputFormat::Csv => {
+                let mut out = String::new();
+                csv::write_row(&mut out, &self.header);
+                self.rows.iter().for_each(|row| csv::write_row(&mut out, row));
+                out
+            }
+            OutputFormat::Table => {
+                let mut widths: Vec<usize> = self.header.iter().map(|h| h.chars().count()).collect();
+                for row in &self.rows {
+                    for (width, cell) in widths.iter_mut().zip(row) {
+                        *width = (*width).max(cell.chars().count());
+                    }
+                }
+                let line = |cells: Vec<&str>| {
+                    let cells: Vec<String> = cells.iter().zip(&widths).map(|(c, w)| format!("{:<w$}", c, w = w)).collect();
+                    cells.join("  ").trim_end().to_owned() + "\n"
+                };
+                let mut out = line(self.header.iter().map(|h| h.to_uppercase()).collect::<Vec<_>>().iter().map(String::as_str).collect());
+                for row in &self.rows {
+                    out.push_str(&line(row.iter().map(String::as_str).collect()));
+                }
+                out
+            }
+        }
+    }
+}
+
+pub fn exercises_output(exercises: &[Exercise]) -> Output {
+    let rows = exercises
+        .iter()
+        .map(|e| vec![e.id.to_string(), e.name.clone(), e.description.clone(), MuscleGroup::format_list(&e.muscle_groups)])
+        .collect();
+    Output::new(vec!["id", "name", "description", "muscle_groups"], rows, &exercises)
+}
+
+pub fn persons_output(persons: &[Person], units: UnitSystem) -> Output {
+    let rows = persons
+        .iter()
+        .map(|p| vec![
+            p.id.to_string(),
+            p.first_name.clone(),
+            p.last_name.clone(),
+            p.birth_date.to_string(),
+            p.gender.to_string(),
+            p.height.format(units),
+        ])
+        .collect();
+    Output::new(vec!["id", "first_name", "last_name", "birth_date", "gender", "height"], rows, &persons)
+}
+
+fn invalid(field: &str, err: impl ToString) -> CliError {
+    CliError::Invalid { field: field.to_owned(), err: err.to_string() }
+}
+
+pub fn apply_exercise_fields(exercise: &mut Exercise, fields: &Fields) -> Result<(), CliError> {
+    for (name, value) in fields {
+        match name.as_str() {
+            "name" => exercise.name = value.trim().to_owned(),
+            "description" => exercise.description = value.clone(),
+            "muscle-groups" => exercise.muscle_groups = MuscleGroup::parse_list(value).map_err(|err| invalid(name, err))?,
+            _ => return Err(usage(format!("Unknown field `--{}`", name))),
+        }
+    }
+    if exercise.name.is_empty() {
+        return Err(invalid("name", "must not be empty"));
+    }
+    Ok(())
+}
+
+pub fn apply_person_fields(person: &mut Person, fields: &Fields, units: UnitSystem) -> Result<(), CliError> {
+    for (name, value) in fields {
+        match name.as_str() {
+            "first-name" => person.first_name = value.trim().to_owned(),
+            "last-name" => person.last_name = value.trim().to_owned(),
+            "birth-date" => person.birth_date = NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map_err(|err| invalid(name, err))?,
+            "gender" => person.gender = value.as_str().into(),
+            "height" => person.height = Length::parse(value, units).map_err(|err| invalid(name, err))?,
+            _ => return Err(usage(format!("Unknown field `--{}`", name))),
+        }
+    }
+    if person.first_name.is_empty() || person.last_name.is_empty() {
+        return Err(invalid("name", "first and last names must not be empty"));
+    }
+    Ok(())
+}
+
+fn require(fields: &Fields, required: &[&str]) -> Result<(), CliError> {
+    match required.iter().find(|r| !fields.iter().any(|(name, _)| name == *r)) {
+        Some(missing) => Err(usage(format!("Missing `--{}`", missing))),
+        None => Ok(()),
+    }
+}
+
+/// Every table, for the backups
+fn load_everything(controller: &Controller) -> controller::Result<()> {
+    controller.person().load_all()?;
+    controller.exercises().load_all()?;
+    controller.routines().load_all()?;
+    controller.workouts().load_all()?;
+    controller.sets().load_all()?;
+    controller.measurements().load_all()?;
+    controller.goals().load_all()
+}
+
+fn run_exercises(command: &EntityCommand, controller: &Controller) -> Result<Option<Output>, CliError> {
+    controller.exercises().load_all()?;
+    let get = |id: ID| controller.db().exercises().get_one(id).cloned().ok_or(CliError::NotFound("exercise", id));
+    let output = match command {
+        EntityCommand::List => {
+            let mut exercises: Vec<Exercise> = controller.db().exercises().get_all().cloned().collect();
+            exercises.sort_by_key(|e| e.name.to_lowercase());
+            exercises_output(&exercises)
+        }
+        EntityCommand::Get(id) => exercises_output(&[get(*id)?]),
+        EntityCommand::Add(fields) => {
+            require(fields, &["name"])?;
+            let mut exercise = Exercise::new(0, String::new(), String::new());
+            apply_exercise_fields(&mut exercise, fields)?;
+            let id = controller.exercises().insert(&exercise)?;
+            exercises_output(&[get(id)?])
+        }
+        EntityCommand::Update(id, fields) => {
+            let mut exercise = get(*id)?;
+            apply_exercise_fields(&mut exercise, fields)?;
+            controller.exercises().update(exercise.clone())?;
+            exercises_output(&[exercise])
+        }
+        EntityCommand::Remove(id) => {
+            get(*id)?;
+            controller.exercises().remove_exercise(*id)?;
+            return Ok(None);
+        }
+    };
+    Ok(Some(output))
+}
+
+fn run_persons(command: &EntityCommand, controller: &Controller) -> Result<Option<Output>, CliError> {
+    controller.person().load_all()?;
+    let units = controller.cfg().units;
+    let get = |id: ID| controller.db().persons().get_one(id).cloned().ok_or(CliError::NotFound("person", id));
+    let output = match command {
+        EntityCommand::List => {
+            let mut persons: Vec<Person> = controller.db().persons().get_all().cloned().collect();
+            persons.sort_by_key(|p| p.id);
+            persons_output(&persons, units)
+        }
+        EntityCommand::Get(id) => persons_output(&[get(*id)?], units),
+        EntityCommand::Add(fields) => {
+            require(fields, &["first-name", "last-name", "birth-date"])?;
+            let mut person = Person::default();
+            apply_person_fields(&mut person, fields, units)?;
+            let id = controller.person().insert(&person)?;
+            persons_output(&[get(id)?], units)
+        }
+        EntityCommand::Update(id, fields) => {
+            let mut person = get(*id)?;
+            apply_person_fields(&mut person, fields, units)?;
+            controller.person().update(person.clone())?;
+            persons_output(&[person], units)
+        }
+        EntityCommand::Remove(id) => {
+            get(*id)?;
+            controller.person().remove_person(*id)?;
+            return Ok(None);
+        }
+    };
+    Ok(Some(output))
+}
+
+fn export(controller: &Controller, dir: &Path) -> Result<String, CliError> {
+    load_everything(controller)?;
+    let backup = Backup::new(&controller.db(), controller.cfg(), Local::now().naive_local());
+    Ok(backup.export(dir)?.display().to_string())
+}
+
+fn import(controller: &Controller, path: &Path, dry_run: bool) -> Result<String, CliError> {
+    load_everything(controller)?;
+    let backup = Backup::load(path)?;
+    let report = if dry_run {
+        backup::restore(&backup, DryRun::new(&controller.db()))?
+    } else {
+        backup::restore(&backup, controller)?
+    };
+    Ok(report.describe())
+}
+
+/// Runs a command other than the UI against the server and writes its output
+pub fn run(cli: &Cli, controller: &Controller, out: &mut impl Write) -> Result<(), CliError> {
+    let output = match &cli.command {
+        Command::Tui => return Ok(()),
+        Command::Help => {
+            writeln!(out, "{}", USAGE)?;
+            return Ok(());
+        }
+        Command::Exercises(command) => run_exercises(command, controller)?,
+        Command::Persons(command) => run_persons(command, controller)?,
+        Command::Export { dir } => {
+            let path = export(controller, dir)?;
+            Some(Output::new(vec!["path"], vec![vec![path.clone()]], &serde_json::json!({ "path": path })))
+        }
+        Command::Import { path, dry_run } => {
+            let report = import(controller, path, *dry_run)?;
+            let rows = report.lines().map(|l| vec![l.to_owned()]).collect();
+            Some(Output::new(vec!["restored"], rows, &report.lines().collect::<Vec<_>>()))
+        }
+    };
+    if let Some(output) = output {
+        out.write_all(output.render(cli.format).as_bytes())?;
+    }
+    Ok(())
+}
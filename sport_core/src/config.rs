@@ -1,39 +1,58 @@
-use std::net::{IpAddr, Ipv4Addr};
+use std::{collections::BTreeMap, iter, path::PathBuf};
 use confy::ConfyError;
-use crossterm::event::KeyCode;
+use log::LevelFilter;
 use serde_derive::{Deserialize, Serialize};
+use sport_core_db::units::UnitSystem;
+use thiserror::Error;
 
-#[derive(Clone, Copy)]
-pub struct KeyMap {
-    pub quit: char,
-    pub accept: KeyCode,
-    pub reject: KeyCode,
-}
-
-impl Default for KeyMap {
-    fn default() -> Self {
-        Self { quit: 'q', accept: KeyCode::Enter, reject: KeyCode::Esc }
-    }
-}
+use crate::{
+    analytics::{OneRepMaxFormula, Schedule, StreakRule},
+    calculators::{ActivityLevel, BmrFormula},
+    ics::CalendarConfig,
+    keys::KeyMap,
+    plates::PlateInventory,
+    timer::TimerConfig,
+};
 
 pub type ConfigError = confy::ConfyError;
 
-#[derive(Debug, Serialize, Deserialize)]
+pub const APP_NAME: &str = "sport_helper";
+
+/// Name of the profile in the `server` and `account` sections
+pub const DEFAULT_PROFILE: &str = "default";
+
+pub const SCHEMES: [&str; 2] = ["http", "https"];
+
+fn default_scheme() -> String {
+    SCHEMES[0].to_owned()
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ServerConfig {
-    pub host: IpAddr,
+    /// Name or address of the server
+    pub host: String,
     pub port: u16,
+    #[serde(default = "default_scheme")]
+    pub scheme: String,
+}
+
+impl ServerConfig {
+    pub fn url(&self) -> String {
+        format!("{}://{}:{}", self.scheme, self.host, self.port)
+    }
 }
 
 impl Default for ServerConfig {
     fn default() -> Self {
         Self {
-            host: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+            host: "127.0.0.1".to_owned(),
             port: 5050,
+            scheme: default_scheme(),
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct AccountConfig {
     pub id: u64,
 }
@@ -44,24 +63,223 @@ impl AccountConfig {
     }
 }
 
-impl Default for AccountConfig {
-    fn default() -> Self {
-        AccountConfig { id: 0 }
-    }
+/// Server and account to work with, like a staging and a production one
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct Profile {
+    pub server: ServerConfig,
+    #[serde(default)]
+    pub account: AccountConfig,
+}
+
+#[derive(Error, Debug)]
+pub enum ProfileError {
+    #[error("No profile `{name}`, there are {known}")]
+    Unknown { name: String, known: String },
+    #[error("Can`t load the config: {0}")]
+    Config(#[from] ConfyError),
 }
 
+/// Plain values come before the sections, TOML can`t write them after a table
 #[derive(Default, Debug, Serialize, Deserialize)]
 pub struct Config {
+    /// Preferred unit system for display and input
+    #[serde(default)]
+    pub units: UnitSystem,
+    /// Formula of the estimated one rep max in the analytics
+    #[serde(default)]
+    pub one_rep_max: OneRepMaxFormula,
+    /// Defaults of the health calculators
+    #[serde(default)]
+    pub activity: ActivityLevel,
+    #[serde(default)]
+    pub bmr_formula: BmrFormula,
+    /// Profile used at start, the `default` one if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Settings of the `default` profile
     pub server: ServerConfig,
     pub account: AccountConfig,
+    /// What counts as keeping a training streak
+    #[serde(default)]
+    pub streak: StreakRule,
+    /// Planned training for the adherence metrics
+    #[serde(default)]
+    pub schedule: Schedule,
+    /// Bar and plates of the plate calculator
+    #[serde(default)]
+    pub plates: PlateInventory,
+    #[serde(default)]
+    pub timer: TimerConfig,
+    /// Days and time of the routines in the exported calendar
+    #[serde(default)]
+    pub calendar: CalendarConfig,
+    /// Keys of the actions by name like `quick_entry = "ctrl+e"`
+    #[serde(default)]
+    pub keys: KeyMap,
+    /// More profiles by name, `[profiles.staging.server]` and `[profiles.staging.account]`
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+    /// File the config was read from and is stored to
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+    /// Name and settings of the profile in use with the overrides applied, set by `use_profile`
+    #[serde(skip)]
+    pub active: Option<(String, Profile)>,
+    /// Overrides applied to the config, applied again to another profile
+    #[serde(skip)]
+    pub overrides: Overrides,
 }
 
 impl Config {
+    /// File in the user config directory
+    pub fn default_path() -> Result<PathBuf, ConfyError> {
+        confy::get_configuration_file_path(APP_NAME, None)
+    }
+
     pub fn load() -> Result<Self, ConfyError> {
-         confy::load("sport_helper", None)
+        Self::load_path(Self::default_path()?)
+    }
+
+    /// A missing file is created with the defaults
+    pub fn load_path(path: PathBuf) -> Result<Self, ConfyError> {
+        let mut cfg: Config = confy::load_path(&path)?;
+        cfg.path = Some(path);
+        Ok(cfg)
     }
 
     pub fn store(self) -> Result<(), ConfyError> {
-        confy::store("sport_helper", None, self)
+        match self.path.clone() {
+            Some(path) => confy::store_path(path, self),
+            None => confy::store(APP_NAME, None, self),
+        }
+    }
+
+    /// The `default` profile first, a `default` in the profiles can`t be used
+    pub fn profile_names(&self) -> Vec<&str> {
+        iter::once(DEFAULT_PROFILE)
+            .chain(self.profiles.keys().map(String::as_str).filter(|name| *name != DEFAULT_PROFILE))
+            .collect()
+    }
+
+    pub fn profile(&self, name: &str) -> Option<Profile> {
+        match name {
+            DEFAULT_PROFILE => Some(Profile { server: self.server.clone(), account: self.account.clone() }),
+            _ => self.profiles.get(name).cloned(),
+        }
+    }
+
+    pub fn use_profile(&mut self, name: &str) -> Result<(), ProfileError> {
+        let profile = self.profile(name).ok_or_else(|| ProfileError::Unknown {
+            name: name.to_owned(),
+            known: self.profile_names().join(", "),
+        })?;
+        self.active = Some((name.to_owned(), profile));
+        Ok(())
+    }
+
+    pub fn active_profile(&self) -> &str {
+        self.active.as_ref().map_or(DEFAULT_PROFILE, |(name, _)| name)
+    }
+
+    /// Server of the profile in use
+    pub fn server(&self) -> &ServerConfig {
+        self.active.as_ref().map_or(&self.server, |(_, profile)| &profile.server)
+    }
+
+    /// Account of the profile in use
+    pub fn account(&self) -> &AccountConfig {
+        self.active.as_ref().map_or(&self.account, |(_, profile)| &profile.account)
+    }
+}
+
+/// Names of the settings on the command line and their environment variables
+pub const OVERRIDES: [(&str, &str); 6] = [
+    ("config", "SPORT_HELPER_CONFIG"),
+    ("profile", "SPORT_HELPER_PROFILE"),
+    ("scheme", "SPORT_HELPER_SCHEME"),
+    ("host", "SPORT_HELPER_HOST"),
+    ("port", "SPORT_HELPER_PORT"),
+    ("log-level", "SPORT_HELPER_LOG"),
+];
+
+/// Settings given on the command line or in the environment, they win over the config file
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Overrides {
+    pub config: Option<PathBuf>,
+    pub profile: Option<String>,
+    pub scheme: Option<String>,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub log_level: Option<LevelFilter>,
+}
+
+impl Overrides {
+    /// Sets one of the `OVERRIDES` by name
+    pub fn set(&mut self, name: &str, value: String) -> Result<(), String> {
+        match name {
+            "config" => self.config = Some(value.into()),
+            "profile" => self.profile = Some(value),
+            "scheme" if SCHEMES.contains(&value.as_str()) => self.scheme = Some(value),
+            "scheme" => return Err(format!("`{}` is not one of {}", value, SCHEMES.join(", "))),
+            "host" => self.host = Some(value),
+            "port" => self.port = Some(value.parse().map_err(|_| format!("`{}` is not a port number", value))?),
+            "log-level" => {
+                self.log_level = Some(value.parse().map_err(|_| format!("`{}` is not one of off, error, warn, info, debug, trace", value))?)
+            }
+            _ => return Err(format!("Unknown setting `{}`", name)),
+        }
+        Ok(())
+    }
+
+    /// Settings of the environment variables, empty ones are ignored
+    pub fn from_env(var: impl Fn(&str) -> Option<String>) -> Result<Self, (&'static str, String)> {
+        let mut overrides = Overrides::default();
+        for (name, var_name) in OVERRIDES {
+            if let Some(value) = var(var_name).filter(|value| !value.is_empty()) {
+                overrides.set(name, value).map_err(|err| (var_name, err))?;
+            }
+        }
+        Ok(overrides)
+    }
+
+    /// Values missing here are taken from `other`
+    pub fn or(self, other: Overrides) -> Overrides {
+        Overrides {
+            config: self.config.or(other.config),
+            profile: self.profile.or(other.profile),
+            scheme: self.scheme.or(other.scheme),
+            host: self.host.or(other.host),
+            port: self.port.or(other.port),
+            log_level: self.log_level.or(other.log_level),
+        }
+    }
+
+    /// The given file, else the one in the user config directory
+    pub fn config_path(&self) -> Result<PathBuf, ConfyError> {
+        match &self.config {
+            Some(path) => Ok(path.clone()),
+            None => Config::default_path(),
+        }
+    }
+
+    /// Selects the profile, the one of the config if not given, and overrides its server
+    pub fn apply(&self, cfg: &mut Config) -> Result<(), ProfileError> {
+        let name = self.profile.clone().or_else(|| cfg.profile.clone()).unwrap_or_else(|| DEFAULT_PROFILE.to_owned());
+        cfg.use_profile(&name)?;
+        cfg.overrides = self.clone();
+        // the copy in use, overrides are not stored with the config
+        let Some((_, Profile { server, .. })) = &mut cfg.active else {
+            return Ok(());
+        };
+        if let Some(scheme) = &self.scheme {
+            server.scheme = scheme.clone();
+        }
+        if let Some(host) = &self.host {
+            server.host = host.clone();
+        }
+        if let Some(port) = self.port {
+            server.port = port;
+        }
+        Ok(())
     }
 }
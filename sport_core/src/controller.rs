@@ -1,4 +1,4 @@
-use crate::{rest::{self, RestClientError}, config::{KeyMap, Config}};
+use crate::{rest::{self, RestClientError}, analytics::OneRepMaxFormula, config::{Config, Overrides, ProfileError}, keys::KeyMap, records};
 use sport_core_db::{
     entity,
     model::{self, Table},
@@ -14,22 +14,31 @@ pub struct Controller {
     db: Rc<RefCell<model::Db>>,
     exercises: ControllerExercises,
     person: ControllerPerson,
-    key_map: KeyMap,
+    workouts: ControllerWorkouts,
+    sets: ControllerWorkoutSets,
+    measurements: ControllerMeasurements,
+    goals: ControllerGoals,
+    routines: ControllerRoutines,
     cfg: Config,
 }
 
 impl Controller {
-    pub fn new(host: &str, cfg: Config, key_map: KeyMap) -> Self { 
+    pub fn new(host: &str, cfg: Config) -> Self { 
         let rt = Rc::new(tokio::runtime::Builder::new_current_thread()
                          .enable_all().build().expect("Can`t create client runtime"));
         let db = Rc::new(RefCell::new(model::Db::default()));
+        let client = Rc::new(rest::Client::default(host));
 
         Self { 
             db: db.clone(),
-            exercises: ControllerExercises::new(host, rt.clone(), db.clone()),
-            person: ControllerPerson::new(host, rt, db),
+            exercises: ControllerExercises::new(client.clone(), rt.clone(), db.clone()),
+            person: ControllerPerson::new(client.clone(), rt.clone(), db.clone()),
+            workouts: ControllerWorkouts::new(client.clone(), rt.clone(), db.clone(), cfg.one_rep_max),
+            sets: ControllerWorkoutSets::new(client.clone(), rt.clone(), db.clone(), cfg.one_rep_max),
+            measurements: ControllerMeasurements::new(client.clone(), rt.clone(), db.clone()),
+            goals: ControllerGoals::new(client.clone(), rt.clone(), db.clone()),
+            routines: ControllerRoutines::new(client, rt, db),
             cfg,
-            key_map,
         } 
     }
 
@@ -38,10 +47,24 @@ impl Controller {
     }
 
     pub fn key_map(&self) -> &KeyMap {
-        &self.key_map
+        &self.cfg.keys
     }
 
-    pub fn db(&self) -> Ref<model::Db> {
+    /// Controller of another profile with the config file read again, the data of this one is dropped.
+    /// Overrides of the command line and environment are applied to the other profile too
+    pub fn switch_profile(&self, name: &str) -> std::result::Result<Controller, ProfileError> {
+        let mut cfg = match &self.cfg.path {
+            Some(path) => Config::load_path(path.clone())?,
+            None => Config::load()?,
+        };
+        let overrides = Overrides { profile: Some(name.to_owned()), ..self.cfg.overrides.clone() };
+        overrides.apply(&mut cfg)?;
+        info!("Switching to the profile {} at {}", name, cfg.server().url());
+        self.db.replace(model::Db::default());
+        Ok(Controller::new(&cfg.server().url(), cfg))
+    }
+
+    pub fn db(&self) -> Ref<'_, model::Db> {
         Ref::map(self.db.borrow(), |db| db)
     }
 
@@ -52,19 +75,39 @@ impl Controller {
     pub fn person(&self) -> &ControllerPerson {
         &self.person
     }
+
+    pub fn workouts(&self) -> &ControllerWorkouts {
+        &self.workouts
+    }
+
+    pub fn sets(&self) -> &ControllerWorkoutSets {
+        &self.sets
+    }
+
+    pub fn measurements(&self) -> &ControllerMeasurements {
+        &self.measurements
+    }
+
+    pub fn goals(&self) -> &ControllerGoals {
+        &self.goals
+    }
+
+    pub fn routines(&self) -> &ControllerRoutines {
+        &self.routines
+    }
 }
 
 pub struct ControllerExercises {
     rt: Rc<Runtime>,
-    client: rest::Client,
+    client: Rc<rest::Client>,
     db: Rc<RefCell<model::Db>>,
 }
 
 impl ControllerExercises {
-    pub fn new(host: &str, rt: Rc<Runtime>, db: Rc<RefCell<model::Db>>) -> Self {
+    pub fn new(client: Rc<rest::Client>, rt: Rc<Runtime>, db: Rc<RefCell<model::Db>>) -> Self {
         Self { 
             rt,
-            client: rest::Client::default(&host),
+            client,
             db,
         }
     }
@@ -97,13 +140,14 @@ impl ControllerExercises {
         }
     }
 
-    /// Insert exercise on server
-    pub fn insert(&self, data: &entity::Exercise) -> Result<()> {
-        match self.rt.block_on(self.client.exercise().insert(&data)) {
+    /// Insert exercise on server, returns the inserted id
+    pub fn insert(&self, data: &entity::Exercise) -> Result<entity::ID> {
+        match self.rt.block_on(self.client.exercise().insert(data)) {
             Ok(inserted) => {
                 info!("Inserted exercise: {:#?}", inserted);
+                let id = inserted.id;
                 self.db.borrow_mut().exercises_mut().insert(inserted);
-                Ok(())
+                Ok(id)
             }
             Err(err) => {
                 error!("{}", err);
@@ -145,15 +189,15 @@ impl ControllerExercises {
 
 pub struct ControllerPerson {
     rt: Rc<Runtime>,
-    client: rest::Client,
+    client: Rc<rest::Client>,
     db: Rc<RefCell<model::Db>>,
 }
 
 impl ControllerPerson {
-    pub fn new(host: &str, rt: Rc<Runtime>, db: Rc<RefCell<model::Db>>) -> Self {
+    pub fn new(client: Rc<rest::Client>, rt: Rc<Runtime>, db: Rc<RefCell<model::Db>>) -> Self {
         Self { 
             rt,
-            client: rest::Client::default(&host),
+            client,
             db,
         }
     }
@@ -169,13 +213,30 @@ impl ControllerPerson {
         }
     }
 
-    /// Insert person on server
-    pub fn insert(&self, data: &entity::Person) -> Result<()> {
-        match self.rt.block_on(self.client.person().insert(&data)) {
+    /// Get persons from server
+    pub fn load_all(&self) -> Result<()> {
+        match self.rt.block_on(self.client.person().get_all()) {
+            Ok(data) => {
+                info!("GET persons: {:#?}", data);
+                data.into_iter()
+                    .for_each(|p| {self.db.borrow_mut().persons_mut().insert(p);});
+                Ok(())
+            }
+            Err(err) => {
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Insert person on server, returns the inserted id
+    pub fn insert(&self, data: &entity::Person) -> Result<entity::ID> {
+        match self.rt.block_on(self.client.person().insert(data)) {
             Ok(inserted) => {
                 info!("Inserted person: {:#?}", inserted);
+                let id = inserted.id;
                 self.db.borrow_mut().persons_mut().insert(inserted);
-                Ok(())
+                Ok(id)
             }
             Err(err) => {
                 error!("{}", err);
@@ -214,3 +275,432 @@ impl ControllerPerson {
         }
     }
 }
+
+pub struct ControllerWorkouts {
+    rt: Rc<Runtime>,
+    client: Rc<rest::Client>,
+    db: Rc<RefCell<model::Db>>,
+    /// Formula of the best one rep max records
+    one_rep_max: OneRepMaxFormula,
+}
+
+impl ControllerWorkouts {
+    pub fn new(client: Rc<rest::Client>, rt: Rc<Runtime>, db: Rc<RefCell<model::Db>>, one_rep_max: OneRepMaxFormula) -> Self {
+        Self {
+            rt,
+            client,
+            db,
+            one_rep_max,
+        }
+    }
+
+    /// Get workouts from server
+    pub fn load_all(&self) -> Result<()> {
+        match self.rt.block_on(self.client.workout().get_all()) {
+            Ok(data) => {
+                info!("GET workouts: {:#?}", data);
+                let mut db = self.db.borrow_mut();
+                db.workouts_mut().clear();
+                data.into_iter()
+                    .for_each(|e| {db.workouts_mut().insert(e);});
+                records::rebuild(&mut db, self.one_rep_max);
+                Ok(())
+            }
+            Err(err) => {
+                let mut db = self.db.borrow_mut();
+                db.workouts_mut().clear(); // clear previous
+                records::rebuild(&mut db, self.one_rep_max);
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Insert workout on server, returns the inserted id
+    pub fn insert(&self, data: &entity::Workout) -> Result<entity::ID> {
+        match self.rt.block_on(self.client.workout().insert(data)) {
+            Ok(inserted) => {
+                info!("Inserted workout: {:#?}", inserted);
+                let id = inserted.id;
+                self.db.borrow_mut().workouts_mut().insert(inserted);
+                Ok(id)
+            }
+            Err(err) => {
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Update workout on server
+    pub fn update(&self, data: entity::Workout) -> Result<()> {
+        match self.rt.block_on(self.client.workout().update(&data, data.id)) {
+            Ok(updated) => {
+                info!("Updated workout: {:#?}", updated);
+                let mut db = self.db.borrow_mut();
+                db.workouts_mut().update(data);
+                // the date orders the records
+                records::rebuild(&mut db, self.one_rep_max);
+                Ok(())
+            }
+            Err(err) => {
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Removes workout from server
+    pub fn remove(&self, id: entity::ID) -> Result<()> {
+        match self.rt.block_on(self.client.workout().remove(id)) {
+            Ok(data) => {
+                info!("Removed workout: {:#?}", data);
+                let mut db = self.db.borrow_mut();
+                db.workouts_mut().remove(id);
+                let sets: Vec<entity::ID> = db.sets().get_all().filter(|s| s.workout_id == id).map(|s| s.id).collect();
+                sets.into_iter().for_each(|set_id| db.sets_mut().remove(set_id));
+                records::rebuild(&mut db, self.one_rep_max);
+                Ok(())
+            }
+            Err(err) => {
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+}
+
+pub struct ControllerWorkoutSets {
+    rt: Rc<Runtime>,
+    client: Rc<rest::Client>,
+    db: Rc<RefCell<model::Db>>,
+    /// Formula of the best one rep max records
+    one_rep_max: OneRepMaxFormula,
+}
+
+impl ControllerWorkoutSets {
+    pub fn new(client: Rc<rest::Client>, rt: Rc<Runtime>, db: Rc<RefCell<model::Db>>, one_rep_max: OneRepMaxFormula) -> Self {
+        Self {
+            rt,
+            client,
+            db,
+            one_rep_max,
+        }
+    }
+
+    /// Get workout sets from server
+    pub fn load_all(&self) -> Result<()> {
+        match self.rt.block_on(self.client.set().get_all()) {
+            Ok(data) => {
+                info!("GET workout sets: {:#?}", data);
+                let mut db = self.db.borrow_mut();
+                db.sets_mut().clear();
+                data.into_iter()
+                    .for_each(|e| {db.sets_mut().insert(e);});
+                records::rebuild(&mut db, self.one_rep_max);
+                Ok(())
+            }
+            Err(err) => {
+                let mut db = self.db.borrow_mut();
+                db.sets_mut().clear(); // clear previous
+                records::rebuild(&mut db, self.one_rep_max);
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Insert workout set on server, returns its ID and the personal records it beats
+    pub fn insert(&self, data: &entity::WorkoutSet) -> Result<(entity::ID, Vec<entity::PersonalRecord>)> {
+        match self.rt.block_on(self.client.set().insert(data)) {
+            Ok(inserted) => {
+                info!("Inserted workout set: {:#?}", inserted);
+                let id = inserted.id;
+                let mut db = self.db.borrow_mut();
+                db.sets_mut().insert(inserted);
+                Ok((id, records::register_set(&mut db, id, self.one_rep_max)))
+            }
+            Err(err) => {
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Update workout set on server
+    pub fn update(&self, data: entity::WorkoutSet) -> Result<()> {
+        match self.rt.block_on(self.client.set().update(&data, data.id)) {
+            Ok(updated) => {
+                info!("Updated workout set: {:#?}", updated);
+                let mut db = self.db.borrow_mut();
+                db.sets_mut().update(data);
+                records::rebuild(&mut db, self.one_rep_max);
+                Ok(())
+            }
+            Err(err) => {
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Removes workout set from server
+    pub fn remove(&self, id: entity::ID) -> Result<()> {
+        match self.rt.block_on(self.client.set().remove(id)) {
+            Ok(data) => {
+                info!("Removed workout set: {:#?}", data);
+                let mut db = self.db.borrow_mut();
+                db.sets_mut().remove(id);
+                records::rebuild(&mut db, self.one_rep_max);
+                Ok(())
+            }
+            Err(err) => {
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+}
+
+pub struct ControllerMeasurements {
+    rt: Rc<Runtime>,
+    client: Rc<rest::Client>,
+    db: Rc<RefCell<model::Db>>,
+}
+
+impl ControllerMeasurements {
+    pub fn new(client: Rc<rest::Client>, rt: Rc<Runtime>, db: Rc<RefCell<model::Db>>) -> Self {
+        Self {
+            rt,
+            client,
+            db,
+        }
+    }
+
+    /// Get measurements from server
+    pub fn load_all(&self) -> Result<()> {
+        match self.rt.block_on(self.client.measurement().get_all()) {
+            Ok(data) => {
+                info!("GET measurements: {:#?}", data);
+                let mut db = self.db.borrow_mut();
+                db.measurements_mut().clear();
+                data.into_iter()
+                    .for_each(|e| {db.measurements_mut().insert(e);});
+                Ok(())
+            }
+            Err(err) => {
+                self.db.borrow_mut().measurements_mut().clear(); // clear previous
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Insert measurement on server, returns the inserted id
+    pub fn insert(&self, data: &entity::Measurement) -> Result<entity::ID> {
+        match self.rt.block_on(self.client.measurement().insert(data)) {
+            Ok(inserted) => {
+                info!("Inserted measurement: {:#?}", inserted);
+                let id = inserted.id;
+                self.db.borrow_mut().measurements_mut().insert(inserted);
+                Ok(id)
+            }
+            Err(err) => {
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Update measurement on server
+    pub fn update(&self, data: entity::Measurement) -> Result<()> {
+        match self.rt.block_on(self.client.measurement().update(&data, data.id)) {
+            Ok(updated) => {
+                info!("Updated measurement: {:#?}", updated);
+                self.db.borrow_mut().measurements_mut().update(data);
+                Ok(())
+            }
+            Err(err) => {
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Removes measurement from server
+    pub fn remove(&self, id: entity::ID) -> Result<()> {
+        match self.rt.block_on(self.client.measurement().remove(id)) {
+            Ok(data) => {
+                info!("Removed measurement: {:#?}", data);
+                self.db.borrow_mut().measurements_mut().remove(id);
+                Ok(())
+            }
+            Err(err) => {
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+}
+
+pub struct ControllerGoals {
+    rt: Rc<Runtime>,
+    client: Rc<rest::Client>,
+    db: Rc<RefCell<model::Db>>,
+}
+
+impl ControllerGoals {
+    pub fn new(client: Rc<rest::Client>, rt: Rc<Runtime>, db: Rc<RefCell<model::Db>>) -> Self {
+        Self {
+            rt,
+            client,
+            db,
+        }
+    }
+
+    /// Get goals from server
+    pub fn load_all(&self) -> Result<()> {
+        match self.rt.block_on(self.client.goal().get_all()) {
+            Ok(data) => {
+                info!("GET goals: {:#?}", data);
+                let mut db = self.db.borrow_mut();
+                db.goals_mut().clear();
+                data.into_iter()
+                    .for_each(|e| {db.goals_mut().insert(e);});
+                Ok(())
+            }
+            Err(err) => {
+                self.db.borrow_mut().goals_mut().clear(); // clear previous
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Insert goal on server, returns the inserted id
+    pub fn insert(&self, data: &entity::Goal) -> Result<entity::ID> {
+        match self.rt.block_on(self.client.goal().insert(data)) {
+            Ok(inserted) => {
+                info!("Inserted goal: {:#?}", inserted);
+                let id = inserted.id;
+                self.db.borrow_mut().goals_mut().insert(inserted);
+                Ok(id)
+            }
+            Err(err) => {
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Update goal on server
+    pub fn update(&self, data: entity::Goal) -> Result<()> {
+        match self.rt.block_on(self.client.goal().update(&data, data.id)) {
+            Ok(updated) => {
+                info!("Updated goal: {:#?}", updated);
+                self.db.borrow_mut().goals_mut().update(data);
+                Ok(())
+            }
+            Err(err) => {
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Removes goal from server
+    pub fn remove(&self, id: entity::ID) -> Result<()> {
+        match self.rt.block_on(self.client.goal().remove(id)) {
+            Ok(data) => {
+                info!("Removed goal: {:#?}", data);
+                self.db.borrow_mut().goals_mut().remove(id);
+                Ok(())
+            }
+            Err(err) => {
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+}
+
+pub struct ControllerRoutines {
+    rt: Rc<Runtime>,
+    client: Rc<rest::Client>,
+    db: Rc<RefCell<model::Db>>,
+}
+
+impl ControllerRoutines {
+    pub fn new(client: Rc<rest::Client>, rt: Rc<Runtime>, db: Rc<RefCell<model::Db>>) -> Self {
+        Self {
+            rt,
+            client,
+            db,
+        }
+    }
+
+    /// Get routines from server
+    pub fn load_all(&self) -> Result<()> {
+        match self.rt.block_on(self.client.routine().get_all()) {
+            Ok(data) => {
+                info!("GET routines: {:#?}", data);
+                let mut db = self.db.borrow_mut();
+                db.routines_mut().clear();
+                data.into_iter()
+                    .for_each(|e| {db.routines_mut().insert(e);});
+                Ok(())
+            }
+            Err(err) => {
+                self.db.borrow_mut().routines_mut().clear(); // clear previous
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Insert routine on server, returns the inserted id
+    pub fn insert(&self, data: &entity::Routine) -> Result<entity::ID> {
+        match self.rt.block_on(self.client.routine().insert(data)) {
+            Ok(inserted) => {
+                info!("Inserted routine: {:#?}", inserted);
+                let id = inserted.id;
+                self.db.borrow_mut().routines_mut().insert(inserted);
+                Ok(id)
+            }
+            Err(err) => {
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Update routine on server
+    pub fn update(&self, data: entity::Routine) -> Result<()> {
+        match self.rt.block_on(self.client.routine().update(&data, data.id)) {
+            Ok(updated) => {
+                info!("Updated routine: {:#?}", updated);
+                self.db.borrow_mut().routines_mut().update(data);
+                Ok(())
+            }
+            Err(err) => {
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Removes routine from server
+    pub fn remove(&self, id: entity::ID) -> Result<()> {
+        match self.rt.block_on(self.client.routine().remove(id)) {
+            Ok(data) => {
+                info!("Removed routine: {:#?}", data);
+                self.db.borrow_mut().routines_mut().remove(id);
+                Ok(())
+            }
+            Err(err) => {
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CsvError {
+    #[error("No header row")]
+    Empty,
+    #[error("Unclosed quote starting at line {0}")]
+    UnclosedQuote(usize),
+}
+
+/// Fields of one row with the line it starts at
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CsvRecord {
+    pub line: usize,
+    pub fields: Vec<String>,
+}
+
+impl CsvRecord {
+    pub fn get(&self, index: usize) -> &str {
+        self.fields.get(index).map(String::as_str).unwrap_or_default()
+    }
+}
+
+/// Comma, semicolon or tab, whichever is most frequent in the first line
+pub fn detect_delimiter(text: &str) -> char {
+    let first = text.lines().next().unwrap_or_default();
+    [',', ';', '\t']
+        .into_iter()
+        .max_by_key(|d| (first.matches(*d).count(), *d == ','))
+        .unwrap_or(',')
+}
+
+/// RFC 4180 rows with quoted fields and doubled quotes, blank lines skipped
+pub fn parse(text: &str) -> Result<Vec<CsvRecord>, CsvError> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let delimiter = detect_delimiter(text);
+    let mut records = vec![];
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if field.is_empty() => in_quotes = true,
+            '\n' if in_quotes => {
+                line += 1;
+                field.push(c);
+            }
+            '\r' if !in_quotes => (),
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                let row = std::mem::take(&mut fields);
+                if row.iter().any(|f| !f.is_empty()) {
+                    records.push(CsvRecord { line: record_line, fields: row });
+                }
+                line += 1;
+                record_line = line;
+            }
+            c if c == delimiter && !in_quotes => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(CsvError::UnclosedQuote(record_line));
+    }
+    fields.push(field);
+    if fields.iter().any(|f| !f.is_empty()) {
+        records.push(CsvRecord { line: record_line, fields });
+    }
+    Ok(records)
+}
+
+/// Field quoted when it holds a delimiter, quote or line break
+pub fn escape(field: &str) -> String {
+    if field.contains([',', ';', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+pub fn write_row<S: AsRef<str>>(out: &mut String, fields: &[S]) {
+    let row: Vec<String> = fields.iter().map(|f| escape(f.as_ref())).collect();
+    out.push_str(&row.join(","));
+    out.push('\n');
+}
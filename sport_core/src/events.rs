@@ -3,7 +3,6 @@ use std::{time::Duration, collections::VecDeque};
 
 use crossterm;
 use thiserror::Error;
-use log::error;
 
 pub struct Events<T> {
     events: RefCell<VecDeque<T>>,
@@ -0,0 +1,180 @@
+use std::{collections::HashSet, fs, io, path::Path};
+
+use sport_core_db::{
+    entity::{Exercise, MuscleGroup},
+    model::{Db, Table},
+};
+
+use crate::{
+    controller::Controller,
+    csv::{self, CsvError, CsvRecord},
+};
+
+pub const FILE_NAME: &str = "exercises.csv";
+const HEADER: [&str; 3] = ["name", "description", "muscle_groups"];
+
+/// All exercises by name with the muscle groups in one field
+pub fn export(db: &Db) -> String {
+    let mut exercises: Vec<&Exercise> = db.exercises().get_all().collect();
+    exercises.sort_by_key(|e| e.name.to_lowercase());
+    let mut out = String::new();
+    csv::write_row(&mut out, &HEADER);
+    for e in exercises {
+        csv::write_row(&mut out, &[e.name.as_str(), e.description.as_str(), &MuscleGroup::format_list(&e.muscle_groups)]);
+    }
+    out
+}
+
+pub fn export_to(db: &Db, path: &Path) -> io::Result<()> {
+    fs::write(path, export(db))
+}
+
+/// Exercise field filled from a column
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExerciseField {
+    Name,
+    Description,
+    MuscleGroups,
+    Skip,
+}
+
+impl ExerciseField {
+    pub const ALL: [ExerciseField; 4] = [
+        ExerciseField::Name,
+        ExerciseField::Description,
+        ExerciseField::MuscleGroups,
+        ExerciseField::Skip,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExerciseField::Name => "Name",
+            ExerciseField::Description => "Description",
+            ExerciseField::MuscleGroups => "Muscle groups",
+            ExerciseField::Skip => "Skip",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|f| f == self).unwrap_or_default();
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Field of a header name like `Exercise`, `Notes` or `Muscles`
+    fn guess(header: &str) -> Self {
+        let header = header.trim().to_lowercase();
+        if ["name", "exercise", "title"].iter().any(|h| header.contains(h)) {
+            ExerciseField::Name
+        } else if ["desc", "note", "comment"].iter().any(|h| header.contains(h)) {
+            ExerciseField::Description
+        } else if ["muscle", "group", "target"].iter().any(|h| header.contains(h)) {
+            ExerciseField::MuscleGroups
+        } else {
+            ExerciseField::Skip
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RowStatus {
+    New,
+    /// Name already in the database or earlier in the file
+    Duplicate,
+    Invalid(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct ImportRow {
+    pub line: usize,
+    pub exercise: Exercise,
+    pub status: RowStatus,
+}
+
+/// Outcome of one row, `Ok` when the exercise was created
+#[derive(Clone, Debug)]
+pub struct RowReport {
+    pub line: usize,
+    pub name: String,
+    pub result: Result<(), String>,
+}
+
+/// Rows of a CSV file with the column to field mapping
+#[derive(Clone, Debug)]
+pub struct ExerciseImport {
+    pub header: Vec<String>,
+    /// Field of each header column
+    pub mapping: Vec<ExerciseField>,
+    records: Vec<CsvRecord>,
+}
+
+impl ExerciseImport {
+    /// Mapping guessed from the header, each field taken by its first column only
+    pub fn read(text: &str) -> Result<Self, CsvError> {
+        let mut records = csv::parse(text)?.into_iter();
+        let header = records.next().ok_or(CsvError::Empty)?.fields;
+        let mut mapping: Vec<ExerciseField> = vec![];
+        for column in &header {
+            let field = ExerciseField::guess(column);
+            mapping.push(if mapping.contains(&field) { ExerciseField::Skip } else { field });
+        }
+        Ok(Self { header, mapping, records: records.collect() })
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::read(&text).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    fn value(&self, record: &CsvRecord, field: ExerciseField) -> String {
+        self.mapping
+            .iter()
+            .position(|f| *f == field)
+            .map(|i| record.get(i).trim().to_owned())
+            .unwrap_or_default()
+    }
+
+    /// Rows checked against the exercises in `db`
+    pub fn rows(&self, db: &Db) -> Vec<ImportRow> {
+        let mut names: HashSet<String> = db.exercises().get_all().map(|e| e.name.trim().to_lowercase()).collect();
+        self.records
+            .iter()
+            .map(|record| {
+                let name = self.value(record, ExerciseField::Name);
+                let groups = self.value(record, ExerciseField::MuscleGroups).replace(';', ",");
+                let mut exercise = Exercise::new(0, name.clone(), self.value(record, ExerciseField::Description));
+                let status = match MuscleGroup::parse_list(&groups) {
+                    _ if name.is_empty() => RowStatus::Invalid("Empty name".to_owned()),
+                    Err(err) => RowStatus::Invalid(err),
+                    Ok(_) if !names.insert(name.to_lowercase()) => RowStatus::Duplicate,
+                    Ok(groups) => {
+                        exercise.muscle_groups = groups;
+                        RowStatus::New
+                    }
+                };
+                ImportRow { line: record.line, exercise, status }
+            })
+            .collect()
+    }
+}
+
+/// Creates the new exercises of `rows`, reports skipped and failed ones
+pub fn import(controller: &Controller, rows: &[ImportRow]) -> Vec<RowReport> {
+    rows.iter()
+        .map(|row| {
+            let result = match &row.status {
+                RowStatus::New => controller.exercises().insert(&row.exercise).map(|_| ()).map_err(|err| err.to_string()),
+                RowStatus::Duplicate => Err("Skipped, already exists".to_owned()),
+                RowStatus::Invalid(err) => Err(format!("Skipped, {}", err)),
+            };
+            RowReport { line: row.line, name: row.exercise.name.clone(), result }
+        })
+        .collect()
+}
@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use sport_core_db::units::{Distance, Duration};
+use thiserror::Error;
+
+use crate::tracks::{Lap, Track, TrackFormat, TrackPoint};
+
+/// Seconds between the unix epoch and the FIT one, 1989-12-31 00:00 UTC
+const FIT_EPOCH: i64 = 631_065_600;
+
+const MESG_SESSION: u16 = 18;
+const MESG_LAP: u16 = 19;
+const MESG_RECORD: u16 = 20;
+
+const FIELD_TIMESTAMP: u8 = 253;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum FitError {
+    #[error("Not a FIT file")]
+    Header,
+    #[error("File ends in the middle of a message at byte {0}")]
+    Truncated(usize),
+    #[error("Data message of the undefined local type {0}")]
+    UndefinedMessage(u8),
+    #[error("Checksum mismatch, the file is damaged")]
+    Crc,
+}
+
+/// CRC-16 as specified by the FIT protocol
+fn crc(data: &[u8]) -> u16 {
+    const TABLE: [u16; 16] = [
+        0x0000, 0xCC01, 0xD801, 0x1400, 0xF001, 0x3C00, 0x2800, 0xE401,
+        0xA001, 0x6C00, 0x7800, 0xB401, 0x5000, 0x9C01, 0x8801, 0x4400,
+    ];
+    data.iter().fold(0, |crc, byte| {
+        let crc = (crc >> 4) & 0x0FFF ^ TABLE[(crc & 0xF) as usize] ^ TABLE[(byte & 0xF) as usize];
+        (crc >> 4) & 0x0FFF ^ TABLE[(crc & 0xF) as usize] ^ TABLE[((byte >> 4) & 0xF) as usize]
+    })
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], FitError> {
+        let bytes = self.data.get(self.pos..self.pos + n).ok_or(FitError::Truncated(self.pos))?;
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8, FitError> {
+        Ok(self.take(1)?[0])
+    }
+}
+
+#[derive(Clone, Copy)]
+struct FieldDefinition {
+    number: u8,
+    size: usize,
+    base_type: u8,
+}
+
+struct Definition {
+    global: u16,
+    big_endian: bool,
+    fields: Vec<FieldDefinition>,
+    /// Developer fields are skipped, only their total size matters
+    developer_size: usize,
+}
+
+/// Unsigned or signed integer of a field, `None` for the invalid marker of its base type
+fn value(bytes: &[u8], field: FieldDefinition, big_endian: bool) -> Option<i64> {
+    let mut raw = [0u8; 8];
+    let n = field.size.min(8);
+    if big_endian {
+        raw[8 - n..].copy_from_slice(&bytes[..n]);
+        raw.reverse();
+    } else {
+        raw[..n].copy_from_slice(&bytes[..n]);
+    }
+    let unsigned = u64::from_le_bytes(raw);
+    let value = match (field.base_type & 0x1F, n) {
+        // enum, uint8, uint8z, byte
+        (0x00 | 0x02 | 0x0A | 0x0D, 1) => (unsigned != 0xFF).then_some(unsigned as i64),
+        (0x01, 1) => (unsigned != 0x7F).then_some(unsigned as u8 as i8 as i64),
+        (0x03, 2) => (unsigned != 0x7FFF).then_some(unsigned as u16 as i16 as i64),
+        (0x04 | 0x0B, 2) => (unsigned != 0xFFFF).then_some(unsigned as i64),
+        (0x05, 4) => (unsigned != 0x7FFF_FFFF).then_some(unsigned as u32 as i32 as i64),
+        (0x06 | 0x0C, 4) => (unsigned != 0xFFFF_FFFF).then_some(unsigned as i64),
+        // strings, floats and arrays are not read
+        _ => None,
+    };
+    // the `z` types mark invalid values with zero
+    value.filter(|v| !(matches!(field.base_type & 0x1F, 0x0A..=0x0C) && *v == 0))
+}
+
+fn time(fit_seconds: i64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(fit_seconds + FIT_EPOCH, 0)
+}
+
+/// Degrees of a position in semicircles
+fn degrees(semicircles: i64) -> f64 {
+    semicircles as f64 * 180.0 / 2f64.powi(31)
+}
+
+/// Sport names of the FIT profile the tracks share with GPX and TCX
+fn sport(value: i64) -> &'static str {
+    match value {
+        1 => "running",
+        2 => "cycling",
+        5 => "swimming",
+        11 => "walking",
+        17 => "hiking",
+        _ => "",
+    }
+}
+
+/// Record, lap and session messages of an activity file, others are skipped
+pub fn decode(data: &[u8]) -> Result<Track, FitError> {
+    let header_size = *data.first().ok_or(FitError::Header)? as usize;
+    if header_size < 12 || data.get(8..12) != Some(b".FIT".as_slice()) {
+        return Err(FitError::Header);
+    }
+    let data_size = u32::from_le_bytes(data[4..8].try_into().unwrap_or_default()) as usize;
+    let end = header_size + data_size;
+    let file = data.get(..end + 2).ok_or(FitError::Truncated(data.len()))?;
+    if crc(file) != 0 {
+        return Err(FitError::Crc);
+    }
+
+    let mut reader = Reader { data: &file[..end], pos: header_size };
+    let mut definitions: HashMap<u8, Definition> = HashMap::new();
+    let mut last_timestamp: i64 = 0;
+    let mut track = Track { format: TrackFormat::Fit, name: String::new(), sport: String::new(), ascent: None, laps: vec![], points: vec![] };
+    let mut session: Option<Lap> = None;
+
+    while reader.pos < end {
+        let header = reader.byte()?;
+        // compressed timestamp headers carry the low five bits of the time
+        let (local, compressed_offset) = if header & 0x80 != 0 {
+            ((header >> 5) & 0x03, Some((header & 0x1F) as i64))
+        } else {
+            (header & 0x0F, None)
+        };
+
+        if compressed_offset.is_none() && header & 0x40 != 0 {
+            reader.byte()?;
+            let big_endian = reader.byte()? == 1;
+            let global = reader.take(2)?;
+            let global = if big_endian { u16::from_be_bytes([global[0], global[1]]) } else { u16::from_le_bytes([global[0], global[1]]) };
+            let count = reader.byte()?;
+            let mut fields = Vec::with_capacity(count as usize);
+            for _ in 0..count {
+                let field = reader.take(3)?;
+                fields.push(FieldDefinition { number: field[0], size: field[1] as usize, base_type: field[2] });
+            }
+            let mut developer_size = 0;
+            if header & 0x20 != 0 {
+                let count = reader.byte()?;
+                for _ in 0..count {
+                    developer_size += reader.take(3)?[1] as usize;
+                }
+            }
+            definitions.insert(local, Definition { global, big_endian, fields, developer_size });
+            continue;
+        }
+
+        let definition = definitions.get(&local).ok_or(FitError::UndefinedMessage(local))?;
+        let mut values: HashMap<u8, i64> = HashMap::new();
+        for field in &definition.fields {
+            let bytes = reader.take(field.size)?;
+            if let Some(v) = value(bytes, *field, definition.big_endian) {
+                values.insert(field.number, v);
+            }
+        }
+        reader.take(definition.developer_size)?;
+
+        if let Some(offset) = compressed_offset {
+            let mut timestamp = (last_timestamp & !0x1F) | offset;
+            if offset < last_timestamp & 0x1F {
+                timestamp += 0x20;
+            }
+            values.insert(FIELD_TIMESTAMP, timestamp);
+        }
+        if let Some(timestamp) = values.get(&FIELD_TIMESTAMP) {
+            last_timestamp = *timestamp;
+        }
+
+        let get = |number: u8| values.get(&number).copied();
+        match definition.global {
+            MESG_RECORD => track.points.push(TrackPoint {
+                time: get(FIELD_TIMESTAMP).and_then(time),
+                position: get(0).zip(get(1)).map(|(lat, lon)| (degrees(lat), degrees(lon))),
+                // enhanced altitude first, both scaled by 5 with a 500 m offset
+                elevation: get(78).or(get(2)).map(|a| a as f64 / 5.0 - 500.0),
+                distance: get(5).map(|d| d as f64 / 100.0),
+                heart_rate: get(3).map(|hr| hr as u32),
+            }),
+            MESG_LAP | MESG_SESSION => {
+                let lap = Lap {
+                    started_at: get(2).and_then(time),
+                    duration: Duration::from_seconds(get(8).unwrap_or_default() as f64 / 1000.0),
+                    distance: Distance::from_meters(get(9).unwrap_or_default() as f64 / 100.0),
+                };
+                if definition.global == MESG_LAP {
+                    track.laps.push(lap);
+                } else {
+                    session = Some(lap);
+                    track.sport = get(5).map(sport).unwrap_or_default().to_owned();
+                    track.ascent = get(22).map(|a| a as f64);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    // some devices write the session totals only
+    if track.laps.is_empty() {
+        track.laps.extend(session);
+    }
+    Ok(track)
+}
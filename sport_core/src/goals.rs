@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDate};
+use sport_core_db::{
+    entity::{Goal, GoalMetric, ID},
+    model::{Db, Table},
+};
+
+use crate::analytics::{self, OneRepMaxFormula, Period};
+
+const DAYS_PER_WEEK: i64 = 7;
+/// Trends flatter than this, in value per day, are rounding noise of constant values
+const MIN_SLOPE: f64 = 1e-9;
+
+/// Value of a goal metric at a date
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MetricPoint {
+    pub date: NaiveDate,
+    pub value: f64,
+}
+
+impl MetricPoint {
+    pub fn new(date: NaiveDate, value: f64) -> Self { Self { date, value } }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoalProgress {
+    /// Metric value when the goal was started
+    pub start: f64,
+    pub current: f64,
+    pub target: f64,
+    /// Completed part of the goal in percents, `0..=100`
+    pub percent: f64,
+    /// Date the target is reached if the current trend continues
+    pub projected: Option<NaiveDate>,
+}
+
+impl GoalProgress {
+    pub fn is_reached(&self) -> bool {
+        self.percent >= 100.0
+    }
+}
+
+/// Sums the values of the dated entries in 7 day windows ending at `today`, oldest first
+fn weekly_points(values: impl Iterator<Item = (NaiveDate, f64)>, since: NaiveDate, today: NaiveDate) -> Vec<MetricPoint> {
+    let mut sums = vec![0.0];
+    while today - Duration::days(DAYS_PER_WEEK * sums.len() as i64) >= since {
+        sums.push(0.0);
+    }
+    for (date, value) in values {
+        let days = (today - date).num_days();
+        if days < 0 {
+            continue;
+        }
+        if let Some(sum) = sums.get_mut((days / DAYS_PER_WEEK) as usize) {
+            *sum += value;
+        }
+    }
+    sums.iter()
+        .enumerate()
+        .rev()
+        .map(|(i, sum)| MetricPoint::new(today - Duration::days(DAYS_PER_WEEK * i as i64), *sum))
+        .collect()
+}
+
+/// History of a metric of a person, oldest first
+///
+/// Weekly metrics are bucketed in 7 day windows from `since` to `today`,
+/// other metrics have a point per workout or measurement.
+pub fn metric_history(db: &Db, person_id: ID, metric: &GoalMetric, since: NaiveDate, today: NaiveDate, formula: OneRepMaxFormula) -> Vec<MetricPoint> {
+    let workouts: HashMap<ID, NaiveDate> = db.workouts()
+        .get_all()
+        .filter(|w| w.person_id == person_id)
+        .map(|w| (w.id, w.date()))
+        .collect();
+    match metric {
+        GoalMetric::OneRepMax { exercise_id } => {
+            analytics::one_rep_max_history(db, person_id, *exercise_id, Period::until(today), formula)
+        }
+        GoalMetric::BodyWeight => analytics::body_weight_history(db, person_id, Period::until(today)),
+        GoalMetric::WeeklyVolume { exercise_id } => {
+            let tonnage = db.sets()
+                .get_all()
+                .filter(|s| exercise_id.is_none_or(|id| s.exercise_id == id))
+                .filter_map(|s| workouts.get(&s.workout_id).map(|date| (*date, s.tonnage().kilograms())));
+            weekly_points(tonnage, since, today)
+        }
+        GoalMetric::WeeklySessions => weekly_points(workouts.values().map(|date| (*date, 1.0)), since, today),
+    }
+}
+
+/// Least squares slope of the points in value per day
+fn trend_slope(points: &[MetricPoint]) -> Option<f64> {
+    if points.len() < 2 {
+        return None;
+    }
+    let origin = points[0].date;
+    let xs: Vec<f64> = points.iter().map(|p| (p.date - origin).num_days() as f64).collect();
+    let n = points.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.value).sum::<f64>() / n;
+    let (mut num, mut den) = (0.0, 0.0);
+    for (x, p) in xs.iter().zip(points) {
+        num += (x - mean_x) * (p.value - mean_y);
+        den += (x - mean_x) * (x - mean_x);
+    }
+    if den == 0.0 {
+        return None;
+    }
+    Some(num / den)
+}
+
+/// Progress of the goal computed from the logged data up to `today`
+pub fn progress(db: &Db, goal: &Goal, today: NaiveDate, formula: OneRepMaxFormula) -> GoalProgress {
+    let history = metric_history(db, goal.person_id, &goal.metric, goal.start_date, today, formula);
+    let (start, trend): (f64, Vec<MetricPoint>) = match goal.metric {
+        GoalMetric::WeeklyVolume { .. } | GoalMetric::WeeklySessions => (0.0, history),
+        GoalMetric::OneRepMax { .. } | GoalMetric::BodyWeight => {
+            let baseline = history.iter()
+                .rev()
+                .find(|p| p.date <= goal.start_date)
+                .or_else(|| history.first())
+                .map_or(0.0, |p| p.value);
+            let trend = history.into_iter().filter(|p| p.date >= goal.start_date).collect();
+            (baseline, trend)
+        }
+    };
+    let current = trend.last().map_or(start, |p| p.value);
+    let target = goal.target;
+
+    let percent = if target == start {
+        100.0
+    } else {
+        ((current - start) / (target - start) * 100.0).clamp(0.0, 100.0)
+    };
+    let projected = if percent >= 100.0 {
+        None
+    } else {
+        trend_slope(&trend)
+            .filter(|slope| slope.abs() >= MIN_SLOPE && slope.signum() == (target - current).signum())
+            .and_then(|slope| Duration::try_days(((target - current) / slope).ceil() as i64))
+            .and_then(|days| today.checked_add_signed(days))
+    };
+
+    GoalProgress { start, current, target, percent, projected }
+}
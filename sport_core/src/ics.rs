@@ -0,0 +1,365 @@
+use std::{fs, io, path::{Path, PathBuf}};
+
+use chrono::{DateTime, Datelike, Duration as Days, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday};
+use serde_derive::{Deserialize, Serialize};
+use sport_core_db::{
+    entity::{Routine, Workout, ID},
+    model::{Db, Table},
+    units::Duration,
+};
+use thiserror::Error;
+
+pub const FILE_NAME: &str = "sport_helper-plan.ics";
+const PRODID: &str = "-//sport_helper//training plan//EN";
+const DATE_TIME: &str = "%Y%m%dT%H%M%S";
+const UTC_DATE_TIME: &str = "%Y%m%dT%H%M%SZ";
+/// Longest content line in octets before folding
+const LINE_LIMIT: usize = 75;
+
+const WEEKDAYS: [(Weekday, &str); 7] = [
+    (Weekday::Mon, "MO"),
+    (Weekday::Tue, "TU"),
+    (Weekday::Wed, "WE"),
+    (Weekday::Thu, "TH"),
+    (Weekday::Fri, "FR"),
+    (Weekday::Sat, "SA"),
+    (Weekday::Sun, "SU"),
+];
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum IcsError {
+    #[error("Missing BEGIN:VCALENDAR")]
+    NotCalendar,
+    #[error("Line {0}: not a `NAME:value` property")]
+    Property(usize),
+    #[error("Event without {0}")]
+    MissingProperty(&'static str),
+    #[error("Invalid {name} `{value}`")]
+    Value { name: &'static str, value: String },
+}
+
+/// When the routines are put into the calendar
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct CalendarConfig {
+    /// Training days, the routines take turns on them
+    pub days: Vec<Weekday>,
+    pub start_time: NaiveTime,
+    pub session_minutes: u32,
+    /// Weeks the plan repeats for
+    pub weeks: u32,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            days: vec![Weekday::Mon, Weekday::Wed, Weekday::Fri],
+            start_time: NaiveTime::from_hms_opt(18, 0, 0).unwrap_or_default(),
+            session_minutes: 60,
+            weeks: 12,
+        }
+    }
+}
+
+/// Weekly repetition of an event
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Recurrence {
+    pub days: Vec<Weekday>,
+    /// Occurrences in total
+    pub count: u32,
+}
+
+/// Calendar event of a planned training session
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlannedSession {
+    pub uid: String,
+    pub title: String,
+    /// Floating local time, shown as is in every time zone
+    pub start: NaiveDateTime,
+    pub duration: Duration,
+    pub description: String,
+    pub recurrence: Option<Recurrence>,
+}
+
+fn exercise_name(db: &Db, id: ID) -> String {
+    db.exercises().get_one(id).map(|e| e.name.clone()).unwrap_or_else(|| format!("Exercise {}", id))
+}
+
+/// One line per exercise with the sets and the target reps
+pub fn routine_description(db: &Db, routine: &Routine) -> String {
+    routine.exercises
+        .iter()
+        .map(|e| {
+            let reps = if e.min_reps == e.max_reps { e.min_reps.to_string() } else { format!("{}-{}", e.min_reps, e.max_reps) };
+            format!("{}: {} x {}", exercise_name(db, e.exercise_id), e.sets, reps)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Exercises of the sets in their order with the count of sets, then the notes
+fn workout_description(db: &Db, workout: &Workout) -> String {
+    let mut sets: Vec<(u32, ID)> = db.sets().get_all().filter(|s| s.workout_id == workout.id).map(|s| (s.order, s.exercise_id)).collect();
+    sets.sort_unstable();
+    let mut exercises: Vec<(ID, u32)> = vec![];
+    for (_, exercise_id) in sets {
+        match exercises.last_mut() {
+            Some((id, count)) if *id == exercise_id => *count += 1,
+            _ => exercises.push((exercise_id, 1)),
+        }
+    }
+    let mut lines: Vec<String> = exercises.into_iter().map(|(id, count)| format!("{}: {} sets", exercise_name(db, id), count)).collect();
+    if !workout.notes.is_empty() {
+        lines.push(workout.notes.clone());
+    }
+    lines.join("\n")
+}
+
+/// Workouts of the person dated after `now` once, then the routines repeating weekly from their first day after it
+pub fn planned_sessions(db: &Db, person_id: ID, cfg: &CalendarConfig, now: NaiveDateTime) -> Vec<PlannedSession> {
+    let default_duration = Duration::from_minutes(cfg.session_minutes as f64);
+    let mut workouts: Vec<&Workout> = db.workouts().get_all().filter(|w| w.person_id == person_id && w.started_at > now).collect();
+    workouts.sort_by_key(|w| w.started_at);
+    let mut sessions: Vec<PlannedSession> = workouts
+        .into_iter()
+        .map(|w| PlannedSession {
+            uid: format!("workout-{}@sport_helper", w.id),
+            title: w.name.clone(),
+            start: w.started_at,
+            duration: if w.duration.is_zero() { default_duration } else { w.duration },
+            description: workout_description(db, w),
+            recurrence: None,
+        })
+        .collect();
+
+    let mut routines: Vec<&Routine> = db.routines().get_all().filter(|r| r.person_id == person_id).collect();
+    routines.sort_by(|a, b| a.name.cmp(&b.name));
+    let mut days = cfg.days.clone();
+    days.sort_by_key(|d| d.num_days_from_monday());
+    days.dedup();
+    // routines beyond the count of training days are left out
+    for (i, routine) in routines.iter().enumerate() {
+        let routine_days: Vec<Weekday> = days.iter().copied().skip(i).step_by(routines.len()).collect();
+        // starts on the first of its days after now
+        let Some(start) = (0..7)
+            .map(|offset| now.date() + Days::days(offset))
+            .map(|date| date.and_time(cfg.start_time))
+            .find(|start| *start > now && routine_days.contains(&start.weekday()))
+        else {
+            continue;
+        };
+        sessions.push(PlannedSession {
+            uid: format!("routine-{}@sport_helper", routine.id),
+            title: routine.name.clone(),
+            start,
+            duration: default_duration,
+            description: routine_description(db, routine),
+            recurrence: Some(Recurrence { count: routine_days.len() as u32 * cfg.weeks, days: routine_days }),
+        });
+    }
+    sessions
+}
+
+/// Backslash, semicolon, comma and new line escaped as for TEXT values
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => (),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(c) => out.push(c),
+            None => (),
+        }
+    }
+    out
+}
+
+/// Content line ended with CRLF, folded at 75 octets without splitting characters
+fn write_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > LINE_LIMIT {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+/// `PT1H30M` style duration, whole seconds
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.seconds().round() as u64;
+    let mut out = "PT".to_owned();
+    for (value, unit) in [(seconds / 3600, 'H'), (seconds / 60 % 60, 'M'), (seconds % 60, 'S')] {
+        if value > 0 {
+            out.push_str(&format!("{}{}", value, unit));
+        }
+    }
+    if out.len() == 2 {
+        out.push_str("0S");
+    }
+    out
+}
+
+fn parse_duration(value: &str) -> Option<Duration> {
+    let mut seconds = 0.0;
+    let mut number = String::new();
+    for c in value.strip_prefix('P')?.chars() {
+        match c {
+            'T' => (),
+            '0'..='9' => number.push(c),
+            _ => {
+                let n: f64 = number.parse().ok()?;
+                seconds += n * match c {
+                    'W' => 604_800.0,
+                    'D' => 86_400.0,
+                    'H' => 3600.0,
+                    'M' => 60.0,
+                    'S' => 1.0,
+                    _ => return None,
+                };
+                number.clear();
+            }
+        }
+    }
+    number.is_empty().then(|| Duration::from_seconds(seconds))
+}
+
+fn format_recurrence(recurrence: &Recurrence) -> String {
+    let days: Vec<&str> = WEEKDAYS.iter().filter(|(d, _)| recurrence.days.contains(d)).map(|(_, code)| *code).collect();
+    format!("FREQ=WEEKLY;BYDAY={};COUNT={}", days.join(","), recurrence.count)
+}
+
+fn parse_recurrence(value: &str) -> Option<Recurrence> {
+    let mut recurrence = Recurrence { days: vec![], count: 0 };
+    for part in value.split(';') {
+        match part.split_once('=')? {
+            ("FREQ", freq) if freq != "WEEKLY" => return None,
+            ("BYDAY", days) => {
+                recurrence.days = days
+                    .split(',')
+                    .map(|day| WEEKDAYS.iter().find(|(_, code)| *code == day).map(|(d, _)| *d))
+                    .collect::<Option<_>>()?;
+            }
+            ("COUNT", count) => recurrence.count = count.parse().ok()?,
+            _ => (),
+        }
+    }
+    Some(recurrence)
+}
+
+/// VCALENDAR of the sessions, `stamp` marks when it was written and is the only time in UTC
+pub fn export(sessions: &[PlannedSession], stamp: DateTime<Utc>) -> String {
+    let mut out = String::new();
+    for line in ["BEGIN:VCALENDAR", "VERSION:2.0", &format!("PRODID:{}", PRODID), "CALSCALE:GREGORIAN"] {
+        write_line(&mut out, line);
+    }
+    for session in sessions {
+        write_line(&mut out, "BEGIN:VEVENT");
+        write_line(&mut out, &format!("UID:{}", session.uid));
+        write_line(&mut out, &format!("DTSTAMP:{}", stamp.format(UTC_DATE_TIME)));
+        write_line(&mut out, &format!("DTSTART:{}", session.start.format(DATE_TIME)));
+        write_line(&mut out, &format!("DURATION:{}", format_duration(session.duration)));
+        write_line(&mut out, &format!("SUMMARY:{}", escape(&session.title)));
+        if !session.description.is_empty() {
+            write_line(&mut out, &format!("DESCRIPTION:{}", escape(&session.description)));
+        }
+        if let Some(recurrence) = &session.recurrence {
+            write_line(&mut out, &format!("RRULE:{}", format_recurrence(recurrence)));
+        }
+        write_line(&mut out, "END:VEVENT");
+    }
+    write_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+/// Writes the calendar into `dir` and returns its path
+pub fn export_to(sessions: &[PlannedSession], stamp: DateTime<Utc>, dir: &Path) -> io::Result<PathBuf> {
+    let path = dir.join(FILE_NAME);
+    fs::write(&path, export(sessions, stamp))?;
+    Ok(path)
+}
+
+/// Events of a calendar as written by `export`, unknown properties and components are skipped
+pub fn parse(text: &str) -> Result<Vec<PlannedSession>, IcsError> {
+    // unfolding joins the lines starting with a space or a tab to the previous one
+    let mut lines: Vec<(usize, String)> = vec![];
+    for (i, line) in text.split('\n').enumerate() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some((_, last))) => last.push_str(rest),
+            _ if line.is_empty() => (),
+            _ => lines.push((i + 1, line.to_owned())),
+        }
+    }
+    if lines.first().map(|(_, l)| l.as_str()) != Some("BEGIN:VCALENDAR") {
+        return Err(IcsError::NotCalendar);
+    }
+
+    let mut sessions = vec![];
+    let mut event: Option<Vec<(String, String)>> = None;
+    for (number, line) in lines {
+        let (name, value) = line.split_once(':').ok_or(IcsError::Property(number))?;
+        // parameters like `DTSTART;TZID=...` are ignored
+        let name = name.split(';').next().unwrap_or_default().to_uppercase();
+        match (name.as_str(), value, &mut event) {
+            ("BEGIN", "VEVENT", _) => event = Some(vec![]),
+            ("END", "VEVENT", Some(_)) => sessions.push(parse_event(event.take().unwrap_or_default())?),
+            (_, _, Some(properties)) => properties.push((name, value.to_owned())),
+            _ => (),
+        }
+    }
+    Ok(sessions)
+}
+
+fn parse_event(properties: Vec<(String, String)>) -> Result<PlannedSession, IcsError> {
+    let get = |name: &'static str| properties.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str());
+    let required = |name: &'static str| get(name).ok_or(IcsError::MissingProperty(name));
+    let invalid = |name: &'static str, value: &str| IcsError::Value { name, value: value.to_owned() };
+
+    let start = required("DTSTART")?;
+    let start = NaiveDateTime::parse_from_str(start.trim_end_matches('Z'), DATE_TIME)
+        .or_else(|_| NaiveDate::parse_from_str(start, "%Y%m%d").map(|d| d.and_time(NaiveTime::MIN)))
+        .map_err(|_| invalid("DTSTART", start))?;
+    let duration = match (get("DURATION"), get("DTEND")) {
+        (Some(duration), _) => parse_duration(duration).ok_or_else(|| invalid("DURATION", duration))?,
+        (None, Some(end)) => {
+            let end = NaiveDateTime::parse_from_str(end.trim_end_matches('Z'), DATE_TIME).map_err(|_| invalid("DTEND", end))?;
+            Duration::from_seconds((end - start).num_seconds() as f64)
+        }
+        (None, None) => Duration::default(),
+    };
+    let recurrence = match get("RRULE") {
+        Some(rule) => Some(parse_recurrence(rule).ok_or_else(|| invalid("RRULE", rule))?),
+        None => None,
+    };
+    Ok(PlannedSession {
+        uid: required("UID")?.to_owned(),
+        title: unescape(get("SUMMARY").unwrap_or_default()),
+        start,
+        duration,
+        description: unescape(get("DESCRIPTION").unwrap_or_default()),
+        recurrence,
+    })
+}
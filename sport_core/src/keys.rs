@@ -0,0 +1,531 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{de, ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum KeyMapError {
+    #[error("Unknown action `{0}`")]
+    UnknownAction(String),
+    #[error("Invalid key `{key}` of {action}: {err}")]
+    Key { action: Action, key: String, err: String },
+    #[error("`{key}` is bound to both {first} and {second} on the {context} keys")]
+    Conflict { key: KeyBinding, first: Action, second: Action, context: Context },
+}
+
+/// Commands bound to keys, named as in the `[keys]` section of the config
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Action {
+    Quit,
+    Profile,
+    Accept,
+    Reject,
+    Close,
+    Add,
+    Delete,
+    Update,
+    Reload,
+    Import,
+    ExportCsv,
+    AddSet,
+    QuickEntry,
+    ImportTrack,
+    Timer,
+    AddExercise,
+    RemoveExercise,
+    Calendar,
+    Today,
+    Range,
+    Weekly,
+    Monthly,
+    Markdown,
+    Html,
+    BmrFormula,
+    ActivityLevel,
+    Backup,
+    Restore,
+    Pause,
+    Skip,
+    StopTimer,
+    Plates,
+    NextMapping,
+    PreviousSeries,
+    NextSeries,
+    PreviousExercise,
+    NextExercise,
+    PreviousPeriod,
+    NextPeriod,
+    ExercisesPage,
+    WorkoutsPage,
+    RoutinesPage,
+    HistoryPage,
+    ProgressPage,
+    BalancePage,
+    ReportsPage,
+    AccountPage,
+}
+
+impl Action {
+    pub const ALL: [Action; 47] = [
+        Action::Quit, Action::Profile, Action::Accept, Action::Reject, Action::Close,
+        Action::Add, Action::Delete, Action::Update, Action::Reload, Action::Import, Action::ExportCsv,
+        Action::AddSet, Action::QuickEntry, Action::ImportTrack, Action::Timer,
+        Action::AddExercise, Action::RemoveExercise, Action::Calendar,
+        Action::Today, Action::Range, Action::Weekly, Action::Monthly, Action::Markdown, Action::Html,
+        Action::BmrFormula, Action::ActivityLevel, Action::Backup, Action::Restore,
+        Action::Pause, Action::Skip, Action::StopTimer, Action::Plates, Action::NextMapping,
+        Action::PreviousSeries, Action::NextSeries, Action::PreviousExercise, Action::NextExercise,
+        Action::PreviousPeriod, Action::NextPeriod,
+        Action::ExercisesPage, Action::WorkoutsPage, Action::RoutinesPage, Action::HistoryPage,
+        Action::ProgressPage, Action::BalancePage, Action::ReportsPage, Action::AccountPage,
+    ];
+
+    /// Pages of the menu in its order
+    pub const PAGES: [Action; 8] = [
+        Action::ExercisesPage, Action::WorkoutsPage, Action::RoutinesPage, Action::HistoryPage,
+        Action::ProgressPage, Action::BalancePage, Action::ReportsPage, Action::AccountPage,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Profile => "profile",
+            Action::Accept => "accept",
+            Action::Reject => "reject",
+            Action::Close => "close",
+            Action::Add => "add",
+            Action::Delete => "delete",
+            Action::Update => "update",
+            Action::Reload => "reload",
+            Action::Import => "import",
+            Action::ExportCsv => "export_csv",
+            Action::AddSet => "add_set",
+            Action::QuickEntry => "quick_entry",
+            Action::ImportTrack => "import_track",
+            Action::Timer => "timer",
+            Action::AddExercise => "add_exercise",
+            Action::RemoveExercise => "remove_exercise",
+            Action::Calendar => "calendar",
+            Action::Today => "today",
+            Action::Range => "range",
+            Action::Weekly => "weekly",
+            Action::Monthly => "monthly",
+            Action::Markdown => "markdown",
+            Action::Html => "html",
+            Action::BmrFormula => "bmr_formula",
+            Action::ActivityLevel => "activity_level",
+            Action::Backup => "backup",
+            Action::Restore => "restore",
+            Action::Pause => "pause",
+            Action::Skip => "skip",
+            Action::StopTimer => "stop_timer",
+            Action::Plates => "plates",
+            Action::NextMapping => "next_mapping",
+            Action::PreviousSeries => "previous_series",
+            Action::NextSeries => "next_series",
+            Action::PreviousExercise => "previous_exercise",
+            Action::NextExercise => "next_exercise",
+            Action::PreviousPeriod => "previous_period",
+            Action::NextPeriod => "next_period",
+            Action::ExercisesPage => "exercises_page",
+            Action::WorkoutsPage => "workouts_page",
+            Action::RoutinesPage => "routines_page",
+            Action::HistoryPage => "history_page",
+            Action::ProgressPage => "progress_page",
+            Action::BalancePage => "balance_page",
+            Action::ReportsPage => "reports_page",
+            Action::AccountPage => "account_page",
+        }
+    }
+
+    /// Name in the help lines
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Profile => "Profile",
+            Action::Accept => "Accept",
+            Action::Reject => "Reject",
+            Action::Close => "Exit",
+            Action::Add => "Add",
+            Action::Delete => "Delete",
+            Action::Update => "Update",
+            Action::Reload => "Reload",
+            Action::Import => "Import",
+            Action::ExportCsv => "Export CSV",
+            Action::AddSet => "Add set",
+            Action::QuickEntry => "Quick entry",
+            Action::ImportTrack => "Track",
+            Action::Timer => "Timer",
+            Action::AddExercise => "Add exercise",
+            Action::RemoveExercise => "Remove last exercise",
+            Action::Calendar => "Calendar",
+            Action::Today => "Today",
+            Action::Range => "Range",
+            Action::Weekly => "Weekly",
+            Action::Monthly => "Monthly",
+            Action::Markdown => "Markdown",
+            Action::Html => "HTML",
+            Action::BmrFormula => "BMR formula",
+            Action::ActivityLevel => "Activity level",
+            Action::Backup => "Backup",
+            Action::Restore => "Restore",
+            Action::Pause => "Pause",
+            Action::Skip => "Skip",
+            Action::StopTimer => "Close",
+            Action::Plates => "Plates",
+            Action::NextMapping => "Mapping",
+            Action::PreviousSeries => "Previous series",
+            Action::NextSeries => "Next series",
+            Action::PreviousExercise => "Previous exercise",
+            Action::NextExercise => "Next exercise",
+            Action::PreviousPeriod => "Previous period",
+            Action::NextPeriod => "Next period",
+            Action::ExercisesPage => "Exercises",
+            Action::WorkoutsPage => "Workouts",
+            Action::RoutinesPage => "Routines",
+            Action::HistoryPage => "History",
+            Action::ProgressPage => "Progress",
+            Action::BalancePage => "Balance",
+            Action::ReportsPage => "Reports",
+            Action::AccountPage => "Account",
+        }
+    }
+
+    fn default_binding(&self) -> KeyBinding {
+        let code = match self {
+            Action::Quit | Action::Close => KeyCode::Char('q'),
+            Action::Profile => KeyCode::Char('p'),
+            Action::Accept => KeyCode::Enter,
+            Action::Reject | Action::StopTimer => KeyCode::Esc,
+            Action::Add => KeyCode::Char('a'),
+            Action::Delete => KeyCode::Char('d'),
+            Action::Update => KeyCode::Char('u'),
+            Action::Reload => KeyCode::Char('r'),
+            Action::Import | Action::Restore => KeyCode::Char('i'),
+            Action::ExportCsv | Action::RemoveExercise => KeyCode::Char('x'),
+            Action::AddSet => KeyCode::Char('s'),
+            Action::QuickEntry | Action::AddExercise | Action::Markdown | Action::Backup => KeyCode::Char('e'),
+            Action::ImportTrack => KeyCode::Char('g'),
+            Action::Timer | Action::Today | Action::Range => KeyCode::Char('t'),
+            Action::Calendar => KeyCode::Char('c'),
+            Action::Weekly => KeyCode::Char('w'),
+            Action::Monthly => KeyCode::Char('m'),
+            Action::Html => KeyCode::Char('h'),
+            Action::BmrFormula => KeyCode::Char('b'),
+            Action::ActivityLevel => KeyCode::Char('l'),
+            Action::Pause | Action::NextMapping => KeyCode::Char(' '),
+            Action::Skip => KeyCode::Char('>'),
+            Action::Plates => KeyCode::Insert,
+            Action::PreviousSeries | Action::PreviousPeriod => KeyCode::Left,
+            Action::NextSeries | Action::NextPeriod => KeyCode::Right,
+            Action::PreviousExercise => KeyCode::Up,
+            Action::NextExercise => KeyCode::Down,
+            Action::ExercisesPage => KeyCode::Char('1'),
+            Action::WorkoutsPage => KeyCode::Char('2'),
+            Action::RoutinesPage => KeyCode::Char('3'),
+            Action::HistoryPage => KeyCode::Char('4'),
+            Action::ProgressPage => KeyCode::Char('5'),
+            Action::BalancePage => KeyCode::Char('6'),
+            Action::ReportsPage => KeyCode::Char('7'),
+            Action::AccountPage => KeyCode::Char('8'),
+        };
+        KeyBinding { code, modifiers: KeyModifiers::NONE }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Action {
+    type Err = KeyMapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Action::ALL.into_iter().find(|a| a.name() == s).ok_or_else(|| KeyMapError::UnknownAction(s.to_owned()))
+    }
+}
+
+/// Where the keys are pressed, the actions of one context need different keys
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Context {
+    /// Everywhere out of the dialogs
+    Global,
+    /// Popups with the editors and messages
+    Dialog,
+    /// Running timer above the pages
+    Timer,
+    Exercises,
+    Workouts,
+    Routines,
+    History,
+    Progress,
+    Balance,
+    Reports,
+    Account,
+}
+
+impl Context {
+    pub const ALL: [Context; 11] = [
+        Context::Global, Context::Dialog, Context::Timer,
+        Context::Exercises, Context::Workouts, Context::Routines, Context::History,
+        Context::Progress, Context::Balance, Context::Reports, Context::Account,
+    ];
+
+    /// Actions in the order of the help line
+    pub fn actions(&self) -> &'static [Action] {
+        match self {
+            Context::Global => &[
+                Action::Profile, Action::Quit,
+                Action::ExercisesPage, Action::WorkoutsPage, Action::RoutinesPage, Action::HistoryPage,
+                Action::ProgressPage, Action::BalancePage, Action::ReportsPage, Action::AccountPage,
+            ],
+            Context::Dialog => &[Action::Close, Action::Reject, Action::Accept, Action::Plates, Action::NextMapping],
+            Context::Timer => &[Action::Pause, Action::Skip, Action::StopTimer],
+            Context::Exercises => &[Action::Add, Action::Delete, Action::Update, Action::Import, Action::ExportCsv, Action::Reload],
+            Context::Workouts => &[
+                Action::Add, Action::Delete, Action::Update, Action::AddSet, Action::QuickEntry,
+                Action::Import, Action::ImportTrack, Action::Timer, Action::Reload,
+            ],
+            Context::Routines => &[
+                Action::Add, Action::Delete, Action::Update, Action::AddExercise,
+                Action::RemoveExercise, Action::Calendar, Action::Reload,
+            ],
+            Context::History => &[Action::Today, Action::Reload],
+            Context::Progress => &[
+                Action::PreviousSeries, Action::NextSeries, Action::PreviousExercise, Action::NextExercise,
+                Action::Range, Action::Reload,
+            ],
+            Context::Balance => &[Action::Reload],
+            Context::Reports => &[
+                Action::PreviousPeriod, Action::NextPeriod,
+                Action::Weekly, Action::Monthly, Action::Today, Action::Markdown, Action::Html, Action::Reload,
+            ],
+            Context::Account => &[Action::Reload, Action::BmrFormula, Action::ActivityLevel, Action::Backup, Action::Restore],
+        }
+    }
+
+    /// Contexts whose keys work at the same time, the timer and the global keys stay above the pages
+    fn shared(&self) -> &'static [Context] {
+        match self {
+            Context::Global | Context::Dialog => &[],
+            Context::Timer => &[Context::Global],
+            _ => &[Context::Global, Context::Timer],
+        }
+    }
+}
+
+impl fmt::Display for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Context::Global => "global",
+            Context::Dialog => "dialog",
+            Context::Timer => "timer",
+            Context::Exercises => "exercises",
+            Context::Workouts => "workouts",
+            Context::Routines => "routines",
+            Context::History => "history",
+            Context::Progress => "progress",
+            Context::Balance => "balance",
+            Context::Reports => "reports",
+            Context::Account => "account",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A key with its modifiers, written like `a`, `>`, `ctrl+s`, `alt+enter` or `f5`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+const KEY_NAMES: [(&str, KeyCode); 16] = [
+    ("space", KeyCode::Char(' ')),
+    ("enter", KeyCode::Enter),
+    ("esc", KeyCode::Esc),
+    ("tab", KeyCode::Tab),
+    ("backtab", KeyCode::BackTab),
+    ("backspace", KeyCode::Backspace),
+    ("insert", KeyCode::Insert),
+    ("delete", KeyCode::Delete),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+];
+
+impl KeyBinding {
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        let mut modifiers = event.modifiers;
+        // the shift is in the case of a char already
+        if matches!(event.code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        self.code == event.code && self.modifiers == modifiers
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // `+` alone or at the end is the key itself
+        let (prefix, key) = match s.strip_suffix('+') {
+            Some(prefix) if prefix.is_empty() || prefix.ends_with('+') => (prefix.strip_suffix('+').unwrap_or(prefix), "+"),
+            _ => s.rsplit_once('+').unwrap_or(("", s)),
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in prefix.split('+').filter(|m| !m.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier `{}`, use ctrl, alt or shift", modifier)),
+            };
+        }
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            (Some(_), Some(_)) => {
+                let name = key.to_lowercase();
+                let function = name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()).filter(|n| (1..=12).contains(n));
+                match (function, name.as_str()) {
+                    (Some(n), _) => KeyCode::F(n),
+                    (None, "ins") => KeyCode::Insert,
+                    (None, "del") => KeyCode::Delete,
+                    _ => KEY_NAMES.iter().find(|(n, _)| *n == name).map(|(_, code)| *code).ok_or_else(|| format!("unknown key `{}`", key))?,
+                }
+            }
+            (None, _) => return Err("no key".to_owned()),
+        };
+        // shift+a is written as `A` by the terminal, the shift of a char is not compared
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            code => code,
+        };
+        Ok(KeyBinding { code, modifiers })
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [(KeyModifiers::CONTROL, "ctrl+"), (KeyModifiers::ALT, "alt+"), (KeyModifiers::SHIFT, "shift+")] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(name)?;
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "f{}", n),
+            code => f.write_str(KEY_NAMES.iter().find(|(_, c)| *c == code).map_or("?", |(name, _)| name)),
+        }
+    }
+}
+
+/// Keys of the actions, the defaults for the ones missing in the config
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyMap {
+    bindings: BTreeMap<Action, KeyBinding>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self { bindings: Action::ALL.iter().map(|a| (*a, a.default_binding())).collect() }
+    }
+}
+
+impl KeyMap {
+    pub fn binding(&self, action: Action) -> KeyBinding {
+        self.bindings.get(&action).copied().unwrap_or_else(|| action.default_binding())
+    }
+
+    pub fn bind(&mut self, action: Action, binding: KeyBinding) {
+        self.bindings.insert(action, binding);
+    }
+
+    /// Action of the context bound to the key
+    pub fn action(&self, context: Context, event: &KeyEvent) -> Option<Action> {
+        context.actions().iter().copied().find(|a| self.binding(*a).matches(event))
+    }
+
+    pub fn is(&self, action: Action, event: &KeyEvent) -> bool {
+        self.binding(action).matches(event)
+    }
+
+    /// Help line of the context like `Add[a] Delete[d]`, the menu shows the keys of the pages
+    pub fn help(&self, context: Context) -> String {
+        let help: Vec<String> = context.actions()
+            .iter()
+            .filter(|a| !Action::PAGES.contains(a))
+            .map(|a| format!("{}[{}]", a.label(), self.binding(*a)))
+            .collect();
+        help.join(" ")
+    }
+
+    /// Popup title commands like "Exit: [q], Accept: [enter]"
+    pub fn commands(&self, commands: &[(&str, Action)]) -> String {
+        let commands: Vec<String> = commands.iter().map(|(name, a)| format!("{}: [{}]", name, self.binding(*a))).collect();
+        commands.join(", ")
+    }
+
+    /// Two actions working at the same time can`t share a key
+    pub fn check(&self) -> Result<(), KeyMapError> {
+        for context in Context::ALL {
+            let mut bound: Vec<(KeyBinding, Action)> = vec![];
+            let actions = context.actions().iter().chain(context.shared().iter().flat_map(|c| c.actions()));
+            for action in actions {
+                let key = self.binding(*action);
+                if let Some((_, first)) = bound.iter().find(|(k, _)| *k == key) {
+                    return Err(KeyMapError::Conflict { key, first: *first, second: *action, context });
+                }
+                bound.push((key, *action));
+            }
+        }
+        Ok(())
+    }
+
+    /// Bindings of the `[keys]` section, checked for conflicts
+    pub fn from_names<'a>(names: impl IntoIterator<Item = (&'a str, &'a str)>) -> Result<Self, KeyMapError> {
+        let mut keymap = KeyMap::default();
+        for (name, key) in names {
+            let action: Action = name.parse()?;
+            let binding = key.parse().map_err(|err| KeyMapError::Key { action, key: key.to_owned(), err })?;
+            keymap.bind(action, binding);
+        }
+        keymap.check()?;
+        Ok(keymap)
+    }
+}
+
+impl Serialize for KeyMap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.bindings.len()))?;
+        for (action, binding) in &self.bindings {
+            map.serialize_entry(action.name(), &binding.to_string())?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyMap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let names = BTreeMap::<String, String>::deserialize(deserializer)?;
+        KeyMap::from_names(names.iter().map(|(n, k)| (n.as_str(), k.as_str()))).map_err(de::Error::custom)
+    }
+}
@@ -2,3 +2,22 @@ pub mod config;
 pub mod events;
 pub mod rest;
 pub mod controller;
+pub mod analytics;
+pub mod backup;
+pub mod balance;
+pub mod calculators;
+pub mod cli;
+pub mod csv;
+pub mod exercises_csv;
+pub mod fit;
+pub mod goals;
+pub mod ics;
+pub mod keys;
+pub mod plates;
+pub mod progression;
+pub mod quick_entry;
+pub mod records;
+pub mod reports;
+pub mod timer;
+pub mod tracks;
+pub mod workout_import;
@@ -0,0 +1,174 @@
+use serde_derive::{Deserialize, Serialize};
+use sport_core_db::units::{format_number, Mass, UnitSystem};
+
+/// Precision of the plate math, in kilograms
+const RESOLUTION: f64 = 0.01;
+/// Warm-up ramp as percentages of the working weight and reps, after a set with the empty bar
+const WARM_UP_STEPS: [(f64, u32); 3] = [(0.4, 5), (0.6, 3), (0.8, 2)];
+const EMPTY_BAR_REPS: u32 = 10;
+
+/// Pairs of plates of one weight
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct PlateStock {
+    pub weight: Mass,
+    pub pairs: u32,
+}
+
+impl PlateStock {
+    pub fn new(kilograms: f64, pairs: u32) -> Self {
+        Self { weight: Mass::from_kilograms(kilograms), pairs }
+    }
+}
+
+/// Bar and plates available in the gym
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PlateInventory {
+    pub bar: Mass,
+    pub plates: Vec<PlateStock>,
+}
+
+impl Default for PlateInventory {
+    fn default() -> Self {
+        Self {
+            bar: Mass::from_kilograms(20.0),
+            plates: vec![
+                PlateStock::new(25.0, 4),
+                PlateStock::new(20.0, 2),
+                PlateStock::new(15.0, 2),
+                PlateStock::new(10.0, 2),
+                PlateStock::new(5.0, 2),
+                PlateStock::new(2.5, 2),
+                PlateStock::new(1.25, 2),
+            ],
+        }
+    }
+}
+
+/// Plates on each side of the bar for a target weight
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlateLoad {
+    pub target: Mass,
+    /// Loaded weight with the bar, the nearest achievable one to the target
+    pub total: Mass,
+    /// Plate weights and counts on one side, heaviest first
+    pub per_side: Vec<(Mass, u32)>,
+}
+
+impl PlateLoad {
+    pub fn is_exact(&self) -> bool {
+        (self.total.kilograms() - self.target.kilograms()).abs() < RESOLUTION / 2.0
+    }
+
+    /// Loaded minus target weight
+    pub fn difference(&self) -> Mass {
+        self.total - self.target
+    }
+
+    /// Per-side plates in the units of `system` as `25 + 25 + 2.5`, `-` for the empty bar
+    pub fn describe(&self, system: UnitSystem) -> String {
+        if self.per_side.is_empty() {
+            return "-".to_owned();
+        }
+        self.per_side
+            .iter()
+            .flat_map(|(weight, count)| std::iter::repeat_n(weight.value_in(system), *count as usize))
+            .map(|value| format_number(value, 2))
+            .collect::<Vec<String>>()
+            .join(" + ")
+    }
+}
+
+fn to_units(weight: Mass) -> usize {
+    (weight.kilograms() / RESOLUTION).round().max(0.0) as usize
+}
+
+fn from_units(units: usize) -> Mass {
+    Mass::from_kilograms(units as f64 * RESOLUTION)
+}
+
+/// Heaviest available plates first, `None` if they don't add up to `side` exactly
+fn greedy(stock: &[PlateStock], side: usize) -> Option<Vec<(Mass, u32)>> {
+    let mut left = side;
+    let mut per_side = vec![];
+    for plate in stock {
+        let count = (left / to_units(plate.weight)).min(plate.pairs as usize);
+        if count > 0 {
+            per_side.push((plate.weight, count as u32));
+            left -= count * to_units(plate.weight);
+        }
+    }
+    (left == 0).then_some(per_side)
+}
+
+impl PlateInventory {
+    /// Fewest plates per side for the target, or for the nearest achievable weight, lighter on ties
+    pub fn load(&self, target: Mass) -> PlateLoad {
+        let mut stock: Vec<PlateStock> = self.plates.iter().filter(|p| !p.weight.is_zero() && p.pairs > 0).copied().collect();
+        stock.sort_by(|a, b| b.weight.kilograms().total_cmp(&a.weight.kilograms()));
+        let plates: Vec<usize> = stock
+            .iter()
+            .flat_map(|p| std::iter::repeat_n(to_units(p.weight), p.pairs as usize))
+            .collect();
+        let max: usize = plates.iter().sum();
+
+        // fewest plates reaching every side weight, with the last added plate for the backtracking
+        let mut best: Vec<Option<(u32, usize)>> = vec![None; max + 1];
+        best[0] = Some((0, 0));
+        for plate in plates {
+            for sum in (plate..=max).rev() {
+                if let Some((count, _)) = best[sum - plate] {
+                    if best[sum].is_none_or(|(c, _)| count + 1 < c) {
+                        best[sum] = Some((count + 1, plate));
+                    }
+                }
+            }
+        }
+
+        let side = to_units(target - self.bar) / 2;
+        let side = (0..=max)
+            .filter(|s| best[*s].is_some())
+            .min_by_key(|s| (s.abs_diff(side), *s))
+            .unwrap_or_default();
+
+        // heaviest plates first as loaded in the gym, unless it misses the weight
+        let per_side = match greedy(&stock, side) {
+            Some(per_side) if per_side.iter().map(|(_, c)| c).sum::<u32>() == best[side].map(|(c, _)| c).unwrap_or_default() => per_side,
+            _ => {
+                let mut per_side: Vec<(Mass, u32)> = vec![];
+                let mut sum = side;
+                while let Some((_, plate)) = best[sum].filter(|_| sum > 0) {
+                    match per_side.iter_mut().find(|(w, _)| to_units(*w) == plate) {
+                        Some((_, count)) => *count += 1,
+                        None => per_side.push((from_units(plate), 1)),
+                    }
+                    sum -= plate;
+                }
+                per_side.sort_by(|a, b| b.0.kilograms().total_cmp(&a.0.kilograms()));
+                per_side
+            }
+        };
+        PlateLoad { target, total: self.bar + from_units(side) * 2.0, per_side }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WarmUpSet {
+    pub reps: u32,
+    pub load: PlateLoad,
+}
+
+/// Ramp from the empty bar to the working weight, without repeated or working-weight sets
+pub fn warm_up(inventory: &PlateInventory, working: Mass) -> Vec<WarmUpSet> {
+    if working.kilograms() <= inventory.bar.kilograms() {
+        return vec![];
+    }
+    let mut sets = vec![WarmUpSet { reps: EMPTY_BAR_REPS, load: inventory.load(inventory.bar) }];
+    for (percent, reps) in WARM_UP_STEPS {
+        let load = inventory.load(working * percent);
+        let last = sets.last().map(|s| s.load.total.kilograms()).unwrap_or_default();
+        if load.total.kilograms() > last && load.total.kilograms() < working.kilograms() {
+            sets.push(WarmUpSet { reps, load });
+        }
+    }
+    sets
+}
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use sport_core_db::{
+    entity::{ProgressionScheme, RoutineExercise, Workout, WorkoutSet, ID},
+    model::{Db, Table},
+    units::Mass,
+};
+
+use crate::analytics::OneRepMaxFormula;
+
+/// Weights closer than this are the same weight, in kilograms
+const SAME_WEIGHT_EPSILON: f64 = 0.005;
+/// Rounding of the wave weights without an increment, in kilograms
+const DEFAULT_ROUNDING: f64 = 2.5;
+
+/// Logged set reduced to what the progression looks at
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SetPerformance {
+    pub reps: u32,
+    pub weight: Mass,
+}
+
+/// Sets of one exercise logged in one session
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionPerformance {
+    pub date: NaiveDate,
+    pub sets: Vec<SetPerformance>,
+}
+
+impl SessionPerformance {
+    pub fn new(date: NaiveDate, sets: Vec<SetPerformance>) -> Self { Self { date, sets } }
+
+    /// Heaviest weight of the session
+    pub fn top_weight(&self) -> Mass {
+        self.sets
+            .iter()
+            .map(|s| s.weight)
+            .max_by(|a, b| a.kilograms().total_cmp(&b.kilograms()))
+            .unwrap_or_default()
+    }
+
+    /// Sets done with the top weight
+    pub fn working_sets(&self) -> Vec<SetPerformance> {
+        let top = self.top_weight();
+        self.sets
+            .iter()
+            .filter(|s| (s.weight.kilograms() - top.kilograms()).abs() < SAME_WEIGHT_EPSILON)
+            .copied()
+            .collect()
+    }
+
+    /// Fewest reps among the working sets
+    pub fn min_reps(&self) -> u32 {
+        self.working_sets().iter().map(|s| s.reps).min().unwrap_or_default()
+    }
+
+    /// Every planned set done with at least `reps` reps at the top weight
+    pub fn hit(&self, target: &RoutineExercise, reps: u32) -> bool {
+        let working = self.working_sets();
+        working.len() as u32 >= target.sets && working.iter().all(|s| s.reps >= reps)
+    }
+
+    /// A working set fell below the bottom of the reps range
+    pub fn is_failure(&self, target: &RoutineExercise) -> bool {
+        !self.sets.is_empty() && self.min_reps() < target.min_reps
+    }
+
+    pub fn best_one_rep_max(&self, formula: OneRepMaxFormula) -> Mass {
+        self.sets
+            .iter()
+            .filter_map(|s| formula.estimate(s.weight, s.reps))
+            .max_by(|a, b| a.kilograms().total_cmp(&b.kilograms()))
+            .unwrap_or_default()
+    }
+}
+
+/// Proposed targets of the next session
+#[derive(Clone, Debug, PartialEq)]
+pub struct Suggestion {
+    pub sets: u32,
+    pub reps: u32,
+    pub weight: Mass,
+    pub reason: String,
+}
+
+impl Suggestion {
+    pub fn new(sets: u32, reps: u32, weight: Mass, reason: &str) -> Self {
+        Self { sets, reps, weight, reason: reason.to_owned() }
+    }
+}
+
+/// Chooses the next targets of a routine exercise from its logged sessions
+pub trait ProgressionRule {
+    fn name(&self) -> &'static str;
+
+    /// Targets after the `history` sessions, oldest first, `None` without history
+    fn suggest(&self, target: &RoutineExercise, history: &[SessionPerformance]) -> Option<Suggestion>;
+}
+
+/// Same weight until every set reaches the top of the reps range, then more weight
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RepCeiling;
+
+impl ProgressionRule for RepCeiling {
+    fn name(&self) -> &'static str { "Rep ceiling" }
+
+    fn suggest(&self, target: &RoutineExercise, history: &[SessionPerformance]) -> Option<Suggestion> {
+        let last = history.last()?;
+        let weight = last.top_weight();
+        if last.hit(target, target.max_reps) {
+            Some(Suggestion::new(target.sets, target.max_reps, weight + target.increment, "rep ceiling reached, add weight"))
+        } else {
+            Some(Suggestion::new(target.sets, target.max_reps, weight, "repeat the weight"))
+        }
+    }
+}
+
+/// One more rep per session up to the top of the range, then more weight from the bottom of it
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DoubleProgression;
+
+impl ProgressionRule for DoubleProgression {
+    fn name(&self) -> &'static str { "Double progression" }
+
+    fn suggest(&self, target: &RoutineExercise, history: &[SessionPerformance]) -> Option<Suggestion> {
+        let last = history.last()?;
+        let weight = last.top_weight();
+        if last.hit(target, target.max_reps) {
+            return Some(Suggestion::new(target.sets, target.min_reps, weight + target.increment, "top of the range reached, add weight"));
+        }
+        let reps = (last.min_reps() + 1).clamp(target.min_reps, target.max_reps);
+        Some(Suggestion::new(target.sets, reps, weight, "add a rep"))
+    }
+}
+
+/// Cuts the weight of the wrapped rule after consecutive failed sessions
+#[derive(Clone, Copy, Debug)]
+pub struct DeloadAfterFailures<R> {
+    pub inner: R,
+    /// Consecutive failed sessions triggering a deload
+    pub failures: u32,
+    /// Removed part of the weight, `0.1` for ten percents
+    pub deload: f64,
+}
+
+impl<R> DeloadAfterFailures<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner, failures: 2, deload: 0.1 }
+    }
+}
+
+impl<R: ProgressionRule> ProgressionRule for DeloadAfterFailures<R> {
+    fn name(&self) -> &'static str { self.inner.name() }
+
+    fn suggest(&self, target: &RoutineExercise, history: &[SessionPerformance]) -> Option<Suggestion> {
+        let failures = self.failures.max(1) as usize;
+        if history.len() >= failures && history[history.len() - failures..].iter().all(|s| s.is_failure(target)) {
+            let weight = round_to(history.last()?.top_weight() * (1.0 - self.deload), target.increment);
+            return Some(Suggestion::new(target.sets, target.max_reps, weight, "deload after failed sessions"));
+        }
+        self.inner.suggest(target, history)
+    }
+}
+
+/// Cycles through percentages of the estimated one rep max, one step per session
+#[derive(Clone, Debug)]
+pub struct PercentageWave {
+    /// Percentage of the one rep max and reps of every step
+    pub steps: Vec<(f64, u32)>,
+    pub formula: OneRepMaxFormula,
+}
+
+impl PercentageWave {
+    /// 5 reps at 75%, 3 at 80% and a single at 85% of the one rep max estimated by `formula`
+    pub fn new(formula: OneRepMaxFormula) -> Self {
+        Self { steps: vec![(0.75, 5), (0.80, 3), (0.85, 1)], formula }
+    }
+}
+
+impl ProgressionRule for PercentageWave {
+    fn name(&self) -> &'static str { "Percentage wave" }
+
+    fn suggest(&self, target: &RoutineExercise, history: &[SessionPerformance]) -> Option<Suggestion> {
+        if history.is_empty() || self.steps.is_empty() {
+            return None;
+        }
+        let one_rep_max = history
+            .iter()
+            .map(|s| s.best_one_rep_max(self.formula))
+            .max_by(|a, b| a.kilograms().total_cmp(&b.kilograms()))?;
+        let (percent, reps) = self.steps[history.len() % self.steps.len()];
+        let reason = format!("{:.0}% of the estimated 1RM", percent * 100.0);
+        Some(Suggestion::new(target.sets, reps, round_to(one_rep_max * percent, target.increment), &reason))
+    }
+}
+
+/// Rounds down to a multiple of `step`, 2.5 kg without a step
+fn round_to(weight: Mass, step: Mass) -> Mass {
+    let step = if step.is_zero() { DEFAULT_ROUNDING } else { step.kilograms() };
+    Mass::from_kilograms((weight.kilograms() / step + 1e-9).floor() * step)
+}
+
+/// Built-in rule of the scheme, `formula` estimates the one rep max for the percentage based ones
+pub fn rule_for(scheme: ProgressionScheme, formula: OneRepMaxFormula) -> Box<dyn ProgressionRule> {
+    match scheme {
+        ProgressionScheme::RepCeiling => Box::new(DeloadAfterFailures::new(RepCeiling)),
+        ProgressionScheme::DoubleProgression => Box::new(DeloadAfterFailures::new(DoubleProgression)),
+        ProgressionScheme::PercentageWave => Box::new(PercentageWave::new(formula)),
+    }
+}
+
+/// Sessions of the exercise started before `before`, oldest first
+pub fn exercise_history(db: &Db, person_id: ID, exercise_id: ID, before: NaiveDateTime) -> Vec<SessionPerformance> {
+    let workouts: HashMap<ID, &Workout> = db.workouts()
+        .get_all()
+        .filter(|w| w.person_id == person_id && w.started_at < before)
+        .map(|w| (w.id, w))
+        .collect();
+    let mut sessions: HashMap<ID, Vec<&WorkoutSet>> = HashMap::new();
+    for set in db.sets().get_all().filter(|s| s.exercise_id == exercise_id && s.reps > 0) {
+        if workouts.contains_key(&set.workout_id) {
+            sessions.entry(set.workout_id).or_default().push(set);
+        }
+    }
+    let mut sessions: Vec<(NaiveDateTime, SessionPerformance)> = sessions
+        .into_iter()
+        .map(|(workout_id, mut sets)| {
+            let workout = workouts[&workout_id];
+            sets.sort_by_key(|s| s.order);
+            let sets = sets.iter().map(|s| SetPerformance { reps: s.reps, weight: s.weight }).collect();
+            (workout.started_at, SessionPerformance::new(workout.date(), sets))
+        })
+        .collect();
+    sessions.sort_by_key(|(started_at, _)| *started_at);
+    sessions.into_iter().map(|(_, s)| s).collect()
+}
+
+/// Targets of the routine exercise in the workout, based on the earlier sessions
+pub fn suggest(db: &Db, workout: &Workout, target: &RoutineExercise, formula: OneRepMaxFormula) -> Option<Suggestion> {
+    let history = exercise_history(db, workout.person_id, target.exercise_id, workout.started_at);
+    rule_for(target.scheme, formula).suggest(target, &history)
+}
@@ -0,0 +1,334 @@
+use std::ops::Range;
+
+use sport_core_db::{
+    entity::{WorkoutSet, ID},
+    model::{Db, Table},
+    units::{Distance, Duration, Mass, ParseQuantityError, Quantity, UnitSystem},
+};
+use thiserror::Error;
+
+/// Unitless first numbers of `AxB` up to this are set counts, heavier ones are weights
+const MAX_SETS: f64 = 10.0;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum QuickEntryErrorKind {
+    #[error("Nothing to log")]
+    Empty,
+    #[error("No exercise given and no previous one")]
+    MissingExercise,
+    #[error("Unknown exercise `{0}`")]
+    UnknownExercise(String),
+    #[error("Ambiguous exercise, one of: {}", .0.join(", "))]
+    AmbiguousExercise(Vec<String>),
+    #[error("Invalid {what}: {err}")]
+    Quantity { what: &'static str, err: ParseQuantityError },
+    #[error("Invalid reps `{0}`")]
+    Reps(String),
+    #[error("Invalid RPE `{0}`, expected 1 to 10")]
+    Rpe(String),
+    #[error("{0} given twice")]
+    Repeated(&'static str),
+    #[error("Unexpected `{0}`")]
+    Unexpected(String),
+}
+
+/// Parse error with the byte range of the offending input
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("{kind}")]
+pub struct QuickEntryError {
+    pub kind: QuickEntryErrorKind,
+    pub span: Range<usize>,
+}
+
+impl QuickEntryError {
+    fn new(kind: QuickEntryErrorKind, span: Range<usize>) -> Self {
+        Self { kind, span }
+    }
+
+    /// Input with a caret line under the span
+    pub fn pointer(&self, input: &str) -> String {
+        let start = input[..self.span.start.min(input.len())].chars().count();
+        let width = input[self.span.start.min(input.len())..self.span.end.min(input.len())].chars().count().max(1);
+        format!("{}\n{}{}", input, " ".repeat(start), "^".repeat(width))
+    }
+}
+
+type Result<T> = std::result::Result<T, QuickEntryError>;
+
+/// Sets of one exercise read from a line
+#[derive(Clone, Debug)]
+pub struct QuickEntry {
+    pub exercise_id: ID,
+    pub sets: Vec<WorkoutSet>,
+}
+
+impl QuickEntry {
+    /// Sets of the workout numbered from `first_order`
+    pub fn into_sets(self, workout_id: ID, first_order: u32) -> Vec<WorkoutSet> {
+        self.sets
+            .into_iter()
+            .enumerate()
+            .map(|(i, set)| WorkoutSet { workout_id, order: first_order + i as u32, ..set })
+            .collect()
+    }
+}
+
+struct Token<'a> {
+    text: &'a str,
+    span: Range<usize>,
+}
+
+/// Splits on whitespace and before `@`
+fn tokenize(input: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut start = None;
+    for (i, c) in input.char_indices().chain(std::iter::once((input.len(), ' '))) {
+        if c.is_whitespace() || c == '@' {
+            if let Some(s) = start.take() {
+                tokens.push(Token { text: &input[s..i], span: s..i });
+            }
+            if c == '@' {
+                start = Some(i);
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    tokens
+}
+
+fn is_number(s: &str) -> bool {
+    !s.is_empty() && s.replace(',', ".").parse::<f64>().is_ok()
+}
+
+fn is_duration(s: &str) -> bool {
+    s.ends_with("min") || s.ends_with('h') || (s.ends_with('s') && !s.ends_with("lbs") && !s.ends_with("kgs"))
+}
+
+fn has_digit_start(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+}
+
+/// Collected values of the numeric part
+#[derive(Default)]
+struct Values {
+    /// `AxB` not yet known as sets x reps or weight x reps
+    pair: Option<(f64, bool, u32, Range<usize>)>,
+    sets: Option<u32>,
+    reps: Vec<u32>,
+    weight: Option<Mass>,
+    distance: Option<Distance>,
+    duration: Option<Duration>,
+    rpe: Option<f64>,
+}
+
+fn set_once<T>(slot: &mut Option<T>, value: T, what: &'static str, span: &Range<usize>) -> Result<()> {
+    if slot.is_some() {
+        return Err(QuickEntryError::new(QuickEntryErrorKind::Repeated(what), span.clone()));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+fn quantity<Q: Quantity>(s: &str, units: UnitSystem, what: &'static str, span: &Range<usize>) -> Result<Q> {
+    Q::parse(s, units).map_err(|err| QuickEntryError::new(QuickEntryErrorKind::Quantity { what, err }, span.clone()))
+}
+
+fn parse_reps(s: &str, span: &Range<usize>) -> Result<Vec<u32>> {
+    s.split(',')
+        .map(|r| r.trim().parse::<u32>().ok().filter(|r| *r > 0))
+        .collect::<Option<Vec<u32>>>()
+        .ok_or_else(|| QuickEntryError::new(QuickEntryErrorKind::Reps(s.to_owned()), span.clone()))
+}
+
+fn parse_rpe(s: &str, span: &Range<usize>) -> Result<f64> {
+    s.replace(',', ".")
+        .parse::<f64>()
+        .ok()
+        .filter(|rpe| (1.0..=10.0).contains(rpe))
+        .ok_or_else(|| QuickEntryError::new(QuickEntryErrorKind::Rpe(s.to_owned()), span.clone()))
+}
+
+impl Values {
+    fn read(&mut self, tokens: &[Token], units: UnitSystem) -> Result<()> {
+        let mut i = 0;
+        while i < tokens.len() {
+            let token = &tokens[i];
+            let text = token.text.to_lowercase();
+            let span = &token.span;
+            if let Some(rest) = text.strip_prefix("rpe") {
+                let (value, span) = match rest.is_empty() {
+                    true => {
+                        i += 1;
+                        let next = tokens.get(i).ok_or_else(|| QuickEntryError::new(QuickEntryErrorKind::Rpe("".to_owned()), span.clone()))?;
+                        (next.text.to_owned(), next.span.clone())
+                    }
+                    false => (rest.to_owned(), span.clone()),
+                };
+                let rpe = parse_rpe(&value, &span)?;
+                set_once(&mut self.rpe, rpe, "RPE", &span)?;
+            } else if let Some(rest) = text.strip_prefix('@') {
+                let (value, span) = match rest.is_empty() {
+                    true => {
+                        i += 1;
+                        let next = tokens.get(i).ok_or_else(|| QuickEntryError::new(QuickEntryErrorKind::Unexpected("@".to_owned()), span.clone()))?;
+                        (next.text.to_owned(), next.span.clone())
+                    }
+                    false => (rest.to_owned(), span.clone()),
+                };
+                let weight = quantity(&value, units, "weight", &span)?;
+                set_once(&mut self.weight, weight, "Weight", &span)?;
+            } else if text.contains(':') {
+                let duration = quantity(&text, units, "duration", span)?;
+                set_once(&mut self.duration, duration, "Duration", span)?;
+            } else if let Some((left, right)) = text.split_once('x').filter(|(l, _)| has_digit_start(l)) {
+                if self.pair.is_some() || !self.reps.is_empty() {
+                    return Err(QuickEntryError::new(QuickEntryErrorKind::Repeated("Reps"), span.clone()));
+                }
+                let reps = parse_reps(right, span)?;
+                if reps.len() > 1 || !is_number(left) {
+                    // weight x reps of every set
+                    let weight = quantity(left, units, "weight", span)?;
+                    set_once(&mut self.weight, weight, "Weight", span)?;
+                    self.reps = reps;
+                } else {
+                    let value = left.replace(',', ".").parse::<f64>().unwrap_or_default();
+                    self.pair = Some((value, left.contains(['.', ',']), reps[0], span.clone()));
+                }
+            } else if has_digit_start(&text) && is_duration(&text) {
+                let duration = quantity(&text, units, "duration", span)?;
+                set_once(&mut self.duration, duration, "Duration", span)?;
+            } else if has_digit_start(&text) && text.ends_with(['m', 'i']) {
+                let distance = quantity(&text, units, "distance", span)?;
+                set_once(&mut self.distance, distance, "Distance", span)?;
+            } else if has_digit_start(&text) {
+                let weight = quantity(&text, units, "weight", span)?;
+                set_once(&mut self.weight, weight, "Weight", span)?;
+            } else {
+                return Err(QuickEntryError::new(QuickEntryErrorKind::Unexpected(token.text.to_owned()), span.clone()));
+            }
+            i += 1;
+        }
+
+        if let Some((value, is_decimal, reps, span)) = self.pair.take() {
+            if self.weight.is_some() || (value <= MAX_SETS && !is_decimal) {
+                if value < 1.0 || value.fract() != 0.0 {
+                    return Err(QuickEntryError::new(QuickEntryErrorKind::Reps(value.to_string()), span));
+                }
+                self.sets = Some(value as u32);
+                self.reps = vec![reps];
+            } else {
+                self.weight = Some(Mass::from_value_in(value, units));
+                self.reps = vec![reps];
+            }
+        }
+        Ok(())
+    }
+
+    fn into_sets(self, exercise_id: ID) -> Vec<WorkoutSet> {
+        let set = WorkoutSet {
+            exercise_id,
+            weight: self.weight.unwrap_or_default(),
+            distance: self.distance.unwrap_or_default(),
+            duration: self.duration.unwrap_or_default(),
+            rpe: self.rpe,
+            ..WorkoutSet::default()
+        };
+        if self.reps.is_empty() {
+            return vec![set];
+        }
+        let sets = self.sets.unwrap_or(1) as usize;
+        self.reps
+            .iter()
+            .flat_map(|reps| std::iter::repeat_n(WorkoutSet { reps: *reps, ..set.clone() }, sets))
+            .collect()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.reps.is_empty() && self.weight.is_none() && self.distance.is_none() && self.duration.is_none()
+    }
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = (row[j] + 1).min(current + 1).min(previous + usize::from(ca != *cb));
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+fn is_subsequence(query: &str, name: &str) -> bool {
+    let mut chars = name.chars();
+    query.chars().filter(|c| !c.is_whitespace()).all(|c| chars.any(|n| n == c))
+}
+
+/// How well `query` matches `name`, lower is better, `None` for no match
+fn match_rank(query: &str, name: &str) -> Option<usize> {
+    let words: Vec<&str> = name.split_whitespace().collect();
+    if name == query {
+        Some(0)
+    } else if name.starts_with(query) {
+        Some(1)
+    } else if query.split_whitespace().all(|q| words.iter().any(|w| w.starts_with(q))) {
+        Some(2)
+    } else if is_subsequence(query, name) && query.len() * 2 >= name.len() {
+        Some(3)
+    } else {
+        let distance = words.iter().map(|w| edit_distance(query, w)).chain([edit_distance(query, name)]).min()?;
+        (distance <= (query.chars().count() / 4).max(1)).then_some(4 + distance)
+    }
+}
+
+/// Exercise named by `query`, exactly, by prefix, by initial words, or with a typo
+pub fn find_exercise(db: &Db, query: &str) -> std::result::Result<ID, QuickEntryErrorKind> {
+    let query = query.trim().to_lowercase();
+    let mut ranked: Vec<(usize, &str, ID)> = db.exercises()
+        .get_all()
+        .filter_map(|e| match_rank(&query, &e.name.to_lowercase()).map(|rank| (rank, e.name.as_str(), e.id)))
+        .collect();
+    ranked.sort_by_key(|(rank, name, _)| (*rank, name.len()));
+    match ranked.as_slice() {
+        [] => Err(QuickEntryErrorKind::UnknownExercise(query)),
+        [(rank, _, id), rest @ ..] if rest.first().is_none_or(|(r, _, _)| r > rank) => Ok(*id),
+        [(rank, ..), ..] => {
+            let mut names: Vec<String> = ranked.iter().filter(|(r, ..)| r == rank).map(|(_, n, _)| n.to_string()).collect();
+            names.sort();
+            Err(QuickEntryErrorKind::AmbiguousExercise(names))
+        }
+    }
+}
+
+/// Parses `bench 3x8@80kg`, `5x5 100`, `squat 120x5,5,4 rpe8` or `run 5km 25:30`,
+/// the exercise defaults to `last_exercise`, bare numbers are in the `units` system
+pub fn parse(db: &Db, input: &str, units: UnitSystem, last_exercise: Option<ID>) -> Result<QuickEntry> {
+    let tokens = tokenize(input);
+    if tokens.is_empty() {
+        return Err(QuickEntryError::new(QuickEntryErrorKind::Empty, 0..input.len()));
+    }
+    let name_len = tokens
+        .iter()
+        .position(|t| has_digit_start(t.text) || t.text.starts_with('@') || t.text.to_lowercase().starts_with("rpe"))
+        .unwrap_or(tokens.len());
+    let (name, values) = tokens.split_at(name_len);
+    let exercise_id = match (name.first(), name.last()) {
+        (Some(first), Some(last)) => {
+            let span = first.span.start..last.span.end;
+            find_exercise(db, &input[span.clone()]).map_err(|kind| QuickEntryError::new(kind, span))?
+        }
+        _ => last_exercise.ok_or_else(|| QuickEntryError::new(QuickEntryErrorKind::MissingExercise, 0..0))?,
+    };
+
+    let mut parsed = Values::default();
+    parsed.read(values, units)?;
+    if parsed.is_empty() {
+        return Err(QuickEntryError::new(QuickEntryErrorKind::Empty, input.len()..input.len()));
+    }
+    Ok(QuickEntry { exercise_id, sets: parsed.into_sets(exercise_id) })
+}
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use sport_core_db::{
+    entity::{PersonalRecord, RecordKind, Workout, WorkoutSet, ID},
+    model::{Db, Table},
+};
+
+use crate::analytics::OneRepMaxFormula;
+
+/// Weights are the same for the reps record to a hundredth of a kilogram
+const WEIGHT_STEPS_PER_KILOGRAM: f64 = 100.0;
+
+/// Record value set by a workout set
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RecordValue {
+    pub kind: RecordKind,
+    /// Value in base units (kilograms, reps, meters or seconds per kilometer)
+    pub value: f64,
+    /// Best earlier value, `None` if the exercise had no such performance
+    pub previous: Option<f64>,
+}
+
+impl RecordValue {
+    fn new(kind: RecordKind, value: f64, previous: Option<f64>) -> Self { Self { kind, value, previous } }
+}
+
+/// Value of the set for the record kind, `None` if the set doesn't apply to it
+pub fn set_value(kind: RecordKind, set: &WorkoutSet, formula: OneRepMaxFormula) -> Option<f64> {
+    let is_lift = set.reps > 0 && !set.weight.is_zero();
+    match kind {
+        RecordKind::HeaviestWeight => is_lift.then(|| set.weight.kilograms()),
+        RecordKind::MostReps => (set.reps > 0).then_some(set.reps as f64),
+        RecordKind::BestOneRepMax => formula.estimate_set(set).filter(|_| is_lift).map(|m| m.kilograms()),
+        RecordKind::LongestDistance => (!set.distance.is_zero()).then(|| set.distance.meters()),
+        RecordKind::FastestPace => set.pace().map(|pace| pace.seconds()),
+    }
+}
+
+/// Lower values are better only for the pace
+fn is_better(kind: RecordKind, value: f64, best: f64) -> bool {
+    match kind {
+        RecordKind::FastestPace => value < best,
+        _ => value > best,
+    }
+}
+
+fn weight_key(set: &WorkoutSet) -> i64 {
+    (set.weight.kilograms() * WEIGHT_STEPS_PER_KILOGRAM).round() as i64
+}
+
+/// Key of the best value, the reps are compared at the same weight only
+fn best_key(kind: RecordKind, set: &WorkoutSet) -> (RecordKind, i64) {
+    let weight = if kind == RecordKind::MostReps { weight_key(set) } else { 0 };
+    (kind, weight)
+}
+
+/// Best values of the sets seen so far of one exercise
+struct Bests {
+    formula: OneRepMaxFormula,
+    values: HashMap<(RecordKind, i64), f64>,
+}
+
+impl Bests {
+    fn new(formula: OneRepMaxFormula) -> Self { Self { formula, values: HashMap::new() } }
+
+    /// Records set by `set`, the first performance of a kind too, without a previous value
+    fn records(&self, set: &WorkoutSet) -> Vec<RecordValue> {
+        RecordKind::ALL
+            .iter()
+            .filter_map(|kind| {
+                let value = set_value(*kind, set, self.formula)?;
+                match self.values.get(&best_key(*kind, set)) {
+                    Some(best) if !is_better(*kind, value, *best) => None,
+                    best => Some(RecordValue::new(*kind, value, best.copied())),
+                }
+            })
+            .collect()
+    }
+
+    fn add(&mut self, set: &WorkoutSet) {
+        for kind in RecordKind::ALL {
+            if let Some(value) = set_value(kind, set, self.formula) {
+                self.values.entry(best_key(kind, set))
+                    .and_modify(|best| if is_better(kind, value, *best) { *best = value })
+                    .or_insert(value);
+            }
+        }
+    }
+}
+
+/// Records `set` sets against the `previous` sets of the same exercise
+///
+/// The first performance of a kind is returned too, without a previous value.
+pub fn detect(previous: &[&WorkoutSet], set: &WorkoutSet, formula: OneRepMaxFormula) -> Vec<RecordValue> {
+    let mut bests = Bests::new(formula);
+    previous.iter().for_each(|p| bests.add(p));
+    bests.records(set)
+}
+
+/// Moment of the set used to order the sets history
+type SetTime = (NaiveDateTime, u32, ID);
+
+fn set_time(workouts: &HashMap<ID, &Workout>, set: &WorkoutSet) -> Option<SetTime> {
+    workouts.get(&set.workout_id).map(|w| (w.started_at, set.order, set.id))
+}
+
+/// Records of the set stored in `db` and whether the same person logged the exercise after it
+fn detect_with_later(db: &Db, set_id: ID, formula: OneRepMaxFormula) -> (Vec<PersonalRecord>, bool) {
+    let set = match db.sets().get_one(set_id) {
+        Some(set) => set,
+        None => return (vec![], false),
+    };
+    let workouts: HashMap<ID, &Workout> = db.workouts().get_all().map(|w| (w.id, w)).collect();
+    let (workout, time) = match (workouts.get(&set.workout_id), set_time(&workouts, set)) {
+        (Some(workout), Some(time)) => (*workout, time),
+        _ => return (vec![], false),
+    };
+    let (mut previous, mut has_later) = (vec![], false);
+    let others = db.sets()
+        .get_all()
+        .filter(|s| s.exercise_id == set.exercise_id && s.id != set.id)
+        .filter(|s| workouts.get(&s.workout_id).is_some_and(|w| w.person_id == workout.person_id));
+    for other in others {
+        match set_time(&workouts, other) {
+            Some(t) if t < time => previous.push(other),
+            Some(_) => has_later = true,
+            None => {}
+        }
+    }
+    let records = detect(&previous, set, formula)
+        .into_iter()
+        .map(|r| PersonalRecord {
+            id: 0,
+            person_id: workout.person_id,
+            exercise_id: set.exercise_id,
+            set_id: set.id,
+            kind: r.kind,
+            date: workout.date(),
+            value: r.value,
+            previous: r.previous,
+        })
+        .collect();
+    (records, has_later)
+}
+
+/// Records set by the set stored in `db`, against the earlier sets of the same person and exercise
+pub fn detect_in_db(db: &Db, set_id: ID, formula: OneRepMaxFormula) -> Vec<PersonalRecord> {
+    detect_with_later(db, set_id, formula).0
+}
+
+/// Stores records of a newly saved set, returns the beaten ones
+///
+/// A backdated set changes the records after it, so the history is rebuilt then.
+pub fn register_set(db: &mut Db, set_id: ID, formula: OneRepMaxFormula) -> Vec<PersonalRecord> {
+    let (records, has_later) = detect_with_later(db, set_id, formula);
+    if has_later {
+        rebuild(db, formula);
+    } else {
+        let mut next_id = db.records().get_all().map(|r| r.id).max().unwrap_or(0);
+        for mut record in records.iter().cloned() {
+            next_id += 1;
+            record.id = next_id;
+            db.records_mut().insert(record);
+        }
+    }
+    records.into_iter().filter(|r| r.is_improvement()).collect()
+}
+
+/// Recomputes the whole records history from the logged sets
+pub fn rebuild(db: &mut Db, formula: OneRepMaxFormula) {
+    let workouts: HashMap<ID, &Workout> = db.workouts().get_all().map(|w| (w.id, w)).collect();
+    let mut groups: HashMap<(ID, ID), Vec<(SetTime, &WorkoutSet)>> = HashMap::new();
+    for set in db.sets().get_all() {
+        if let (Some(workout), Some(time)) = (workouts.get(&set.workout_id), set_time(&workouts, set)) {
+            groups.entry((workout.person_id, set.exercise_id)).or_default().push((time, set));
+        }
+    }
+    let mut records = vec![];
+    for ((person_id, exercise_id), mut sets) in groups {
+        sets.sort_by_key(|(time, _)| *time);
+        let mut bests = Bests::new(formula);
+        for ((time, _, _), set) in sets {
+            records.extend(bests.records(set).into_iter().map(|r| PersonalRecord {
+                id: 0,
+                person_id,
+                exercise_id,
+                set_id: set.id,
+                kind: r.kind,
+                date: time.date(),
+                value: r.value,
+                previous: r.previous,
+            }));
+            bests.add(set);
+        }
+    }
+    records.sort_by_key(|r| (r.date, r.set_id));
+    db.records_mut().clear();
+    for (i, mut record) in records.into_iter().enumerate() {
+        record.id = i as ID + 1;
+        db.records_mut().insert(record);
+    }
+}
+
+/// Records history of a person and exercise, oldest first
+pub fn history(db: &Db, person_id: ID, exercise_id: ID) -> Vec<&PersonalRecord> {
+    let mut records: Vec<&PersonalRecord> = db.records()
+        .get_all()
+        .filter(|r| r.person_id == person_id && r.exercise_id == exercise_id)
+        .collect();
+    records.sort_by_key(|r| (r.date, r.id));
+    records
+}
@@ -0,0 +1,296 @@
+use std::{collections::BTreeMap, fmt::Write, fs, io, path::{Path, PathBuf}};
+
+use chrono::NaiveDate;
+use sport_core_db::{
+    entity::{MuscleGroup, RecordKind, ID},
+    model::{Db, Table},
+    units::{Duration, Quantity, UnitSystem},
+};
+
+use crate::analytics::{self, Period, Volume};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportKind {
+    Weekly,
+    Monthly,
+}
+
+impl ReportKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReportKind::Weekly => "Weekly",
+            ReportKind::Monthly => "Monthly",
+        }
+    }
+
+    /// Week or month containing `date`
+    pub fn period(&self, date: NaiveDate) -> Period {
+        match self {
+            ReportKind::Weekly => Period::week_of(date),
+            ReportKind::Monthly => Period::month_of(date),
+        }
+    }
+
+    /// Period of the same kind right before `period`
+    pub fn previous(&self, period: Period) -> Period {
+        match period.start.pred_opt() {
+            Some(date) => self.period(date),
+            None => period,
+        }
+    }
+}
+
+/// Totals of a period
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PeriodSummary {
+    pub sessions: u32,
+    pub duration: Duration,
+    pub volume: Volume,
+}
+
+impl PeriodSummary {
+    pub fn new(db: &Db, person_id: ID, period: Period) -> Self {
+        let workouts = analytics::workouts_in(db, person_id, period);
+        Self {
+            sessions: workouts.len() as u32,
+            duration: workouts.iter().map(|w| w.duration).sum(),
+            volume: analytics::sets_in(db, person_id, period).into_iter().map(|(_, s)| s).collect(),
+        }
+    }
+}
+
+/// Personal record beaten within the period
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReportRecord {
+    pub date: NaiveDate,
+    pub exercise: String,
+    pub kind: RecordKind,
+    pub value: f64,
+    pub previous: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    pub kind: ReportKind,
+    pub person_id: ID,
+    pub period: Period,
+    pub current: PeriodSummary,
+    pub previous: PeriodSummary,
+    /// Sets per muscle group in this and the previous period
+    pub muscle_groups: BTreeMap<MuscleGroup, (u32, u32)>,
+    pub records: Vec<ReportRecord>,
+}
+
+/// Summary of the week or month containing `date`, compared with the previous one
+pub fn generate(db: &Db, person_id: ID, kind: ReportKind, date: NaiveDate) -> Report {
+    let period = kind.period(date);
+    let previous = kind.previous(period);
+
+    let mut muscle_groups: BTreeMap<MuscleGroup, (u32, u32)> = BTreeMap::new();
+    for (group, volume) in analytics::volume_by_muscle_group(db, person_id, period) {
+        muscle_groups.entry(group).or_default().0 = volume.sets;
+    }
+    for (group, volume) in analytics::volume_by_muscle_group(db, person_id, previous) {
+        muscle_groups.entry(group).or_default().1 = volume.sets;
+    }
+
+    let mut records: Vec<ReportRecord> = db.records()
+        .get_all()
+        .filter(|r| r.person_id == person_id && period.contains(r.date) && r.is_improvement())
+        .map(|r| ReportRecord {
+            date: r.date,
+            exercise: db.exercises().get_one(r.exercise_id).map(|e| e.name.clone()).unwrap_or_default(),
+            kind: r.kind,
+            value: r.value,
+            previous: r.previous,
+        })
+        .collect();
+    let kind_order = |kind: RecordKind| RecordKind::ALL.iter().position(|k| *k == kind);
+    records.sort_by(|a, b| (a.date, &a.exercise, kind_order(a.kind)).cmp(&(b.date, &b.exercise, kind_order(b.kind))));
+
+    Report {
+        kind,
+        person_id,
+        period,
+        current: PeriodSummary::new(db, person_id, period),
+        previous: PeriodSummary::new(db, person_id, previous),
+        muscle_groups,
+        records,
+    }
+}
+
+/// Relative change in percents, `None` if there is nothing to compare with
+pub fn change(current: f64, previous: f64) -> Option<f64> {
+    (previous != 0.0).then(|| (current - previous) / previous * 100.0)
+}
+
+/// Signed change like `+12%`, `-` if there is nothing to compare with
+pub fn format_change(current: f64, previous: f64) -> String {
+    match change(current, previous) {
+        Some(percent) => format!("{:+.0}%", percent),
+        None => "-".to_owned(),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "md",
+            ReportFormat::Html => "html",
+        }
+    }
+}
+
+impl Report {
+    pub fn title(&self) -> String {
+        format!("{} report {} - {}", self.kind.name(), self.period.start, self.period.end)
+    }
+
+    /// Default file name of the exported report
+    pub fn file_name(&self, format: ReportFormat) -> String {
+        format!("{}-report-{}.{}", self.kind.name().to_lowercase(), self.period.start, format.extension())
+    }
+
+    /// Rows of the summary table: name, current, previous and change
+    pub fn summary_rows(&self, units: UnitSystem) -> Vec<[String; 4]> {
+        let (current, previous) = (&self.current, &self.previous);
+        vec![
+            [
+                "Sessions".to_owned(),
+                current.sessions.to_string(),
+                previous.sessions.to_string(),
+                format_change(current.sessions as f64, previous.sessions as f64),
+            ],
+            [
+                "Duration".to_owned(),
+                current.duration.format(units),
+                previous.duration.format(units),
+                format_change(current.duration.seconds(), previous.duration.seconds()),
+            ],
+            [
+                "Sets".to_owned(),
+                current.volume.sets.to_string(),
+                previous.volume.sets.to_string(),
+                format_change(current.volume.sets as f64, previous.volume.sets as f64),
+            ],
+            [
+                "Tonnage".to_owned(),
+                current.volume.tonnage.format(units),
+                previous.volume.tonnage.format(units),
+                format_change(current.volume.tonnage.kilograms(), previous.volume.tonnage.kilograms()),
+            ],
+        ]
+    }
+
+    /// Rows of the records table: date, exercise, record, value and previous value
+    pub fn record_rows(&self, units: UnitSystem) -> Vec<[String; 5]> {
+        self.records
+            .iter()
+            .map(|r| [
+                r.date.to_string(),
+                r.exercise.clone(),
+                r.kind.name().to_owned(),
+                r.kind.format_value(r.value, units),
+                r.previous.map(|v| r.kind.format_value(v, units)).unwrap_or_else(|| "-".to_owned()),
+            ])
+            .collect()
+    }
+
+    /// Rows of the muscle groups table: group, current sets, previous sets and change
+    pub fn muscle_group_rows(&self) -> Vec<[String; 4]> {
+        self.muscle_groups
+            .iter()
+            .map(|(group, (current, previous))| [
+                group.to_string(),
+                current.to_string(),
+                previous.to_string(),
+                format_change(*current as f64, *previous as f64),
+            ])
+            .collect()
+    }
+
+    pub fn render(&self, format: ReportFormat, units: UnitSystem) -> String {
+        match format {
+            ReportFormat::Markdown => self.to_markdown(units),
+            ReportFormat::Html => self.to_html(units),
+        }
+    }
+
+    /// Writes the report into `dir` under its default file name, returns the file path
+    pub fn export(&self, dir: &Path, format: ReportFormat, units: UnitSystem) -> io::Result<PathBuf> {
+        let path = dir.join(self.file_name(format));
+        fs::write(&path, self.render(format, units))?;
+        Ok(path)
+    }
+
+    pub fn to_markdown(&self, units: UnitSystem) -> String {
+        let mut s = format!("# {}\n\n", self.title());
+        markdown_table(&mut s, &["", "This period", "Previous", "Change"], &self.summary_rows(units));
+        s.push_str("\n## Sets per muscle group\n\n");
+        markdown_table(&mut s, &["Muscle group", "Sets", "Previous", "Change"], &self.muscle_group_rows());
+        s.push_str("\n## Personal records\n\n");
+        if self.records.is_empty() {
+            s.push_str("No new records\n");
+        } else {
+            markdown_table(&mut s, &["Date", "Exercise", "Record", "Value", "Previous"], &self.record_rows(units));
+        }
+        s
+    }
+
+    pub fn to_html(&self, units: UnitSystem) -> String {
+        let title = escape_html(&self.title());
+        let mut s = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n</head>\n<body>\n<h1>{0}</h1>\n",
+            title,
+        );
+        html_table(&mut s, &["", "This period", "Previous", "Change"], &self.summary_rows(units));
+        s.push_str("<h2>Sets per muscle group</h2>\n");
+        html_table(&mut s, &["Muscle group", "Sets", "Previous", "Change"], &self.muscle_group_rows());
+        s.push_str("<h2>Personal records</h2>\n");
+        if self.records.is_empty() {
+            s.push_str("<p>No new records</p>\n");
+        } else {
+            html_table(&mut s, &["Date", "Exercise", "Record", "Value", "Previous"], &self.record_rows(units));
+        }
+        s.push_str("</body>\n</html>\n");
+        s
+    }
+}
+
+fn markdown_table<const N: usize>(s: &mut String, header: &[&str; N], rows: &[[String; N]]) {
+    let escape = |cell: &str| cell.replace('|', "\\|");
+    let _ = writeln!(s, "| {} |", header.map(escape).join(" | "));
+    let _ = writeln!(s, "|{}", " --- |".repeat(N));
+    for row in rows {
+        let _ = writeln!(s, "| {} |", row.iter().map(|c| escape(c)).collect::<Vec<String>>().join(" | "));
+    }
+}
+
+fn html_table<const N: usize>(s: &mut String, header: &[&str; N], rows: &[[String; N]]) {
+    s.push_str("<table>\n<tr>");
+    for cell in header {
+        let _ = write!(s, "<th>{}</th>", escape_html(cell));
+    }
+    s.push_str("</tr>\n");
+    for row in rows {
+        s.push_str("<tr>");
+        for cell in row {
+            let _ = write!(s, "<td>{}</td>", escape_html(cell));
+        }
+        s.push_str("</tr>\n");
+    }
+    s.push_str("</table>\n");
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -4,29 +4,51 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::fmt::{Debug, Display};
 use thiserror::Error;
 
-use sport_core_db::entity::{Exercise, Person, ID};
+use sport_core_db::entity::{Exercise, Goal, Measurement, Person, Routine, Workout, WorkoutSet, ID};
 
 pub type Result<T> = std::result::Result<T, RestClientError>;
 pub type PCL = Box<dyn RestEntityClient<Person, Person>>;
 pub type ECL = Box<dyn RestEntityClient<Exercise, Exercise>>;
+pub type WCL = Box<dyn RestEntityClient<Workout, Workout>>;
+pub type SCL = Box<dyn RestEntityClient<WorkoutSet, WorkoutSet>>;
+pub type MCL = Box<dyn RestEntityClient<Measurement, Measurement>>;
+pub type GCL = Box<dyn RestEntityClient<Goal, Goal>>;
+pub type RCL = Box<dyn RestEntityClient<Routine, Routine>>;
 
 pub struct Client {
     person: PCL,
     exercise: ECL,
+    workout: WCL,
+    set: SCL,
+    measurement: MCL,
+    goal: GCL,
+    routine: RCL,
 }
 
 impl Client {
-    pub fn new(pcl: PCL, ecl: ECL) -> Self {
+    pub fn new(pcl: PCL, ecl: ECL, wcl: WCL, scl: SCL, mcl: MCL, gcl: GCL, rcl: RCL) -> Self {
         Self {
             person: pcl,
             exercise: ecl,
+            workout: wcl,
+            set: scl,
+            measurement: mcl,
+            goal: gcl,
+            routine: rcl,
         }
     }
 
+    /// Clients of every entity sharing one connection pool
     pub fn default(host: &str) -> Self {
+        let req_client = reqwest::Client::new();
         Self {
-            person: Box::new(PersonRestClient::new(host)),
-            exercise: Box::new(ExerciseRestClient::new(host)),
+            person: Box::new(PersonRestClient::new(host, req_client.clone())),
+            exercise: Box::new(ExerciseRestClient::new(host, req_client.clone())),
+            workout: Box::new(WorkoutRestClient::new(host, req_client.clone())),
+            set: Box::new(WorkoutSetRestClient::new(host, req_client.clone())),
+            measurement: Box::new(MeasurementRestClient::new(host, req_client.clone())),
+            goal: Box::new(GoalRestClient::new(host, req_client.clone())),
+            routine: Box::new(RoutineRestClient::new(host, req_client)),
         }
     }
 
@@ -37,6 +59,26 @@ impl Client {
     pub fn exercise(&self) -> &ECL {
         &self.exercise
     }
+
+    pub fn workout(&self) -> &WCL {
+        &self.workout
+    }
+
+    pub fn set(&self) -> &SCL {
+        &self.set
+    }
+
+    pub fn measurement(&self) -> &MCL {
+        &self.measurement
+    }
+
+    pub fn goal(&self) -> &GCL {
+        &self.goal
+    }
+
+    pub fn routine(&self) -> &RCL {
+        &self.routine
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -72,11 +114,26 @@ pub enum RestClientError {
     #[error("request error: `{0}`")]
     Request(#[from] reqwest::Error),
     #[error("server message: `{0}`")]
-    ServerMessage(ServerMessage), // TODO check status
+    ServerMessage(ServerMessage),
     #[error("server error: `{0}`")]
     ServerError(ServerError),
 }
 
+/// Error of a response without the OK status, a rejected request for the 4xx ones and a server failure otherwise
+///
+/// A body of another shape is kept as the message.
+async fn status_error(res: reqwest::Response) -> RestClientError {
+    let status = res.status();
+    let body = res.text().await.unwrap_or_default();
+    if status.is_client_error() {
+        let message = serde_json::from_str(&body).unwrap_or_else(|_| ServerMessage { error_type: status.to_string(), message: body });
+        RestClientError::ServerMessage(message)
+    } else {
+        let error = serde_json::from_str(&body).unwrap_or_else(|_| ServerError { error_type: status.to_string(), error: body });
+        RestClientError::ServerError(error)
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ServerOperationStatus {
     status: String,
@@ -91,7 +148,7 @@ B: Serialize + Sync,
     async fn get_one(&self, id: ID) -> Result<T> {
         let res = self
             .request_client()
-            .get(&format!("{}/{}", self.path(), id))
+            .get(format!("{}/{}", self.path(), id))
             .header(reqwest::header::CONTENT_TYPE, "application/json")
             .header(reqwest::header::ACCEPT, "application/json")
             .send()
@@ -102,8 +159,8 @@ B: Serialize + Sync,
                 Ok(res.json().await?)
             }
             _ => {
-                warn!("Request GET one: server message");
-                Err(RestClientError::ServerError(res.json().await?))
+                warn!("Request GET one: server error");
+                Err(status_error(res).await)
             }
         }
     }
@@ -123,7 +180,7 @@ B: Serialize + Sync,
             }
             _ => {
                 warn!("Request GET all: server error");
-                Ok(res.json().await?)
+                Err(status_error(res).await)
             }
         }
     }
@@ -144,7 +201,7 @@ B: Serialize + Sync,
             }
             _ => {
                 warn!("Request POST insert: server error");
-                Ok(res.json().await?)
+                Err(status_error(res).await)
             }
         }
     }
@@ -166,7 +223,7 @@ B: Serialize + Sync,
             }
             _ => {
                 warn!("Request PUT update: server error");
-                Ok(res.json().await?)
+                Err(status_error(res).await)
             }
         }
     }
@@ -174,7 +231,7 @@ B: Serialize + Sync,
     async fn remove(&self, id: ID) -> Result<ServerOperationStatus> {
         let res = self
             .request_client()
-            .delete(&format!("{}/{}", self.path(), id))
+            .delete(format!("{}/{}", self.path(), id))
             .header(reqwest::header::CONTENT_TYPE, "application/json")
             .header(reqwest::header::ACCEPT, "application/json")
             .send()
@@ -186,7 +243,7 @@ B: Serialize + Sync,
             }
             _ => {
                 warn!("Request DELETE one: server error");
-                Ok(res.json().await?)
+                Err(status_error(res).await)
             }
         }
     }
@@ -201,9 +258,9 @@ struct ExerciseRestClient {
 }
 
 impl ExerciseRestClient {
-    pub fn new(host: &str) -> Self {
+    pub fn new(host: &str, req_client: reqwest::Client) -> Self {
         Self {
-            req_client: reqwest::Client::new(),
+            req_client,
             path: format!("{}/exercises", host),
         }
     }
@@ -226,9 +283,9 @@ struct PersonRestClient {
 }
 
 impl PersonRestClient {
-    pub fn new(host: &str) -> Self {
+    pub fn new(host: &str, req_client: reqwest::Client) -> Self {
         Self {
-            req_client: reqwest::Client::new(),
+            req_client,
             path: format!("{}/persons", host),
         }
     }
@@ -244,3 +301,128 @@ impl RestEntityClient<Person, Person> for PersonRestClient {
         &self.req_client
     }
 }
+
+struct WorkoutRestClient {
+    req_client: reqwest::Client,
+    path: String,
+}
+
+impl WorkoutRestClient {
+    pub fn new(host: &str, req_client: reqwest::Client) -> Self {
+        Self {
+            req_client,
+            path: format!("{}/workouts", host),
+        }
+    }
+}
+
+#[async_trait]
+impl RestEntityClient<Workout, Workout> for WorkoutRestClient {
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn request_client(&self) -> &reqwest::Client {
+        &self.req_client
+    }
+}
+
+struct WorkoutSetRestClient {
+    req_client: reqwest::Client,
+    path: String,
+}
+
+impl WorkoutSetRestClient {
+    pub fn new(host: &str, req_client: reqwest::Client) -> Self {
+        Self {
+            req_client,
+            path: format!("{}/workout_sets", host),
+        }
+    }
+}
+
+#[async_trait]
+impl RestEntityClient<WorkoutSet, WorkoutSet> for WorkoutSetRestClient {
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn request_client(&self) -> &reqwest::Client {
+        &self.req_client
+    }
+}
+
+struct MeasurementRestClient {
+    req_client: reqwest::Client,
+    path: String,
+}
+
+impl MeasurementRestClient {
+    pub fn new(host: &str, req_client: reqwest::Client) -> Self {
+        Self {
+            req_client,
+            path: format!("{}/measurements", host),
+        }
+    }
+}
+
+#[async_trait]
+impl RestEntityClient<Measurement, Measurement> for MeasurementRestClient {
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn request_client(&self) -> &reqwest::Client {
+        &self.req_client
+    }
+}
+
+struct GoalRestClient {
+    req_client: reqwest::Client,
+    path: String,
+}
+
+impl GoalRestClient {
+    pub fn new(host: &str, req_client: reqwest::Client) -> Self {
+        Self {
+            req_client,
+            path: format!("{}/goals", host),
+        }
+    }
+}
+
+#[async_trait]
+impl RestEntityClient<Goal, Goal> for GoalRestClient {
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn request_client(&self) -> &reqwest::Client {
+        &self.req_client
+    }
+}
+
+struct RoutineRestClient {
+    req_client: reqwest::Client,
+    path: String,
+}
+
+impl RoutineRestClient {
+    pub fn new(host: &str, req_client: reqwest::Client) -> Self {
+        Self {
+            req_client,
+            path: format!("{}/routines", host),
+        }
+    }
+}
+
+#[async_trait]
+impl RestEntityClient<Routine, Routine> for RoutineRestClient {
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn request_client(&self) -> &reqwest::Client {
+        &self.req_client
+    }
+}
@@ -0,0 +1,405 @@
+use std::{cell::Cell, fmt::Display, str::FromStr, time::{Duration, Instant}};
+
+use serde_derive::{Deserialize, Serialize};
+use sport_core_db::units::{self, Quantity, UnitSystem};
+use thiserror::Error;
+
+/// Source of the current time, faked in the tests
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Clock moving only when advanced
+#[derive(Debug)]
+pub struct FakeClock {
+    now: Cell<Instant>,
+}
+
+impl Default for FakeClock {
+    fn default() -> Self {
+        Self { now: Cell::new(Instant::now()) }
+    }
+}
+
+impl FakeClock {
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+}
+
+impl Clock for &FakeClock {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PhaseKind {
+    Work,
+    Rest,
+}
+
+impl PhaseKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PhaseKind::Work => "work",
+            PhaseKind::Rest => "rest",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Phase {
+    pub kind: PhaseKind,
+    pub duration: Duration,
+    /// Like `Round 3/10`
+    pub label: String,
+}
+
+impl Phase {
+    pub fn new(kind: PhaseKind, duration: Duration, label: &str) -> Self {
+        Self { kind, duration, label: label.to_owned() }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TimerMode {
+    /// Countdown between sets
+    Rest(Duration),
+    /// Every minute, or every `interval`, on the minute for `rounds`
+    Emom { interval: Duration, rounds: u32 },
+    /// As many rounds as possible within the time
+    Amrap(Duration),
+    Tabata { work: Duration, rest: Duration, rounds: u32 },
+    Custom(Vec<Phase>),
+}
+
+impl TimerMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimerMode::Rest(_) => "Rest",
+            TimerMode::Emom { .. } => "EMOM",
+            TimerMode::Amrap(_) => "AMRAP",
+            TimerMode::Tabata { .. } => "Tabata",
+            TimerMode::Custom(_) => "Intervals",
+        }
+    }
+
+    pub fn phases(&self) -> Vec<Phase> {
+        match self {
+            TimerMode::Rest(duration) => vec![Phase::new(PhaseKind::Rest, *duration, "Rest")],
+            TimerMode::Emom { interval, rounds } => (1..=*rounds)
+                .map(|i| Phase::new(PhaseKind::Work, *interval, &format!("Round {}/{}", i, rounds)))
+                .collect(),
+            TimerMode::Amrap(duration) => vec![Phase::new(PhaseKind::Work, *duration, "AMRAP")],
+            TimerMode::Tabata { work, rest, rounds } => (1..=*rounds)
+                .flat_map(|i| [
+                    Phase::new(PhaseKind::Work, *work, &format!("Work {}/{}", i, rounds)),
+                    Phase::new(PhaseKind::Rest, *rest, &format!("Rest {}/{}", i, rounds)),
+                ])
+                .collect(),
+            TimerMode::Custom(phases) => phases.clone(),
+        }
+    }
+}
+
+/// Longest phase, and rest between sets
+pub const MAX_DURATION: Duration = Duration::from_secs(24 * 60 * 60);
+/// Most rounds of an EMOM or Tabata, every round is a phase of its own
+pub const MAX_ROUNDS: u32 = 1000;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum TimerModeError {
+    #[error("Unknown timer `{0}`, expected rest, emom, amrap, tabata or custom")]
+    UnknownMode(String),
+    #[error("Invalid duration `{0}`")]
+    Duration(String),
+    #[error("Duration `{0}` is over 24 hours")]
+    DurationLimit(String),
+    #[error("Invalid rounds `{0}`")]
+    Rounds(String),
+    #[error("Rounds `{0}` are over {max}", max = MAX_ROUNDS)]
+    RoundsLimit(String),
+    #[error("Expected `work` or `rest` before `{0}`")]
+    PhaseKind(String),
+    #[error("No phases")]
+    Empty,
+}
+
+/// Whole seconds of a duration as `90`, `90s`, `2m`, `1:30` or `1:00:00`
+pub fn parse_duration(s: &str) -> Result<Duration, TimerModeError> {
+    let seconds = units::Duration::parse(s, UnitSystem::Metric)
+        .map(|d| d.seconds().round())
+        .map_err(|_| TimerModeError::Duration(s.to_owned()))?;
+    if seconds < 1.0 {
+        return Err(TimerModeError::Duration(s.to_owned()));
+    }
+    if seconds > MAX_DURATION.as_secs_f64() {
+        return Err(TimerModeError::DurationLimit(s.to_owned()));
+    }
+    Ok(Duration::from_secs(seconds as u64))
+}
+
+/// `m:ss` or `h:mm:ss`
+pub fn format_duration(duration: Duration) -> String {
+    units::Duration::from(Duration::from_secs(duration.as_secs())).format(UnitSystem::Metric)
+}
+
+fn parse_rounds(s: &str) -> Result<u32, TimerModeError> {
+    let rounds = s.trim_start_matches('x')
+        .parse::<u64>()
+        .ok()
+        .filter(|r| *r > 0)
+        .ok_or_else(|| TimerModeError::Rounds(s.to_owned()))?;
+    if rounds > MAX_ROUNDS as u64 {
+        return Err(TimerModeError::RoundsLimit(s.to_owned()));
+    }
+    Ok(rounds as u32)
+}
+
+/// Parses `rest 1:30`, `emom 1:00 x10`, `amrap 12m`, `tabata`, `tabata 20 10 x8`
+/// and `custom work 40, rest 20, work 40`
+impl FromStr for TimerMode {
+    type Err = TimerModeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        let (mode, args) = s.split_once(char::is_whitespace).unwrap_or((&s, ""));
+        let args: Vec<&str> = args.split_whitespace().collect();
+        match (mode, args.as_slice()) {
+            ("rest", [duration]) => Ok(TimerMode::Rest(parse_duration(duration)?)),
+            ("emom", [rounds]) => Ok(TimerMode::Emom { interval: Duration::from_secs(60), rounds: parse_rounds(rounds)? }),
+            ("emom", [interval, rounds]) => Ok(TimerMode::Emom { interval: parse_duration(interval)?, rounds: parse_rounds(rounds)? }),
+            ("amrap", [duration]) => Ok(TimerMode::Amrap(parse_duration(duration)?)),
+            ("tabata", []) => Ok(TimerMode::Tabata { work: Duration::from_secs(20), rest: Duration::from_secs(10), rounds: 8 }),
+            ("tabata", [work, rest, rounds]) => Ok(TimerMode::Tabata {
+                work: parse_duration(work)?,
+                rest: parse_duration(rest)?,
+                rounds: parse_rounds(rounds)?,
+            }),
+            ("custom", _) => {
+                let phases = args
+                    .join(" ")
+                    .split(',')
+                    .filter(|p| !p.trim().is_empty())
+                    .map(|phase| {
+                        let (kind, duration) = phase.trim().split_once(' ').unwrap_or(("", phase.trim()));
+                        let kind = match kind {
+                            "work" => PhaseKind::Work,
+                            "rest" => PhaseKind::Rest,
+                            _ => return Err(TimerModeError::PhaseKind(duration.to_owned())),
+                        };
+                        let label = if kind == PhaseKind::Work { "Work" } else { "Rest" };
+                        Ok(Phase::new(kind, parse_duration(duration)?, label))
+                    })
+                    .collect::<Result<Vec<Phase>, TimerModeError>>()?;
+                if phases.is_empty() {
+                    return Err(TimerModeError::Empty);
+                }
+                Ok(TimerMode::Custom(phases))
+            }
+            ("rest" | "emom" | "amrap" | "tabata", _) => Err(TimerModeError::Duration(args.join(" "))),
+            _ => Err(TimerModeError::UnknownMode(mode.to_owned())),
+        }
+    }
+}
+
+impl Display for TimerMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimerMode::Rest(duration) => write!(f, "rest {}", format_duration(*duration)),
+            TimerMode::Emom { interval, rounds } => write!(f, "emom {} x{}", format_duration(*interval), rounds),
+            TimerMode::Amrap(duration) => write!(f, "amrap {}", format_duration(*duration)),
+            TimerMode::Tabata { work, rest, rounds } => write!(f, "tabata {} {} x{}", work.as_secs(), rest.as_secs(), rounds),
+            TimerMode::Custom(phases) => {
+                let phases: Vec<String> = phases
+                    .iter()
+                    .map(|p| format!("{} {}", p.kind.name(), format_duration(p.duration)))
+                    .collect();
+                write!(f, "custom {}", phases.join(", "))
+            }
+        }
+    }
+}
+
+/// Defaults of the timers
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimerConfig {
+    /// Rest between sets in seconds
+    pub rest_seconds: u64,
+    /// Start the rest countdown after logging a set
+    pub auto_rest: bool,
+    /// Ring the terminal bell on phase changes
+    pub bell: bool,
+}
+
+impl Default for TimerConfig {
+    fn default() -> Self {
+        Self { rest_seconds: 90, auto_rest: true, bell: true }
+    }
+}
+
+impl TimerConfig {
+    pub fn rest(&self) -> TimerMode {
+        TimerMode::Rest(Duration::from_secs(self.rest_seconds.clamp(1, MAX_DURATION.as_secs())))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TimerEvent {
+    /// Entered the phase at `index`, also sent for the first one on start
+    PhaseChanged { index: usize, phase: Phase },
+    Finished,
+}
+
+/// Phases of a timer mode counted down by a clock, advanced by `tick`
+pub struct Timer<C: Clock> {
+    clock: C,
+    mode: TimerMode,
+    phases: Vec<Phase>,
+    /// Current phase and the time spent in it before the last resume
+    index: usize,
+    elapsed: Duration,
+    resumed_at: Option<Instant>,
+    is_started: bool,
+    is_finished: bool,
+}
+
+impl<C: Clock> Timer<C> {
+    pub fn new(mode: TimerMode, clock: C) -> Self {
+        Self {
+            clock,
+            phases: mode.phases(),
+            mode,
+            index: 0,
+            elapsed: Duration::ZERO,
+            resumed_at: None,
+            is_started: false,
+            is_finished: false,
+        }
+    }
+
+    pub fn mode(&self) -> &TimerMode {
+        &self.mode
+    }
+
+    pub fn phases(&self) -> &[Phase] {
+        &self.phases
+    }
+
+    pub fn start(&mut self) -> Vec<TimerEvent> {
+        if self.is_started {
+            return vec![];
+        }
+        self.is_started = true;
+        self.resumed_at = Some(self.clock.now());
+        match self.phases.first() {
+            Some(phase) => vec![TimerEvent::PhaseChanged { index: 0, phase: phase.clone() }],
+            None => self.finish(),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.resumed_at.is_some() && !self.is_finished
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.is_started && self.resumed_at.is_none() && !self.is_finished
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.is_finished
+    }
+
+    pub fn pause(&mut self) {
+        if let Some(resumed_at) = self.resumed_at.take() {
+            self.elapsed += self.clock.now() - resumed_at;
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if self.is_paused() {
+            self.resumed_at = Some(self.clock.now());
+        }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        if self.is_running() {
+            self.pause();
+        } else {
+            self.resume();
+        }
+    }
+
+    /// Time spent in the current phase
+    fn phase_elapsed(&self) -> Duration {
+        self.elapsed + self.resumed_at.map(|r| self.clock.now() - r).unwrap_or_default()
+    }
+
+    pub fn current_phase(&self) -> Option<(usize, &Phase)> {
+        (!self.is_finished).then(|| self.phases.get(self.index).map(|p| (self.index, p))).flatten()
+    }
+
+    /// Time left in the current phase
+    pub fn remaining(&self) -> Duration {
+        self.current_phase()
+            .map(|(_, p)| p.duration.saturating_sub(self.phase_elapsed()))
+            .unwrap_or_default()
+    }
+
+    /// Time left until the end of the last phase
+    pub fn total_remaining(&self) -> Duration {
+        self.remaining() + self.phases.iter().skip(self.index + 1).map(|p| p.duration).sum::<Duration>()
+    }
+
+    /// Moves to the next phase right away
+    pub fn skip(&mut self) -> Vec<TimerEvent> {
+        if !self.is_started || self.is_finished {
+            return vec![];
+        }
+        self.next_phase(Duration::ZERO)
+    }
+
+    fn finish(&mut self) -> Vec<TimerEvent> {
+        self.is_finished = true;
+        self.resumed_at = None;
+        vec![TimerEvent::Finished]
+    }
+
+    /// Enters the next phase with `overrun` already spent in it
+    fn next_phase(&mut self, overrun: Duration) -> Vec<TimerEvent> {
+        self.index += 1;
+        self.elapsed = overrun;
+        if self.resumed_at.is_some() {
+            self.resumed_at = Some(self.clock.now());
+        }
+        match self.phases.get(self.index) {
+            Some(phase) => vec![TimerEvent::PhaseChanged { index: self.index, phase: phase.clone() }],
+            None => self.finish(),
+        }
+    }
+
+    /// Catches up with the clock, an event for every phase change since the last tick
+    pub fn tick(&mut self) -> Vec<TimerEvent> {
+        let mut events = vec![];
+        while self.is_running() {
+            let elapsed = self.phase_elapsed();
+            let duration = self.phases[self.index].duration;
+            if elapsed < duration {
+                break;
+            }
+            events.extend(self.next_phase(elapsed - duration));
+        }
+        events
+    }
+}
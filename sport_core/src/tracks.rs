@@ -0,0 +1,326 @@
+use std::{fs, io, path::Path};
+
+use chrono::{DateTime, Local, Utc};
+use sport_core_db::{
+    entity::{Workout, WorkoutSet, ID},
+    model::{Db, Table},
+    units::{format_number, Distance, Duration, Length, Mass, Quantity, UnitSystem},
+};
+use thiserror::Error;
+
+use crate::{backup::RestoreTarget, controller, fit::{self, FitError}, quick_entry};
+
+const EARTH_RADIUS_METERS: f64 = 6_371_000.0;
+
+#[derive(Error, Debug)]
+pub enum TrackError {
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error("Invalid XML: {0}")]
+    Xml(#[from] roxmltree::Error),
+    #[error("Invalid FIT file: {0}")]
+    Fit(#[from] FitError),
+    #[error("Neither a GPX, TCX nor FIT file")]
+    Format,
+    #[error("The track has no timestamps")]
+    NoTime,
+}
+
+/// File format the track was read from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrackFormat {
+    Gpx,
+    Tcx,
+    Fit,
+}
+
+impl TrackFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TrackFormat::Gpx => "GPX",
+            TrackFormat::Tcx => "TCX",
+            TrackFormat::Fit => "FIT",
+        }
+    }
+}
+
+/// Sample of a recording, every value is optional as devices log different ones
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TrackPoint {
+    pub time: Option<DateTime<Utc>>,
+    /// Latitude and longitude in degrees
+    pub position: Option<(f64, f64)>,
+    /// Meters above the sea level
+    pub elevation: Option<f64>,
+    /// Meters from the start measured by the device
+    pub distance: Option<f64>,
+    pub heart_rate: Option<u32>,
+}
+
+/// Lap totals as the device computed them, paused time excluded
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Lap {
+    pub started_at: Option<DateTime<Utc>>,
+    pub duration: Duration,
+    pub distance: Distance,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Track {
+    pub format: TrackFormat,
+    pub name: String,
+    /// Activity type like `running` or `Biking`
+    pub sport: String,
+    /// Meters climbed as counted by the device
+    pub ascent: Option<f64>,
+    pub laps: Vec<Lap>,
+    pub points: Vec<TrackPoint>,
+}
+
+fn child<'a, 'i>(node: roxmltree::Node<'a, 'i>, name: &str) -> Option<roxmltree::Node<'a, 'i>> {
+    node.children().find(|n| n.tag_name().name() == name)
+}
+
+fn descendant<'a, 'i>(node: roxmltree::Node<'a, 'i>, name: &str) -> Option<roxmltree::Node<'a, 'i>> {
+    node.descendants().find(|n| n.tag_name().name() == name)
+}
+
+fn text<T: std::str::FromStr>(node: Option<roxmltree::Node>) -> Option<T> {
+    node.and_then(|n| n.text()).and_then(|t| t.trim().parse().ok())
+}
+
+fn time(node: Option<roxmltree::Node>) -> Option<DateTime<Utc>> {
+    let text = node.and_then(|n| n.text())?;
+    DateTime::parse_from_rfc3339(text.trim()).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// Great-circle distance in meters
+fn haversine((lat1, lon1): (f64, f64), (lat2, lon2): (f64, f64)) -> f64 {
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lon = (lon2 - lon1).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_METERS * a.sqrt().asin()
+}
+
+impl Track {
+    /// FIT files by their header, XML ones otherwise
+    pub fn load(path: &Path) -> Result<Self, TrackError> {
+        let data = fs::read(path)?;
+        if data.get(8..12) == Some(b".FIT".as_slice()) {
+            return Ok(fit::decode(&data)?);
+        }
+        let xml = String::from_utf8(data).map_err(|_| TrackError::Format)?;
+        Self::read(&xml)
+    }
+
+    /// GPX or TCX by the root element
+    pub fn read(xml: &str) -> Result<Self, TrackError> {
+        let doc = roxmltree::Document::parse(xml)?;
+        let root = doc.root_element();
+        match root.tag_name().name() {
+            "gpx" => Ok(Self::read_gpx(root)),
+            "TrainingCenterDatabase" => Ok(Self::read_tcx(root)),
+            _ => Err(TrackError::Format),
+        }
+    }
+
+    fn read_gpx(root: roxmltree::Node) -> Self {
+        let track = child(root, "trk");
+        let points = root
+            .descendants()
+            .filter(|n| n.tag_name().name() == "trkpt")
+            .map(|point| TrackPoint {
+                time: time(child(point, "time")),
+                position: point.attribute("lat").zip(point.attribute("lon")).and_then(|(lat, lon)| Some((lat.parse().ok()?, lon.parse().ok()?))),
+                elevation: text(child(point, "ele")),
+                distance: None,
+                // Garmin `TrackPointExtension` inside `extensions`
+                heart_rate: child(point, "extensions").and_then(|e| text(descendant(e, "hr"))),
+            })
+            .collect();
+        Self {
+            format: TrackFormat::Gpx,
+            name: text(track.and_then(|t| child(t, "name"))).unwrap_or_default(),
+            sport: text(track.and_then(|t| child(t, "type"))).unwrap_or_default(),
+            ascent: None,
+            laps: vec![],
+            points,
+        }
+    }
+
+    fn read_tcx(root: roxmltree::Node) -> Self {
+        let activity = descendant(root, "Activity");
+        let laps = root
+            .descendants()
+            .filter(|n| n.tag_name().name() == "Lap")
+            .map(|lap| Lap {
+                started_at: lap.attribute("StartTime").and_then(|t| DateTime::parse_from_rfc3339(t).ok()).map(|t| t.with_timezone(&Utc)),
+                duration: Duration::from_seconds(text(child(lap, "TotalTimeSeconds")).unwrap_or_default()),
+                distance: Distance::from_meters(text(child(lap, "DistanceMeters")).unwrap_or_default()),
+            })
+            .collect();
+        let points = root
+            .descendants()
+            .filter(|n| n.tag_name().name() == "Trackpoint")
+            .map(|point| TrackPoint {
+                time: time(child(point, "Time")),
+                position: child(point, "Position")
+                    .and_then(|p| text::<f64>(child(p, "LatitudeDegrees")).zip(text(child(p, "LongitudeDegrees")))),
+                elevation: text(child(point, "AltitudeMeters")),
+                distance: text(child(point, "DistanceMeters")),
+                heart_rate: child(point, "HeartRateBpm").and_then(|hr| text(child(hr, "Value"))),
+            })
+            .collect();
+        Self {
+            format: TrackFormat::Tcx,
+            name: text(activity.and_then(|a| child(a, "Notes"))).unwrap_or_default(),
+            sport: activity.and_then(|a| a.attribute("Sport")).unwrap_or_default().to_owned(),
+            ascent: None,
+            laps,
+            points,
+        }
+    }
+
+    /// Totals of the laps when the device wrote them, otherwise computed from the points
+    pub fn summary(&self) -> Result<TrackSummary, TrackError> {
+        let times: Vec<DateTime<Utc>> = self.points.iter().filter_map(|p| p.time).collect();
+        let started_at = self.laps.iter().find_map(|l| l.started_at).or(times.first().copied()).ok_or(TrackError::NoTime)?;
+
+        let laps_duration: f64 = self.laps.iter().map(|l| l.duration.seconds()).sum();
+        let duration = match times.last() {
+            _ if laps_duration > 0.0 => laps_duration,
+            Some(end) => (*end - started_at).num_seconds().max(0) as f64,
+            None => 0.0,
+        };
+
+        let laps_distance: f64 = self.laps.iter().map(|l| l.distance.meters()).sum();
+        let device_distance = self.points.iter().rev().find_map(|p| p.distance);
+        let distance = match device_distance {
+            _ if laps_distance > 0.0 => laps_distance,
+            Some(distance) => distance,
+            None => {
+                let positions: Vec<(f64, f64)> = self.points.iter().filter_map(|p| p.position).collect();
+                positions.windows(2).map(|w| haversine(w[0], w[1])).sum()
+            }
+        };
+
+        let elevations: Vec<f64> = self.points.iter().filter_map(|p| p.elevation).collect();
+        let elevation_gain = self.ascent.unwrap_or_else(|| elevations.windows(2).map(|w| (w[1] - w[0]).max(0.0)).sum());
+
+        let heart_rate = self.points
+            .iter()
+            .filter_map(|p| Some(((p.time? - started_at).num_seconds().max(0) as u32, p.heart_rate?)))
+            .collect();
+
+        Ok(TrackSummary {
+            name: self.name.clone(),
+            sport: self.sport.clone(),
+            started_at,
+            duration: Duration::from_seconds(duration),
+            distance: Distance::from_meters(distance),
+            elevation_gain: Length::from_meters(elevation_gain),
+            heart_rate,
+        })
+    }
+}
+
+/// What a recording adds up to
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackSummary {
+    pub name: String,
+    pub sport: String,
+    pub started_at: DateTime<Utc>,
+    pub duration: Duration,
+    pub distance: Distance,
+    pub elevation_gain: Length,
+    /// Seconds from the start with the beats per minute
+    pub heart_rate: Vec<(u32, u32)>,
+}
+
+impl TrackSummary {
+    /// Time per kilometer
+    pub fn pace(&self) -> Option<Duration> {
+        if self.distance.is_zero() || self.duration.is_zero() {
+            return None;
+        }
+        Some(Duration::from_seconds(self.duration.seconds() / self.distance.kilometers()))
+    }
+
+    pub fn average_heart_rate(&self) -> Option<u32> {
+        if self.heart_rate.is_empty() {
+            return None;
+        }
+        let total: u32 = self.heart_rate.iter().map(|(_, bpm)| bpm).sum();
+        Some((total as f64 / self.heart_rate.len() as f64).round() as u32)
+    }
+
+    pub fn max_heart_rate(&self) -> Option<u32> {
+        self.heart_rate.iter().map(|(_, bpm)| *bpm).max()
+    }
+
+    /// Exercise named after the sport, `running` finds `Run` as well
+    pub fn guess_exercise(&self, db: &Db) -> Option<ID> {
+        let sport = self.sport.to_lowercase();
+        let names: &[&str] = match sport.as_str() {
+            "running" | "run" => &["running", "run"],
+            "biking" | "cycling" | "ride" => &["cycling", "bike", "biking"],
+            "walking" | "hiking" => &["walking", "hiking", "walk"],
+            "swimming" => &["swimming", "swim"],
+            _ => &[],
+        };
+        names
+            .iter()
+            .copied()
+            .chain(std::iter::once(sport.as_str()))
+            .filter(|name| !name.is_empty())
+            .find_map(|name| quick_entry::find_exercise(db, name).ok())
+    }
+
+    /// Lines for the workout notes and the summary view
+    pub fn describe(&self, units: UnitSystem) -> String {
+        let mut lines = vec![
+            format!("Distance: {}", self.distance.format(units)),
+            format!("Duration: {}", self.duration.format(units)),
+        ];
+        if let Some(pace) = self.pace() {
+            let per = Distance::from_value_in(1.0, units).kilometers();
+            lines.push(format!("Pace: {}/{}", Duration::from_seconds(pace.seconds() * per).format(units), Distance::unit_symbol(units)));
+        }
+        // `Length` formats body heights in centimeters
+        let elevation_gain = match units {
+            UnitSystem::Metric => format!("{} m", format_number(self.elevation_gain.meters(), 0)),
+            UnitSystem::Imperial => format!("{} ft", format_number(self.elevation_gain.inches() / 12.0, 0)),
+        };
+        lines.push(format!("Elevation gain: {}", elevation_gain));
+        if let (Some(average), Some(max)) = (self.average_heart_rate(), self.max_heart_rate()) {
+            lines.push(format!("Heart rate: {} bpm average, {} bpm max", average, max));
+        }
+        lines.join("\n")
+    }
+
+    /// Workout in local time with one set holding the distance and duration
+    pub fn to_workout(&self, person_id: ID, exercise_id: ID, units: UnitSystem) -> (Workout, WorkoutSet) {
+        let name = [&self.name, &self.sport].into_iter().find(|n| !n.is_empty()).cloned().unwrap_or_else(|| "Cardio".to_owned());
+        let mut workout = Workout::new(0, person_id, name, self.started_at.with_timezone(&Local).naive_local(), self.duration);
+        workout.notes = self.describe(units);
+        let set = WorkoutSet {
+            distance: self.distance,
+            duration: self.duration,
+            ..WorkoutSet::new(0, 0, exercise_id, 1, 0, Mass::default())
+        };
+        (workout, set)
+    }
+
+    /// Id of the new workout, `None` when the person has one starting at the same time
+    pub fn import<T: RestoreTarget>(&self, person_id: ID, exercise_id: ID, units: UnitSystem, mut target: T) -> controller::Result<Option<ID>> {
+        let (workout, set) = self.to_workout(person_id, exercise_id, units);
+        let exists = target.lookup(|db| db.workouts().get_all().any(|w| w.person_id == person_id && w.started_at == workout.started_at));
+        if exists {
+            return Ok(None);
+        }
+        let workout_id = target.insert_workout(&workout)?;
+        target.insert_set(&WorkoutSet { workout_id, ..set })?;
+        Ok(Some(workout_id))
+    }
+}
@@ -0,0 +1,361 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use sport_core_db::{
+    entity::{Exercise, Workout, WorkoutSet, ID},
+    model::{Db, Table},
+    units::{Distance, Duration, Mass, ParseQuantityError, Quantity, UnitSystem},
+};
+use thiserror::Error;
+
+use crate::{
+    backup::RestoreTarget,
+    csv::{self, CsvError, CsvRecord},
+    quick_entry,
+};
+
+/// Start times of the exports, Strong first and then Hevy
+const DATE_FORMATS: [&str; 4] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%d %b %Y, %H:%M", "%Y-%m-%dT%H:%M:%S"];
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum WorkoutImportError {
+    #[error("{0}")]
+    Csv(#[from] CsvError),
+    #[error("Missing `{0}` column")]
+    MissingColumn(&'static str),
+    #[error("Line {line}: invalid {what} `{value}`")]
+    Value { line: usize, what: &'static str, value: String },
+}
+
+/// Layout of the export
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// `Date`, `Workout Name`, `Exercise Name`, `Set Order`, `Weight`, `Reps`, ...
+    Strong,
+    /// `title`, `start_time`, `exercise_title`, `set_index`, `weight_kg`, `reps`, ...
+    Hevy,
+}
+
+impl ImportFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ImportFormat::Strong => "Strong",
+            ImportFormat::Hevy => "Hevy",
+        }
+    }
+}
+
+/// Column of a value with the unit in its header name, `None` unit for the user's system
+#[derive(Clone, Copy, Debug, Default)]
+struct Column {
+    index: Option<usize>,
+    unit: Option<&'static str>,
+}
+
+#[derive(Debug)]
+struct Columns {
+    date: usize,
+    end: Column,
+    workout: Column,
+    duration: Column,
+    exercise: usize,
+    set_order: Column,
+    weight: Column,
+    weight_unit: Column,
+    reps: Column,
+    distance: Column,
+    seconds: Column,
+    notes: Column,
+    workout_notes: Column,
+    rpe: Column,
+}
+
+impl Columns {
+    fn new(header: &[String]) -> Result<Self, WorkoutImportError> {
+        let header: Vec<String> = header.iter().map(|h| h.trim().to_lowercase()).collect();
+        let find = |names: &[(&str, Option<&'static str>)]| {
+            names
+                .iter()
+                .find_map(|(name, unit)| header.iter().position(|h| h == name).map(|i| Column { index: Some(i), unit: *unit }))
+                .unwrap_or_default()
+        };
+        let required = |column: Column, name: &'static str| column.index.ok_or(WorkoutImportError::MissingColumn(name));
+        Ok(Self {
+            date: required(find(&[("date", None), ("start_time", None)]), "date")?,
+            end: find(&[("end_time", None)]),
+            workout: find(&[("workout name", None), ("title", None)]),
+            duration: find(&[("duration", None)]),
+            exercise: required(find(&[("exercise name", None), ("exercise_title", None), ("exercise", None)]), "exercise name")?,
+            set_order: find(&[("set order", None), ("set_index", None)]),
+            weight: find(&[("weight", None), ("weight_kg", Some("kg")), ("weight_lbs", Some("lb"))]),
+            weight_unit: find(&[("weight unit", None)]),
+            reps: find(&[("reps", None)]),
+            distance: find(&[("distance", None), ("distance_km", Some("km")), ("distance_miles", Some("mi"))]),
+            seconds: find(&[("seconds", None), ("duration_seconds", Some("s"))]),
+            notes: find(&[("notes", None), ("exercise_notes", None)]),
+            workout_notes: find(&[("workout notes", None), ("description", None)]),
+            rpe: find(&[("rpe", None)]),
+        })
+    }
+}
+
+/// Set of an imported workout, the exercise still by name
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedSet {
+    pub exercise: String,
+    pub reps: u32,
+    pub weight: Mass,
+    pub distance: Distance,
+    pub duration: Duration,
+    pub rpe: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedWorkout {
+    pub name: String,
+    pub started_at: NaiveDateTime,
+    pub duration: Duration,
+    pub notes: String,
+    pub sets: Vec<ImportedSet>,
+}
+
+/// Workouts of an export with the detected layout
+#[derive(Clone, Debug)]
+pub struct WorkoutImport {
+    pub format: ImportFormat,
+    pub workouts: Vec<ImportedWorkout>,
+}
+
+fn parse_date(value: &str) -> Option<NaiveDateTime> {
+    DATE_FORMATS.iter().find_map(|f| NaiveDateTime::parse_from_str(value.trim(), f).ok())
+}
+
+/// Strong durations like `1h 5m` or `45m 10s`, anything else as a plain duration
+fn parse_duration(value: &str) -> Result<Duration, ParseQuantityError> {
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    if parts.len() > 1 || parts.iter().any(|p| p.ends_with('m') && !p.ends_with("min")) {
+        return parts.iter().try_fold(Duration::default(), |total, part| {
+            let part = match part.strip_suffix('m') {
+                Some(number) => Duration::from_minutes(number.parse().map_err(|_| ParseQuantityError::Number(part.to_string()))?),
+                None => Duration::parse(part, UnitSystem::Metric)?,
+            };
+            Ok(total + part)
+        });
+    }
+    Duration::parse(value, UnitSystem::Metric)
+}
+
+/// Empty fields are zero, values without a unit column are in `units`
+fn quantity<Q: Quantity + Default>(record: &CsvRecord, column: Column, unit: Option<&str>, units: UnitSystem, what: &'static str) -> Result<Q, WorkoutImportError> {
+    let Some(index) = column.index else {
+        return Ok(Q::default());
+    };
+    let value = record.get(index).trim();
+    if value.is_empty() {
+        return Ok(Q::default());
+    }
+    let text = match column.unit.or(unit) {
+        Some(unit) => format!("{} {}", value, unit),
+        None => value.to_owned(),
+    };
+    Q::parse(&text, units).map_err(|_| WorkoutImportError::Value { line: record.line, what, value: value.to_owned() })
+}
+
+fn text(record: &CsvRecord, column: Column) -> String {
+    column.index.map(|i| record.get(i).trim().to_owned()).unwrap_or_default()
+}
+
+impl WorkoutImport {
+    /// Sets grouped into workouts by start time and name, in the file order
+    pub fn read(text_data: &str, units: UnitSystem) -> Result<Self, WorkoutImportError> {
+        let mut records = csv::parse(text_data)?.into_iter();
+        let header = records.next().ok_or(CsvError::Empty)?.fields;
+        let format = if header.iter().any(|h| h.trim() == "exercise_title") { ImportFormat::Hevy } else { ImportFormat::Strong };
+        let columns = Columns::new(&header)?;
+
+        let mut workouts: Vec<ImportedWorkout> = vec![];
+        for record in records {
+            // Strong writes rest timers as rows of their own
+            if text(&record, columns.set_order).eq_ignore_ascii_case("rest timer") {
+                continue;
+            }
+            let date = record.get(columns.date);
+            let started_at = parse_date(date)
+                .ok_or_else(|| WorkoutImportError::Value { line: record.line, what: "date", value: date.to_owned() })?;
+            let name = text(&record, columns.workout);
+            let exercise = record.get(columns.exercise).trim().to_owned();
+            if exercise.is_empty() {
+                return Err(WorkoutImportError::Value { line: record.line, what: "exercise name", value: exercise });
+            }
+            let reps = text(&record, columns.reps);
+            let rpe = text(&record, columns.rpe);
+            let weight_unit = text(&record, columns.weight_unit);
+            let set = ImportedSet {
+                exercise,
+                reps: match reps.split('.').next().unwrap_or_default() {
+                    "" => 0,
+                    r => r.parse().map_err(|_| WorkoutImportError::Value { line: record.line, what: "reps", value: reps.clone() })?,
+                },
+                weight: quantity(&record, columns.weight, Some(weight_unit.as_str()).filter(|u| !u.is_empty()), units, "weight")?,
+                distance: quantity(&record, columns.distance, None, units, "distance")?,
+                duration: quantity(&record, columns.seconds, Some("s"), units, "seconds")?,
+                rpe: match rpe.as_str() {
+                    "" => None,
+                    r => Some(r.parse().map_err(|_| WorkoutImportError::Value { line: record.line, what: "RPE", value: rpe.clone() })?),
+                },
+            };
+
+            let set_notes = text(&record, columns.notes);
+            let workout = match workouts.iter_mut().position(|w| w.started_at == started_at && w.name == name) {
+                Some(i) => &mut workouts[i],
+                None => {
+                    let duration = match (columns.duration.index, columns.end.index.and_then(|i| parse_date(record.get(i)))) {
+                        (_, Some(end)) => Duration::from_seconds((end - started_at).num_seconds().max(0) as f64),
+                        (Some(i), None) if !record.get(i).trim().is_empty() => parse_duration(record.get(i))
+                            .map_err(|_| WorkoutImportError::Value { line: record.line, what: "duration", value: record.get(i).to_owned() })?,
+                        _ => Duration::default(),
+                    };
+                    let notes = text(&record, columns.workout_notes);
+                    workouts.push(ImportedWorkout { name, started_at, duration, notes, sets: vec![] });
+                    workouts.last_mut().unwrap()
+                }
+            };
+            // sets have no notes of their own, they are kept with the workout
+            let set_notes = format!("{}: {}", set.exercise, set_notes);
+            if !set_notes.ends_with(": ") && !workout.notes.lines().any(|l| l == set_notes) {
+                if !workout.notes.is_empty() {
+                    workout.notes.push('\n');
+                }
+                workout.notes.push_str(&set_notes);
+            }
+            workout.sets.push(set);
+        }
+        Ok(Self { format, workouts })
+    }
+
+    /// Exercise names in the order of their first set
+    pub fn exercise_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = vec![];
+        for set in self.workouts.iter().flat_map(|w| &w.sets) {
+            if !names.iter().any(|n| n.eq_ignore_ascii_case(&set.exercise)) {
+                names.push(set.exercise.clone());
+            }
+        }
+        names
+    }
+
+    pub fn sets_count(&self) -> usize {
+        self.workouts.iter().map(|w| w.sets.len()).sum()
+    }
+}
+
+/// What an imported exercise name becomes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resolution {
+    Existing(ID),
+    Create,
+    /// Sets of the exercise are left out
+    Skip,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExerciseMapping {
+    pub name: String,
+    pub resolution: Resolution,
+    /// Matched by a similar name rather than the same one, to be confirmed
+    pub is_guess: bool,
+}
+
+/// Same names map to their exercise, similar ones are guessed and unknown ones skipped
+pub fn map_exercises(db: &Db, names: &[String]) -> Vec<ExerciseMapping> {
+    names
+        .iter()
+        .map(|name| {
+            let same = db.exercises().get_all().find(|e| e.name.trim().eq_ignore_ascii_case(name.trim())).map(|e| e.id);
+            let (resolution, is_guess) = match same {
+                Some(id) => (Resolution::Existing(id), false),
+                // Strong adds the equipment like `Squat (Barbell)`
+                None => match quick_entry::find_exercise(db, name)
+                    .or_else(|_| quick_entry::find_exercise(db, name.split(" (").next().unwrap_or_default()))
+                {
+                    Ok(id) => (Resolution::Existing(id), true),
+                    Err(_) => (Resolution::Skip, false),
+                },
+            };
+            ExerciseMapping { name: name.clone(), resolution, is_guess }
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    pub exercises_created: usize,
+    pub workouts_created: usize,
+    /// Workouts with the same start and name already there
+    pub workouts_existing: usize,
+    pub sets_created: usize,
+    /// Sets of skipped exercises
+    pub sets_skipped: usize,
+}
+
+impl ImportReport {
+    pub fn describe(&self) -> String {
+        format!(
+            "Exercises created: {}\nWorkouts created: {}, already there: {}\nSets created: {}, skipped: {}",
+            self.exercises_created, self.workouts_created, self.workouts_existing, self.sets_created, self.sets_skipped,
+        )
+    }
+}
+
+/// Creates the exercises marked for it, then the new workouts of `person_id` with their sets
+pub fn import<T: RestoreTarget>(
+    import: &WorkoutImport,
+    mappings: &[ExerciseMapping],
+    person_id: ID,
+    mut target: T,
+) -> Result<ImportReport, crate::rest::RestClientError> {
+    let mut report = ImportReport::default();
+    let mut exercises: HashMap<String, ID> = HashMap::new();
+    for mapping in mappings {
+        let id = match mapping.resolution {
+            Resolution::Existing(id) => id,
+            Resolution::Create => {
+                report.exercises_created += 1;
+                target.insert_exercise(&Exercise::new(0, mapping.name.clone(), String::new()))?
+            }
+            Resolution::Skip => continue,
+        };
+        exercises.insert(mapping.name.to_lowercase(), id);
+    }
+
+    for imported in &import.workouts {
+        let exists = target.lookup(|db| db.workouts()
+            .get_all()
+            .any(|w| w.person_id == person_id && w.started_at == imported.started_at && w.name == imported.name));
+        if exists {
+            report.workouts_existing += 1;
+            continue;
+        }
+        let mut workout = Workout::new(0, person_id, imported.name.clone(), imported.started_at, imported.duration);
+        workout.notes = imported.notes.clone();
+        let workout_id = target.insert_workout(&workout)?;
+        report.workouts_created += 1;
+
+        let mut order = 0;
+        for set in &imported.sets {
+            let Some(exercise_id) = exercises.get(&set.exercise.to_lowercase()) else {
+                report.sets_skipped += 1;
+                continue;
+            };
+            order += 1;
+            target.insert_set(&WorkoutSet {
+                distance: set.distance,
+                duration: set.duration,
+                rpe: set.rpe,
+                ..WorkoutSet::new(0, workout_id, *exercise_id, order, set.reps, set.weight)
+            })?;
+            report.sets_created += 1;
+        }
+    }
+    Ok(report)
+}
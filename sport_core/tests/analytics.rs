@@ -0,0 +1,216 @@
+use chrono::NaiveDate;
+use sport_core::analytics::{self, OneRepMaxFormula, Period, Schedule, StreakRule, StreakUnit, Streaks, Volume};
+use sport_core_db::{
+    entity::{Exercise, MuscleGroup, Workout, WorkoutSet, ID},
+    model::{Db, Table},
+    units::{Duration, Mass},
+};
+
+const PERSON: ID = 1;
+const OTHER_PERSON: ID = 2;
+const BENCH: ID = 10;
+const ROW: ID = 11;
+
+fn date(month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+}
+
+fn kilograms(mass: Option<Mass>) -> f64 {
+    (mass.unwrap().kilograms() * 100.0).round() / 100.0
+}
+
+fn add_workout(db: &mut Db, id: ID, person_id: ID, date: NaiveDate) {
+    let started_at = date.and_hms_opt(18, 0, 0).unwrap();
+    db.workouts_mut().insert(Workout::new(id, person_id, "Workout".to_owned(), started_at, Duration::default()));
+}
+
+fn add_set(db: &mut Db, id: ID, workout_id: ID, exercise_id: ID, kilograms: f64, reps: u32) {
+    db.sets_mut().insert(WorkoutSet::new(id, workout_id, exercise_id, id as u32, reps, Mass::from_kilograms(kilograms)));
+}
+
+/// Two weeks of bench press and rows, plus a workout of another person
+fn sample_db() -> Db {
+    let mut db = Db::default();
+    db.exercises_mut().insert(Exercise {
+        muscle_groups: vec![MuscleGroup::Chest, MuscleGroup::Triceps],
+        ..Exercise::new(BENCH, "Bench press".to_owned(), "".to_owned())
+    });
+    db.exercises_mut().insert(Exercise {
+        muscle_groups: vec![MuscleGroup::Back, MuscleGroup::Biceps],
+        ..Exercise::new(ROW, "Row".to_owned(), "".to_owned())
+    });
+    // Monday and Thursday of the first week, Tuesday of the second
+    add_workout(&mut db, 1, PERSON, date(3, 2));
+    add_workout(&mut db, 2, PERSON, date(3, 5));
+    add_workout(&mut db, 3, PERSON, date(3, 10));
+    add_workout(&mut db, 4, OTHER_PERSON, date(3, 3));
+    add_set(&mut db, 1, 1, BENCH, 80.0, 5);
+    add_set(&mut db, 2, 1, BENCH, 80.0, 5);
+    add_set(&mut db, 3, 1, ROW, 60.0, 10);
+    add_set(&mut db, 4, 2, BENCH, 85.0, 3);
+    add_set(&mut db, 5, 3, BENCH, 90.0, 2);
+    add_set(&mut db, 6, 3, ROW, 70.0, 8);
+    add_set(&mut db, 7, 4, BENCH, 200.0, 1);
+    db
+}
+
+#[test]
+fn formulas_estimate_one_rep_max() {
+    let weight = Mass::from_kilograms(100.0);
+    assert_eq!(kilograms(OneRepMaxFormula::Epley.estimate(weight, 10)), 133.33);
+    assert_eq!(kilograms(OneRepMaxFormula::Brzycki.estimate(weight, 10)), 133.33);
+    assert_eq!(kilograms(OneRepMaxFormula::Lombardi.estimate(weight, 10)), 125.89);
+    assert_eq!(kilograms(OneRepMaxFormula::Epley.estimate(weight, 5)), 116.67);
+    assert_eq!(kilograms(OneRepMaxFormula::Brzycki.estimate(weight, 5)), 112.5);
+}
+
+#[test]
+fn single_rep_is_the_weight_for_every_formula() {
+    let weight = Mass::from_kilograms(140.0);
+    for formula in OneRepMaxFormula::ALL {
+        assert_eq!(formula.estimate(weight, 1), Some(weight));
+        assert!(formula.estimate(weight, 0).unwrap().is_zero());
+    }
+}
+
+#[test]
+fn brzycki_gives_no_estimate_above_twelve_reps() {
+    let weight = Mass::from_kilograms(20.0);
+    assert_eq!(kilograms(OneRepMaxFormula::Brzycki.estimate(weight, 12)), 28.8);
+    assert_eq!(OneRepMaxFormula::Brzycki.estimate(weight, 13), None);
+    assert_eq!(OneRepMaxFormula::Brzycki.estimate(weight, 50), None);
+    assert!(OneRepMaxFormula::Epley.estimate(weight, 50).is_some());
+}
+
+#[test]
+fn periods() {
+    // 2026-03-05 is a Thursday
+    assert_eq!(Period::week_of(date(3, 5)), Period::new(date(3, 2), date(3, 8)));
+    assert_eq!(Period::month_of(date(2, 14)), Period::new(date(2, 1), date(2, 28)));
+    assert_eq!(Period::month_of(date(12, 31)), Period::new(date(12, 1), date(12, 31)));
+    assert_eq!(Period::last_days(7, date(3, 8)), Period::new(date(3, 2), date(3, 8)));
+    assert_eq!(Period::new(date(3, 2), date(3, 8)).previous(), Period::new(date(2, 23), date(3, 1)));
+    assert_eq!(Period::month_of(date(3, 1)).days(), 31);
+}
+
+#[test]
+fn workout_volume_sums_its_sets() {
+    let db = sample_db();
+    let volume = analytics::workout_volume(&db, 1);
+    assert_eq!(volume, Volume { sets: 3, reps: 20, tonnage: Mass::from_kilograms(1400.0) });
+    assert!(analytics::workout_volume(&db, 100).is_empty());
+}
+
+#[test]
+fn exercise_volume_is_limited_to_person_and_period() {
+    let db = sample_db();
+    let first_week = Period::week_of(date(3, 2));
+    let volume = analytics::exercise_volume(&db, PERSON, BENCH, first_week);
+    assert_eq!(volume, Volume { sets: 3, reps: 13, tonnage: Mass::from_kilograms(1055.0) });
+
+    let volumes = analytics::volume_by_exercise(&db, PERSON, Period::until(date(3, 31)));
+    assert_eq!(volumes[&BENCH].sets, 4);
+    assert_eq!(volumes[&ROW].tonnage, Mass::from_kilograms(1160.0));
+}
+
+#[test]
+fn muscle_group_volume_counts_every_group_of_the_exercise() {
+    let db = sample_db();
+    let volumes = analytics::volume_by_muscle_group(&db, PERSON, Period::until(date(3, 31)));
+    assert_eq!(volumes.len(), 4);
+    assert_eq!(volumes[&MuscleGroup::Chest], volumes[&MuscleGroup::Triceps]);
+    assert_eq!(volumes[&MuscleGroup::Chest].sets, 4);
+    assert_eq!(volumes[&MuscleGroup::Back].sets, 2);
+}
+
+#[test]
+fn weekly_volume_includes_empty_weeks() {
+    let db = sample_db();
+    let weeks = analytics::weekly_volume(&db, PERSON, Period::new(date(3, 2), date(3, 22)));
+    let sessions: Vec<(NaiveDate, u32, u32)> = weeks.iter().map(|w| (w.week_start, w.sessions, w.volume.sets)).collect();
+    assert_eq!(sessions, vec![(date(3, 2), 2, 4), (date(3, 9), 1, 2), (date(3, 16), 0, 0)]);
+
+    let weeks = analytics::weekly_volume(&db, PERSON, Period::until(date(3, 10)));
+    assert_eq!(weeks.len(), 2);
+    assert!(analytics::weekly_volume(&Db::default(), PERSON, Period::until(date(3, 10))).is_empty());
+}
+
+#[test]
+fn best_one_rep_max_uses_the_formula() {
+    let db = sample_db();
+    let all = Period::until(date(3, 31));
+    let epley = analytics::best_one_rep_max(&db, PERSON, BENCH, all, OneRepMaxFormula::Epley);
+    assert_eq!(kilograms(epley), 96.0);
+    let brzycki = analytics::best_one_rep_max(&db, PERSON, BENCH, all, OneRepMaxFormula::Brzycki);
+    assert_eq!(kilograms(brzycki), 92.57);
+    assert!(analytics::best_one_rep_max(&db, OTHER_PERSON, ROW, all, OneRepMaxFormula::Epley).is_none());
+}
+
+#[test]
+fn relative_intensity_compares_with_one_rep_max() {
+    let set = WorkoutSet::new(1, 1, BENCH, 1, 5, Mass::from_kilograms(80.0));
+    assert_eq!(analytics::relative_intensity(&set, Mass::from_kilograms(100.0)), Some(0.8));
+    assert_eq!(analytics::relative_intensity(&set, Mass::default()), None);
+
+    let db = sample_db();
+    // every row set is the best estimation up to its date
+    let intensity = analytics::average_intensity(&db, PERSON, ROW, Period::until(date(3, 31)), OneRepMaxFormula::Epley).unwrap();
+    let first = 60.0 / (60.0 * (1.0 + 10.0 / 30.0));
+    let second = 70.0 / (70.0 * (1.0 + 8.0 / 30.0));
+    assert!((intensity - (first + second) / 2.0).abs() < 1e-9);
+}
+
+#[test]
+fn histories_have_a_point_per_day() {
+    let mut db = sample_db();
+    add_set(&mut db, 8, 1, BENCH, 85.0, 5);
+    let all = Period::until(date(3, 31));
+
+    let one_rep_max = analytics::one_rep_max_history(&db, PERSON, BENCH, all, OneRepMaxFormula::Epley);
+    let one_rep_max: Vec<(NaiveDate, f64)> = one_rep_max.iter().map(|p| (p.date, (p.value * 100.0).round() / 100.0)).collect();
+    assert_eq!(one_rep_max, vec![(date(3, 2), 99.17), (date(3, 5), 93.5), (date(3, 10), 96.0)]);
+
+    let tonnage = analytics::tonnage_history(&db, PERSON, Some(ROW), Period::new(date(3, 1), date(3, 7)));
+    assert_eq!(tonnage.len(), 1);
+    assert_eq!(tonnage[0].value, 600.0);
+    let tonnage = analytics::tonnage_history(&db, PERSON, None, all);
+    assert_eq!(tonnage.iter().map(|p| p.value).sum::<f64>(), 1400.0 + 425.0 + 255.0 + 180.0 + 560.0);
+}
+
+#[test]
+fn weekly_streak_is_not_broken_by_the_current_week() {
+    let db = sample_db();
+    let rule = StreakRule::default();
+    // weeks of March 2nd and 9th have sessions, the week of the 16th is in progress
+    assert_eq!(analytics::streaks(&db, PERSON, rule, date(3, 18)), Streaks { current: 2, longest: 2 });
+    assert_eq!(analytics::streaks(&db, PERSON, rule, date(3, 25)), Streaks { current: 0, longest: 2 });
+
+    let rule = StreakRule { min_sessions: 2, ..rule };
+    assert_eq!(analytics::streaks(&db, PERSON, rule, date(3, 11)), Streaks { current: 1, longest: 1 });
+    assert_eq!(analytics::streaks(&db, PERSON, rule, date(3, 16)), Streaks { current: 0, longest: 1 });
+    assert_eq!(analytics::streaks(&db, OTHER_PERSON + 1, rule, date(3, 16)), Streaks::default());
+}
+
+#[test]
+fn daily_streak_counts_consecutive_days() {
+    let mut db = sample_db();
+    add_workout(&mut db, 5, PERSON, date(3, 11));
+    add_workout(&mut db, 6, PERSON, date(3, 12));
+    let rule = StreakRule { unit: StreakUnit::Day, min_sessions: 1 };
+    assert_eq!(analytics::streaks(&db, PERSON, rule, date(3, 12)), Streaks { current: 3, longest: 3 });
+    assert_eq!(analytics::streaks(&db, PERSON, rule, date(3, 13)), Streaks { current: 3, longest: 3 });
+    assert_eq!(analytics::streaks(&db, PERSON, rule, date(3, 14)), Streaks { current: 0, longest: 3 });
+}
+
+#[test]
+fn adherence_caps_extra_sessions_per_week() {
+    let db = sample_db();
+    let schedule = Schedule { sessions_per_week: 2 };
+    let adherence = analytics::adherence(&db, PERSON, schedule, analytics::last_complete_weeks(3, date(3, 18)));
+    assert_eq!((adherence.planned, adherence.done, adherence.missed), (6, 3, 3));
+    assert_eq!(adherence.percent, 50.0);
+
+    let adherence = analytics::adherence(&db, PERSON, Schedule { sessions_per_week: 1 }, Period::new(date(3, 2), date(3, 15)));
+    assert_eq!((adherence.planned, adherence.done, adherence.missed), (2, 2, 0));
+    assert_eq!(analytics::adherence(&db, PERSON, Schedule { sessions_per_week: 0 }, Period::new(date(3, 2), date(3, 15))).percent, 100.0);
+}
@@ -0,0 +1,194 @@
+use chrono::NaiveDate;
+use sport_core::{
+    backup::{self, Backup, BackupError, DryRun, RestoreTarget, TableReport},
+    config::Config,
+    controller,
+};
+use sport_core_db::{
+    entity::{Exercise, Gender, Goal, GoalMetric, Measurement, Person, Routine, RoutineExercise, Workout, WorkoutSet, ID},
+    model::{Db, Table},
+    units::{Duration, Length, Mass, UnitSystem},
+};
+
+const PERSON: ID = 1;
+const SQUAT: ID = 10;
+const BENCH: ID = 11;
+const WORKOUT: ID = 20;
+const ROUTINE: ID = 30;
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 5, day).unwrap()
+}
+
+fn person() -> Person {
+    Person::new(PERSON, "Ann".to_owned(), "Lee".to_owned(), date(1), Gender::Female, Length::from_centimeters(170.0))
+}
+
+fn source() -> Db {
+    let mut db = Db::default();
+    db.persons_mut().insert(person());
+    db.exercises_mut().insert(Exercise::new(SQUAT, "Squat".to_owned(), String::new()));
+    db.exercises_mut().insert(Exercise::new(BENCH, "Bench press".to_owned(), String::new()));
+    let mut routine = Routine::new(ROUTINE, PERSON, "Legs".to_owned());
+    routine.exercises.push(RoutineExercise { exercise_id: SQUAT, sets: 3, min_reps: 5, max_reps: 5, ..RoutineExercise::default() });
+    db.routines_mut().insert(routine);
+    let started_at = date(10).and_hms_opt(18, 0, 0).unwrap();
+    db.workouts_mut().insert(Workout {
+        routine_id: Some(ROUTINE),
+        ..Workout::new(WORKOUT, PERSON, "Legs".to_owned(), started_at, Duration::from_minutes(60.0))
+    });
+    db.sets_mut().insert(WorkoutSet::new(40, WORKOUT, SQUAT, 1, 5, Mass::from_kilograms(100.0)));
+    db.sets_mut().insert(WorkoutSet::new(41, WORKOUT, BENCH, 2, 8, Mass::from_kilograms(70.0)));
+    db.measurements_mut().insert(Measurement::new(50, PERSON, date(10), Mass::from_kilograms(60.0)));
+    db.goals_mut().insert(Goal::new(60, PERSON, GoalMetric::OneRepMax { exercise_id: BENCH }, 80.0, date(1), date(30)));
+    db
+}
+
+/// Server stand-in numbering new entries from 100
+struct Memory {
+    db: Db,
+    next_id: ID,
+}
+
+impl Memory {
+    fn new(db: Db) -> Self {
+        Self { db, next_id: 100 }
+    }
+
+    fn next(&mut self) -> ID {
+        self.next_id += 1;
+        self.next_id
+    }
+}
+
+impl RestoreTarget for &mut Memory {
+    fn lookup<R>(&self, f: impl FnOnce(&Db) -> R) -> R { f(&self.db) }
+
+    fn insert_person(&mut self, data: &Person) -> controller::Result<ID> {
+        let id = self.next();
+        self.db.persons_mut().insert(Person { id, ..data.clone() });
+        Ok(id)
+    }
+
+    fn insert_exercise(&mut self, data: &Exercise) -> controller::Result<ID> {
+        let id = self.next();
+        self.db.exercises_mut().insert(Exercise { id, ..data.clone() });
+        Ok(id)
+    }
+
+    fn insert_workout(&mut self, data: &Workout) -> controller::Result<ID> {
+        let id = self.next();
+        self.db.workouts_mut().insert(Workout { id, ..data.clone() });
+        Ok(id)
+    }
+
+    fn insert_set(&mut self, data: &WorkoutSet) -> controller::Result<ID> {
+        let id = self.next();
+        self.db.sets_mut().insert(WorkoutSet { id, ..data.clone() });
+        Ok(id)
+    }
+
+    fn insert_measurement(&mut self, data: &Measurement) -> controller::Result<ID> {
+        let id = self.next();
+        self.db.measurements_mut().insert(Measurement { id, ..data.clone() });
+        Ok(id)
+    }
+
+    fn insert_goal(&mut self, data: &Goal) -> controller::Result<ID> {
+        let id = self.next();
+        self.db.goals_mut().insert(Goal { id, ..data.clone() });
+        Ok(id)
+    }
+
+    fn insert_routine(&mut self, data: &Routine) -> controller::Result<ID> {
+        let id = self.next();
+        self.db.routines_mut().insert(Routine { id, ..data.clone() });
+        Ok(id)
+    }
+}
+
+fn backup() -> Backup {
+    let cfg = Config { units: UnitSystem::Imperial, ..Config::default() };
+    Backup::new(&source(), &cfg, date(11).and_hms_opt(9, 0, 0).unwrap())
+}
+
+fn counts(report: &backup::RestoreReport) -> Vec<(&'static str, usize, usize, usize)> {
+    report.tables.iter().map(|t: &TableReport| (t.table, t.created, t.existing, t.skipped)).collect()
+}
+
+#[test]
+fn json_round_trip_and_version_check() {
+    let backup = backup();
+    let json = backup.to_json().unwrap();
+    let read = Backup::from_json(&json).unwrap();
+    assert_eq!((read.version, read.sets.len(), read.persons[0].first_name.as_str()), (backup::VERSION, 2, "Ann"));
+    assert!(!read.settings.contains_key("server") && !read.settings.contains_key("account"));
+    assert_eq!(read.file_name(), "sport_helper-backup-2026-05-11.json");
+
+    let newer = json.replacen(&format!("\"version\": {}", backup::VERSION), "\"version\": 99", 1);
+    assert!(matches!(Backup::from_json(&newer), Err(BackupError::Version(99))));
+    assert!(matches!(Backup::from_json("{"), Err(BackupError::Json(_))));
+}
+
+#[test]
+fn restore_into_empty_server_remaps_ids() {
+    let mut server = Memory::new(Db::default());
+    let report = backup::restore(&backup(), &mut server).unwrap();
+    assert_eq!(report.created(), 9);
+    let db = &server.db;
+    let person = db.persons().get_all().next().unwrap().id;
+    let squat = db.exercises().get_all().find(|e| e.name == "Squat").unwrap().id;
+    let bench = db.exercises().get_all().find(|e| e.name == "Bench press").unwrap().id;
+    let routine = db.routines().get_all().next().unwrap();
+    assert_eq!((routine.person_id, routine.exercises[0].exercise_id), (person, squat));
+    let workout = db.workouts().get_all().next().unwrap();
+    assert_eq!((workout.person_id, workout.routine_id), (person, Some(routine.id)));
+    let mut sets: Vec<(ID, ID, u32)> = db.sets().get_all().map(|s| (s.workout_id, s.exercise_id, s.order)).collect();
+    sets.sort_by_key(|s| s.2);
+    assert_eq!(sets, vec![(workout.id, squat, 1), (workout.id, bench, 2)]);
+    let goal = db.goals().get_all().next().unwrap();
+    assert_eq!((goal.person_id, goal.metric), (person, GoalMetric::OneRepMax { exercise_id: bench }));
+}
+
+#[test]
+fn restore_reuses_existing_entries() {
+    let mut existing = Db::default();
+    existing.persons_mut().insert(Person { id: 7, ..person() });
+    existing.exercises_mut().insert(Exercise::new(8, "SQUAT".to_owned(), String::new()));
+    let mut server = Memory::new(existing);
+    let report = backup::restore(&backup(), &mut server).unwrap();
+    assert_eq!(counts(&report), vec![
+        ("persons", 0, 1, 0),
+        ("exercises", 1, 1, 0),
+        ("routines", 1, 0, 0),
+        ("workouts", 1, 0, 0),
+        ("sets", 2, 0, 0),
+        ("measurements", 1, 0, 0),
+        ("goals", 1, 0, 0),
+    ]);
+    assert!(server.db.sets().get_all().any(|s| s.exercise_id == 8));
+    // a second restore finds everything
+    let again = backup::restore(&backup(), &mut server).unwrap();
+    assert_eq!(again.created(), 0);
+    assert!(again.describe().starts_with("persons: 0 new, 1 existing, 0 skipped\nexercises: 0 new, 2 existing"));
+}
+
+#[test]
+fn dry_run_reports_without_changes() {
+    let mut backup = backup();
+    backup.sets.push(WorkoutSet::new(42, 999, SQUAT, 3, 5, Mass::default()));
+    let db = Db::default();
+    let report = backup::restore(&backup, DryRun::new(&db)).unwrap();
+    assert_eq!(report.created(), 9);
+    assert_eq!(report.tables[4], TableReport { table: "sets", created: 2, existing: 0, skipped: 1 });
+    assert!(db.sets().is_empty());
+}
+
+#[test]
+fn settings_keep_the_server_and_account() {
+    let mut cfg = Config::default();
+    cfg.account.id = 5;
+    cfg.server.port = 9000;
+    let restored = backup().apply_settings(&cfg).unwrap();
+    assert_eq!((restored.units, restored.account.id, restored.server.port), (UnitSystem::Imperial, 5, 9000));
+}
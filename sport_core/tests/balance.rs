@@ -0,0 +1,102 @@
+use chrono::NaiveDate;
+use sport_core::balance::{self, BalanceWarning, Status};
+use sport_core_db::{
+    entity::{Exercise, MuscleGroup, Workout, WorkoutSet, ID},
+    model::{Db, Table},
+    units::{Duration, Mass},
+};
+
+const PERSON: ID = 1;
+const BENCH: ID = 10;
+const ROW: ID = 11;
+const SQUAT: ID = 12;
+
+fn date(month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+}
+
+fn exercise(id: ID, groups: Vec<MuscleGroup>) -> Exercise {
+    Exercise { muscle_groups: groups, ..Exercise::new(id, format!("Exercise {}", id), "".to_owned()) }
+}
+
+/// Logs a workout with `sets` sets of every listed exercise
+fn log(db: &mut Db, date: NaiveDate, exercises: &[(ID, u32)]) {
+    let workout_id = db.workouts().get_all().count() as ID + 1;
+    let started_at = date.and_hms_opt(18, 0, 0).unwrap();
+    db.workouts_mut().insert(Workout::new(workout_id, PERSON, "Workout".to_owned(), started_at, Duration::default()));
+    for (exercise_id, sets) in exercises {
+        for _ in 0..*sets {
+            let id = db.sets().get_all().count() as ID + 1;
+            db.sets_mut().insert(WorkoutSet::new(id, workout_id, *exercise_id, id as u32, 8, Mass::from_kilograms(50.0)));
+        }
+    }
+}
+
+fn sample_db() -> Db {
+    let mut db = Db::default();
+    db.exercises_mut().insert(exercise(BENCH, vec![MuscleGroup::Chest, MuscleGroup::Triceps]));
+    db.exercises_mut().insert(exercise(ROW, vec![MuscleGroup::Back, MuscleGroup::Biceps]));
+    db.exercises_mut().insert(exercise(SQUAT, vec![MuscleGroup::Quads, MuscleGroup::Glutes]));
+    // three steady weeks before March 23rd
+    for day in [2, 9, 16] {
+        log(&mut db, date(3, day), &[(BENCH, 3), (ROW, 3), (SQUAT, 6)]);
+    }
+    db
+}
+
+#[test]
+fn steady_training_has_no_warnings() {
+    let mut db = sample_db();
+    log(&mut db, date(3, 23), &[(BENCH, 3), (ROW, 3), (SQUAT, 6)]);
+    let balance = balance::balance(&db, PERSON, date(3, 24));
+    assert!(balance.warnings.is_empty());
+    assert_eq!(balance.push_pull, Some(1.0));
+    assert_eq!(balance.upper_lower, Some(1.0));
+    let chest = balance.groups.iter().find(|g| g.group == MuscleGroup::Chest).unwrap();
+    assert_eq!((chest.weekly_sets, chest.chronic_sets, chest.acwr), (3, 3.0, Some(1.0)));
+    assert_eq!(chest.status, Status::Ok);
+}
+
+#[test]
+fn sudden_volume_is_a_load_spike() {
+    let mut db = sample_db();
+    log(&mut db, date(3, 23), &[(BENCH, 9), (ROW, 3), (SQUAT, 6)]);
+    let balance = balance::balance(&db, PERSON, date(3, 24));
+    let chest = balance.groups.iter().find(|g| g.group == MuscleGroup::Chest).unwrap();
+    assert_eq!(chest.acwr, Some(3.0));
+    assert_eq!(chest.status, Status::Danger);
+    assert!(balance.warnings.contains(&BalanceWarning::LoadSpike { group: MuscleGroup::Chest, acwr: 3.0 }));
+    assert!(balance.warnings.contains(&BalanceWarning::PushPull(3.0)));
+    assert_eq!(balance.warnings[0].status(), Status::Danger);
+}
+
+#[test]
+fn skipped_groups_and_missing_ratios() {
+    let mut db = sample_db();
+    log(&mut db, date(3, 23), &[(BENCH, 3), (ROW, 3)]);
+    let balance = balance::balance(&db, PERSON, date(3, 24));
+    assert_eq!(balance.upper_lower, None);
+    assert!(balance.warnings.contains(&BalanceWarning::Skipped(MuscleGroup::Quads)));
+    let quads = balance.groups.iter().find(|g| g.group == MuscleGroup::Quads).unwrap();
+    assert_eq!(quads.status, Status::Warning);
+}
+
+#[test]
+fn first_week_has_no_workload_ratio() {
+    let mut db = Db::default();
+    db.exercises_mut().insert(exercise(BENCH, vec![MuscleGroup::Chest]));
+    log(&mut db, date(3, 2), &[(BENCH, 5)]);
+    let balance = balance::balance(&db, PERSON, date(3, 3));
+    assert_eq!(balance.groups.len(), 1);
+    assert_eq!(balance.groups[0].acwr, None);
+    assert!(balance.warnings.is_empty());
+}
+
+#[test]
+fn ratio_statuses_are_symmetric() {
+    assert_eq!(balance::ratio_status(1.2), Status::Ok);
+    assert_eq!(balance::ratio_status(1.6), Status::Warning);
+    assert_eq!(balance::ratio_status(1.0 / 1.6), Status::Warning);
+    assert_eq!(balance::ratio_status(0.4), Status::Danger);
+    assert_eq!(balance::acwr_status(1.4), Status::Warning);
+}
@@ -0,0 +1,73 @@
+use chrono::NaiveDate;
+use sport_core::calculators::{self, ActivityLevel, BmiCategory, BmrFormula};
+use sport_core_db::{
+    entity::{Gender, Measurement, Person, ID},
+    model::{Db, Table},
+    units::{Length, Mass},
+};
+
+const PERSON: ID = 1;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 0.01, "{} != {}", actual, expected);
+}
+
+#[test]
+fn body_mass_index() {
+    let bmi = calculators::bmi(Mass::from_kilograms(80.0), Length::from_centimeters(180.0)).unwrap();
+    assert_close(bmi, 24.69);
+    assert_eq!(BmiCategory::of(bmi), BmiCategory::Normal);
+    assert_eq!(BmiCategory::of(31.0), BmiCategory::Obese);
+    assert_eq!(calculators::bmi(Mass::default(), Length::from_centimeters(180.0)), None);
+    let (low, high) = calculators::ideal_weight_range(Length::from_centimeters(180.0));
+    assert_close(low.kilograms(), 59.94);
+    assert_close(high.kilograms(), 81.0);
+}
+
+#[test]
+fn basal_metabolic_rate_formulas() {
+    let (weight, height) = (Mass::from_kilograms(80.0), Length::from_centimeters(180.0));
+    assert_close(BmrFormula::MifflinStJeor.bmr(weight, height, 30, Gender::Male), 1780.0);
+    assert_close(BmrFormula::MifflinStJeor.bmr(weight, height, 30, Gender::Female), 1614.0);
+    assert_close(BmrFormula::MifflinStJeor.bmr(weight, height, 30, Gender::Other), 1697.0);
+    assert_close(BmrFormula::HarrisBenedict.bmr(weight, height, 30, Gender::Male), 1853.632);
+    assert_close(calculators::tdee(1780.0, ActivityLevel::Moderate), 2759.0);
+}
+
+#[test]
+fn heart_rate_zones() {
+    assert_eq!(calculators::max_heart_rate(40), 180);
+    let zones = calculators::heart_rate_zones(40, None);
+    assert_eq!(zones.len(), 5);
+    assert_eq!((zones[0].low, zones[0].high), (90, 108));
+    assert_eq!((zones[4].low, zones[4].high), (162, 180));
+    // Karvonen: 60 + (180 - 60) * percent
+    let zones = calculators::heart_rate_zones(40, Some(60));
+    assert_eq!((zones[0].low, zones[0].high), (120, 132));
+    assert_eq!(zones[4].high, 180);
+}
+
+#[test]
+fn health_uses_the_latest_measurements() {
+    let mut db = Db::default();
+    let person = Person::new(PERSON, "A".to_owned(), "B".to_owned(), date(1996, 5, 1), Gender::Male, Length::from_centimeters(180.0));
+    db.persons_mut().insert(person);
+    db.measurements_mut().insert(Measurement {
+        resting_heart_rate: Some(60),
+        ..Measurement::new(1, PERSON, date(2026, 1, 1), Mass::from_kilograms(90.0))
+    });
+    db.measurements_mut().insert(Measurement::new(2, PERSON, date(2026, 3, 1), Mass::from_kilograms(80.0)));
+
+    let health = calculators::health(&db, PERSON, date(2026, 3, 2), BmrFormula::MifflinStJeor, ActivityLevel::Sedentary).unwrap();
+    assert_eq!(health.age, 29);
+    assert_eq!(health.weight, Mass::from_kilograms(80.0));
+    assert_eq!(health.resting_heart_rate, Some(60));
+    assert_close(health.bmr.unwrap(), 1785.0);
+    assert_close(health.tdee.unwrap(), 2142.0);
+    assert_eq!(health.max_heart_rate, 191);
+    assert_eq!(calculators::health(&db, 2, date(2026, 3, 2), BmrFormula::default(), ActivityLevel::default()), None);
+}
@@ -0,0 +1,175 @@
+use std::{
+    env, fs,
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    path::PathBuf,
+    process, thread,
+};
+
+use log::LevelFilter;
+
+use chrono::NaiveDate;
+use sport_core::{
+    backup::BackupError,
+    cli::{self, exit_code, Cli, CliError, Command, EntityCommand, OutputFormat},
+    config::Config,
+    controller::Controller,
+    rest::RestClientError,
+};
+use sport_core_db::{
+    entity::{Exercise, Gender, MuscleGroup, Person},
+    units::{Length, UnitSystem},
+};
+
+fn parse(args: &str) -> Result<Cli, CliError> {
+    Cli::parse(args.split_whitespace().map(str::to_owned))
+}
+
+/// Server answering the requests in turn with the status and body, its url
+fn serve(responses: Vec<(u16, String)>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for (status, body) in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                if line.trim().is_empty() {
+                    break;
+                }
+            }
+            reader.by_ref().take(length).read_to_end(&mut vec![]).unwrap();
+            let response = format!(
+                "HTTP/1.1 {} Status\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status, body.len(), body,
+            );
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+        }
+    });
+    url
+}
+
+fn fields(pairs: &[(&str, &str)]) -> cli::Fields {
+    pairs.iter().map(|(n, v)| (n.to_string(), v.to_string())).collect()
+}
+
+#[test]
+fn commands_and_output_options_are_parsed() {
+    assert_eq!(parse("").unwrap(), Cli::default());
+    assert_eq!(parse("exercises list --json").unwrap(), Cli { format: OutputFormat::Json, command: Command::Exercises(EntityCommand::List), ..Cli::default() });
+    assert_eq!(parse("--csv persons rm 4").unwrap(), Cli { format: OutputFormat::Csv, command: Command::Persons(EntityCommand::Remove(4)), ..Cli::default() });
+    assert_eq!(
+        parse("exercises update 7 --name Squat --muscle-groups quads,glutes").unwrap().command,
+        Command::Exercises(EntityCommand::Update(7, fields(&[("name", "Squat"), ("muscle-groups", "quads,glutes")]))),
+    );
+    assert_eq!(parse("export --dir /tmp").unwrap().command, Command::Export { dir: PathBuf::from("/tmp") });
+    assert_eq!(parse("import --dry-run backup.json").unwrap().command, Command::Import { path: PathBuf::from("backup.json"), dry_run: true });
+    assert_eq!(parse("exercises --help").unwrap().command, Command::Help);
+}
+
+#[test]
+fn usage_errors_exit_with_64() {
+    for args in ["frob", "exercises", "exercises get", "exercises get x", "exercises list extra", "persons add --name Ann", "import", "exercises add --name", "--port x", "--scheme ftp", "--log-level loud", "--host"] {
+        let err = parse(args).unwrap_err();
+        assert!(matches!(err, CliError::Usage(_)), "{}", args);
+        assert_eq!(err.exit_code(), exit_code::USAGE);
+    }
+}
+
+#[test]
+fn exit_codes_follow_the_error_kind() {
+    let parse_err = serde_json::from_str::<u32>("x").unwrap_err();
+    assert_eq!(CliError::Rest(RestClientError::Parse(parse_err)).exit_code(), exit_code::PROTOCOL);
+    assert_eq!(CliError::NotFound("exercise", 3).exit_code(), exit_code::NOT_FOUND);
+    assert_eq!(CliError::Backup(BackupError::Version(9)).exit_code(), exit_code::DATA);
+    assert_eq!(CliError::Io(std::io::Error::other("disk")).exit_code(), exit_code::IO);
+}
+
+#[test]
+fn rejected_requests_and_server_failures_exit_with_their_codes() {
+    let exercises = serde_json::to_string(&[Exercise::new(3, "Squat".to_owned(), String::new())]).unwrap();
+    let rejected = r#"{"type":"constraint","message":"exercise 3 is in use"}"#.to_owned();
+    let url = serve(vec![(200, exercises.clone()), (400, rejected), (200, exercises), (500, "database is down".to_owned())]);
+    let controller = Controller::new(&url, Config::default());
+
+    let err = cli::run(&parse("exercises rm 3").unwrap(), &controller, &mut vec![]).unwrap_err();
+    assert!(matches!(&err, CliError::Rest(RestClientError::ServerMessage(_))), "{:?}", err);
+    assert!(err.to_string().contains("exercise 3 is in use"), "{}", err);
+    assert_eq!(err.exit_code(), exit_code::DATA);
+
+    let err = cli::run(&parse("exercises add --name Deadlift").unwrap(), &controller, &mut vec![]).unwrap_err();
+    assert!(err.to_string().contains("database is down"), "{}", err);
+    assert_eq!(err.exit_code(), exit_code::SOFTWARE);
+}
+
+#[test]
+fn fields_are_validated() {
+    let mut exercise = Exercise::new(1, "Squat".to_owned(), String::new());
+    cli::apply_exercise_fields(&mut exercise, &fields(&[("description", "Back squat"), ("muscle-groups", "quads, glutes")])).unwrap();
+    assert_eq!((exercise.description.as_str(), exercise.muscle_groups.clone()), ("Back squat", vec![MuscleGroup::Quads, MuscleGroup::Glutes]));
+    let err = cli::apply_exercise_fields(&mut exercise, &fields(&[("muscle-groups", "wings")])).unwrap_err();
+    assert_eq!(err.exit_code(), exit_code::DATA);
+    assert!(cli::apply_exercise_fields(&mut exercise, &fields(&[("name", " ")])).is_err());
+
+    let mut person = Person::default();
+    let values = fields(&[("first-name", "Ann"), ("last-name", "Lee"), ("birth-date", "1990-02-03"), ("gender", "f"), ("height", "5' 7\"")]);
+    cli::apply_person_fields(&mut person, &values, UnitSystem::Imperial).unwrap();
+    assert_eq!((person.birth_date, person.gender), (NaiveDate::from_ymd_opt(1990, 2, 3).unwrap(), Gender::Female));
+    assert_eq!(person.height, Length::from_feet_inches(5.0, 7.0));
+    assert!(cli::apply_person_fields(&mut person, &fields(&[("birth-date", "03.02.1990")]), UnitSystem::Metric).is_err());
+}
+
+#[test]
+fn output_is_rendered_as_table_csv_or_json() {
+    let mut bench = Exercise::new(2, "Bench press".to_owned(), "Flat, paused".to_owned());
+    bench.muscle_groups = vec![MuscleGroup::Chest];
+    let output = cli::exercises_output(&[Exercise::new(10, "Squat".to_owned(), String::new()), bench]);
+    assert_eq!(
+        output.render(OutputFormat::Table),
+        "ID  NAME         DESCRIPTION   MUSCLE_GROUPS\n10  Squat\n2   Bench press  Flat, paused  chest\n",
+    );
+    assert_eq!(output.render(OutputFormat::Csv), "id,name,description,muscle_groups\n10,Squat,,\n2,Bench press,\"Flat, paused\",chest\n");
+    let json: serde_json::Value = serde_json::from_str(&output.render(OutputFormat::Json)).unwrap();
+    assert_eq!((json[1]["name"].as_str(), json[1]["id"].as_u64()), (Some("Bench press"), Some(2)));
+}
+
+#[test]
+fn options_win_over_environment_variables() {
+    let cli = parse("--host=staging.example.com exercises list --port 8443 --log-level debug").unwrap();
+    assert_eq!(cli.command, Command::Exercises(EntityCommand::List));
+    let env = |var: &str| match var {
+        "SPORT_HELPER_HOST" => Some("localhost".to_owned()),
+        "SPORT_HELPER_SCHEME" => Some("https".to_owned()),
+        "SPORT_HELPER_PORT" => Some(String::new()),
+        _ => None,
+    };
+    let overrides = cli::overrides(&cli, env).unwrap();
+    assert_eq!(overrides.host.as_deref(), Some("staging.example.com"));
+    assert_eq!((overrides.scheme.as_deref(), overrides.port), (Some("https"), Some(8443)));
+    assert_eq!(overrides.log_level, Some(LevelFilter::Debug));
+
+    let err = cli::overrides(&Cli::default(), |var| (var == "SPORT_HELPER_PORT").then(|| "http".to_owned())).unwrap_err();
+    assert!(matches!(&err, CliError::Invalid { field, .. } if field == "SPORT_HELPER_PORT"));
+}
+
+#[test]
+fn config_file_is_overridden_and_malformed_ones_are_reported() {
+    let path = env::temp_dir().join(format!("sport_helper-cli-{}.toml", process::id()));
+    fs::write(&path, "[server]\nhost = \"10.0.0.2\"\nport = 5050\n[account]\nid = 3\n").unwrap();
+    let overrides = parse(&format!("--config {} --scheme https --port 443", path.display())).unwrap().overrides;
+    let cfg = cli::load_config(&overrides).unwrap();
+    assert_eq!(cfg.server().url(), "https://10.0.0.2:443");
+    assert_eq!((cfg.account().id, cfg.path.as_deref()), (3, Some(path.as_path())));
+
+    fs::write(&path, "[server\nhost = ").unwrap();
+    let err = cli::load_config(&overrides).unwrap_err();
+    fs::remove_file(&path).unwrap();
+    assert!(matches!(&err, CliError::Config { path: p, .. } if p == &path));
+    assert_eq!(err.exit_code(), exit_code::CONFIG);
+}
@@ -0,0 +1,94 @@
+use std::{env, fs, path::PathBuf, process};
+
+use sport_core::{
+    cli::{self, exit_code, CliError},
+    config::{Config, Overrides, ProfileError},
+    controller::Controller,
+};
+use sport_core_db::units::UnitSystem;
+
+fn temp_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("sport_helper-{}-{}.toml", name, process::id()))
+}
+
+const PROFILES: &str = r#"
+profile = "staging"
+
+[server]
+host = "127.0.0.1"
+port = 5050
+
+[account]
+id = 1
+
+[profiles.staging.server]
+host = "staging.example.com"
+port = 8080
+
+[profiles.staging.account]
+id = 7
+"#;
+
+#[test]
+fn default_config_is_written_and_read_back() {
+    let path = temp_path("config");
+    let cfg = Config { units: UnitSystem::Imperial, ..Config::default() };
+    confy::store_path(&path, cfg).unwrap();
+    let read: Config = confy::load_path(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!((read.units, read.server.port, read.calendar.weeks), (UnitSystem::Imperial, 5050, 12));
+}
+
+#[test]
+fn profile_of_the_config_is_used_unless_overridden() {
+    let path = temp_path("profiles");
+    fs::write(&path, PROFILES).unwrap();
+    let overrides = Overrides { config: Some(path.clone()), ..Overrides::default() };
+    let cfg = cli::load_config(&overrides).unwrap();
+    assert_eq!(cfg.profile_names(), ["default", "staging"]);
+    assert_eq!((cfg.active_profile(), cfg.server().url().as_str(), cfg.account().id), ("staging", "http://staging.example.com:8080", 7));
+
+    let default = Overrides { profile: Some("default".to_owned()), port: Some(6000), ..overrides.clone() };
+    let cfg = cli::load_config(&default).unwrap();
+    assert_eq!((cfg.server().url().as_str(), cfg.account().id), ("http://127.0.0.1:6000", 1));
+    // the overridden port stays out of the file
+    cfg.store().unwrap();
+    let cfg = cli::load_config(&overrides).unwrap();
+    assert_eq!(cfg.server.port, 5050);
+
+    let unknown = Overrides { profile: Some("production".to_owned()), ..overrides };
+    let err = cli::load_config(&unknown).unwrap_err();
+    fs::remove_file(&path).unwrap();
+    assert!(matches!(&err, CliError::Profile(ProfileError::Unknown { name, .. }) if name == "production"));
+    assert_eq!(err.exit_code(), exit_code::CONFIG);
+}
+
+#[test]
+fn switching_profiles_builds_another_controller() {
+    let path = temp_path("switch");
+    fs::write(&path, PROFILES).unwrap();
+    let mut cfg = Config::load_path(path.clone()).unwrap();
+    cfg.use_profile("default").unwrap();
+    let controller = Controller::new(&cfg.server().url(), cfg);
+
+    let staging = controller.switch_profile("staging").unwrap();
+    assert_eq!((staging.cfg().active_profile(), staging.cfg().account().id), ("staging", 7));
+    assert!(matches!(controller.switch_profile("production"), Err(ProfileError::Unknown { .. })));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn switching_profiles_keeps_the_overrides() {
+    let path = temp_path("switch-overrides");
+    fs::write(&path, PROFILES).unwrap();
+    let overrides = Overrides { config: Some(path.clone()), host: Some("localhost".to_owned()), ..Overrides::default() };
+    let cfg = cli::load_config(&overrides).unwrap();
+    let controller = Controller::new(&cfg.server().url(), cfg);
+
+    let default = controller.switch_profile("default").unwrap();
+    assert_eq!(default.cfg().server().url(), "http://localhost:5050");
+    let staging = default.switch_profile("staging").unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(staging.cfg().server().url(), "http://localhost:8080");
+    assert_eq!(staging.cfg().account().id, 7);
+}
@@ -0,0 +1,69 @@
+use sport_core::{
+    csv::{self, CsvError},
+    exercises_csv::{self, ExerciseField, ExerciseImport, RowStatus},
+};
+use sport_core_db::{
+    entity::{Exercise, MuscleGroup},
+    model::{Db, Table},
+};
+
+fn db() -> Db {
+    let mut db = Db::default();
+    let mut squat = Exercise::new(1, "Squat".to_owned(), "Back squat, \"low bar\"".to_owned());
+    squat.muscle_groups = vec![MuscleGroup::Quads, MuscleGroup::Glutes];
+    db.exercises_mut().insert(squat);
+    db.exercises_mut().insert(Exercise::new(2, "Bench Press".to_owned(), String::new()));
+    db
+}
+
+#[test]
+fn quoted_fields_and_delimiters() {
+    let records = csv::parse("a,\"b, c\",\"say \"\"hi\"\"\"\r\n\n\"multi\nline\",x\n").unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].fields, vec!["a", "b, c", "say \"hi\""]);
+    assert_eq!((records[1].line, records[1].get(0), records[1].get(5)), (3, "multi\nline", ""));
+    assert_eq!(csv::parse("name;notes\nSquat;deep").unwrap()[1].fields, vec!["Squat", "deep"]);
+    assert_eq!(csv::parse("a,\"b\n"), Err(CsvError::UnclosedQuote(1)));
+}
+
+#[test]
+fn export_reads_back() {
+    let db = db();
+    let text = exercises_csv::export(&db);
+    assert!(text.starts_with("name,description,muscle_groups\nBench Press,,\n"));
+    let import = ExerciseImport::read(&text).unwrap();
+    assert_eq!(import.mapping, vec![ExerciseField::Name, ExerciseField::Description, ExerciseField::MuscleGroups]);
+    let rows = import.rows(&Db::default());
+    assert_eq!(rows[1].exercise.description, "Back squat, \"low bar\"");
+    assert_eq!(rows[1].exercise.muscle_groups, vec![MuscleGroup::Quads, MuscleGroup::Glutes]);
+    assert!(rows.iter().all(|r| r.status == RowStatus::New));
+}
+
+#[test]
+fn mapping_is_guessed_from_the_header() {
+    let mut import = ExerciseImport::read("Id,Exercise Name,Notes,Target muscles,Name\n7,Row,,back\n").unwrap();
+    assert_eq!(
+        import.mapping,
+        vec![ExerciseField::Skip, ExerciseField::Name, ExerciseField::Description, ExerciseField::MuscleGroups, ExerciseField::Skip],
+    );
+    import.mapping[3] = import.mapping[3].next();
+    let row = &import.rows(&Db::default())[0];
+    assert_eq!((row.exercise.name.as_str(), row.exercise.muscle_groups.len()), ("Row", 0));
+    assert_eq!(ExerciseField::Skip.next(), ExerciseField::Name);
+}
+
+#[test]
+fn duplicates_and_invalid_rows() {
+    let text = "name,muscle groups\nsquat,\nDeadlift,back;hamstrings\nDEADLIFT,\n,chest\nCurl,arms\n";
+    let rows = ExerciseImport::read(text).unwrap().rows(&db());
+    let statuses: Vec<(usize, RowStatus)> = rows.iter().map(|r| (r.line, r.status.clone())).collect();
+    assert_eq!(statuses, vec![
+        (2, RowStatus::Duplicate),
+        (3, RowStatus::New),
+        (4, RowStatus::Duplicate),
+        (5, RowStatus::Invalid("Empty name".to_owned())),
+        (6, RowStatus::Invalid("Unknown muscle group `arms`".to_owned())),
+    ]);
+    assert_eq!(rows[1].exercise.muscle_groups, vec![MuscleGroup::Back, MuscleGroup::Hamstrings]);
+    assert!(matches!(ExerciseImport::read("\n\n"), Err(CsvError::Empty)));
+}
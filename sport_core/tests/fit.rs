@@ -0,0 +1,56 @@
+use std::{fs, path::{Path, PathBuf}};
+
+use chrono::{TimeZone, Utc};
+use sport_core::{
+    fit::{self, FitError},
+    tracks::{Track, TrackFormat},
+};
+use sport_core_db::units::{Distance, Duration};
+
+fn fixture_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/interval_run.fit")
+}
+
+fn fixture() -> Vec<u8> {
+    fs::read(fixture_path()).unwrap()
+}
+
+#[test]
+fn records_laps_and_session_are_read() {
+    let track = fit::decode(&fixture()).unwrap();
+    assert_eq!((track.format, track.sport.as_str(), track.ascent), (TrackFormat::Fit, "running", Some(13.0)));
+    let start = Utc.with_ymd_and_hms(2026, 5, 10, 6, 30, 0).unwrap();
+    // compressed timestamps continue the full one of the first record
+    let times: Vec<_> = track.points.iter().map(|p| p.time.unwrap()).collect();
+    assert_eq!(times, (0..4).map(|i| start + chrono::Duration::seconds(20 * i)).collect::<Vec<_>>());
+    let (lat, lon) = track.points[1].position.unwrap();
+    assert!((lat - 52.001).abs() < 1e-6 && (lon - 13.0).abs() < 1e-6);
+    let elevations: Vec<f64> = track.points.iter().filter_map(|p| p.elevation).collect();
+    assert_eq!(elevations, vec![10.0, 15.0, 12.0, 20.0]);
+    // 0xFF marks a missing heart rate
+    let heart_rates: Vec<Option<u32>> = track.points.iter().map(|p| p.heart_rate).collect();
+    assert_eq!(heart_rates, vec![Some(120), Some(130), None, Some(150)]);
+    assert_eq!(track.points[3].distance, Some(333.0));
+    // the lap definition is big endian
+    assert_eq!(track.laps.len(), 1);
+    assert_eq!((track.laps[0].started_at, track.laps[0].duration), (Some(start), Duration::from_minutes(1.0)));
+}
+
+#[test]
+fn fit_summary_and_load_by_header() {
+    let track = Track::load(&fixture_path()).unwrap();
+    let summary = track.summary().unwrap();
+    assert_eq!((summary.duration, summary.distance), (Duration::from_seconds(60.0), Distance::from_meters(333.0)));
+    assert_eq!(summary.elevation_gain.meters(), 13.0);
+    assert_eq!(summary.heart_rate, vec![(0, 120), (20, 130), (60, 150)]);
+}
+
+#[test]
+fn damaged_files_are_rejected() {
+    let data = fixture();
+    assert_eq!(fit::decode(b"<gpx></gpx>").unwrap_err(), FitError::Header);
+    assert!(matches!(fit::decode(&data[..data.len() - 10]), Err(FitError::Truncated(_))));
+    let mut flipped = data.clone();
+    flipped[40] ^= 0x01;
+    assert_eq!(fit::decode(&flipped).unwrap_err(), FitError::Crc);
+}
@@ -0,0 +1,129 @@
+use chrono::NaiveDate;
+use sport_core::{
+    analytics::OneRepMaxFormula,
+    goals::{self, MetricPoint},
+};
+use sport_core_db::{
+    entity::{Goal, GoalMetric, Measurement, Workout, WorkoutSet, ID},
+    model::{Db, Table},
+    units::{Duration, Mass},
+};
+
+const PERSON: ID = 1;
+const BENCH: ID = 10;
+const SQUAT: ID = 11;
+const FORMULA: OneRepMaxFormula = OneRepMaxFormula::Epley;
+
+fn date(month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+}
+
+fn add_workout(db: &mut Db, id: ID, person_id: ID, date: NaiveDate) {
+    let started_at = date.and_hms_opt(18, 0, 0).unwrap();
+    db.workouts_mut().insert(Workout::new(id, person_id, "Workout".to_owned(), started_at, Duration::default()));
+}
+
+fn add_set(db: &mut Db, id: ID, workout_id: ID, exercise_id: ID, kilograms: f64, reps: u32) {
+    db.sets_mut().insert(WorkoutSet::new(id, workout_id, exercise_id, id as u32, reps, Mass::from_kilograms(kilograms)));
+}
+
+fn weigh(db: &mut Db, id: ID, day: u32, kilograms: f64) {
+    db.measurements_mut().insert(Measurement::new(id, PERSON, date(3, day), Mass::from_kilograms(kilograms)));
+}
+
+/// Bench press singles on three Mondays, squats on a Thursday, a workout of another person
+fn sample_db() -> Db {
+    let mut db = Db::default();
+    for (id, day) in [(1, 2), (2, 5), (3, 9), (4, 16)] {
+        add_workout(&mut db, id, PERSON, date(3, day));
+    }
+    add_workout(&mut db, 5, PERSON + 1, date(3, 10));
+    add_set(&mut db, 1, 1, BENCH, 100.0, 1);
+    add_set(&mut db, 2, 2, SQUAT, 120.0, 5);
+    add_set(&mut db, 3, 3, BENCH, 105.0, 1);
+    add_set(&mut db, 4, 4, BENCH, 110.0, 1);
+    add_set(&mut db, 5, 4, BENCH, 80.0, 5);
+    add_set(&mut db, 6, 5, BENCH, 200.0, 1);
+    db
+}
+
+fn goal(metric: GoalMetric, target: f64) -> Goal {
+    Goal::new(1, PERSON, metric, target, date(3, 2), date(5, 31))
+}
+
+#[test]
+fn one_rep_max_goal_starts_from_the_baseline() {
+    let progress = goals::progress(&sample_db(), &goal(GoalMetric::OneRepMax { exercise_id: BENCH }, 120.0), date(3, 16), FORMULA);
+    assert_eq!((progress.start, progress.current, progress.target), (100.0, 110.0, 120.0));
+    assert_eq!(progress.percent, 50.0);
+    assert!(!progress.is_reached());
+    // 5 kg a week is 10 kg in two more weeks
+    assert_eq!(progress.projected, Some(date(3, 30)));
+
+    let reached = goals::progress(&sample_db(), &goal(GoalMetric::OneRepMax { exercise_id: BENCH }, 105.0), date(3, 16), FORMULA);
+    assert_eq!(reached.percent, 100.0);
+    assert!(reached.is_reached());
+    assert_eq!(reached.projected, None);
+}
+
+#[test]
+fn weekly_goals_count_the_last_seven_days() {
+    let db = sample_db();
+    let sessions = goals::metric_history(&db, PERSON, &GoalMetric::WeeklySessions, date(3, 2), date(3, 16), FORMULA);
+    assert_eq!(sessions, vec![
+        MetricPoint::new(date(3, 2), 1.0),
+        MetricPoint::new(date(3, 9), 2.0),
+        MetricPoint::new(date(3, 16), 1.0),
+    ]);
+    let volume = goals::metric_history(&db, PERSON, &GoalMetric::WeeklyVolume { exercise_id: Some(BENCH) }, date(3, 2), date(3, 16), FORMULA);
+    let volume: Vec<f64> = volume.iter().map(|p| p.value).collect();
+    assert_eq!(volume, vec![100.0, 105.0, 510.0]);
+    let all = goals::metric_history(&db, PERSON, &GoalMetric::WeeklyVolume { exercise_id: None }, date(3, 2), date(3, 16), FORMULA);
+    assert_eq!(all[1].value, 705.0);
+
+    let progress = goals::progress(&db, &goal(GoalMetric::WeeklySessions, 4.0), date(3, 16), FORMULA);
+    assert_eq!((progress.start, progress.current, progress.percent), (0.0, 1.0, 25.0));
+}
+
+#[test]
+fn body_weight_goal_projects_a_steady_loss() {
+    let mut db = Db::default();
+    weigh(&mut db, 1, 2, 90.0);
+    weigh(&mut db, 2, 9, 89.0);
+    weigh(&mut db, 3, 16, 88.0);
+    let progress = goals::progress(&db, &goal(GoalMetric::BodyWeight, 85.0), date(3, 16), FORMULA);
+    assert_eq!((progress.start, progress.current), (90.0, 88.0));
+    assert_eq!(progress.percent, 40.0);
+    assert_eq!(progress.projected, Some(date(4, 6)));
+}
+
+#[test]
+fn flat_or_opposite_trend_has_no_projection() {
+    let mut db = Db::default();
+    weigh(&mut db, 1, 2, 90.0);
+    weigh(&mut db, 2, 9, 90.0);
+    let flat = goals::progress(&db, &goal(GoalMetric::BodyWeight, 85.0), date(3, 16), FORMULA);
+    assert_eq!((flat.percent, flat.projected), (0.0, None));
+
+    weigh(&mut db, 3, 16, 92.0);
+    let gaining = goals::progress(&db, &goal(GoalMetric::BodyWeight, 85.0), date(3, 16), FORMULA);
+    assert_eq!((gaining.current, gaining.percent, gaining.projected), (92.0, 0.0, None));
+
+    // constant values have no trend, even with the rounding of the slope
+    let mut constant = Db::default();
+    for (id, day) in (1..=7).zip([2, 4, 6, 8, 10, 12, 14]) {
+        weigh(&mut constant, id, day, 116.1);
+    }
+    let flat = goals::progress(&constant, &goal(GoalMetric::BodyWeight, 100.0), date(3, 16), FORMULA);
+    assert_eq!((flat.current, flat.projected), (116.1, None));
+    // a projection beyond the calendar is none either
+    let mut slow = Db::default();
+    weigh(&mut slow, 1, 2, 90.0);
+    weigh(&mut slow, 2, 9, 90.0 - 1e-6);
+    assert_eq!(goals::progress(&slow, &goal(GoalMetric::BodyWeight, -1e12), date(3, 16), FORMULA).projected, None);
+
+    // a single point has no trend
+    let single = goals::progress(&sample_db(), &goal(GoalMetric::OneRepMax { exercise_id: SQUAT }, 150.0), date(3, 16), FORMULA);
+    assert_eq!((single.start, single.current, single.projected), (140.0, 140.0, None));
+    assert_eq!(goals::progress(&Db::default(), &goal(GoalMetric::BodyWeight, 85.0), date(3, 16), FORMULA).current, 0.0);
+}
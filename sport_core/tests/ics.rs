@@ -0,0 +1,105 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use sport_core::ics::{self, CalendarConfig, IcsError, PlannedSession, Recurrence};
+use sport_core_db::{
+    entity::{Exercise, Routine, RoutineExercise, Workout, WorkoutSet, ID},
+    model::{Db, Table},
+    units::{Duration, Mass},
+};
+
+const PERSON: ID = 1;
+
+/// Wednesday
+fn now() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2026, 5, 13).unwrap().and_hms_opt(12, 0, 0).unwrap()
+}
+
+/// `now` in a zone two hours ahead of UTC
+fn stamp() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 5, 13, 10, 0, 0).unwrap()
+}
+
+fn db() -> Db {
+    let mut db = Db::default();
+    db.exercises_mut().insert(Exercise::new(10, "Squat".to_owned(), String::new()));
+    db.exercises_mut().insert(Exercise::new(11, "Bench press, paused".to_owned(), String::new()));
+    let mut legs = Routine::new(20, PERSON, "Legs".to_owned());
+    legs.exercises.push(RoutineExercise { exercise_id: 10, sets: 3, min_reps: 5, max_reps: 5, ..RoutineExercise::default() });
+    db.routines_mut().insert(legs);
+    let mut push = Routine::new(21, PERSON, "Push".to_owned());
+    push.exercises.push(RoutineExercise { exercise_id: 11, sets: 4, min_reps: 6, max_reps: 8, ..RoutineExercise::default() });
+    db.routines_mut().insert(push);
+    db.routines_mut().insert(Routine::new(22, 2, "Other person".to_owned()));
+
+    let past = Workout::new(30, PERSON, "Done".to_owned(), now() - chrono::Duration::days(1), Duration::from_minutes(50.0));
+    db.workouts_mut().insert(past);
+    let mut planned = Workout::new(31, PERSON, "Test day".to_owned(), now() + chrono::Duration::days(2), Duration::default());
+    planned.notes = "Max singles; warm up well".to_owned();
+    db.workouts_mut().insert(planned);
+    db.sets_mut().insert(WorkoutSet::new(40, 31, 10, 1, 1, Mass::default()));
+    db.sets_mut().insert(WorkoutSet::new(41, 31, 10, 2, 1, Mass::default()));
+    db.sets_mut().insert(WorkoutSet::new(42, 31, 11, 3, 1, Mass::default()));
+    db
+}
+
+#[test]
+fn routines_take_turns_on_the_training_days() {
+    let sessions = ics::planned_sessions(&db(), PERSON, &CalendarConfig::default(), now());
+    let titles: Vec<&str> = sessions.iter().map(|s| s.title.as_str()).collect();
+    assert_eq!(titles, vec!["Test day", "Legs", "Push"]);
+
+    let test_day = &sessions[0];
+    assert_eq!((test_day.duration, test_day.recurrence.clone()), (Duration::from_minutes(60.0), None));
+    assert_eq!(test_day.description, "Squat: 2 sets\nBench press, paused: 1 sets\nMax singles; warm up well");
+
+    // Monday and Friday for the first routine, Wednesday evening for the second one
+    let legs = &sessions[1];
+    assert_eq!(legs.start, NaiveDate::from_ymd_opt(2026, 5, 15).unwrap().and_hms_opt(18, 0, 0).unwrap());
+    assert_eq!(legs.recurrence, Some(Recurrence { days: vec![Weekday::Mon, Weekday::Fri], count: 24 }));
+    assert_eq!(legs.description, "Squat: 3 x 5");
+    let push = &sessions[2];
+    assert_eq!(push.start, now().date().and_hms_opt(18, 0, 0).unwrap());
+    assert_eq!(push.recurrence, Some(Recurrence { days: vec![Weekday::Wed], count: 12 }));
+    assert_eq!(push.description, "Bench press, paused: 4 x 6-8");
+}
+
+#[test]
+fn export_round_trips_through_the_parser() {
+    let sessions = ics::planned_sessions(&db(), PERSON, &CalendarConfig::default(), now());
+    let text = ics::export(&sessions, stamp());
+    assert!(text.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(text.contains("DTSTAMP:20260513T100000Z\r\n"));
+    assert!(text.contains("RRULE:FREQ=WEEKLY;BYDAY=MO,FR;COUNT=24\r\n"));
+    assert!(text.contains("DTSTART:20260515T180000\r\nDURATION:PT1H\r\n"));
+    assert!(text.contains("DESCRIPTION:Bench press\\, paused: 4 x 6-8\r\n"));
+    assert!(text.lines().all(|l| l.len() <= 75));
+    assert_eq!(ics::parse(&text).unwrap(), sessions);
+}
+
+#[test]
+fn long_lines_are_folded_and_unfolded() {
+    let session = PlannedSession {
+        uid: "long@sport_helper".to_owned(),
+        title: "Long, ünïcödé; title".to_owned(),
+        start: now(),
+        duration: Duration::from_seconds(5430.0),
+        description: "Row: 5 sets\n".repeat(20),
+        recurrence: None,
+    };
+    let text = ics::export(std::slice::from_ref(&session), stamp());
+    assert!(text.contains("DURATION:PT1H30M30S\r\n"));
+    assert!(text.contains("\r\n "));
+    assert_eq!(ics::parse(&text).unwrap(), vec![session]);
+}
+
+#[test]
+fn foreign_calendars_are_read_or_rejected() {
+    let text = "BEGIN:VCALENDAR\nBEGIN:VTIMEZONE\nTZID:Europe/Berlin\nEND:VTIMEZONE\nBEGIN:VEVENT\nUID:1\n\
+        DTSTART;TZID=Europe/Berlin:20260515T070000\nDTEND;TZID=Europe/Berlin:20260515T074500\nSUMMARY:Run\nEND:VEVENT\nEND:VCALENDAR\n";
+    let sessions = ics::parse(text).unwrap();
+    assert_eq!((sessions[0].title.as_str(), sessions[0].duration), ("Run", Duration::from_minutes(45.0)));
+    assert_eq!(sessions[0].start.time(), NaiveTime::from_hms_opt(7, 0, 0).unwrap());
+
+    assert_eq!(ics::parse("BEGIN:VEVENT").unwrap_err(), IcsError::NotCalendar);
+    assert_eq!(ics::parse("BEGIN:VCALENDAR\nBEGIN:VEVENT\nSUMMARY:x\nEND:VEVENT").unwrap_err(), IcsError::MissingProperty("DTSTART"));
+    assert_eq!(ics::parse("BEGIN:VCALENDAR\nbroken").unwrap_err(), IcsError::Property(2));
+}
@@ -0,0 +1,121 @@
+use std::{env, fs, process};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use sport_core::{
+    config::Config,
+    keys::{Action, Context, KeyBinding, KeyMap, KeyMapError},
+};
+
+const SERVER: &str = "[server]\nhost = \"127.0.0.1\"\nport = 5050\n\n[account]\nid = 1\n\n";
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+    KeyEvent::new(code, modifiers)
+}
+
+#[test]
+fn default_bindings_do_not_conflict() {
+    assert_eq!(KeyMap::default().check(), Ok(()));
+}
+
+#[test]
+fn bindings_are_parsed_and_written_back() {
+    for (text, written) in [
+        ("a", "a"),
+        ("ctrl+s", "ctrl+s"),
+        ("Ctrl+Alt+Del", "ctrl+alt+delete"),
+        ("shift+a", "A"),
+        ("shift+1", "1"),
+        ("ctrl+shift+/", "ctrl+/"),
+        ("shift+tab", "backtab"),
+        ("space", "space"),
+        ("ctrl++", "ctrl++"),
+        ("F5", "f5"),
+    ] {
+        let binding: KeyBinding = text.parse().unwrap();
+        assert_eq!(binding.to_string(), written);
+        assert_eq!(written.parse::<KeyBinding>().unwrap(), binding);
+    }
+    assert!("hyper+a".parse::<KeyBinding>().is_err());
+    assert!("f13".parse::<KeyBinding>().is_err());
+    assert!("".parse::<KeyBinding>().is_err());
+}
+
+#[test]
+fn modifiers_must_match() {
+    let mut keymap = KeyMap::default();
+    keymap.bind(Action::Add, "ctrl+n".parse().unwrap());
+    assert!(keymap.is(Action::Add, &key(KeyCode::Char('n'), KeyModifiers::CONTROL)));
+    assert!(!keymap.is(Action::Add, &key(KeyCode::Char('n'), KeyModifiers::NONE)));
+    assert!(!keymap.is(Action::Add, &key(KeyCode::Char('a'), KeyModifiers::NONE)));
+    // terminals report shift along with the upper case char
+    keymap.bind(Action::Delete, "D".parse().unwrap());
+    assert!(keymap.is(Action::Delete, &key(KeyCode::Char('D'), KeyModifiers::SHIFT)));
+    // the shift of a char is dropped when parsing too
+    keymap.bind(Action::Skip, "shift+/".parse().unwrap());
+    assert!(keymap.is(Action::Skip, &key(KeyCode::Char('/'), KeyModifiers::SHIFT)));
+    keymap.bind(Action::Pause, "shift+1".parse().unwrap());
+    assert!(keymap.is(Action::Pause, &key(KeyCode::Char('1'), KeyModifiers::SHIFT)));
+    assert!(keymap.is(Action::Pause, &key(KeyCode::Char('1'), KeyModifiers::NONE)));
+    assert_eq!(keymap.action(Context::Exercises, &key(KeyCode::Char('n'), KeyModifiers::CONTROL)), Some(Action::Add));
+    assert_eq!(keymap.action(Context::Workouts, &key(KeyCode::Char('r'), KeyModifiers::NONE)), Some(Action::Reload));
+}
+
+#[test]
+fn help_follows_the_bindings() {
+    let keymap = KeyMap::from_names([("pause", "ctrl+p"), ("skip", "ctrl+n")]).unwrap();
+    assert_eq!(keymap.help(Context::Timer), "Pause[ctrl+p] Skip[ctrl+n] Close[esc]");
+    // the menu shows the keys of the pages
+    assert_eq!(keymap.help(Context::Global), "Profile[p] Quit[q]");
+    assert_eq!(keymap.commands(&[("Exit", Action::Close), ("Accept", Action::Accept)]), "Exit: [q], Accept: [enter]");
+}
+
+#[test]
+fn conflicts_and_unknown_names_are_rejected() {
+    assert_eq!(KeyMap::from_names([("jump", "j")]), Err(KeyMapError::UnknownAction("jump".to_owned())));
+    assert!(matches!(KeyMap::from_names([("add", "ctrl+")]), Err(KeyMapError::Key { action: Action::Add, .. })));
+    // the page keys are active along with the global ones
+    assert_eq!(
+        KeyMap::from_names([("add", "q")]),
+        Err(KeyMapError::Conflict { key: "q".parse().unwrap(), first: Action::Add, second: Action::Quit, context: Context::Exercises }),
+    );
+    // pages don`t share keys with each other
+    assert!(KeyMap::from_names([("calendar", "w")]).is_ok());
+    assert!(matches!(KeyMap::from_names([("add", "2")]), Err(KeyMapError::Conflict { second: Action::WorkoutsPage, .. })));
+}
+
+#[test]
+fn arrows_of_the_progress_and_reports_pages_are_bindings_too() {
+    let keymap = KeyMap::default();
+    assert_eq!(keymap.action(Context::Progress, &key(KeyCode::Down, KeyModifiers::NONE)), Some(Action::NextExercise));
+    assert_eq!(keymap.action(Context::Reports, &key(KeyCode::Left, KeyModifiers::NONE)), Some(Action::PreviousPeriod));
+    assert_eq!(
+        keymap.help(Context::Reports),
+        "Previous period[left] Next period[right] Weekly[w] Monthly[m] Today[t] Markdown[e] HTML[h] Reload[r]",
+    );
+
+    let keymap = KeyMap::from_names([("previous_series", "h"), ("next_series", "l")]).unwrap();
+    assert!(keymap.help(Context::Progress).starts_with("Previous series[h] Next series[l] Previous exercise[up]"));
+    assert_eq!(
+        KeyMap::from_names([("range", "down")]),
+        Err(KeyMapError::Conflict { key: "down".parse().unwrap(), first: Action::NextExercise, second: Action::Range, context: Context::Progress }),
+    );
+}
+
+#[test]
+fn keys_section_of_the_config_is_merged_with_the_defaults() {
+    let path = env::temp_dir().join(format!("sport_helper-keys-{}.toml", process::id()));
+    fs::write(&path, format!("{}[keys]\nadd = \"ctrl+n\"\nquit = \"ctrl+q\"\n", SERVER)).unwrap();
+    let cfg = Config::load_path(path.clone()).unwrap();
+    assert_eq!(cfg.keys.binding(Action::Add).to_string(), "ctrl+n");
+    assert_eq!(cfg.keys.binding(Action::Quit).to_string(), "ctrl+q");
+    assert_eq!(cfg.keys.binding(Action::Delete).to_string(), "d");
+
+    cfg.store().unwrap();
+    let read = Config::load_path(path.clone()).unwrap();
+    assert_eq!(read.keys, KeyMap::from_names([("add", "ctrl+n"), ("quit", "ctrl+q")]).unwrap());
+
+    fs::write(&path, format!("{}[keys]\nadd = \"d\"\n", SERVER)).unwrap();
+    let err = Config::load_path(path.clone()).unwrap_err();
+    assert!(format!("{:?}", err).contains("bound to both"), "{:?}", err);
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,72 @@
+use chrono::NaiveDate;
+use sport_core_db::{entity::{Gender, Person}, units::Length};
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn person(json: &str) -> Person {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn old_person_payloads_are_read() {
+    let old = person(r#"{"id":1,"first_name":"Ann","last_name":"Lee","birth_date":"1990-05-17T00:00:00Z","gender":"F","height":168}"#);
+    assert_eq!(old.birth_date, date(1990, 5, 17));
+    assert_eq!(old.gender, Gender::Female);
+    assert_eq!(old.height.centimeters().round(), 168.0);
+
+    // naive timestamps and offsets keep the calendar date of UTC
+    let naive = person(r#"{"id":2,"first_name":"","last_name":"","birth_date":"1985-01-02T10:30:00.5","gender":"man","height":180.4}"#);
+    assert_eq!(naive.birth_date, date(1985, 1, 2));
+    assert_eq!(naive.gender, Gender::Male);
+    assert!((naive.height.centimeters() - 180.4).abs() < 1e-9);
+    let offset = person(r#"{"id":3,"first_name":"","last_name":"","birth_date":"1985-01-02T01:00:00+03:00","gender":"","height":0}"#);
+    assert_eq!(offset.birth_date, date(1985, 1, 1));
+    assert_eq!(offset.gender, Gender::Other);
+
+    let err = serde_json::from_str::<Person>(r#"{"id":4,"first_name":"","last_name":"","birth_date":"17.05.1990","gender":"f","height":160}"#);
+    assert!(err.unwrap_err().to_string().contains("invalid birth date `17.05.1990`"));
+}
+
+#[test]
+fn person_is_written_in_the_server_format_and_read_back() {
+    let old = person(r#"{"id":1,"first_name":"Ann","last_name":"Lee","birth_date":"1990-05-17T00:00:00Z","gender":"Female","height":167.6}"#);
+    let json = serde_json::to_value(&old).unwrap();
+    assert_eq!(json["birth_date"], "1990-05-17T00:00:00Z");
+    assert_eq!(json["gender"], "female");
+    assert_eq!(json["height"], 168);
+
+    let read: Person = serde_json::from_value(json).unwrap();
+    assert_eq!((read.id, read.first_name.as_str(), read.last_name.as_str()), (1, "Ann", "Lee"));
+    assert_eq!((read.birth_date, read.gender), (old.birth_date, old.gender));
+    assert_eq!(read.height.centimeters(), 168.0);
+}
+
+#[test]
+fn inserted_and_updated_persons_keep_the_timestamp_birth_date() {
+    // the body of the insert and update requests
+    let new = Person::new(0, "Ann".to_owned(), "Lee".to_owned(), date(1990, 2, 3), Gender::Female, Length::from_centimeters(168.0));
+    let body = serde_json::to_string(&new).unwrap();
+    assert!(body.contains(r#""birth_date":"1990-02-03T00:00:00Z""#), "{}", body);
+
+    let updated = Person { id: 5, birth_date: date(2000, 12, 31), ..new };
+    let body = serde_json::to_string(&updated).unwrap();
+    assert!(body.contains(r#""birth_date":"2000-12-31T00:00:00Z""#), "{}", body);
+    assert_eq!(person(&body).birth_date, date(2000, 12, 31));
+}
+
+#[test]
+fn age_counts_full_years() {
+    let person = Person { birth_date: date(1990, 5, 17), ..Person::default() };
+    assert_eq!(person.age_at(date(2020, 5, 16)), Some(29));
+    assert_eq!(person.age_at(date(2020, 5, 17)), Some(30));
+    assert_eq!(person.age_at(date(1990, 5, 17)), Some(0));
+    assert_eq!(person.age_at(date(1990, 5, 16)), None);
+
+    // a leap day birthday comes on the 1st of March in the other years
+    let leap = Person { birth_date: date(2000, 2, 29), ..Person::default() };
+    assert_eq!(leap.age_at(date(2001, 2, 28)), Some(0));
+    assert_eq!(leap.age_at(date(2001, 3, 1)), Some(1));
+    assert_eq!(leap.age_at(date(2004, 2, 29)), Some(4));
+}
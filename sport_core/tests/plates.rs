@@ -0,0 +1,58 @@
+use sport_core::plates::{self, PlateInventory, PlateStock};
+use sport_core_db::units::{Mass, UnitSystem};
+
+fn kg(value: f64) -> Mass {
+    Mass::from_kilograms(value)
+}
+
+fn side(load: &sport_core::plates::PlateLoad) -> Vec<(f64, u32)> {
+    load.per_side.iter().map(|(w, c)| (w.kilograms(), *c)).collect()
+}
+
+#[test]
+fn exact_weight_uses_fewest_plates() {
+    let inventory = PlateInventory::default();
+    let load = inventory.load(kg(102.5));
+    assert!(load.is_exact());
+    assert_eq!(side(&load), vec![(25.0, 1), (15.0, 1), (1.25, 1)]);
+    assert_eq!(load.describe(UnitSystem::Metric), "25 + 15 + 1.25");
+    assert_eq!(load.describe(UnitSystem::Imperial), "55.12 + 33.07 + 2.76");
+    let load = inventory.load(kg(140.0));
+    assert_eq!(side(&load), vec![(25.0, 2), (10.0, 1)]);
+}
+
+#[test]
+fn unreachable_weight_gives_the_nearest_one() {
+    let inventory = PlateInventory::default();
+    let load = inventory.load(kg(101.0));
+    assert!(!load.is_exact());
+    assert_eq!(load.total, kg(100.0));
+    assert_eq!(load.difference(), kg(-1.0));
+    let light = inventory.load(kg(15.0));
+    assert_eq!((light.total, light.describe(UnitSystem::Imperial)), (kg(20.0), "-".to_owned()));
+}
+
+#[test]
+fn limited_inventory() {
+    let inventory = PlateInventory { bar: kg(15.0), plates: vec![PlateStock::new(20.0, 1), PlateStock::new(5.0, 3)] };
+    // 35 kg per side is beyond the 20 + 5 + 5 + 5 available
+    let load = inventory.load(kg(85.0));
+    assert_eq!(load.total, kg(85.0));
+    let load = inventory.load(kg(200.0));
+    assert_eq!(load.total, kg(85.0));
+    assert_eq!(side(&load), vec![(20.0, 1), (5.0, 3)]);
+    // 15 kg per side needs three fives without a ten
+    assert_eq!(side(&inventory.load(kg(45.0))), vec![(5.0, 3)]);
+}
+
+#[test]
+fn warm_up_ramp() {
+    let inventory = PlateInventory::default();
+    let sets = plates::warm_up(&inventory, kg(100.0));
+    let ramp: Vec<(f64, u32)> = sets.iter().map(|s| (s.load.total.kilograms(), s.reps)).collect();
+    assert_eq!(ramp, vec![(20.0, 10), (40.0, 5), (60.0, 3), (80.0, 2)]);
+    let short = plates::warm_up(&inventory, kg(30.0));
+    let ramp: Vec<f64> = short.iter().map(|s| s.load.total.kilograms()).collect();
+    assert_eq!(ramp, vec![20.0, 25.0]);
+    assert!(plates::warm_up(&inventory, kg(20.0)).is_empty());
+}
@@ -0,0 +1,103 @@
+use chrono::NaiveDate;
+use sport_core::{
+    analytics::OneRepMaxFormula,
+    progression::{
+        self, DeloadAfterFailures, DoubleProgression, PercentageWave, ProgressionRule, RepCeiling, SessionPerformance,
+        SetPerformance,
+    },
+};
+use sport_core_db::{
+    entity::{ProgressionScheme, RoutineExercise, Workout, WorkoutSet, ID},
+    model::{Db, Table},
+    units::{Duration, Mass},
+};
+
+const PERSON: ID = 1;
+const SQUAT: ID = 10;
+
+fn kg(value: f64) -> Mass {
+    Mass::from_kilograms(value)
+}
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 3, day).unwrap()
+}
+
+fn target(scheme: ProgressionScheme) -> RoutineExercise {
+    RoutineExercise { exercise_id: SQUAT, sets: 3, min_reps: 6, max_reps: 8, increment: kg(2.5), scheme }
+}
+
+fn session(day: u32, weight: f64, reps: &[u32]) -> SessionPerformance {
+    let sets = reps.iter().map(|reps| SetPerformance { reps: *reps, weight: kg(weight) }).collect();
+    SessionPerformance::new(date(day), sets)
+}
+
+#[test]
+fn rep_ceiling_adds_weight_only_at_the_top_of_the_range() {
+    let target = target(ProgressionScheme::RepCeiling);
+    let repeat = RepCeiling.suggest(&target, &[session(2, 100.0, &[8, 8, 7])]).unwrap();
+    assert_eq!((repeat.sets, repeat.reps, repeat.weight), (3, 8, kg(100.0)));
+    let progress = RepCeiling.suggest(&target, &[session(2, 100.0, &[8, 8, 8])]).unwrap();
+    assert_eq!((progress.reps, progress.weight), (8, kg(102.5)));
+    assert_eq!(RepCeiling.suggest(&target, &[]), None);
+}
+
+#[test]
+fn warm_up_sets_are_not_working_sets() {
+    let target = target(ProgressionScheme::RepCeiling);
+    let mut last = session(2, 100.0, &[8, 8, 8]);
+    last.sets.insert(0, SetPerformance { reps: 3, weight: kg(60.0) });
+    assert_eq!(last.min_reps(), 8);
+    assert_eq!(RepCeiling.suggest(&target, &[last]).unwrap().weight, kg(102.5));
+}
+
+#[test]
+fn double_progression_adds_reps_then_weight() {
+    let target = target(ProgressionScheme::DoubleProgression);
+    let more_reps = DoubleProgression.suggest(&target, &[session(2, 100.0, &[7, 6, 6])]).unwrap();
+    assert_eq!((more_reps.reps, more_reps.weight), (7, kg(100.0)));
+    let more_weight = DoubleProgression.suggest(&target, &[session(2, 100.0, &[8, 8, 8])]).unwrap();
+    assert_eq!((more_weight.reps, more_weight.weight), (6, kg(102.5)));
+}
+
+#[test]
+fn deload_after_repeated_failures() {
+    let target = target(ProgressionScheme::RepCeiling);
+    let rule = DeloadAfterFailures::new(RepCeiling);
+    let one_failure = [session(2, 100.0, &[8, 8, 8]), session(4, 102.5, &[6, 5, 4])];
+    assert_eq!(rule.suggest(&target, &one_failure).unwrap().weight, kg(102.5));
+    let two_failures = [session(4, 102.5, &[6, 5, 4]), session(6, 102.5, &[6, 5, 5])];
+    let deload = rule.suggest(&target, &two_failures).unwrap();
+    assert_eq!((deload.reps, deload.weight), (8, kg(90.0)));
+}
+
+#[test]
+fn percentage_wave_cycles_through_the_steps() {
+    let target = target(ProgressionScheme::PercentageWave);
+    let wave = PercentageWave::new(OneRepMaxFormula::Epley);
+    // 100 kg for 6 reps is a 120 kg estimated 1RM with the Epley formula
+    let history = vec![session(2, 100.0, &[6])];
+    let second = wave.suggest(&target, &history).unwrap();
+    assert_eq!((second.reps, second.weight), (3, kg(95.0)));
+    let history = vec![session(2, 100.0, &[6]), session(4, 95.0, &[3]), session(6, 100.0, &[1])];
+    let restart = wave.suggest(&target, &history).unwrap();
+    assert_eq!((restart.reps, restart.weight), (5, kg(90.0)));
+}
+
+#[test]
+fn suggestion_ignores_the_current_and_later_workouts() {
+    let mut db = Db::default();
+    for (id, day, reps) in [(1, 2, 8), (2, 4, 6), (3, 6, 8)] {
+        let started_at = date(day).and_hms_opt(18, 0, 0).unwrap();
+        db.workouts_mut().insert(Workout::new(id, PERSON, "Legs".to_owned(), started_at, Duration::default()));
+        for order in 0..3 {
+            let set_id = id * 10 + order as ID;
+            db.sets_mut().insert(WorkoutSet::new(set_id, id, SQUAT, order, reps, kg(100.0)));
+        }
+    }
+    let current = db.workouts().get_one(2).unwrap().clone();
+    let history = progression::exercise_history(&db, PERSON, SQUAT, current.started_at);
+    assert_eq!(history.len(), 1);
+    let suggestion = progression::suggest(&db, &current, &target(ProgressionScheme::RepCeiling), OneRepMaxFormula::Epley).unwrap();
+    assert_eq!(suggestion.weight, kg(102.5));
+}
@@ -0,0 +1,118 @@
+use sport_core::quick_entry::{self, QuickEntryErrorKind};
+use sport_core_db::{
+    entity::{Exercise, WorkoutSet, ID},
+    model::{Db, Table},
+    units::{Distance, Duration, Mass, UnitSystem},
+};
+
+const BENCH: ID = 1;
+const SQUAT: ID = 2;
+const RUN: ID = 3;
+const FRONT_SQUAT: ID = 4;
+
+fn kg(value: f64) -> Mass {
+    Mass::from_kilograms(value)
+}
+
+fn db() -> Db {
+    let mut db = Db::default();
+    for (id, name) in [(BENCH, "Bench Press"), (SQUAT, "Squat"), (RUN, "Running"), (FRONT_SQUAT, "Front Squat")] {
+        db.exercises_mut().insert(Exercise::new(id, name.to_owned(), String::new()));
+    }
+    db
+}
+
+fn sets(input: &str) -> (ID, Vec<WorkoutSet>) {
+    let entry = quick_entry::parse(&db(), input, UnitSystem::Metric, Some(SQUAT)).unwrap();
+    (entry.exercise_id, entry.sets)
+}
+
+fn reps_weights(sets: &[WorkoutSet]) -> Vec<(u32, f64)> {
+    sets.iter().map(|s| (s.reps, s.weight.kilograms())).collect()
+}
+
+#[test]
+fn sets_times_reps_at_weight() {
+    let (exercise, bench) = sets("bench 3x8@80kg");
+    assert_eq!(exercise, BENCH);
+    assert_eq!(reps_weights(&bench), vec![(8, 80.0); 3]);
+    let (exercise, last) = sets("5x5 100");
+    assert_eq!(exercise, SQUAT);
+    assert_eq!(reps_weights(&last), vec![(5, 100.0); 5]);
+    let (_, spaced) = sets("bench press 3x8 @ 80");
+    assert_eq!(reps_weights(&spaced), vec![(8, 80.0); 3]);
+}
+
+#[test]
+fn weight_times_rep_list_with_rpe() {
+    let (exercise, squat) = sets("squat 120x5,5,4 rpe8");
+    assert_eq!(exercise, SQUAT);
+    assert_eq!(reps_weights(&squat), vec![(5, 120.0), (5, 120.0), (4, 120.0)]);
+    assert!(squat.iter().all(|s| s.rpe == Some(8.0)));
+    // a single heavy pair is weight x reps
+    assert_eq!(reps_weights(&sets("squat 100x5").1), vec![(5, 100.0)]);
+    assert_eq!(reps_weights(&sets("squat 60kgx12 rpe 7.5").1), vec![(12, 60.0)]);
+}
+
+#[test]
+fn cardio_distance_and_duration() {
+    let (exercise, run) = sets("run 5km 25:30");
+    assert_eq!(exercise, RUN);
+    assert_eq!(run.len(), 1);
+    assert_eq!(run[0].distance, Distance::from_kilometers(5.0));
+    assert_eq!(run[0].duration, Duration::from_seconds(1530.0));
+    assert_eq!(sets("run 30min").1[0].duration, Duration::from_minutes(30.0));
+}
+
+#[test]
+fn fuzzy_exercise_names() {
+    let db = db();
+    assert_eq!(quick_entry::find_exercise(&db, "SQUAT"), Ok(SQUAT));
+    assert_eq!(quick_entry::find_exercise(&db, "fr sq"), Ok(FRONT_SQUAT));
+    assert_eq!(quick_entry::find_exercise(&db, "bnch"), Ok(BENCH));
+    assert_eq!(quick_entry::find_exercise(&db, "runing"), Ok(RUN));
+    assert_eq!(quick_entry::find_exercise(&db, "deadlift"), Err(QuickEntryErrorKind::UnknownExercise("deadlift".to_owned())));
+}
+
+#[test]
+fn ambiguous_exercise_lists_candidates() {
+    let mut db = db();
+    db.exercises_mut().insert(Exercise::new(5, "Squat Jump".to_owned(), String::new()));
+    db.exercises_mut().insert(Exercise::new(6, "Squat Hold".to_owned(), String::new()));
+    assert_eq!(quick_entry::find_exercise(&db, "squat "), Ok(SQUAT));
+    assert_eq!(quick_entry::find_exercise(&db, "squat j"), Ok(5));
+    assert_eq!(quick_entry::find_exercise(&db, "sq h"), Ok(6));
+    db.exercises_mut().insert(Exercise::new(7, "Squat Hop".to_owned(), String::new()));
+    assert_eq!(
+        quick_entry::find_exercise(&db, "squat ho"),
+        Err(QuickEntryErrorKind::AmbiguousExercise(vec!["Squat Hold".to_owned(), "Squat Hop".to_owned()])),
+    );
+}
+
+#[test]
+fn errors_point_at_the_input() {
+    let db = db();
+    let parse = |input: &str| quick_entry::parse(&db, input, UnitSystem::Metric, None).unwrap_err();
+    let err = parse("deadlift 3x5@140");
+    assert_eq!((err.kind, err.span), (QuickEntryErrorKind::UnknownExercise("deadlift".to_owned()), 0..8));
+    let err = parse("bench 3x8@80zz");
+    assert_eq!(err.span, 9..14);
+    assert_eq!(err.pointer("bench 3x8@80zz"), "bench 3x8@80zz\n         ^^^^^");
+    let err = parse("bench 3x8 rpe12");
+    assert_eq!((err.kind, err.span), (QuickEntryErrorKind::Rpe("12".to_owned()), 10..15));
+    let err = parse("bench 3x8 80 90");
+    assert_eq!((err.kind, err.span), (QuickEntryErrorKind::Repeated("Weight"), 13..15));
+    let err = parse("bench 3x8 fast");
+    assert_eq!(err.kind, QuickEntryErrorKind::Unexpected("fast".to_owned()));
+    assert_eq!(parse("3x8").kind, QuickEntryErrorKind::MissingExercise);
+    assert_eq!(parse("  ").kind, QuickEntryErrorKind::Empty);
+}
+
+#[test]
+fn sets_are_numbered_in_the_workout() {
+    let entry = quick_entry::parse(&db(), "bench 2x8@80", UnitSystem::Metric, None).unwrap();
+    let numbered = entry.into_sets(9, 4);
+    let orders: Vec<(ID, ID, u32)> = numbered.iter().map(|s| (s.workout_id, s.exercise_id, s.order)).collect();
+    assert_eq!(orders, vec![(9, BENCH, 4), (9, BENCH, 5)]);
+    assert_eq!(numbered[0].weight, kg(80.0));
+}
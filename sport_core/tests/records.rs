@@ -0,0 +1,180 @@
+use chrono::NaiveDate;
+use sport_core::{analytics::OneRepMaxFormula, records};
+use sport_core_db::{
+    entity::{RecordKind, Workout, WorkoutSet, ID},
+    model::{Db, Table},
+    units::{Distance, Duration, Mass},
+};
+
+const PERSON: ID = 1;
+const SQUAT: ID = 10;
+const RUN: ID = 20;
+const FORMULA: OneRepMaxFormula = OneRepMaxFormula::Epley;
+
+fn lift(id: ID, workout_id: ID, kilograms: f64, reps: u32) -> WorkoutSet {
+    WorkoutSet::new(id, workout_id, SQUAT, id as u32, reps, Mass::from_kilograms(kilograms))
+}
+
+fn run(id: ID, workout_id: ID, kilometers: f64, minutes: f64) -> WorkoutSet {
+    WorkoutSet {
+        distance: Distance::from_kilometers(kilometers),
+        duration: Duration::from_minutes(minutes),
+        ..WorkoutSet::new(id, workout_id, RUN, id as u32, 0, Mass::default())
+    }
+}
+
+fn kinds(values: &[records::RecordValue]) -> Vec<RecordKind> {
+    values.iter().map(|r| r.kind).collect()
+}
+
+fn db_with_workouts(days: &[u32]) -> Db {
+    let mut db = Db::default();
+    for (i, day) in days.iter().enumerate() {
+        let started_at = NaiveDate::from_ymd_opt(2026, 3, *day).unwrap().and_hms_opt(18, 0, 0).unwrap();
+        db.workouts_mut().insert(Workout::new(i as ID + 1, PERSON, "Legs".to_owned(), started_at, Duration::default()));
+    }
+    db
+}
+
+#[test]
+fn first_lift_sets_first_records_without_previous() {
+    let set = lift(1, 1, 100.0, 5);
+    let values = records::detect(&[], &set, FORMULA);
+    assert_eq!(kinds(&values), vec![RecordKind::HeaviestWeight, RecordKind::MostReps, RecordKind::BestOneRepMax]);
+    assert!(values.iter().all(|r| r.previous.is_none()));
+}
+
+#[test]
+fn heavier_weight_beats_weight_and_one_rep_max() {
+    let previous = lift(1, 1, 100.0, 5);
+    let set = lift(2, 2, 105.0, 5);
+    let values = records::detect(&[&previous], &set, FORMULA);
+    assert_eq!(kinds(&values), vec![RecordKind::HeaviestWeight, RecordKind::MostReps, RecordKind::BestOneRepMax]);
+    assert_eq!(values[0].previous, Some(100.0));
+    assert_eq!(values[0].value, 105.0);
+    // first time with 105 kg, so the reps record has nothing to beat
+    assert_eq!(values[1].previous, None);
+}
+
+#[test]
+fn one_rep_max_record_follows_the_formula() {
+    let value = |formula, set: &WorkoutSet| records::set_value(RecordKind::BestOneRepMax, set, formula);
+    assert_eq!(value(OneRepMaxFormula::Brzycki, &lift(1, 1, 100.0, 5)), Some(112.5));
+    // Brzycki gives no estimate for that many reps, so there is no record either
+    let set = lift(2, 1, 60.0, 15);
+    assert_eq!(value(OneRepMaxFormula::Brzycki, &set), None);
+    assert_eq!(value(OneRepMaxFormula::Epley, &set), Some(90.0));
+    assert_eq!(kinds(&records::detect(&[], &set, OneRepMaxFormula::Brzycki)), vec![RecordKind::HeaviestWeight, RecordKind::MostReps]);
+}
+
+#[test]
+fn more_reps_only_counts_at_the_same_weight() {
+    let previous = [lift(1, 1, 100.0, 5), lift(2, 1, 80.0, 12)];
+    let previous: Vec<&WorkoutSet> = previous.iter().collect();
+
+    let values = records::detect(&previous, &lift(3, 2, 100.0, 6), FORMULA);
+    assert_eq!(kinds(&values), vec![RecordKind::MostReps, RecordKind::BestOneRepMax]);
+    assert_eq!(values[0].previous, Some(5.0));
+    assert_eq!(values[0].value, 6.0);
+
+    let values = records::detect(&previous, &lift(4, 2, 100.0, 5), FORMULA);
+    assert!(values.is_empty());
+}
+
+#[test]
+fn equal_performance_is_not_a_record() {
+    let previous = lift(1, 1, 100.0, 5);
+    assert!(records::detect(&[&previous], &lift(2, 2, 100.0, 5), FORMULA).is_empty());
+}
+
+#[test]
+fn single_rep_one_rep_max_is_the_weight() {
+    let set = lift(1, 1, 140.0, 1);
+    let values = records::detect(&[], &set, FORMULA);
+    let one_rep_max = values.iter().find(|r| r.kind == RecordKind::BestOneRepMax).unwrap();
+    assert_eq!(one_rep_max.value, 140.0);
+}
+
+#[test]
+fn longer_distance_and_faster_pace() {
+    let previous = run(1, 1, 5.0, 30.0);
+    let values = records::detect(&[&previous], &run(2, 2, 10.0, 55.0), FORMULA);
+    assert_eq!(kinds(&values), vec![RecordKind::LongestDistance, RecordKind::FastestPace]);
+    assert_eq!(values[0].value, 10_000.0);
+    assert_eq!(values[1].previous, Some(360.0));
+    assert_eq!(values[1].value, 330.0);
+
+    let values = records::detect(&[&previous], &run(3, 2, 3.0, 20.0), FORMULA);
+    assert!(values.is_empty());
+}
+
+#[test]
+fn distance_without_duration_has_no_pace() {
+    let set = WorkoutSet { duration: Duration::default(), ..run(1, 1, 5.0, 0.0) };
+    assert_eq!(kinds(&records::detect(&[], &set, FORMULA)), vec![RecordKind::LongestDistance]);
+}
+
+#[test]
+fn register_set_compares_only_earlier_sets_and_returns_improvements() {
+    let mut db = db_with_workouts(&[1, 8]);
+    db.sets_mut().insert(lift(1, 1, 100.0, 5));
+    records::rebuild(&mut db, FORMULA);
+    assert_eq!(db.records().len(), 3);
+
+    db.sets_mut().insert(lift(2, 2, 110.0, 3));
+    let beaten = records::register_set(&mut db, 2, FORMULA);
+    assert_eq!(beaten.len(), 2);
+    assert!(beaten.iter().all(|r| r.person_id == PERSON && r.exercise_id == SQUAT && r.set_id == 2));
+    assert!(beaten.iter().any(|r| r.kind == RecordKind::HeaviestWeight && r.previous == Some(100.0)));
+    // most reps at 110 kg is the first performance, stored but not announced
+    assert_eq!(db.records().len(), 6);
+}
+
+#[test]
+fn rebuild_replays_sets_in_chronological_order() {
+    let mut db = db_with_workouts(&[8, 1]);
+    // the workout 2 is the earlier one
+    db.sets_mut().insert(lift(1, 1, 110.0, 5));
+    db.sets_mut().insert(lift(2, 2, 100.0, 5));
+    records::rebuild(&mut db, FORMULA);
+
+    let history = records::history(&db, PERSON, SQUAT);
+    let heaviest: Vec<(f64, Option<f64>)> = history
+        .iter()
+        .filter(|r| r.kind == RecordKind::HeaviestWeight)
+        .map(|r| (r.value, r.previous))
+        .collect();
+    assert_eq!(heaviest, vec![(100.0, None), (110.0, Some(100.0))]);
+}
+
+#[test]
+fn backdated_set_recomputes_the_later_records() {
+    let mut db = db_with_workouts(&[1, 8, 15]);
+    db.sets_mut().insert(lift(1, 1, 100.0, 5));
+    db.sets_mut().insert(lift(3, 3, 105.0, 5));
+    records::rebuild(&mut db, FORMULA);
+
+    // logged last but done on the 8th, heavier than the 15th
+    db.sets_mut().insert(lift(2, 2, 110.0, 5));
+    let beaten = records::register_set(&mut db, 2, FORMULA);
+    assert!(beaten.iter().any(|r| r.kind == RecordKind::HeaviestWeight && r.previous == Some(100.0)));
+
+    let heaviest: Vec<(ID, f64)> = records::history(&db, PERSON, SQUAT)
+        .iter()
+        .filter(|r| r.kind == RecordKind::HeaviestWeight)
+        .map(|r| (r.set_id, r.value))
+        .collect();
+    assert_eq!(heaviest, vec![(1, 100.0), (2, 110.0)]);
+}
+
+#[test]
+fn rebuild_drops_records_of_removed_workouts() {
+    let mut db = db_with_workouts(&[1, 8]);
+    db.sets_mut().insert(lift(1, 1, 100.0, 5));
+    db.sets_mut().insert(lift(2, 2, 110.0, 5));
+    records::rebuild(&mut db, FORMULA);
+    db.workouts_mut().remove(2);
+    records::rebuild(&mut db, FORMULA);
+    assert!(db.records().get_all().all(|r| r.set_id == 1));
+    assert_eq!(db.records().len(), 3);
+}
@@ -0,0 +1,101 @@
+use chrono::NaiveDate;
+use sport_core::{
+    analytics::{OneRepMaxFormula, Period},
+    records,
+    reports::{self, ReportFormat, ReportKind},
+};
+use sport_core_db::{
+    entity::{Exercise, MuscleGroup, Workout, WorkoutSet, ID},
+    model::{Db, Table},
+    units::{Duration, Mass, UnitSystem},
+};
+
+const PERSON: ID = 1;
+const SQUAT: ID = 10;
+
+fn date(month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+}
+
+fn add_workout(db: &mut Db, id: ID, date: NaiveDate, minutes: f64) {
+    let started_at = date.and_hms_opt(18, 0, 0).unwrap();
+    db.workouts_mut().insert(Workout::new(id, PERSON, "Legs".to_owned(), started_at, Duration::from_minutes(minutes)));
+}
+
+fn add_set(db: &mut Db, id: ID, workout_id: ID, kilograms: f64, reps: u32) {
+    db.sets_mut().insert(WorkoutSet::new(id, workout_id, SQUAT, id as u32, reps, Mass::from_kilograms(kilograms)));
+}
+
+/// One session in the week of March 2nd, two in the week of March 9th
+fn sample_db() -> Db {
+    let mut db = Db::default();
+    db.exercises_mut().insert(Exercise {
+        muscle_groups: vec![MuscleGroup::Quads, MuscleGroup::Glutes],
+        ..Exercise::new(SQUAT, "Squat <high bar>".to_owned(), "".to_owned())
+    });
+    add_workout(&mut db, 1, date(3, 3), 60.0);
+    add_workout(&mut db, 2, date(3, 10), 45.0);
+    add_workout(&mut db, 3, date(3, 12), 45.0);
+    add_set(&mut db, 1, 1, 100.0, 5);
+    add_set(&mut db, 2, 2, 100.0, 5);
+    add_set(&mut db, 3, 2, 100.0, 5);
+    add_set(&mut db, 4, 3, 110.0, 3);
+    records::rebuild(&mut db, OneRepMaxFormula::Epley);
+    db
+}
+
+#[test]
+fn weekly_report_compares_with_the_previous_week() {
+    let db = sample_db();
+    let report = reports::generate(&db, PERSON, ReportKind::Weekly, date(3, 11));
+    assert_eq!(report.period, Period::new(date(3, 9), date(3, 15)));
+    assert_eq!(report.current.sessions, 2);
+    assert_eq!(report.current.duration, Duration::from_minutes(90.0));
+    assert_eq!(report.current.volume.sets, 3);
+    assert_eq!(report.current.volume.tonnage, Mass::from_kilograms(1330.0));
+    assert_eq!(report.previous.sessions, 1);
+    assert_eq!(report.muscle_groups[&MuscleGroup::Quads], (3, 1));
+}
+
+#[test]
+fn report_lists_only_records_beaten_in_the_period() {
+    let db = sample_db();
+    let report = reports::generate(&db, PERSON, ReportKind::Weekly, date(3, 11));
+    // the first week only sets first performances, the 110 kg triple beats weight and 1RM
+    let kinds: Vec<&str> = report.records.iter().map(|r| r.kind.name()).collect();
+    assert_eq!(kinds, vec!["Heaviest weight", "Best estimated 1RM"]);
+    assert!(reports::generate(&db, PERSON, ReportKind::Weekly, date(3, 3)).records.is_empty());
+}
+
+#[test]
+fn monthly_report_has_no_previous_data() {
+    let db = sample_db();
+    let report = reports::generate(&db, PERSON, ReportKind::Monthly, date(3, 1));
+    assert_eq!(report.period, Period::new(date(3, 1), date(3, 31)));
+    assert_eq!(report.current.sessions, 3);
+    assert_eq!(report.previous.sessions, 0);
+    assert_eq!(report.summary_rows(UnitSystem::Metric)[0], ["Sessions", "3", "0", "-"].map(str::to_owned));
+}
+
+#[test]
+fn changes_are_signed_percents() {
+    assert_eq!(reports::format_change(3.0, 2.0), "+50%");
+    assert_eq!(reports::format_change(1.0, 2.0), "-50%");
+    assert_eq!(reports::format_change(1.0, 0.0), "-");
+}
+
+#[test]
+fn exports_markdown_and_escaped_html() {
+    let db = sample_db();
+    let report = reports::generate(&db, PERSON, ReportKind::Weekly, date(3, 11));
+    assert_eq!(report.file_name(ReportFormat::Markdown), "weekly-report-2026-03-09.md");
+
+    let markdown = report.render(ReportFormat::Markdown, UnitSystem::Metric);
+    assert!(markdown.starts_with("# Weekly report 2026-03-09 - 2026-03-15\n"));
+    assert!(markdown.contains("| Sessions | 2 | 1 | +100% |"));
+    assert!(markdown.contains("| quads | 3 | 1 | +200% |"));
+
+    let html = report.render(ReportFormat::Html, UnitSystem::Metric);
+    assert!(html.contains("<td>Squat &lt;high bar&gt;</td>"));
+    assert!(html.ends_with("</html>\n"));
+}
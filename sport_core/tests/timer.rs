@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use sport_core::timer::{self, FakeClock, PhaseKind, Timer, TimerConfig, TimerEvent, TimerMode, TimerModeError};
+
+fn secs(seconds: u64) -> Duration {
+    Duration::from_secs(seconds)
+}
+
+fn phase_changes(events: &[TimerEvent]) -> Vec<usize> {
+    events
+        .iter()
+        .filter_map(|e| match e {
+            TimerEvent::PhaseChanged { index, .. } => Some(*index),
+            TimerEvent::Finished => None,
+        })
+        .collect()
+}
+
+#[test]
+fn rest_countdown() {
+    let clock = FakeClock::default();
+    let mut timer = Timer::new(TimerMode::Rest(secs(90)), &clock);
+    assert_eq!(phase_changes(&timer.start()), vec![0]);
+    clock.advance(secs(30));
+    assert!(timer.tick().is_empty());
+    assert_eq!(timer.remaining(), secs(60));
+    clock.advance(secs(61));
+    assert_eq!(timer.tick(), vec![TimerEvent::Finished]);
+    assert!(timer.is_finished());
+    assert_eq!(timer.remaining(), Duration::ZERO);
+    clock.advance(secs(10));
+    assert!(timer.tick().is_empty());
+}
+
+#[test]
+fn tabata_phases_and_catching_up() {
+    let clock = FakeClock::default();
+    let mode: TimerMode = "tabata".parse().unwrap();
+    let mut timer = Timer::new(mode, &clock);
+    assert_eq!(timer.phases().len(), 16);
+    timer.start();
+    clock.advance(secs(20));
+    let events = timer.tick();
+    assert_eq!(phase_changes(&events), vec![1]);
+    assert_eq!(timer.current_phase().unwrap().1.kind, PhaseKind::Rest);
+    // a slow tick crosses several phases at once and keeps the overrun
+    clock.advance(secs(45));
+    assert_eq!(phase_changes(&timer.tick()), vec![2, 3, 4]);
+    assert_eq!(timer.remaining(), secs(15));
+    assert_eq!(timer.total_remaining(), secs(240 - 65));
+}
+
+#[test]
+fn pause_and_skip() {
+    let clock = FakeClock::default();
+    let mut timer = Timer::new("emom 1:00 x3".parse().unwrap(), &clock);
+    timer.start();
+    clock.advance(secs(10));
+    timer.pause();
+    assert!(timer.is_paused());
+    clock.advance(secs(100));
+    assert!(timer.tick().is_empty());
+    assert_eq!(timer.remaining(), secs(50));
+    timer.toggle_pause();
+    clock.advance(secs(5));
+    assert_eq!(timer.remaining(), secs(45));
+    assert_eq!(phase_changes(&timer.skip()), vec![1]);
+    assert_eq!(timer.remaining(), secs(60));
+    assert_eq!(timer.current_phase().unwrap().1.label, "Round 2/3");
+    timer.skip();
+    assert_eq!(timer.skip(), vec![TimerEvent::Finished]);
+}
+
+#[test]
+fn parsing_timer_modes() {
+    assert_eq!("rest 1:30".parse(), Ok(TimerMode::Rest(secs(90))));
+    assert_eq!("EMOM 10".parse(), Ok(TimerMode::Emom { interval: secs(60), rounds: 10 }));
+    assert_eq!("amrap 12m".parse(), Ok(TimerMode::Amrap(secs(720))));
+    assert_eq!("tabata 30 15 x6".parse(), Ok(TimerMode::Tabata { work: secs(30), rest: secs(15), rounds: 6 }));
+    let custom: TimerMode = "custom work 40, rest 20s, work 0:40".parse().unwrap();
+    let kinds: Vec<(PhaseKind, Duration)> = custom.phases().iter().map(|p| (p.kind, p.duration)).collect();
+    assert_eq!(kinds, vec![(PhaseKind::Work, secs(40)), (PhaseKind::Rest, secs(20)), (PhaseKind::Work, secs(40))]);
+    assert_eq!(custom.to_string(), "custom work 0:40, rest 0:20, work 0:40");
+    assert_eq!(custom.to_string().parse(), Ok(custom));
+    assert_eq!("jog 10".parse::<TimerMode>(), Err(TimerModeError::UnknownMode("jog".to_owned())));
+    assert_eq!("rest soon".parse::<TimerMode>(), Err(TimerModeError::Duration("soon".to_owned())));
+    assert_eq!("custom 40".parse::<TimerMode>(), Err(TimerModeError::PhaseKind("40".to_owned())));
+    assert_eq!(timer::parse_duration("1:00:00"), Ok(secs(3600)));
+    assert_eq!(timer::format_duration(secs(3725)), "1:02:05");
+    assert_eq!(timer::parse_duration("1,5 min"), Ok(secs(90)));
+    assert_eq!(timer::parse_duration("0:00"), Err(TimerModeError::Duration("0:00".to_owned())));
+}
+
+#[test]
+fn rounds_and_durations_are_capped() {
+    assert_eq!("emom 1 x1000".parse::<TimerMode>().map(|m| m.phases().len()), Ok(1000));
+    assert_eq!("emom 1 x4000000000".parse::<TimerMode>(), Err(TimerModeError::RoundsLimit("x4000000000".to_owned())));
+    assert_eq!("tabata 10000000000000000000 10 x8".parse::<TimerMode>(), Err(TimerModeError::DurationLimit("10000000000000000000".to_owned())));
+    assert_eq!("custom work 24:00:01".parse::<TimerMode>(), Err(TimerModeError::DurationLimit("24:00:01".to_owned())));
+    assert_eq!(timer::parse_duration("24:00:00"), Ok(timer::MAX_DURATION));
+
+    // the longest timer still counts down without overflowing
+    let mode = TimerMode::Tabata { work: timer::MAX_DURATION, rest: timer::MAX_DURATION, rounds: timer::MAX_ROUNDS };
+    let clock = FakeClock::default();
+    let mut timer = Timer::new(mode, &clock);
+    timer.start();
+    assert_eq!(timer.total_remaining(), timer::MAX_DURATION * 2 * timer::MAX_ROUNDS);
+    let config = TimerConfig { rest_seconds: u64::MAX, ..TimerConfig::default() };
+    assert_eq!(config.rest(), TimerMode::Rest(timer::MAX_DURATION));
+}
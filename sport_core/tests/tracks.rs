@@ -0,0 +1,74 @@
+use std::path::Path;
+
+use chrono::{Local, TimeZone, Utc};
+use sport_core::{
+    backup::DryRun,
+    tracks::{Track, TrackError, TrackFormat},
+};
+use sport_core_db::{
+    entity::Exercise,
+    model::{Db, Table},
+    units::{Distance, Duration, UnitSystem},
+};
+
+fn fixture(name: &str) -> Track {
+    Track::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)).unwrap()
+}
+
+#[test]
+fn gpx_summary_is_computed_from_points() {
+    let track = fixture("morning_run.gpx");
+    assert_eq!((track.format, track.name.as_str(), track.sport.as_str(), track.points.len()), (TrackFormat::Gpx, "Morning Run", "running", 4));
+    let summary = track.summary().unwrap();
+    assert_eq!(summary.started_at, Utc.with_ymd_and_hms(2026, 5, 10, 6, 30, 0).unwrap());
+    assert_eq!(summary.duration, Duration::from_minutes(3.0));
+    // three steps of a thousandth of a degree north
+    assert!((summary.distance.meters() - 333.6).abs() < 0.5, "{}", summary.distance.meters());
+    assert_eq!(summary.elevation_gain.meters(), 13.0);
+    assert_eq!(summary.heart_rate, vec![(0, 120), (60, 130), (120, 140), (180, 150)]);
+    assert_eq!((summary.average_heart_rate(), summary.max_heart_rate()), (Some(135), Some(150)));
+    assert!((summary.pace().unwrap().seconds() - 539.6).abs() < 1.0);
+}
+
+#[test]
+fn tcx_summary_uses_lap_totals() {
+    let track = fixture("evening_ride.tcx");
+    assert_eq!((track.format, track.name.as_str(), track.sport.as_str(), track.laps.len()), (TrackFormat::Tcx, "Evening Ride", "Biking", 2));
+    let summary = track.summary().unwrap();
+    // paused time between the laps is not counted
+    assert_eq!((summary.duration, summary.distance), (Duration::from_seconds(2700.0), Distance::from_kilometers(18.0)));
+    assert_eq!(summary.elevation_gain.meters(), 40.0);
+    assert_eq!(summary.heart_rate, vec![(0, 110), (1500, 150), (3000, 160)]);
+    assert_eq!(summary.pace(), Some(Duration::from_seconds(150.0)));
+    assert_eq!(
+        summary.describe(UnitSystem::Metric),
+        "Distance: 18 km\nDuration: 45:00\nPace: 2:30/km\nElevation gain: 40 m\nHeart rate: 140 bpm average, 160 bpm max",
+    );
+}
+
+#[test]
+fn invalid_files_are_rejected() {
+    assert!(matches!(Track::read("<gpx>"), Err(TrackError::Xml(_))));
+    assert!(matches!(Track::read("<kml></kml>"), Err(TrackError::Format)));
+    let untimed = Track::read(r#"<gpx><trk><trkseg><trkpt lat="1" lon="1"/></trkseg></trk></gpx>"#).unwrap();
+    assert!(matches!(untimed.summary(), Err(TrackError::NoTime)));
+}
+
+#[test]
+fn summary_becomes_a_cardio_workout_once() {
+    let mut db = Db::default();
+    db.exercises_mut().insert(Exercise::new(3, "Squat".to_owned(), String::new()));
+    db.exercises_mut().insert(Exercise::new(4, "Running".to_owned(), String::new()));
+    let summary = fixture("morning_run.gpx").summary().unwrap();
+    assert_eq!(summary.guess_exercise(&db), Some(4));
+
+    let (workout, set) = summary.to_workout(1, 4, UnitSystem::Metric);
+    assert_eq!(workout.started_at, Local.from_utc_datetime(&summary.started_at.naive_utc()).naive_local());
+    assert_eq!((workout.name.as_str(), workout.duration), ("Morning Run", Duration::from_minutes(3.0)));
+    assert!(workout.notes.contains("Heart rate: 135 bpm average"));
+    assert_eq!((set.exercise_id, set.distance, set.duration), (4, summary.distance, summary.duration));
+
+    assert!(summary.import(1, 4, UnitSystem::Metric, DryRun::new(&db)).unwrap().is_some());
+    db.workouts_mut().insert(workout);
+    assert_eq!(summary.import(1, 4, UnitSystem::Metric, DryRun::new(&db)).unwrap(), None);
+}
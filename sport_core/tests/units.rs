@@ -0,0 +1,80 @@
+use sport_core_db::units::{format_number, Distance, Duration, Length, Mass, ParseQuantityError, Quantity, UnitSystem};
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-6, "{} != {}", actual, expected);
+}
+
+#[test]
+fn numbers_drop_trailing_zeros() {
+    assert_eq!(format_number(80.0, 2), "80");
+    assert_eq!(format_number(80.5, 2), "80.5");
+    assert_eq!(format_number(80.125, 1), "80.1");
+    assert_eq!(format_number(100.0, 0), "100");
+}
+
+#[test]
+fn mass_and_distance_round_trip_in_both_systems() {
+    // formatting keeps two decimals of the shown unit
+    for system in [UnitSystem::Metric, UnitSystem::Imperial] {
+        let mass = Mass::parse(&Mass::from_kilograms(102.5).format(system), system).unwrap();
+        assert!((mass.kilograms() - 102.5).abs() < 0.01, "{:?}", mass);
+        let distance = Distance::parse(&Distance::from_kilometers(5.0).format(system), system).unwrap();
+        assert!((distance.kilometers() - 5.0).abs() < 0.01, "{:?}", distance);
+    }
+    assert_eq!(Mass::from_pounds(225.0).format(UnitSystem::Imperial), "225 lb");
+    assert_eq!(Mass::from_pounds(225.0).format(UnitSystem::Metric), "102.06 kg");
+    assert_eq!(Distance::from_miles(1.0).format(UnitSystem::Metric), "1.61 km");
+}
+
+#[test]
+fn bare_numbers_take_the_preferred_unit() {
+    assert_close(Mass::parse("100", UnitSystem::Metric).unwrap().kilograms(), 100.0);
+    assert_close(Mass::parse("100", UnitSystem::Imperial).unwrap().pounds(), 100.0);
+    assert_close(Mass::parse("100 LBS", UnitSystem::Metric).unwrap().pounds(), 100.0);
+    assert_close(Distance::parse("400m", UnitSystem::Imperial).unwrap().meters(), 400.0);
+    assert_close(Length::parse("70", UnitSystem::Imperial).unwrap().inches(), 70.0);
+}
+
+#[test]
+fn comma_is_a_decimal_separator() {
+    assert_close(Mass::parse("80,5 kg", UnitSystem::Metric).unwrap().kilograms(), 80.5);
+    assert_close(Length::parse("1,82 m", UnitSystem::Metric).unwrap().centimeters(), 182.0);
+    assert_close(Duration::parse("1:30,5", UnitSystem::Metric).unwrap().seconds(), 90.5);
+}
+
+#[test]
+fn length_in_feet_and_inches() {
+    let height = Length::parse("5' 11\"", UnitSystem::Metric).unwrap();
+    assert_close(height.inches(), 71.0);
+    assert_eq!(height.format(UnitSystem::Imperial), "5' 11\"");
+    assert_eq!(height.format(UnitSystem::Metric), "180.3 cm");
+    assert_close(Length::parse("6'", UnitSystem::Metric).unwrap().inches(), 72.0);
+    assert_close(Length::parse("6 ft", UnitSystem::Metric).unwrap().inches(), 72.0);
+    // 11.6 inches round up to the next foot
+    assert_eq!(Length::from_feet_inches(5.0, 11.6).format(UnitSystem::Imperial), "6' 0\"");
+    assert_eq!(Length::from_centimeters(180.0).feet_inches().0, 5);
+}
+
+#[test]
+fn duration_in_clock_format() {
+    assert_eq!(Duration::from_seconds(75.0).format(UnitSystem::Metric), "1:15");
+    assert_eq!(Duration::from_seconds(3725.0).format(UnitSystem::Imperial), "1:02:05");
+    assert_close(Duration::parse("1:02:05", UnitSystem::Metric).unwrap().seconds(), 3725.0);
+    assert_close(Duration::parse("1:15", UnitSystem::Metric).unwrap().seconds(), 75.0);
+    assert_close(Duration::parse("90 s", UnitSystem::Metric).unwrap().seconds(), 90.0);
+    assert_close(Duration::parse("2min", UnitSystem::Metric).unwrap().seconds(), 120.0);
+    assert_close(Duration::parse("12m", UnitSystem::Metric).unwrap().seconds(), 720.0);
+    assert_close(Duration::parse("1.5 h", UnitSystem::Metric).unwrap().minutes(), 90.0);
+}
+
+#[test]
+fn bad_input_is_rejected() {
+    assert_eq!(Mass::parse("", UnitSystem::Metric), Err(ParseQuantityError::Empty));
+    assert_eq!(Mass::parse("  kg", UnitSystem::Metric), Err(ParseQuantityError::Number("kg".to_owned())));
+    assert_eq!(Mass::parse("80 stone", UnitSystem::Metric), Err(ParseQuantityError::Unit("stone".to_owned())));
+    assert_eq!(Mass::parse("-5 kg", UnitSystem::Metric), Err(ParseQuantityError::Negative));
+    assert_eq!(Mass::parse("1.2.3", UnitSystem::Metric), Err(ParseQuantityError::Number("1.2.3".to_owned())));
+    assert_eq!(Length::parse("5' x\"", UnitSystem::Metric), Err(ParseQuantityError::Number("x".to_owned())));
+    assert_eq!(Duration::parse("1::30", UnitSystem::Metric), Err(ParseQuantityError::Empty));
+    assert_eq!(Duration::parse("10 days", UnitSystem::Metric), Err(ParseQuantityError::Unit("days".to_owned())));
+}
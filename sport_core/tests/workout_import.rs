@@ -0,0 +1,109 @@
+use chrono::NaiveDate;
+use sport_core::{
+    backup::DryRun,
+    workout_import::{self, ExerciseMapping, ImportFormat, Resolution, WorkoutImport, WorkoutImportError},
+};
+use sport_core_db::{
+    entity::{Exercise, Workout, ID},
+    model::{Db, Table},
+    units::{Distance, Duration, Mass, UnitSystem},
+};
+
+const PERSON: ID = 1;
+const SQUAT: ID = 10;
+const BENCH: ID = 11;
+
+const STRONG: &str = "\
+Date,Workout Name,Duration,Exercise Name,Set Order,Weight,Reps,Distance,Seconds,Notes,Workout Notes,RPE
+2026-05-10 18:00:00,Legs,1h 5m,Squat (Barbell),1,100,5,0,0,,,8
+2026-05-10 18:00:00,Legs,1h 5m,Squat (Barbell),Rest Timer,0,0,0,120,,,
+2026-05-10 18:00:00,Legs,1h 5m,Squat (Barbell),2,100,5,0,0,Felt heavy,,
+2026-05-10 18:00:00,Legs,1h 5m,Rowing,1,0,0,2,600,,,
+2026-05-12 07:30:00,Push,45m,Bench press,1,80,8,0,0,,Morning,
+";
+
+const HEVY: &str = "\
+title,start_time,end_time,description,exercise_title,superset_id,exercise_notes,set_index,set_type,weight_kg,reps,distance_km,duration_seconds,rpe
+Legs,\"10 May 2026, 18:00\",\"10 May 2026, 19:00\",,Squat,,,0,normal,100,5,,,
+Legs,\"10 May 2026, 18:00\",\"10 May 2026, 19:00\",,Squat,,,1,normal,102.5,3,,,9.5
+";
+
+fn db() -> Db {
+    let mut db = Db::default();
+    db.exercises_mut().insert(Exercise::new(SQUAT, "Squat".to_owned(), String::new()));
+    db.exercises_mut().insert(Exercise::new(BENCH, "Bench Press".to_owned(), String::new()));
+    db
+}
+
+#[test]
+fn strong_export_groups_sets_into_workouts() {
+    let import = WorkoutImport::read(STRONG, UnitSystem::Metric).unwrap();
+    assert_eq!(import.format, ImportFormat::Strong);
+    assert_eq!((import.workouts.len(), import.sets_count()), (2, 4));
+    let legs = &import.workouts[0];
+    assert_eq!(legs.started_at, NaiveDate::from_ymd_opt(2026, 5, 10).unwrap().and_hms_opt(18, 0, 0).unwrap());
+    assert_eq!(legs.duration, Duration::from_minutes(65.0));
+    assert_eq!(legs.notes, "Squat (Barbell): Felt heavy");
+    assert_eq!((legs.sets[0].weight, legs.sets[0].reps, legs.sets[0].rpe), (Mass::from_kilograms(100.0), 5, Some(8.0)));
+    assert_eq!((legs.sets[2].distance, legs.sets[2].duration), (Distance::from_kilometers(2.0), Duration::from_minutes(10.0)));
+    assert_eq!((import.workouts[1].duration, import.workouts[1].notes.as_str()), (Duration::from_minutes(45.0), "Morning"));
+    assert_eq!(import.exercise_names(), vec!["Squat (Barbell)", "Rowing", "Bench press"]);
+}
+
+#[test]
+fn hevy_export_uses_unit_columns_and_end_time() {
+    let import = WorkoutImport::read(HEVY, UnitSystem::Imperial).unwrap();
+    assert_eq!(import.format, ImportFormat::Hevy);
+    let workout = &import.workouts[0];
+    assert_eq!((workout.name.as_str(), workout.duration, workout.sets.len()), ("Legs", Duration::from_minutes(60.0), 2));
+    assert_eq!((workout.sets[1].weight, workout.sets[1].rpe), (Mass::from_kilograms(102.5), Some(9.5)));
+}
+
+#[test]
+fn invalid_rows_are_reported_by_line() {
+    assert_eq!(WorkoutImport::read("Weight,Reps\n1,2\n", UnitSystem::Metric).unwrap_err(), WorkoutImportError::MissingColumn("date"));
+    let bad = "Date,Exercise Name,Weight,Reps\n2026-05-10 18:00:00,Squat,heavy,5\n";
+    assert_eq!(
+        WorkoutImport::read(bad, UnitSystem::Metric).unwrap_err(),
+        WorkoutImportError::Value { line: 2, what: "weight", value: "heavy".to_owned() },
+    );
+    for duration in ["1h 5分", "45m 10с"] {
+        let bad = format!("Date,Workout Name,Duration,Exercise Name,Weight,Reps\n2026-05-10 18:00:00,Legs,{},Squat,100,5\n", duration);
+        assert_eq!(
+            WorkoutImport::read(&bad, UnitSystem::Metric).unwrap_err(),
+            WorkoutImportError::Value { line: 2, what: "duration", value: duration.to_owned() },
+        );
+    }
+}
+
+#[test]
+fn exercise_names_are_matched_or_guessed() {
+    let import = WorkoutImport::read(STRONG, UnitSystem::Metric).unwrap();
+    let mappings = workout_import::map_exercises(&db(), &import.exercise_names());
+    let resolved: Vec<(Resolution, bool)> = mappings.iter().map(|m| (m.resolution, m.is_guess)).collect();
+    assert_eq!(resolved, vec![(Resolution::Existing(SQUAT), true), (Resolution::Skip, false), (Resolution::Existing(BENCH), false)]);
+}
+
+#[test]
+fn import_creates_requested_exercises_and_skips_known_workouts() {
+    let mut db = db();
+    let started_at = NaiveDate::from_ymd_opt(2026, 5, 12).unwrap().and_hms_opt(7, 30, 0).unwrap();
+    db.workouts_mut().insert(Workout::new(5, PERSON, "Push".to_owned(), started_at, Duration::default()));
+    let import = WorkoutImport::read(STRONG, UnitSystem::Metric).unwrap();
+    let mappings = vec![
+        ExerciseMapping { name: "Squat (Barbell)".to_owned(), resolution: Resolution::Existing(SQUAT), is_guess: false },
+        ExerciseMapping { name: "Rowing".to_owned(), resolution: Resolution::Create, is_guess: false },
+        ExerciseMapping { name: "Bench press".to_owned(), resolution: Resolution::Skip, is_guess: false },
+    ];
+    let report = workout_import::import(&import, &mappings, PERSON, DryRun::new(&db)).unwrap();
+    assert_eq!(
+        (report.exercises_created, report.workouts_created, report.workouts_existing, report.sets_created, report.sets_skipped),
+        (1, 1, 1, 3, 0),
+    );
+    assert!(report.describe().starts_with("Exercises created: 1\n"));
+    assert_eq!(db.workouts().len(), 1);
+
+    let skipped = [ExerciseMapping { resolution: Resolution::Skip, ..mappings[0].clone() }];
+    let report = workout_import::import(&import, &skipped, PERSON, DryRun::new(&Db::default())).unwrap();
+    assert_eq!((report.workouts_created, report.sets_created, report.sets_skipped), (2, 0, 4));
+}
@@ -1,50 +1,516 @@
-use chrono::{DateTime, Utc};
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{NaiveDate, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::units::{Distance, Duration, Length, Mass, Quantity, UnitSystem};
+
 pub type ID = u64;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase", from = "String")]
+pub enum Gender {
+    Male,
+    Female,
+    /// Other or unspecified
+    #[default]
+    Other,
+}
+
+impl Gender {
+    pub const ALL: [Gender; 3] = [Gender::Male, Gender::Female, Gender::Other];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Gender::Male => "male",
+            Gender::Female => "female",
+            Gender::Other => "other",
+        }
+    }
+}
+
+impl Display for Gender {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Lenient parsing, previously gender was stored as a free string
+impl From<&str> for Gender {
+    fn from(s: &str) -> Self {
+        match s.trim().to_lowercase().as_str() {
+            "m" | "male" | "man" => Gender::Male,
+            "f" | "female" | "woman" => Gender::Female,
+            _ => Gender::Other,
+        }
+    }
+}
+
+impl From<String> for Gender {
+    fn from(s: String) -> Self {
+        Gender::from(s.as_str())
+    }
+}
+
+impl FromStr for Gender {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Gender::from(s))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct Person {
     pub id: ID,
     pub first_name: String,
     pub last_name: String,
-    pub birth_date: DateTime<Utc>, // FIXME only date
-    pub gender: String,
-    pub height: i16,
+    #[serde(serialize_with = "legacy::serialize_birth_date", deserialize_with = "legacy::deserialize_birth_date")]
+    pub birth_date: NaiveDate,
+    pub gender: Gender,
+    #[serde(with = "legacy::height")]
+    pub height: Length,
 }
 
 impl Person {
-    pub fn new(id: ID, first_name: String, last_name: String, birth_date: DateTime<Utc>, gender: String, height: i16) -> Self { 
-        Self { id, first_name, last_name, birth_date, gender, height } 
+    pub fn new(id: ID, first_name: String, last_name: String, birth_date: NaiveDate, gender: Gender, height: Length) -> Self {
+        Self { id, first_name, last_name, birth_date, gender, height }
+    }
+
+    /// Full years at `date`, `None` if `date` is before birth
+    pub fn age_at(&self, date: NaiveDate) -> Option<u32> {
+        date.years_since(self.birth_date)
+    }
+
+    /// Full years today
+    pub fn age(&self) -> Option<u32> {
+        self.age_at(Utc::now().date_naive())
     }
 }
 
-impl Default for Person {
-    fn default() -> Self {
-        Self { 
-            id: 0,
-            first_name: String::from(""),
-            last_name: String::from(""),
-            birth_date: DateTime::default(),
-            gender: String::from(""),
-            height: 0
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum MuscleGroup {
+    Chest,
+    Back,
+    Shoulders,
+    Biceps,
+    Triceps,
+    Forearms,
+    Abs,
+    Quads,
+    Hamstrings,
+    Glutes,
+    Calves,
+}
+
+impl MuscleGroup {
+    pub const ALL: [MuscleGroup; 11] = [
+        MuscleGroup::Chest,
+        MuscleGroup::Back,
+        MuscleGroup::Shoulders,
+        MuscleGroup::Biceps,
+        MuscleGroup::Triceps,
+        MuscleGroup::Forearms,
+        MuscleGroup::Abs,
+        MuscleGroup::Quads,
+        MuscleGroup::Hamstrings,
+        MuscleGroup::Glutes,
+        MuscleGroup::Calves,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MuscleGroup::Chest => "chest",
+            MuscleGroup::Back => "back",
+            MuscleGroup::Shoulders => "shoulders",
+            MuscleGroup::Biceps => "biceps",
+            MuscleGroup::Triceps => "triceps",
+            MuscleGroup::Forearms => "forearms",
+            MuscleGroup::Abs => "abs",
+            MuscleGroup::Quads => "quads",
+            MuscleGroup::Hamstrings => "hamstrings",
+            MuscleGroup::Glutes => "glutes",
+            MuscleGroup::Calves => "calves",
         }
     }
+
+    /// Chest, shoulders and triceps push, back, biceps and forearms pull
+    pub fn is_push(&self) -> bool {
+        matches!(self, MuscleGroup::Chest | MuscleGroup::Shoulders | MuscleGroup::Triceps)
+    }
+
+    pub fn is_pull(&self) -> bool {
+        matches!(self, MuscleGroup::Back | MuscleGroup::Biceps | MuscleGroup::Forearms)
+    }
+
+    pub fn is_upper(&self) -> bool {
+        self.is_push() || self.is_pull()
+    }
+
+    pub fn is_lower(&self) -> bool {
+        matches!(self, MuscleGroup::Quads | MuscleGroup::Hamstrings | MuscleGroup::Glutes | MuscleGroup::Calves)
+    }
+
+    /// Parses comma separated muscle group names
+    pub fn parse_list(s: &str) -> Result<Vec<MuscleGroup>, String> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| name.parse())
+            .collect()
+    }
+
+    pub fn format_list(groups: &[MuscleGroup]) -> String {
+        groups.iter().map(|g| g.as_str()).collect::<Vec<&str>>().join(", ")
+    }
+}
+
+impl Display for MuscleGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl FromStr for MuscleGroup {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase();
+        MuscleGroup::ALL
+            .into_iter()
+            .find(|g| g.as_str() == name)
+            .ok_or_else(|| format!("Unknown muscle group `{}`", s.trim()))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct Exercise {
     pub id: ID,
     pub name: String,
     pub description: String,
+    /// Trained muscle groups, the primary one first
+    #[serde(default)]
+    pub muscle_groups: Vec<MuscleGroup>,
 }
 
 impl Exercise {
-    pub fn new(id: ID, name: String, description: String) -> Self { Self { id, name, description } }
+    pub fn new(id: ID, name: String, description: String) -> Self { Self { id, name, description, muscle_groups: vec![] } }
+}
+
+/// Training session of a person
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Workout {
+    pub id: ID,
+    pub person_id: ID,
+    pub name: String,
+    pub started_at: NaiveDateTime,
+    pub duration: Duration,
+    pub notes: String,
+    /// Routine the session follows
+    #[serde(default)]
+    pub routine_id: Option<ID>,
+}
+
+impl Workout {
+    pub fn new(id: ID, person_id: ID, name: String, started_at: NaiveDateTime, duration: Duration) -> Self {
+        Self { id, person_id, name, started_at, duration, notes: String::new(), routine_id: None }
+    }
+
+    pub fn date(&self) -> NaiveDate {
+        self.started_at.date()
+    }
+}
+
+/// One set of an exercise inside a workout
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct WorkoutSet {
+    pub id: ID,
+    pub workout_id: ID,
+    pub exercise_id: ID,
+    /// Position of the set inside the workout
+    pub order: u32,
+    pub reps: u32,
+    pub weight: Mass,
+    pub distance: Distance,
+    pub duration: Duration,
+    pub rpe: Option<f64>,
+}
+
+impl WorkoutSet {
+    pub fn new(id: ID, workout_id: ID, exercise_id: ID, order: u32, reps: u32, weight: Mass) -> Self {
+        Self { id, workout_id, exercise_id, order, reps, weight, ..Self::default() }
+    }
+
+    /// Weight multiplied by reps
+    pub fn tonnage(&self) -> Mass {
+        self.weight * self.reps as f64
+    }
+
+    /// Time per kilometer, if both distance and duration are logged
+    pub fn pace(&self) -> Option<Duration> {
+        if self.distance.is_zero() || self.duration.is_zero() {
+            return None;
+        }
+        Some(Duration::from_seconds(self.duration.seconds() / self.distance.kilometers()))
+    }
+}
+
+/// How the next targets of a routine exercise are chosen
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressionScheme {
+    /// Same reps until every set reaches the top of the range, then more weight
+    #[default]
+    RepCeiling,
+    /// One more rep per session up to the top of the range, then more weight from the bottom
+    DoubleProgression,
+    /// Weekly waves of percentages of the estimated one rep max
+    PercentageWave,
+}
+
+impl ProgressionScheme {
+    pub const ALL: [ProgressionScheme; 3] = [
+        ProgressionScheme::RepCeiling,
+        ProgressionScheme::DoubleProgression,
+        ProgressionScheme::PercentageWave,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProgressionScheme::RepCeiling => "rep_ceiling",
+            ProgressionScheme::DoubleProgression => "double_progression",
+            ProgressionScheme::PercentageWave => "percentage_wave",
+        }
+    }
+}
+
+impl Display for ProgressionScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for ProgressionScheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase().replace([' ', '-'], "_");
+        ProgressionScheme::ALL
+            .into_iter()
+            .find(|p| p.as_str() == name)
+            .ok_or_else(|| format!("Unknown progression scheme `{}`", s.trim()))
+    }
+}
+
+/// Planned exercise of a routine
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct RoutineExercise {
+    pub exercise_id: ID,
+    pub sets: u32,
+    /// Target reps range
+    pub min_reps: u32,
+    pub max_reps: u32,
+    /// Weight added when progressing
+    pub increment: Mass,
+    pub scheme: ProgressionScheme,
+}
+
+/// Planned session repeated over time
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Routine {
+    pub id: ID,
+    pub person_id: ID,
+    pub name: String,
+    pub exercises: Vec<RoutineExercise>,
+}
+
+impl Routine {
+    pub fn new(id: ID, person_id: ID, name: String) -> Self {
+        Self { id, person_id, name, exercises: vec![] }
+    }
+}
+
+/// Body measurement of a person at a date
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Measurement {
+    pub id: ID,
+    pub person_id: ID,
+    pub date: NaiveDate,
+    pub body_weight: Mass,
+    /// Beats per minute at rest
+    #[serde(default)]
+    pub resting_heart_rate: Option<u32>,
+}
+
+impl Measurement {
+    pub fn new(id: ID, person_id: ID, date: NaiveDate, body_weight: Mass) -> Self {
+        Self { id, person_id, date, body_weight, resting_heart_rate: None }
+    }
+}
+
+/// What a goal is measured by
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GoalMetric {
+    /// Best estimated one-rep max on the exercise
+    OneRepMax { exercise_id: ID },
+    /// Latest body weight
+    BodyWeight,
+    /// Tonnage of the last 7 days, of one exercise or of all of them
+    WeeklyVolume { exercise_id: Option<ID> },
+    /// Number of workouts in the last 7 days
+    #[default]
+    WeeklySessions,
+}
+
+impl GoalMetric {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GoalMetric::OneRepMax { .. } => "1RM",
+            GoalMetric::BodyWeight => "Body weight",
+            GoalMetric::WeeklyVolume { .. } => "Weekly volume",
+            GoalMetric::WeeklySessions => "Weekly sessions",
+        }
+    }
+
+    pub fn exercise_id(&self) -> Option<ID> {
+        match self {
+            GoalMetric::OneRepMax { exercise_id } => Some(*exercise_id),
+            GoalMetric::WeeklyVolume { exercise_id } => *exercise_id,
+            _ => None,
+        }
+    }
+
+    /// Formats a metric value given in base units (kilograms or sessions)
+    pub fn format_value(&self, value: f64, system: UnitSystem) -> String {
+        match self {
+            GoalMetric::WeeklySessions => format!("{}", value.round()),
+            _ => Mass::from_kilograms(value).format(system),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Goal {
+    pub id: ID,
+    pub person_id: ID,
+    pub metric: GoalMetric,
+    /// Target value in base units (kilograms or sessions)
+    pub target: f64,
+    pub start_date: NaiveDate,
+    pub deadline: NaiveDate,
+}
+
+impl Goal {
+    pub fn new(id: ID, person_id: ID, metric: GoalMetric, target: f64, start_date: NaiveDate, deadline: NaiveDate) -> Self {
+        Self { id, person_id, metric, target, start_date, deadline }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordKind {
+    HeaviestWeight,
+    /// Most reps at the same weight
+    MostReps,
+    BestOneRepMax,
+    LongestDistance,
+    /// Lowest time per kilometer
+    FastestPace,
+}
+
+impl RecordKind {
+    pub const ALL: [RecordKind; 5] = [
+        RecordKind::HeaviestWeight,
+        RecordKind::MostReps,
+        RecordKind::BestOneRepMax,
+        RecordKind::LongestDistance,
+        RecordKind::FastestPace,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RecordKind::HeaviestWeight => "Heaviest weight",
+            RecordKind::MostReps => "Most reps",
+            RecordKind::BestOneRepMax => "Best estimated 1RM",
+            RecordKind::LongestDistance => "Longest distance",
+            RecordKind::FastestPace => "Fastest pace",
+        }
+    }
+
+    /// Formats a record value given in base units
+    pub fn format_value(&self, value: f64, system: UnitSystem) -> String {
+        match self {
+            RecordKind::HeaviestWeight | RecordKind::BestOneRepMax => Mass::from_kilograms(value).format(system),
+            RecordKind::MostReps => format!("{} reps", value),
+            RecordKind::LongestDistance => Distance::from_meters(value).format(system),
+            RecordKind::FastestPace => {
+                let per = Distance::unit_symbol(system);
+                let pace = Duration::from_seconds(value * Distance::from_value_in(1.0, system).kilometers());
+                format!("{}/{}", pace.format(system), per)
+            }
+        }
+    }
 }
 
-impl Default for Exercise {
-    fn default() -> Self {
-        Self { id: 0, name: String::from(""), description: String::from("") }
+/// Personal record set on an exercise, kept as the records history
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PersonalRecord {
+    pub id: ID,
+    pub person_id: ID,
+    pub exercise_id: ID,
+    pub set_id: ID,
+    pub kind: RecordKind,
+    pub date: NaiveDate,
+    /// New value in base units (kilograms, reps, meters or seconds per kilometer)
+    pub value: f64,
+    /// Beaten value, `None` for the first performance
+    pub previous: Option<f64>,
+}
+
+impl PersonalRecord {
+    pub fn is_improvement(&self) -> bool {
+        self.previous.is_some()
+    }
+}
+
+/// Serde helpers keeping compatibility with previously serialized data
+mod legacy {
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Midnight UTC in the old full timestamp format the server stores
+    pub fn serialize_birth_date<S: Serializer>(date: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error> {
+        date.and_time(NaiveTime::MIN).and_utc().serialize(serializer)
+    }
+
+    /// Accepts `YYYY-MM-DD` as well as the old full timestamp format
+    pub fn deserialize_birth_date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        if let Ok(date) = NaiveDate::parse_from_str(&s, "%Y-%m-%d") {
+            return Ok(date);
+        }
+        if let Ok(date_time) = DateTime::parse_from_rfc3339(&s) {
+            return Ok(date_time.with_timezone(&Utc).date_naive());
+        }
+        NaiveDateTime::parse_from_str(&s, "%Y-%m-%dT%H:%M:%S%.f")
+            .map(|date_time| date_time.date())
+            .map_err(|_| de::Error::custom(format!("invalid birth date `{}`", s)))
+    }
+
+    /// Height is sent to the server as whole centimeters
+    pub mod height {
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        use crate::units::Length;
+
+        pub fn serialize<S: Serializer>(height: &Length, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_i16(height.centimeters().round() as i16)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Length, D::Error> {
+            Ok(Length::from_centimeters(f64::deserialize(deserializer)?))
+        }
     }
 }
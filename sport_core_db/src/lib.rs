@@ -1,2 +1,3 @@
 pub mod entity;
 pub mod model;
+pub mod units;
@@ -1,24 +1,22 @@
 use std::collections::HashMap;
 
-use crate::entity::{ID, Exercise, Person};
+use crate::entity::{ID, Exercise, Person, Workout, WorkoutSet, Measurement, Goal, PersonalRecord, Routine};
 
 #[derive(Debug)]
 pub enum TableType {
     Exercises,
 }
 
+#[derive(Default)]
 pub struct Db {
     exercises: Exercises,
     persons: Persons,
-}
-
-impl Default for Db {
-    fn default() -> Self {
-        Self { 
-            exercises: Exercises::default(),
-            persons: Persons::default(),
-        }
-    }
+    workouts: Workouts,
+    sets: WorkoutSets,
+    measurements: Measurements,
+    goals: Goals,
+    records: PersonalRecords,
+    routines: Routines,
 }
 
 impl Db {
@@ -37,6 +35,54 @@ impl Db {
     pub fn persons_mut(&mut self) -> &mut Persons {
         &mut self.persons
     }
+
+    pub fn workouts(&self) -> &Workouts {
+        &self.workouts
+    }
+
+    pub fn workouts_mut(&mut self) -> &mut Workouts {
+        &mut self.workouts
+    }
+
+    pub fn sets(&self) -> &WorkoutSets {
+        &self.sets
+    }
+
+    pub fn sets_mut(&mut self) -> &mut WorkoutSets {
+        &mut self.sets
+    }
+
+    pub fn measurements(&self) -> &Measurements {
+        &self.measurements
+    }
+
+    pub fn measurements_mut(&mut self) -> &mut Measurements {
+        &mut self.measurements
+    }
+
+    pub fn goals(&self) -> &Goals {
+        &self.goals
+    }
+
+    pub fn goals_mut(&mut self) -> &mut Goals {
+        &mut self.goals
+    }
+
+    pub fn records(&self) -> &PersonalRecords {
+        &self.records
+    }
+
+    pub fn records_mut(&mut self) -> &mut PersonalRecords {
+        &mut self.records
+    }
+
+    pub fn routines(&self) -> &Routines {
+        &self.routines
+    }
+
+    pub fn routines_mut(&mut self) -> &mut Routines {
+        &mut self.routines
+    }
 }
 
 pub trait Table {
@@ -57,18 +103,17 @@ pub trait Table {
     fn contains(&self, id: ID) -> bool;
 
     fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
+#[derive(Default)]
 pub struct Exercises {
     exercises: HashMap<ID, Exercise>,
 }
 
-impl Default for Exercises {
-    fn default() -> Self {
-        Self { exercises: HashMap::default() }
-    }
-}
-
 impl Table for Exercises {
     type Item = Exercise;
 
@@ -109,16 +154,11 @@ impl Table for Exercises {
     }
 }
 
+#[derive(Default)]
 pub struct Persons {
     persons: HashMap<ID, Person>,
 }
 
-impl Default for Persons {
-    fn default() -> Self {
-        Self { persons: HashMap::default() }
-    }
-}
-
 impl Table for Persons {
     type Item = Person;
 
@@ -158,3 +198,273 @@ impl Table for Persons {
         self.persons.contains_key(&id)
     }
 }
+
+#[derive(Default)]
+pub struct Workouts {
+    workouts: HashMap<ID, Workout>,
+}
+
+impl Table for Workouts {
+    type Item = Workout;
+
+    fn get_one(&self, id: ID) -> Option<&Self::Item> {
+        self.workouts.get(&id)
+    }
+
+    fn get_all<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Self::Item> + 'a> {
+        Box::new(self.workouts.values())
+    }
+
+    fn insert(&mut self, e: Self::Item) -> Option<&Self::Item> {
+        let id = e.id;
+        self.workouts.insert(id, e);
+        self.workouts.get(&id)
+    }
+
+    fn remove(&mut self, id: ID) {
+        self.workouts.remove(&id);
+    }
+
+    fn clear(&mut self) {
+        self.workouts.clear();
+    }
+
+    fn update(&mut self, e: Self::Item) {
+        if let Some(data) = self.workouts.get_mut(&e.id) {
+            *data = e;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.workouts.len()
+    }
+
+    fn contains(&self, id: ID) -> bool {
+        self.workouts.contains_key(&id)
+    }
+}
+
+#[derive(Default)]
+pub struct WorkoutSets {
+    sets: HashMap<ID, WorkoutSet>,
+}
+
+impl Table for WorkoutSets {
+    type Item = WorkoutSet;
+
+    fn get_one(&self, id: ID) -> Option<&Self::Item> {
+        self.sets.get(&id)
+    }
+
+    fn get_all<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Self::Item> + 'a> {
+        Box::new(self.sets.values())
+    }
+
+    fn insert(&mut self, e: Self::Item) -> Option<&Self::Item> {
+        let id = e.id;
+        self.sets.insert(id, e);
+        self.sets.get(&id)
+    }
+
+    fn remove(&mut self, id: ID) {
+        self.sets.remove(&id);
+    }
+
+    fn clear(&mut self) {
+        self.sets.clear();
+    }
+
+    fn update(&mut self, e: Self::Item) {
+        if let Some(data) = self.sets.get_mut(&e.id) {
+            *data = e;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.sets.len()
+    }
+
+    fn contains(&self, id: ID) -> bool {
+        self.sets.contains_key(&id)
+    }
+}
+
+#[derive(Default)]
+pub struct Measurements {
+    measurements: HashMap<ID, Measurement>,
+}
+
+impl Table for Measurements {
+    type Item = Measurement;
+
+    fn get_one(&self, id: ID) -> Option<&Self::Item> {
+        self.measurements.get(&id)
+    }
+
+    fn get_all<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Self::Item> + 'a> {
+        Box::new(self.measurements.values())
+    }
+
+    fn insert(&mut self, e: Self::Item) -> Option<&Self::Item> {
+        let id = e.id;
+        self.measurements.insert(id, e);
+        self.measurements.get(&id)
+    }
+
+    fn remove(&mut self, id: ID) {
+        self.measurements.remove(&id);
+    }
+
+    fn clear(&mut self) {
+        self.measurements.clear();
+    }
+
+    fn update(&mut self, e: Self::Item) {
+        if let Some(data) = self.measurements.get_mut(&e.id) {
+            *data = e;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.measurements.len()
+    }
+
+    fn contains(&self, id: ID) -> bool {
+        self.measurements.contains_key(&id)
+    }
+}
+
+#[derive(Default)]
+pub struct Goals {
+    goals: HashMap<ID, Goal>,
+}
+
+impl Table for Goals {
+    type Item = Goal;
+
+    fn get_one(&self, id: ID) -> Option<&Self::Item> {
+        self.goals.get(&id)
+    }
+
+    fn get_all<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Self::Item> + 'a> {
+        Box::new(self.goals.values())
+    }
+
+    fn insert(&mut self, e: Self::Item) -> Option<&Self::Item> {
+        let id = e.id;
+        self.goals.insert(id, e);
+        self.goals.get(&id)
+    }
+
+    fn remove(&mut self, id: ID) {
+        self.goals.remove(&id);
+    }
+
+    fn clear(&mut self) {
+        self.goals.clear();
+    }
+
+    fn update(&mut self, e: Self::Item) {
+        if let Some(data) = self.goals.get_mut(&e.id) {
+            *data = e;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.goals.len()
+    }
+
+    fn contains(&self, id: ID) -> bool {
+        self.goals.contains_key(&id)
+    }
+}
+
+#[derive(Default)]
+pub struct PersonalRecords {
+    records: HashMap<ID, PersonalRecord>,
+}
+
+impl Table for PersonalRecords {
+    type Item = PersonalRecord;
+
+    fn get_one(&self, id: ID) -> Option<&Self::Item> {
+        self.records.get(&id)
+    }
+
+    fn get_all<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Self::Item> + 'a> {
+        Box::new(self.records.values())
+    }
+
+    fn insert(&mut self, e: Self::Item) -> Option<&Self::Item> {
+        let id = e.id;
+        self.records.insert(id, e);
+        self.records.get(&id)
+    }
+
+    fn remove(&mut self, id: ID) {
+        self.records.remove(&id);
+    }
+
+    fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    fn update(&mut self, e: Self::Item) {
+        if let Some(data) = self.records.get_mut(&e.id) {
+            *data = e;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    fn contains(&self, id: ID) -> bool {
+        self.records.contains_key(&id)
+    }
+}
+
+#[derive(Default)]
+pub struct Routines {
+    routines: HashMap<ID, Routine>,
+}
+
+impl Table for Routines {
+    type Item = Routine;
+
+    fn get_one(&self, id: ID) -> Option<&Self::Item> {
+        self.routines.get(&id)
+    }
+
+    fn get_all<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Self::Item> + 'a> {
+        Box::new(self.routines.values())
+    }
+
+    fn insert(&mut self, e: Self::Item) -> Option<&Self::Item> {
+        let id = e.id;
+        self.routines.insert(id, e);
+        self.routines.get(&id)
+    }
+
+    fn remove(&mut self, id: ID) {
+        self.routines.remove(&id);
+    }
+
+    fn clear(&mut self) {
+        self.routines.clear();
+    }
+
+    fn update(&mut self, e: Self::Item) {
+        if let Some(data) = self.routines.get_mut(&e.id) {
+            *data = e;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.routines.len()
+    }
+
+    fn contains(&self, id: ID) -> bool {
+        self.routines.contains_key(&id)
+    }
+}
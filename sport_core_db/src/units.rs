@@ -0,0 +1,448 @@
+use std::{fmt::Display, ops::{Add, Sub, Mul, Div}, iter::Sum};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+const CENTIMETERS_PER_METER: f64 = 100.0;
+const METERS_PER_INCH: f64 = 0.0254;
+const INCHES_PER_FOOT: f64 = 12.0;
+const KILOGRAMS_PER_POUND: f64 = 0.453_592_37;
+const METERS_PER_KILOMETER: f64 = 1000.0;
+const METERS_PER_MILE: f64 = 1609.344;
+const SECONDS_PER_MINUTE: f64 = 60.0;
+const SECONDS_PER_HOUR: f64 = 3600.0;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+impl Display for UnitSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnitSystem::Metric => write!(f, "metric"),
+            UnitSystem::Imperial => write!(f, "imperial"),
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ParseQuantityError {
+    #[error("Empty value")]
+    Empty,
+    #[error("Invalid number `{0}`")]
+    Number(String),
+    #[error("Unknown unit `{0}`")]
+    Unit(String),
+    #[error("Negative value")]
+    Negative,
+}
+
+/// Physical quantity which can be shown and entered in the user's unit system
+pub trait Quantity: Sized + Copy {
+    /// Formats the value in the unit preferred by `system`
+    fn format(&self, system: UnitSystem) -> String;
+
+    /// Parses a number with an optional unit suffix, a bare number is taken in the unit preferred by `system`
+    fn parse(s: &str, system: UnitSystem) -> Result<Self, ParseQuantityError>;
+
+    /// Unit symbol used for the bare numbers
+    fn unit_symbol(system: UnitSystem) -> &'static str;
+}
+
+macro_rules! quantity {
+    ($(#[$meta:meta])* $name:ident, $base:ident) => {
+        $(#[$meta])*
+        #[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+        #[serde(transparent)]
+        pub struct $name {
+            $base: f64,
+        }
+
+        impl $name {
+            pub fn is_zero(&self) -> bool {
+                self.$base == 0.0
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self { $base: self.$base + rhs.$base }
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self { $base: self.$base - rhs.$base }
+            }
+        }
+
+        impl Mul<f64> for $name {
+            type Output = Self;
+
+            fn mul(self, rhs: f64) -> Self {
+                Self { $base: self.$base * rhs }
+            }
+        }
+
+        impl Div<f64> for $name {
+            type Output = Self;
+
+            fn div(self, rhs: f64) -> Self {
+                Self { $base: self.$base / rhs }
+            }
+        }
+
+        impl Div for $name {
+            type Output = f64;
+
+            fn div(self, rhs: Self) -> f64 {
+                self.$base / rhs.$base
+            }
+        }
+
+        impl Sum for $name {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::default(), |acc, q| acc + q)
+            }
+        }
+    };
+}
+
+quantity!(
+    /// Length quantity (body measurements), stored in meters.
+    Length, meters
+);
+
+quantity!(
+    /// Mass quantity (lifted and body weight), stored in kilograms.
+    Mass, kilograms
+);
+
+quantity!(
+    /// Distance quantity (cardio), stored in meters.
+    Distance, meters
+);
+
+quantity!(
+    /// Duration quantity, stored in seconds.
+    Duration, seconds
+);
+
+impl Length {
+    pub fn from_meters(meters: f64) -> Self {
+        Self { meters }
+    }
+
+    pub fn from_centimeters(centimeters: f64) -> Self {
+        Self::from_meters(centimeters / CENTIMETERS_PER_METER)
+    }
+
+    pub fn from_inches(inches: f64) -> Self {
+        Self::from_meters(inches * METERS_PER_INCH)
+    }
+
+    pub fn from_feet_inches(feet: f64, inches: f64) -> Self {
+        Self::from_inches(feet * INCHES_PER_FOOT + inches)
+    }
+
+    pub fn meters(&self) -> f64 {
+        self.meters
+    }
+
+    pub fn centimeters(&self) -> f64 {
+        self.meters * CENTIMETERS_PER_METER
+    }
+
+    pub fn inches(&self) -> f64 {
+        self.meters / METERS_PER_INCH
+    }
+
+    /// Whole feet and the remaining inches
+    pub fn feet_inches(&self) -> (u32, f64) {
+        let inches = self.inches();
+        let feet = (inches / INCHES_PER_FOOT).floor();
+        (feet as u32, inches - feet * INCHES_PER_FOOT)
+    }
+}
+
+impl Quantity for Length {
+    fn format(&self, system: UnitSystem) -> String {
+        match system {
+            UnitSystem::Metric => format!("{} cm", format_number(self.centimeters(), 1)),
+            UnitSystem::Imperial => {
+                let (feet, inches) = self.feet_inches();
+                let inches = inches.round();
+                if inches >= INCHES_PER_FOOT {
+                    format!("{}' 0\"", feet + 1)
+                } else {
+                    format!("{}' {}\"", feet, inches)
+                }
+            }
+        }
+    }
+
+    fn parse(s: &str, system: UnitSystem) -> Result<Self, ParseQuantityError> {
+        if let Some((feet, inches)) = s.trim().split_once('\'') {
+            let feet = parse_number(feet)?;
+            let inches = inches.trim().trim_end_matches('"');
+            let inches = if inches.is_empty() { 0.0 } else { parse_number(inches)? };
+            return Ok(Self::from_feet_inches(feet, inches));
+        }
+        let (value, unit) = split_unit(s)?;
+        match unit.as_str() {
+            "" => Ok(match system {
+                UnitSystem::Metric => Self::from_centimeters(value),
+                UnitSystem::Imperial => Self::from_inches(value),
+            }),
+            "cm" => Ok(Self::from_centimeters(value)),
+            "m" => Ok(Self::from_meters(value)),
+            "in" | "\"" => Ok(Self::from_inches(value)),
+            "ft" => Ok(Self::from_feet_inches(value, 0.0)),
+            _ => Err(ParseQuantityError::Unit(unit)),
+        }
+    }
+
+    fn unit_symbol(system: UnitSystem) -> &'static str {
+        match system {
+            UnitSystem::Metric => "cm",
+            UnitSystem::Imperial => "in",
+        }
+    }
+}
+
+impl Mass {
+    pub fn from_kilograms(kilograms: f64) -> Self {
+        Self { kilograms }
+    }
+
+    pub fn from_pounds(pounds: f64) -> Self {
+        Self::from_kilograms(pounds * KILOGRAMS_PER_POUND)
+    }
+
+    pub fn kilograms(&self) -> f64 {
+        self.kilograms
+    }
+
+    pub fn pounds(&self) -> f64 {
+        self.kilograms / KILOGRAMS_PER_POUND
+    }
+
+    /// Value in the unit preferred by `system`
+    pub fn value_in(&self, system: UnitSystem) -> f64 {
+        match system {
+            UnitSystem::Metric => self.kilograms(),
+            UnitSystem::Imperial => self.pounds(),
+        }
+    }
+
+    /// Builds mass from a value in the unit preferred by `system`
+    pub fn from_value_in(value: f64, system: UnitSystem) -> Self {
+        match system {
+            UnitSystem::Metric => Self::from_kilograms(value),
+            UnitSystem::Imperial => Self::from_pounds(value),
+        }
+    }
+}
+
+impl Quantity for Mass {
+    fn format(&self, system: UnitSystem) -> String {
+        format!("{} {}", format_number(self.value_in(system), 2), Self::unit_symbol(system))
+    }
+
+    fn parse(s: &str, system: UnitSystem) -> Result<Self, ParseQuantityError> {
+        let (value, unit) = split_unit(s)?;
+        match unit.as_str() {
+            "" => Ok(Self::from_value_in(value, system)),
+            "kg" | "kgs" => Ok(Self::from_kilograms(value)),
+            "lb" | "lbs" => Ok(Self::from_pounds(value)),
+            _ => Err(ParseQuantityError::Unit(unit)),
+        }
+    }
+
+    fn unit_symbol(system: UnitSystem) -> &'static str {
+        match system {
+            UnitSystem::Metric => "kg",
+            UnitSystem::Imperial => "lb",
+        }
+    }
+}
+
+impl Distance {
+    pub fn from_meters(meters: f64) -> Self {
+        Self { meters }
+    }
+
+    pub fn from_kilometers(kilometers: f64) -> Self {
+        Self::from_meters(kilometers * METERS_PER_KILOMETER)
+    }
+
+    pub fn from_miles(miles: f64) -> Self {
+        Self::from_meters(miles * METERS_PER_MILE)
+    }
+
+    pub fn meters(&self) -> f64 {
+        self.meters
+    }
+
+    pub fn kilometers(&self) -> f64 {
+        self.meters / METERS_PER_KILOMETER
+    }
+
+    pub fn miles(&self) -> f64 {
+        self.meters / METERS_PER_MILE
+    }
+
+    /// Value in the unit preferred by `system`
+    pub fn value_in(&self, system: UnitSystem) -> f64 {
+        match system {
+            UnitSystem::Metric => self.kilometers(),
+            UnitSystem::Imperial => self.miles(),
+        }
+    }
+
+    /// Builds distance from a value in the unit preferred by `system`
+    pub fn from_value_in(value: f64, system: UnitSystem) -> Self {
+        match system {
+            UnitSystem::Metric => Self::from_kilometers(value),
+            UnitSystem::Imperial => Self::from_miles(value),
+        }
+    }
+}
+
+impl Quantity for Distance {
+    fn format(&self, system: UnitSystem) -> String {
+        format!("{} {}", format_number(self.value_in(system), 2), Self::unit_symbol(system))
+    }
+
+    fn parse(s: &str, system: UnitSystem) -> Result<Self, ParseQuantityError> {
+        let (value, unit) = split_unit(s)?;
+        match unit.as_str() {
+            "" => Ok(Self::from_value_in(value, system)),
+            "m" => Ok(Self::from_meters(value)),
+            "km" => Ok(Self::from_kilometers(value)),
+            "mi" => Ok(Self::from_miles(value)),
+            _ => Err(ParseQuantityError::Unit(unit)),
+        }
+    }
+
+    fn unit_symbol(system: UnitSystem) -> &'static str {
+        match system {
+            UnitSystem::Metric => "km",
+            UnitSystem::Imperial => "mi",
+        }
+    }
+}
+
+impl Duration {
+    pub fn from_seconds(seconds: f64) -> Self {
+        Self { seconds }
+    }
+
+    pub fn from_minutes(minutes: f64) -> Self {
+        Self::from_seconds(minutes * SECONDS_PER_MINUTE)
+    }
+
+    pub fn from_hours(hours: f64) -> Self {
+        Self::from_seconds(hours * SECONDS_PER_HOUR)
+    }
+
+    pub fn seconds(&self) -> f64 {
+        self.seconds
+    }
+
+    pub fn minutes(&self) -> f64 {
+        self.seconds / SECONDS_PER_MINUTE
+    }
+
+    pub fn hours(&self) -> f64 {
+        self.seconds / SECONDS_PER_HOUR
+    }
+}
+
+impl From<std::time::Duration> for Duration {
+    fn from(d: std::time::Duration) -> Self {
+        Self::from_seconds(d.as_secs_f64())
+    }
+}
+
+impl Quantity for Duration {
+    /// `m:ss` or `h:mm:ss`, the same in every unit system
+    fn format(&self, _system: UnitSystem) -> String {
+        let total = self.seconds.max(0.0).round() as u64;
+        let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+        if hours > 0 {
+            format!("{}:{:02}:{:02}", hours, minutes, seconds)
+        } else {
+            format!("{}:{:02}", minutes, seconds)
+        }
+    }
+
+    /// Accepts `h:mm:ss`, `m:ss` or a number with `s`, `m`, `min` or `h` suffix
+    fn parse(s: &str, _system: UnitSystem) -> Result<Self, ParseQuantityError> {
+        let s = s.trim();
+        if s.contains(':') {
+            let mut seconds = 0.0;
+            for part in s.split(':') {
+                seconds = seconds * SECONDS_PER_MINUTE + parse_number(part)?;
+            }
+            return Ok(Self::from_seconds(seconds));
+        }
+        let (value, unit) = split_unit(s)?;
+        match unit.as_str() {
+            "" | "s" | "sec" => Ok(Self::from_seconds(value)),
+            "m" | "min" => Ok(Self::from_minutes(value)),
+            "h" => Ok(Self::from_hours(value)),
+            _ => Err(ParseQuantityError::Unit(unit)),
+        }
+    }
+
+    fn unit_symbol(_system: UnitSystem) -> &'static str {
+        "s"
+    }
+}
+
+/// Formats a number with up to `precision` decimals, dropping trailing zeros
+pub fn format_number(value: f64, precision: usize) -> String {
+    let s = format!("{:.*}", precision, value);
+    if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.').to_owned()
+    } else {
+        s
+    }
+}
+
+fn parse_number(s: &str) -> Result<f64, ParseQuantityError> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(ParseQuantityError::Empty);
+    }
+    let value: f64 = s.replace(',', ".").parse().map_err(|_| ParseQuantityError::Number(s.to_owned()))?;
+    if !value.is_finite() {
+        return Err(ParseQuantityError::Number(s.to_owned()));
+    }
+    if value < 0.0 {
+        return Err(ParseQuantityError::Negative);
+    }
+    Ok(value)
+}
+
+/// Splits `"80.5 kg"` into the number and the lowercase unit
+fn split_unit(s: &str) -> Result<(f64, String), ParseQuantityError> {
+    let s = s.trim();
+    let unit_start = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ',' || c == '-' || c == '+'))
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(unit_start);
+    if number.is_empty() && !unit.is_empty() {
+        return Err(ParseQuantityError::Number(s.to_owned()));
+    }
+    Ok((parse_number(number)?, unit.trim().to_lowercase()))
+}
@@ -0,0 +1,178 @@
+use std::{path::Path, rc::Rc};
+
+use core_ui::{
+    render::RenderFrame,
+    components::{TextEdit, Component, EventComponent},
+    utils,
+    event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
+    message_box::UiMessageBox,
+    layer::Layer,
+};
+use crossterm::event::Event;
+use sport_core::{backup::{self, Backup, DryRun}, controller, keys::{Action, Context, KeyMap}};
+use tui::{layout::{Rect, Constraint, Direction, Layout}, widgets};
+
+use crate::ui_events::{UiEventSender, SharedUiEvents};
+
+/// Restore of a backup file, the first [ENTER] shows what would change and the second one restores
+pub struct UiBackupRestore {
+    controller: Rc<controller::Controller>,
+    path_editor: TextEdit,
+    /// Backup checked with a dry run and its report
+    checked: Option<(Backup, String)>,
+    ui_event_sender: Option<UiEventSender>,
+    is_focused: bool,
+    is_visible: bool,
+    is_removing_needed: bool,
+}
+
+impl UiBackupRestore {
+    pub fn new(controller: Rc<controller::Controller>, path: String) -> Self {
+        Self {
+            controller,
+            path_editor: TextEdit::new("Backup file:", path, Default::default()),
+            checked: None,
+            ui_event_sender: None,
+            is_focused: true,
+            is_visible: true,
+            is_removing_needed: false,
+        }
+    }
+
+    pub fn set_ui_events_manager(&mut self, ui_events_manager: SharedUiEvents) {
+        self.ui_event_sender = Some(UiEventSender::new(ui_events_manager));
+    }
+
+    fn close(&mut self) {
+        self.set_visible(false);
+        self.is_removing_needed = true;
+    }
+
+    fn show(&mut self, msgbox: UiMessageBox) {
+        if let Some(sender) = &mut self.ui_event_sender {
+            sender.send_add_layer_event(Box::new(msgbox));
+        }
+    }
+
+    fn dry_run(&mut self) {
+        let checked = Backup::load(Path::new(self.path_editor.text.trim())).and_then(|backup| {
+            let report = backup::restore(&backup, DryRun::new(&self.controller.db()))?;
+            Ok((backup, report.describe()))
+        });
+        match checked {
+            Ok(checked) => self.checked = Some(checked),
+            Err(err) => self.show(UiMessageBox::err("Restore", format!("Can`t read backup:\n{}", err))),
+        }
+    }
+
+    fn restore(&mut self) {
+        let Some((backup, _)) = self.checked.take() else {
+            return;
+        };
+        let msgbox = match backup::restore(&backup, self.controller.as_ref()) {
+            Ok(report) => {
+                let settings = backup
+                    .apply_settings(self.controller.cfg())
+                    .map_err(|err| err.to_string())
+                    .and_then(|cfg| cfg.store().map_err(|err| err.to_string()))
+                    .map(|_| "Settings saved, restart to apply them".to_owned())
+                    .unwrap_or_else(|err| format!("Can`t save settings: {}", err));
+                UiMessageBox::info("Restore", format!("Restored\n{}\n\n{}", report.describe(), settings))
+            }
+            Err(err) => UiMessageBox::err("Restore", format!("Restore stopped:\n{}", err)),
+        };
+        self.close();
+        self.show(msgbox);
+    }
+}
+
+impl Component for UiBackupRestore {
+    fn draw(&self, f: &mut RenderFrame, area: Rect) {
+        if !self.is_visible() {
+            return;
+        }
+        let area = utils::centered_rect(60, 60, area);
+        let key_map = self.controller.key_map();
+        let block = widgets::Block::default()
+            .title(format!("Restore backup;  -> Commands({})", key_map.commands(&[("Exit", Action::Reject), ("Check then restore", Action::Accept)])))
+            .borders(widgets::Borders::ALL);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(block, area);
+
+        let l = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .split(area);
+        self.path_editor.draw(f, l[0]);
+        let text = match &self.checked {
+            Some((backup, report)) => format!(
+                "Backup of {}, version {}\nWould restore:\n{}\n\n[{}] to restore",
+                backup.created_at.format("%Y-%m-%d %H:%M"), backup.version, report, key_map.binding(Action::Accept),
+            ),
+            None => format!("[{}] to check what would change", key_map.binding(Action::Accept)),
+        };
+        let paragraph = widgets::Paragraph::new(text)
+            .block(widgets::Block::default().borders(widgets::Borders::ALL).title("Dry run"));
+        f.render_widget(paragraph, l[1]);
+    }
+
+    fn is_visible(&self) -> bool { self.is_visible }
+    fn set_visible(&mut self, value: bool) { self.is_visible = value; }
+}
+
+impl KeyEventDispatcher for UiBackupRestore {}
+
+impl TermEventDispatcher for UiBackupRestore {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::Dialog))
+    }
+
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Accept => {
+                if self.checked.is_some() {
+                    self.restore();
+                } else {
+                    self.dry_run();
+                }
+                true
+            }
+            Action::Reject => {
+                self.close();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl EventComponent for UiBackupRestore {
+    fn focus(&mut self, value: bool) {
+        self.is_focused = value;
+        self.path_editor.focus(value);
+    }
+
+    fn on_focus(&self) -> bool { self.is_focused }
+
+    fn on_term_event(&mut self, event: &Event) -> bool {
+        if !self.is_focused {
+            return false;
+        }
+        if let Event::Key(key) = event {
+            let key_map = self.controller.key_map();
+            if key_map.is(Action::Accept, key) || key_map.is(Action::Reject, key) {
+                return self.dispatch_term_event(event);
+            }
+        }
+        // another file needs another check
+        self.checked = None;
+        self.path_editor.on_term_event(event)
+    }
+}
+
+impl Layer for UiBackupRestore {
+    fn is_modal(&self) -> bool { true }
+
+    fn is_remove_requested(&self) -> bool { self.is_removing_needed }
+}
@@ -5,20 +5,41 @@ use core_ui::{
     components::{TextEdit, Component, EventComponent},
     utils,
     event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
-    message_box::UiMessageBox, layer::Layer, validators::StrValidator
+    message_box::UiMessageBox, layer::Layer, validators::{StrValidator, Validator}
 };
 use crossterm::event::Event;
-use sport_core::controller;
-use sport_core_db::entity;
+use sport_core::{controller, keys::{Action, Context, KeyMap}};
+use sport_core_db::entity::{self, MuscleGroup};
+use thiserror::Error;
 use tui::{layout::{Rect, Constraint, Direction, Layout}, widgets};
 
 use crate::ui_events::{UiEventSender, SharedUiEvents};
 
+const FIELDS_COUNT: usize = 3;
+
+#[derive(Error, Debug)]
+#[error("{0}")]
+pub struct MuscleGroupsValidatorError(String);
+
+/// Accepts comma separated muscle group names
+#[derive(Default)]
+pub struct MuscleGroupsValidator;
+
+impl Validator<str> for MuscleGroupsValidator {
+    type Error = MuscleGroupsValidatorError;
+
+    fn validate<'a>(&'a self, value: &'a str) -> Result<(), Self::Error> {
+        MuscleGroup::parse_list(value).map(|_| ()).map_err(MuscleGroupsValidatorError)
+    }
+}
+
 pub struct UiExerciseEditor {
     controller: Rc<controller::Controller>,
     id: entity::ID,
     name_editor: TextEdit<StrValidator>,
     description_editor: TextEdit<StrValidator>,
+    muscle_groups_editor: TextEdit<MuscleGroupsValidator>,
+    focused_field: usize,
     to_insert: bool,
     ui_event_sender: Option<UiEventSender>,
     is_focused: bool,
@@ -33,6 +54,8 @@ impl UiExerciseEditor {
             id: 0,
             name_editor: TextEdit::new("Name:", "".to_owned(), StrValidator::new(0, 100)),
             description_editor: TextEdit::new("Description: ", "".to_owned(), StrValidator::default()),
+            muscle_groups_editor: TextEdit::new("Muscle groups (comma separated):", "".to_owned(), MuscleGroupsValidator),
+            focused_field: 0,
             to_insert: true,
             ui_event_sender: None,
             is_focused: true,
@@ -42,7 +65,10 @@ impl UiExerciseEditor {
     }
 
     pub fn data(&self) -> entity::Exercise {
-        entity::Exercise::new(self.id, self.name_editor.text.clone(), self.description_editor.text.clone())
+        entity::Exercise {
+            muscle_groups: MuscleGroup::parse_list(&self.muscle_groups_editor.text).unwrap_or_default(),
+            ..entity::Exercise::new(self.id, self.name_editor.text.clone(), self.description_editor.text.clone())
+        }
     }
 
     pub fn load_data(&mut self, data: &entity::Exercise) {
@@ -50,6 +76,7 @@ impl UiExerciseEditor {
         self.id = data.id;
         self.name_editor.text = data.name.to_owned();
         self.description_editor.text = data.description.to_owned();
+        self.muscle_groups_editor.text = MuscleGroup::format_list(&data.muscle_groups);
     }
 
     pub fn set_ui_events_manager(&mut self, ui_events_manager: SharedUiEvents) {
@@ -61,9 +88,21 @@ impl UiExerciseEditor {
         self.is_removing_needed = true;
     }
 
-    fn current_editor(&mut self) -> &mut TextEdit<StrValidator> {
-        let is_name_focused = self.name_editor.on_focus();
-        if is_name_focused { &mut self.name_editor } else { &mut self.description_editor }
+    fn is_valid(&self) -> bool {
+        self.name_editor.is_valid() && self.description_editor.is_valid() && self.muscle_groups_editor.is_valid()
+    }
+
+    fn fields_mut(&mut self) -> [&mut dyn EventComponent; FIELDS_COUNT] {
+        [&mut self.name_editor, &mut self.description_editor, &mut self.muscle_groups_editor]
+    }
+
+    fn focus_field(&mut self, index: usize) {
+        self.focused_field = index % FIELDS_COUNT;
+        let focused_field = self.focused_field;
+        self.fields_mut()
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, f)| f.focus(i == focused_field));
     }
 }
 
@@ -73,8 +112,9 @@ impl Component for UiExerciseEditor {
             return;
         }
         let area = utils::centered_rect(50, 50, area);
+        let key_map = self.controller.key_map();
         let block = widgets::Block::default()
-            .title(r#"Exercise;  -> Commands(Exit: [q], Reject: [ESC], Accept: [ENTER])"#)
+            .title(format!("Exercise;  -> Commands({})", key_map.commands(&[("Exit", Action::Close), ("Reject", Action::Reject), ("Accept", Action::Accept)])))
             .borders(widgets::Borders::ALL);
         f.render_widget(tui::widgets::Clear, area);
         f.render_widget(block, area);
@@ -92,10 +132,11 @@ impl Component for UiExerciseEditor {
             .split(l[1]);
         let l = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Min(3), Constraint::Min(6)].as_ref())
+            .constraints([Constraint::Length(3), Constraint::Min(6), Constraint::Length(3)].as_ref())
             .split(l[1]);
         self.name_editor.draw(f, l[0]);
         self.description_editor.draw(f, l[1]);
+        self.muscle_groups_editor.draw(f, l[2]);
     }
 
     fn is_visible(&self) -> bool { self.is_visible }
@@ -104,61 +145,62 @@ impl Component for UiExerciseEditor {
 
 impl KeyEventDispatcher for UiExerciseEditor {
     fn on_tab(&mut self) -> bool {
-        let is_name_focused = self.name_editor.on_focus();
-        self.description_editor.focus(is_name_focused);
-        self.name_editor.focus(!is_name_focused);
+        self.focus_field(self.focused_field + 1);
         true
     }
 
     fn on_back_tab(&mut self) -> bool {
-        let is_name_focused = self.name_editor.on_focus();
-        self.description_editor.focus(is_name_focused);
-        self.name_editor.focus(!is_name_focused);
+        self.focus_field(self.focused_field + FIELDS_COUNT - 1);
         true
     }
 
-    fn on_enter(&mut self) -> bool {
-        if !self.name_editor.is_valid() || !self.description_editor.is_valid() {
-            return false;
-        }
-        let result = if self.to_insert {
-            self.controller.exercises().insert(&self.data()) 
-        } else {
-            self.controller.exercises().update(self.data()) 
-        };
-        if let Err(err) = result {
-            if let Some(sender) = &mut self.ui_event_sender {
-                let err = format!("Can`t insert exercise:\n{}", err);
-                sender.send_add_layer_event(Box::new(UiMessageBox::err("Exercises", err)));
-                return false;
-            }
-        } else {
-            self.close();
-        }
-
+    fn on_char(&mut self, _c: &char) -> bool {
         true
     }
+}
 
-    fn on_esc(&mut self) -> bool {
-        self.close();
-        true
+impl TermEventDispatcher for UiExerciseEditor {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::Dialog))
     }
 
-    fn on_char(&mut self, c: &char) -> bool {
-        if *c == 'q' {
-            self.close();
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Accept => {
+                if !self.is_valid() {
+                    return false;
+                }
+                let result = if self.to_insert {
+                    self.controller.exercises().insert(&self.data()).map(|_| ())
+                } else {
+                    self.controller.exercises().update(self.data()) 
+                };
+                if let Err(err) = result {
+                    if let Some(sender) = &mut self.ui_event_sender {
+                        let err = format!("Can`t insert exercise:\n{}", err);
+                        sender.send_add_layer_event(Box::new(UiMessageBox::err("Exercises", err)));
+                        return false;
+                    }
+                } else {
+                    self.close();
+                }
+
+                true
+            }
+            Action::Reject | Action::Close => {
+                self.close();
+                true
+            }
+            _ => false,
         }
-        true
     }
 }
-impl TermEventDispatcher for UiExerciseEditor { }
 
 impl EventComponent for UiExerciseEditor {
     fn focus(&mut self, value: bool) {
         self.is_focused = value;
         if value {
-            self.name_editor.focus(value);
-            self.description_editor.focus(!value);
+            self.focus_field(0);
         }
     }
 
@@ -166,7 +208,8 @@ impl EventComponent for UiExerciseEditor {
 
     fn on_term_event(&mut self, event: &Event) -> bool {
         if self.is_focused {
-            if self.current_editor().on_term_event(event) {
+            let focused_field = self.focused_field;
+            if self.fields_mut()[focused_field].on_term_event(event) {
                 return true;
             }
             return self.dispatch_term_event(event);
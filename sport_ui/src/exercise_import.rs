@@ -0,0 +1,279 @@
+use std::{cell::RefCell, path::Path, rc::Rc};
+
+use core_ui::{
+    render::RenderFrame,
+    components::{TextEdit, Component, EventComponent, UiTable},
+    utils,
+    event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
+    message_box::UiMessageBox,
+    layer::Layer,
+};
+use crossterm::event::Event;
+use sport_core::{controller, exercises_csv::{self, ExerciseImport, ImportRow, RowStatus}, keys::{Action, Context, KeyMap}};
+use sport_core_db::entity::MuscleGroup;
+use tui::{layout::{Rect, Constraint, Direction, Layout}, style::{Color, Modifier, Style}, text::{Span, Spans}, widgets};
+
+use crate::ui_events::{UiEventSender, SharedUiEvents};
+
+/// Exercises from a CSV file, previewed with the column mapping before creating them
+pub struct UiExerciseImport {
+    controller: Rc<controller::Controller>,
+    path_editor: TextEdit,
+    import: Option<ExerciseImport>,
+    rows: Vec<ImportRow>,
+    /// Column of the mapping to change
+    column: usize,
+    rows_table: RefCell<UiTable<usize>>,
+    ui_event_sender: Option<UiEventSender>,
+    is_focused: bool,
+    is_visible: bool,
+    is_removing_needed: bool,
+}
+
+impl UiExerciseImport {
+    pub fn new(controller: Rc<controller::Controller>) -> Self {
+        Self {
+            controller,
+            path_editor: TextEdit::new("File: [ENTER] to preview", exercises_csv::FILE_NAME.to_owned(), Default::default()),
+            import: None,
+            rows: vec![],
+            column: 0,
+            rows_table: RefCell::new(UiTable::default()),
+            ui_event_sender: None,
+            is_focused: true,
+            is_visible: true,
+            is_removing_needed: false,
+        }
+    }
+
+    pub fn set_ui_events_manager(&mut self, ui_events_manager: SharedUiEvents) {
+        self.ui_event_sender = Some(UiEventSender::new(ui_events_manager));
+    }
+
+    fn close(&mut self) {
+        self.set_visible(false);
+        self.is_removing_needed = true;
+    }
+
+    fn show(&mut self, msgbox: UiMessageBox) {
+        if let Some(sender) = &mut self.ui_event_sender {
+            sender.send_add_layer_event(Box::new(msgbox));
+        }
+    }
+
+    fn load(&mut self) {
+        match ExerciseImport::load(Path::new(self.path_editor.text.trim())) {
+            Ok(import) => {
+                self.import = Some(import);
+                self.column = 0;
+                self.refresh();
+                self.path_editor.focus(false);
+            }
+            Err(err) => self.show(UiMessageBox::err("Import", format!("Can`t read {}:\n{}", self.path_editor.text, err))),
+        }
+    }
+
+    fn refresh(&mut self) {
+        self.rows = self.import.as_ref().map(|i| i.rows(&self.controller.db())).unwrap_or_default();
+        self.rows_table.borrow_mut().set_values((0..self.rows.len()).collect());
+    }
+
+    fn import(&mut self) {
+        let reports = exercises_csv::import(&self.controller, &self.rows);
+        let created = reports.iter().filter(|r| r.result.is_ok()).count();
+        let mut msg = format!("Created {} of {} exercises\n", created, reports.len());
+        for report in reports {
+            let result = match report.result {
+                Ok(()) => "created".to_owned(),
+                Err(err) => err,
+            };
+            msg.push_str(&format!("\nLine {} `{}`: {}", report.line, report.name, result));
+        }
+        self.close();
+        self.show(UiMessageBox::info("Import", msg));
+    }
+
+    fn draw_mapping(&self, f: &mut RenderFrame, area: Rect) {
+        let Some(import) = &self.import else {
+            return;
+        };
+        let spans: Vec<Span> = import.header
+            .iter()
+            .zip(&import.mapping)
+            .enumerate()
+            .flat_map(|(i, (column, field))| {
+                let style = if i == self.column { Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD) } else { Style::default() };
+                [Span::styled(format!("{} -> {}", column, field.name()), style), Span::raw("  ")]
+            })
+            .collect();
+        let paragraph = widgets::Paragraph::new(Spans::from(spans))
+            .block(widgets::Block::default().borders(widgets::Borders::ALL).title(format!("Columns: Select[LEFT/RIGHT] Change[{}]", self.controller.key_map().binding(Action::NextMapping))));
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_rows(&self, f: &mut RenderFrame, area: Rect) {
+        let rows: Vec<widgets::Row> = self.rows
+            .iter()
+            .map(|row| {
+                let (status, color) = match &row.status {
+                    RowStatus::New => ("New".to_owned(), Color::Green),
+                    RowStatus::Duplicate => ("Duplicate".to_owned(), Color::Yellow),
+                    RowStatus::Invalid(err) => (err.clone(), Color::Red),
+                };
+                widgets::Row::new(vec![
+                    widgets::Cell::from(row.line.to_string()),
+                    widgets::Cell::from(row.exercise.name.clone()),
+                    widgets::Cell::from(row.exercise.description.clone()),
+                    widgets::Cell::from(MuscleGroup::format_list(&row.exercise.muscle_groups)),
+                    widgets::Cell::from(status).style(Style::default().fg(color)),
+                ])
+            })
+            .collect();
+        let theme = *self.rows_table.borrow().theme();
+        let table = widgets::Table::new(rows)
+            .style(theme.table_style)
+            .header(
+                widgets::Row::new(vec!["Line", "Name", "Description", "Muscle groups", "Status"])
+                .style(theme.header_style)
+                .bottom_margin(1),
+                )
+            .block(widgets::Block::default().borders(widgets::Borders::ALL).title("Preview"))
+            .widths(&[
+                    Constraint::Length(5),
+                    Constraint::Length(25),
+                    Constraint::Length(25),
+                    Constraint::Length(25),
+                    Constraint::Min(10),
+            ])
+            .highlight_style(theme.highlight_style)
+            .highlight_symbol(">");
+        f.render_stateful_widget(table, area, self.rows_table.borrow_mut().state());
+    }
+}
+
+impl Component for UiExerciseImport {
+    fn draw(&self, f: &mut RenderFrame, area: Rect) {
+        if !self.is_visible() {
+            return;
+        }
+        let area = utils::centered_rect(80, 80, area);
+        let key_map = self.controller.key_map();
+        let block = widgets::Block::default()
+            .title(format!(
+                "Import exercises;  -> Commands({}, File: [TAB], {})",
+                key_map.commands(&[("Exit", Action::Reject)]), key_map.commands(&[("Import", Action::Accept)]),
+            ))
+            .borders(widgets::Borders::ALL);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(block, area);
+
+        let l = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([Constraint::Length(3), Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .split(area);
+        self.path_editor.draw(f, l[0]);
+        self.draw_mapping(f, l[1]);
+        self.draw_rows(f, l[2]);
+    }
+
+    fn is_visible(&self) -> bool { self.is_visible }
+    fn set_visible(&mut self, value: bool) { self.is_visible = value; }
+}
+
+impl KeyEventDispatcher for UiExerciseImport {
+    fn on_tab(&mut self) -> bool {
+        self.path_editor.focus(true);
+        true
+    }
+
+    fn on_left(&mut self) -> bool {
+        self.column = self.column.saturating_sub(1);
+        true
+    }
+
+    fn on_right(&mut self) -> bool {
+        let columns = self.import.as_ref().map(|i| i.mapping.len()).unwrap_or_default();
+        self.column = (self.column + 1).min(columns.saturating_sub(1));
+        true
+    }
+
+    fn on_up(&mut self) -> bool {
+        self.rows_table.borrow_mut().previous();
+        true
+    }
+
+    fn on_down(&mut self) -> bool {
+        self.rows_table.borrow_mut().next();
+        true
+    }
+
+    fn on_char(&mut self, _c: &char) -> bool {
+        true
+    }
+}
+
+impl TermEventDispatcher for UiExerciseImport {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::Dialog))
+    }
+
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Accept => {
+                if self.rows.is_empty() {
+                    return false;
+                }
+                self.import();
+                true
+            }
+            Action::Reject | Action::Close => {
+                self.close();
+                true
+            }
+            Action::NextMapping => {
+                if let Some(field) = self.import.as_mut().and_then(|i| i.mapping.get_mut(self.column)) {
+                    *field = field.next();
+                    self.refresh();
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl EventComponent for UiExerciseImport {
+    fn focus(&mut self, value: bool) {
+        self.is_focused = value;
+        self.path_editor.focus(value);
+    }
+
+    fn on_focus(&self) -> bool { self.is_focused }
+
+    fn on_term_event(&mut self, event: &Event) -> bool {
+        if !self.is_focused {
+            return false;
+        }
+        if self.path_editor.on_focus() {
+            if let Event::Key(key) = event {
+                if self.controller.key_map().is(Action::Accept, key) {
+                    self.load();
+                    return true;
+                }
+                if self.controller.key_map().is(Action::Reject, key) {
+                    self.close();
+                    return true;
+                }
+            }
+            return self.path_editor.on_term_event(event);
+        }
+        self.dispatch_term_event(event)
+    }
+}
+
+impl Layer for UiExerciseImport {
+    fn is_modal(&self) -> bool { true }
+
+    fn is_remove_requested(&self) -> bool { self.is_removing_needed }
+}
@@ -1,7 +1,7 @@
 use sport_core_db::model::Table;
 use std::cell::RefCell;
-use crate::{ui_events::{UiEventSender, SharedUiEvents, UiEvent}, exercise_editor::UiExerciseEditor};
-use std::rc::Rc;
+use crate::{ui_events::{UiEventSender, SharedUiEvents, UiEvent}, exercise_editor::UiExerciseEditor, exercise_import::UiExerciseImport};
+use std::{path::Path, rc::Rc};
 
 use core_ui::{
     components::{UiTable, Component, EventComponent},
@@ -9,8 +9,8 @@ use core_ui::{
     event_dispatcher::{TermEventDispatcher, KeyEventDispatcher},
     message_box::UiMessageBox
 };
-use sport_core::controller;
-use sport_core_db::entity;
+use sport_core::{controller, exercises_csv, keys::{Action, Context, KeyMap}};
+use sport_core_db::entity::{self, MuscleGroup};
 use tui::{layout::{Rect, Constraint}, widgets, style::{Color, Style}};
 
 pub struct UiExercisesTable {
@@ -18,7 +18,6 @@ pub struct UiExercisesTable {
     ui_event_sender: UiEventSender,
     is_visible: bool,
     inner: RefCell<UiTable<entity::ID>>,
-    commands_help: &'static str,
 }
 
 impl UiExercisesTable {
@@ -28,12 +27,11 @@ impl UiExercisesTable {
             controller: controller.clone(),
             is_visible: true ,
             inner: RefCell::new(UiTable::default()),
-            commands_help: "Add[a] Delete[d] Update[u]",
         }
     }
 
-    pub fn commands_help(&self) -> &'static str {
-        self.commands_help
+    pub fn commands_help(&self) -> String {
+        self.controller.key_map().help(Context::Exercises)
     }
 }
 
@@ -62,16 +60,17 @@ impl Component for UiExercisesTable {
                                         widgets::Cell::from((i + 1).to_string()).style(Style::default().fg(Color::Green)),
                                         widgets::Cell::from(e.name.to_owned()).style(Style::default().fg(color)),
                                         widgets::Cell::from(e.description.to_owned()).style(Style::default().fg(color)),
+                                        widgets::Cell::from(MuscleGroup::format_list(&e.muscle_groups)).style(Style::default().fg(color)),
             ]));
             ids.push(e.id);
         }
         self.inner.borrow_mut().set_values(ids);
 
-        let theme = self.inner.borrow().theme().clone();
+        let theme = *self.inner.borrow().theme();
         let table = widgets::Table::new(rows)
             .style(theme.table_style)
             .header(
-                widgets::Row::new(vec!["#", "Name", "Description"])
+                widgets::Row::new(vec!["#", "Name", "Description", "Muscle groups"])
                 .style(theme.header_style)
                 .bottom_margin(1),
                 )
@@ -84,44 +83,37 @@ impl Component for UiExercisesTable {
                     Constraint::Length(3),
                     Constraint::Length(50),
                     Constraint::Length(30),
+                    Constraint::Length(30),
             ])
             .column_spacing(0)
             .highlight_style(theme.highlight_style)
             .highlight_symbol(">");
 
-        f.render_stateful_widget(table, area, &mut self.inner.borrow_mut().state());
+        f.render_stateful_widget(table, area, self.inner.borrow_mut().state());
     }
 }
 
-impl TermEventDispatcher for UiExercisesTable {}
-
-impl KeyEventDispatcher for UiExercisesTable {
-    fn on_down(&mut self) -> bool {
-        self.inner.borrow_mut().next();
-        true
-    }
-
-    fn on_up(&mut self) -> bool {
-        self.inner.borrow_mut().previous();
-        true
+impl TermEventDispatcher for UiExercisesTable {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::Exercises))
     }
 
-    fn on_char(&mut self, c: &char) -> bool {
-        return match c {
-            'r' => {
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Reload => {
                 if let Err(err) = self.controller.exercises().load_all() {
                     let err = format!("Can`t get all exercises:\n{}", err);
                     self.ui_event_sender.send_add_layer_event(Box::new(UiMessageBox::err("Exercises", err)));
                 }
                 false
             }
-            'a' => {
+            Action::Add => {
                 let mut popup = Box::new(UiExerciseEditor::new(self.controller.clone()));
                 popup.set_ui_events_manager(self.ui_event_sender.ui_events_manager());
                 self.ui_event_sender.send_add_layer_event(popup);
-                return true;
+                true
             }
-            'u' => {
+            Action::Update => {
                 if let Some(id) = self.inner.borrow().get_value() {
                     if let Some(data) = self.controller.db().exercises().get_one(*id) {
                         let mut popup = Box::new(UiExerciseEditor::new(self.controller.clone()));
@@ -133,7 +125,22 @@ impl KeyEventDispatcher for UiExercisesTable {
                 }
                 false
             }
-            'd' => {
+            Action::Import => {
+                let mut popup = Box::new(UiExerciseImport::new(self.controller.clone()));
+                popup.set_ui_events_manager(self.ui_event_sender.ui_events_manager());
+                self.ui_event_sender.send_add_layer_event(popup);
+                true
+            }
+            Action::ExportCsv => {
+                let path = Path::new(exercises_csv::FILE_NAME);
+                let popup = match exercises_csv::export_to(&self.controller.db(), path) {
+                    Ok(()) => UiMessageBox::info("Exercises", format!("Exercises saved to {}", path.display())),
+                    Err(err) => UiMessageBox::err("Exercises", format!("Can`t save exercises:\n{}", err)),
+                };
+                self.ui_event_sender.send_add_layer_event(Box::new(popup));
+                true
+            }
+            Action::Delete => {
                 if let Some(id) = self.inner.borrow_mut().get_value() {
                     let id = *id;
                     if let Some(data) = self.controller.db().exercises().get_one(id) {
@@ -159,6 +166,18 @@ impl KeyEventDispatcher for UiExercisesTable {
     }
 }
 
+impl KeyEventDispatcher for UiExercisesTable {
+    fn on_down(&mut self) -> bool {
+        self.inner.borrow_mut().next();
+        true
+    }
+
+    fn on_up(&mut self) -> bool {
+        self.inner.borrow_mut().previous();
+        true
+    }
+}
+
 impl EventComponent for UiExercisesTable {
     fn focus(&mut self, value: bool) {
         self.inner.borrow_mut().focus(value);
@@ -8,10 +8,28 @@ pub mod components {
 pub mod ui;
 pub mod ui_events;
 pub(crate) mod exercise_editor;
+pub(crate) mod exercise_import;
+pub(crate) mod workout_import;
+pub(crate) mod track_import;
+pub(crate) mod backup_restore;
+pub(crate) mod profile_switcher;
+pub(crate) mod workout_editor;
+pub(crate) mod set_editor;
+pub(crate) mod plate_calculator;
+pub(crate) mod timer_editor;
+pub(crate) mod timer_overlay;
+pub(crate) mod routine_editor;
+pub(crate) mod routine_exercise_editor;
 pub(crate) mod tabs;
 pub(crate) mod menu;
 pub(crate) mod exercises_table;
 pub(crate) mod main_ui_layer;
 pub(crate) mod footer;
 pub(crate) mod page_exercises;
+pub(crate) mod page_workouts;
+pub(crate) mod page_routines;
+pub(crate) mod page_history;
+pub(crate) mod page_progress;
+pub(crate) mod page_balance;
+pub(crate) mod page_reports;
 pub(crate) mod page_account;
@@ -5,33 +5,59 @@ use core_ui::{
     render::RenderFrame,
     event_dispatcher::{TermEventDispatcher, KeyEventDispatcher},
     component::TerminalEvent,
-    layer::Layer
+    layer::Layer,
+    theme::UiTheme,
 };
-use sport_core::controller;
+use sport_core::{controller, keys::{Action, Context, KeyMap}};
 use tui::layout::{Rect, Layout, Direction, Constraint};
 
 use crate::{
     menu::{UiMenu, MenuItem, Page},
-    ui_events::SharedUiEvents,
+    ui_events::{SharedUiEvents, UiEventSender},
     footer::Footer,
+    profile_switcher::UiProfileSwitcher,
     page_exercises::PageExercises,
+    page_workouts::PageWorkouts,
+    page_routines::PageRoutines,
+    page_history::PageHistory,
+    page_progress::PageProgress,
+    page_balance::PageBalance,
+    page_reports::PageReports,
     page_account::PageAccount
 };
 
 pub struct MainUiLayer {
+    controller: Rc<controller::Controller>,
+    ui_event_sender: UiEventSender,
     is_focused: bool,
     menu: UiMenu,
     page_exercises: PageExercises,
+    page_workouts: PageWorkouts,
+    page_routines: PageRoutines,
+    page_history: PageHistory,
+    page_progress: PageProgress,
+    page_balance: PageBalance,
+    page_reports: PageReports,
     page_account: PageAccount,
     footer: Footer,
 }
 
 impl MainUiLayer {
     pub fn new(controller: Rc<controller::Controller>, ui_events_manager: SharedUiEvents) -> Self {
+        let mut menu = UiMenu::new(controller.key_map());
+        menu.set_title(format!("Menu - profile {}", controller.cfg().active_profile()));
         let mut s = Self {
+            controller: controller.clone(),
+            ui_event_sender: UiEventSender::new(ui_events_manager.clone()),
             is_focused: false,
-            menu: UiMenu::new(),
+            menu,
             page_exercises: PageExercises::new(controller.clone(), ui_events_manager.clone()),
+            page_workouts: PageWorkouts::new(controller.clone(), ui_events_manager.clone()),
+            page_routines: PageRoutines::new(controller.clone(), ui_events_manager.clone()),
+            page_history: PageHistory::new(controller.clone(), ui_events_manager.clone()),
+            page_progress: PageProgress::new(controller.clone(), ui_events_manager.clone()),
+            page_balance: PageBalance::new(controller.clone(), ui_events_manager.clone()),
+            page_reports: PageReports::new(controller.clone(), ui_events_manager.clone()),
             page_account: PageAccount::new(controller, ui_events_manager.clone()),
             footer: Footer::default(),
         };
@@ -40,21 +66,42 @@ impl MainUiLayer {
     }
 
     fn current_page_mut(&mut self) -> &mut dyn Page {
-        return match self.menu.current_index() {
+        match self.menu.current_index() {
             MenuItem::Exercises => &mut self.page_exercises,
+            MenuItem::Workouts => &mut self.page_workouts,
+            MenuItem::Routines => &mut self.page_routines,
+            MenuItem::History => &mut self.page_history,
+            MenuItem::Progress => &mut self.page_progress,
+            MenuItem::Balance => &mut self.page_balance,
+            MenuItem::Reports => &mut self.page_reports,
             MenuItem::Account => &mut self.page_account,
         }
     }
 
     fn current_page(&self) -> &dyn Page {
-        return match self.menu.current_index() {
+        match self.menu.current_index() {
             MenuItem::Exercises => &self.page_exercises,
+            MenuItem::Workouts => &self.page_workouts,
+            MenuItem::Routines => &self.page_routines,
+            MenuItem::History => &self.page_history,
+            MenuItem::Progress => &self.page_progress,
+            MenuItem::Balance => &self.page_balance,
+            MenuItem::Reports => &self.page_reports,
             MenuItem::Account => &self.page_account,
         }
     }
 
-    fn pages_mut(&mut self) -> [&mut dyn Page; 2] {
-        [&mut self.page_exercises, &mut self.page_account]
+    fn pages_mut(&mut self) -> [&mut dyn Page; 8] {
+        [
+            &mut self.page_exercises,
+            &mut self.page_workouts,
+            &mut self.page_routines,
+            &mut self.page_history,
+            &mut self.page_progress,
+            &mut self.page_balance,
+            &mut self.page_reports,
+            &mut self.page_account,
+        ]
     }
 
     pub fn switch_menu(&mut self, item: MenuItem) {
@@ -64,10 +111,11 @@ impl MainUiLayer {
             p.focus(false);
             p.set_visible(false);
         });
+        let global_help = self.controller.key_map().help(Context::Global);
         let current_page = self.current_page_mut();
         current_page.focus(true);
         current_page.set_visible(true);
-        let help = format!("{} {}", current_page.commands_help(), "Quit[q]");
+        let help = format!("{} {}", current_page.commands_help(), global_help);
         self.footer.content = help;
     }
 }
@@ -77,6 +125,10 @@ impl Component for MainUiLayer {
         true
     }
 
+    fn apply_theme(&mut self, theme: &UiTheme) {
+        self.pages_mut().iter_mut().for_each(|p| p.apply_theme(theme));
+    }
+
     fn draw(&self, f: &mut RenderFrame, area: Rect) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -97,29 +149,36 @@ impl Component for MainUiLayer {
     }
 }
 
-impl TermEventDispatcher for MainUiLayer {}
-impl KeyEventDispatcher for MainUiLayer {
-    fn on_char(&mut self, c: &char) -> bool {
-        if let Some(index) = c.to_digit(10) {
-            if index > 0 {
-                let index = index as usize - 1;
-                if index < self.menu.items_count() {
-                    self.switch_menu(MenuItem::from(index));
-                    return true;
-                }
+impl TermEventDispatcher for MainUiLayer {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::Global))
+    }
+
+    fn on_action(&mut self, action: Action) -> bool {
+        if action == Action::Profile {
+            let mut switcher = UiProfileSwitcher::new(self.controller.clone());
+            switcher.set_ui_events_manager(self.ui_event_sender.ui_events_manager());
+            self.ui_event_sender.send_add_layer_event(Box::new(switcher));
+            return true;
+        }
+        match Action::PAGES.iter().position(|a| *a == action) {
+            Some(index) if index < self.menu.items_count() => {
+                self.switch_menu(MenuItem::from(index));
+                true
             }
+            _ => false,
         }
-        false
     }
 }
 
+impl KeyEventDispatcher for MainUiLayer {}
+
 impl EventComponent for MainUiLayer {
     fn focus(&mut self, value: bool) {
         self.is_focused = value;
         self.menu.focus(value);
         if !value {
-            self.page_exercises.focus(false);
-            self.page_account.focus(false);
+            self.pages_mut().iter_mut().for_each(|p| p.focus(false));
             self.footer.content.clear();
         } else {
             self.switch_menu(MenuItem::Exercises);
@@ -134,6 +193,9 @@ impl EventComponent for MainUiLayer {
         if !self.on_focus() {
             return false;
         }
+        if self.current_page().is_text_input() {
+            return self.current_page_mut().on_term_event(event);
+        }
         let event_consumed = self.dispatch_term_event(event);
         event_consumed || self.current_page_mut().on_term_event(event)
     }
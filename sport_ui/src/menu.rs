@@ -6,7 +6,7 @@ use core_ui::{
     render::RenderFrame, layer::Layer,
 };
 use crossterm::event::Event;
-use sport_core::controller;
+use sport_core::{controller, keys::{Action, KeyMap}};
 use tui::layout::Rect;
 
 use crate::{tabs::{UiTabs, UiTab}, ui_events::SharedUiEvents};
@@ -15,6 +15,12 @@ use crate::{tabs::{UiTabs, UiTab}, ui_events::SharedUiEvents};
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum MenuItem {
     Exercises,
+    Workouts,
+    Routines,
+    History,
+    Progress,
+    Balance,
+    Reports,
     Account,
 }
 
@@ -22,7 +28,13 @@ impl From<MenuItem> for usize {
     fn from(i: MenuItem) -> usize {
         match i {
             MenuItem::Exercises => 0,
-            MenuItem::Account => 1,
+            MenuItem::Workouts => 1,
+            MenuItem::Routines => 2,
+            MenuItem::History => 3,
+            MenuItem::Progress => 4,
+            MenuItem::Balance => 5,
+            MenuItem::Reports => 6,
+            MenuItem::Account => 7,
         }
     }
 }
@@ -31,7 +43,13 @@ impl From<usize> for MenuItem {
     fn from(i: usize) -> MenuItem {
         match i {
             0 => MenuItem::Exercises,
-            1 => MenuItem::Account,
+            1 => MenuItem::Workouts,
+            2 => MenuItem::Routines,
+            3 => MenuItem::History,
+            4 => MenuItem::Progress,
+            5 => MenuItem::Balance,
+            6 => MenuItem::Reports,
+            7 => MenuItem::Account,
             _ => unreachable!("Can`t convert index to MenuTab"),
         }
     }
@@ -44,17 +62,23 @@ pub struct UiMenu {
 
 #[allow(dead_code)]
 impl UiMenu {
-    pub fn new() -> Self {
+    /// Tabs named with the keys of the pages
+    pub fn new(key_map: &KeyMap) -> Self {
+        let names = ["Exercise", "Workouts", "Routines", "History", "Progress", "Balance", "Reports", "Account"];
         Self {
             tabs: UiTabs::new(
-                      vec![
-                      UiTab::new(String::from("Exercise")),
-                      UiTab::new(String::from("Account")),
-                      ]),
+                      names.iter()
+                      .zip(Action::PAGES)
+                      .map(|(name, action)| UiTab::new(format!("{} [{}]", name, key_map.binding(action))))
+                      .collect()),
                       is_focused: false,
         }
     }
 
+    pub fn set_title(&mut self, title: String) {
+        self.tabs.set_title(title);
+    }
+
     pub fn tabs(&self) -> &UiTabs {
         &self.tabs
     }
@@ -103,6 +127,9 @@ pub trait Page: Layer { // TODO Move from here
     fn new(controller: Rc<controller::Controller>, ui_events_manager: SharedUiEvents) -> Self where Self: Sized;
     // fn set_contoller(controller: Rc<controller::Controller>) {}
 
-    fn commands_help(&self) -> &str { "" }
+    fn commands_help(&self) -> String { String::new() }
+
+    /// Typing into an inline text field, keys go to the page before the menu
+    fn is_text_input(&self) -> bool { false }
 }
 
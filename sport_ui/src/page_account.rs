@@ -1,24 +1,278 @@
-use std::rc::Rc;
+use std::{path::Path, rc::Rc};
 
+use chrono::Local;
 use core_ui::{
     components::{Component, EventComponent},
     event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
-    layer::Layer
+    layer::Layer,
+    message_box::UiMessageBox,
 };
-use sport_core::controller;
+use sport_core::{analytics, backup::Backup, calculators::{self, ActivityLevel, BmiCategory, BmrFormula}, controller, goals, keys::{Action, Context, KeyMap}};
+use sport_core_db::{model::Table, units::Quantity};
+use tui::{layout::{Constraint, Direction, Layout}, widgets, style::{Style, Color}};
 
-use crate::{ui_events::SharedUiEvents, menu::Page};
+use crate::{backup_restore::UiBackupRestore, ui_events::{SharedUiEvents, UiEventSender}, menu::Page};
+
+const PROGRESS_BAR_WIDTH: usize = 10;
+/// Complete weeks checked against the schedule
+const ADHERENCE_WEEKS: u32 = 4;
 
 pub struct PageAccount {
+    controller: Rc<controller::Controller>,
+    ui_event_sender: UiEventSender,
+    bmr_formula: BmrFormula,
+    activity: ActivityLevel,
     is_visible: bool,
     is_focused: bool,
 }
 
+impl PageAccount {
+    fn reload(&mut self) {
+        let id = self.controller.cfg().account().id;
+        self.controller.person().load_one(id);
+        let results = [
+            ("exercises", self.controller.exercises().load_all()),
+            ("workouts", self.controller.workouts().load_all()),
+            ("workout sets", self.controller.sets().load_all()),
+            ("measurements", self.controller.measurements().load_all()),
+            ("goals", self.controller.goals().load_all()),
+            ("routines", self.controller.routines().load_all()),
+        ];
+        for (name, result) in results {
+            if let Err(err) = result {
+                let err = format!("Can`t get all {}:\n{}", name, err);
+                self.ui_event_sender.send_add_layer_event(Box::new(UiMessageBox::err("Account", err)));
+                return;
+            }
+        }
+    }
+
+    fn backup(&self) -> Backup {
+        Backup::new(&self.controller.db(), self.controller.cfg(), Local::now().naive_local())
+    }
+
+    fn export_backup(&mut self) {
+        let popup = match self.backup().export(Path::new(".")) {
+            Ok(path) => UiMessageBox::info("Account", format!("Backup saved to {}", path.display())),
+            Err(err) => UiMessageBox::err("Account", format!("Can`t save backup:\n{}", err)),
+        };
+        self.ui_event_sender.send_add_layer_event(Box::new(popup));
+    }
+
+    fn goals_rows(&self) -> Vec<widgets::Row<'static>> {
+        let cfg = self.controller.cfg();
+        let db = self.controller.db();
+        let today = Local::now().date_naive();
+        let mut goals: Vec<_> = db.goals()
+            .get_all()
+            .filter(|g| g.person_id == cfg.account().id)
+            .collect();
+        goals.sort_by_key(|g| g.deadline);
+        goals.into_iter()
+            .map(|goal| {
+                let progress = goals::progress(&db, goal, today, cfg.one_rep_max);
+                let name = match goal.metric.exercise_id().and_then(|id| db.exercises().get_one(id)) {
+                    Some(exercise) => format!("{} {}", goal.metric.name(), exercise.name),
+                    None => goal.metric.name().to_owned(),
+                };
+                let filled = (progress.percent / 100.0 * PROGRESS_BAR_WIDTH as f64).round() as usize;
+                let bar = format!("[{}{}] {:.0}%", "#".repeat(filled), ".".repeat(PROGRESS_BAR_WIDTH - filled), progress.percent);
+                let projected = if progress.is_reached() {
+                    "reached".to_owned()
+                } else {
+                    progress.projected.map(|d| d.to_string()).unwrap_or_else(|| "-".to_owned())
+                };
+                let color = if progress.is_reached() {
+                    Color::Green
+                } else if progress.projected.is_none_or(|d| d > goal.deadline) {
+                    Color::Yellow
+                } else {
+                    Color::White
+                };
+                widgets::Row::new(vec![
+                    name,
+                    goal.metric.format_value(progress.target, cfg.units),
+                    goal.metric.format_value(progress.current, cfg.units),
+                    bar,
+                    goal.deadline.to_string(),
+                    projected,
+                ])
+                .style(Style::default().fg(color))
+            })
+            .collect()
+    }
+
+    fn consistency_rows(&self) -> Vec<widgets::Row<'static>> {
+        let cfg = self.controller.cfg();
+        let db = self.controller.db();
+        let today = Local::now().date_naive();
+        let streaks = analytics::streaks(&db, cfg.account().id, cfg.streak, today);
+        let period = analytics::last_complete_weeks(ADHERENCE_WEEKS, today);
+        let adherence = analytics::adherence(&db, cfg.account().id, cfg.schedule, period);
+        let unit = cfg.streak.unit;
+        vec![
+            ("Streak rule", format!("{}+ sessions a {}", cfg.streak.min_sessions, unit.name(1))),
+            ("Current streak", format!("{} {}", streaks.current, unit.name(streaks.current))),
+            ("Longest streak", format!("{} {}", streaks.longest, unit.name(streaks.longest))),
+            ("Adherence", format!("{:.0}% ({}/{}) last {} weeks", adherence.percent, adherence.done, adherence.planned, ADHERENCE_WEEKS)),
+            ("Missed", adherence.missed.to_string()),
+        ]
+        .into_iter()
+        .map(|(name, value)| widgets::Row::new(vec![
+                widgets::Cell::from(name).style(Style::default().fg(Color::Green)),
+                widgets::Cell::from(value),
+        ]))
+        .collect()
+    }
+
+    fn calculator_rows(&self) -> (Vec<widgets::Row<'static>>, Vec<widgets::Row<'static>>) {
+        let cfg = self.controller.cfg();
+        let db = self.controller.db();
+        let today = Local::now().date_naive();
+        let health = match calculators::health(&db, cfg.account().id, today, self.bmr_formula, self.activity) {
+            Some(health) => health,
+            None => return (vec![widgets::Row::new(vec!["Account", "not loaded"])], vec![]),
+        };
+        let calories = |value: Option<f64>| value.map(|v| format!("{:.0} kcal", v)).unwrap_or_else(|| "-".to_owned());
+        let weight = if health.weight.is_zero() { "no measurements".to_owned() } else { health.weight.format(cfg.units) };
+        let bmi = health.bmi
+            .map(|bmi| format!("{:.1} ({})", bmi, BmiCategory::of(bmi)))
+            .unwrap_or_else(|| "-".to_owned());
+        let (low, high) = health.ideal_weight;
+        let resting = health.resting_heart_rate.map(|hr| format!("{} bpm", hr)).unwrap_or_else(|| "-".to_owned());
+        let rows = vec![
+            ("Body weight", weight),
+            ("BMI", bmi),
+            ("Ideal weight", format!("{} - {}", low.format(cfg.units), high.format(cfg.units))),
+            ("BMR", format!("{} ({})", calories(health.bmr), self.bmr_formula)),
+            ("TDEE", format!("{} ({})", calories(health.tdee), self.activity)),
+            ("Resting HR", resting),
+            ("Max HR", format!("{} bpm", health.max_heart_rate)),
+        ]
+        .into_iter()
+        .map(|(name, value)| widgets::Row::new(vec![
+                widgets::Cell::from(name).style(Style::default().fg(Color::Green)),
+                widgets::Cell::from(value),
+        ]))
+        .collect();
+        let zones = health.zones
+            .iter()
+            .map(|z| widgets::Row::new(vec![
+                    widgets::Cell::from(format!("Z{} {}", z.number, z.name)).style(Style::default().fg(Color::Green)),
+                    widgets::Cell::from(format!("{}-{} bpm", z.low, z.high)),
+            ]))
+            .collect();
+        (rows, zones)
+    }
+
+    fn profile_rows(&self) -> Vec<widgets::Row<'static>> {
+        let cfg = self.controller.cfg();
+        let db = self.controller.db();
+        let person = match db.persons().get_one(cfg.account().id) {
+            Some(person) => person,
+            None => return vec![widgets::Row::new(vec!["Account", "not loaded"])],
+        };
+        let age = person.age().map(|age| age.to_string()).unwrap_or_default();
+        vec![
+            ("Name", format!("{} {}", person.first_name, person.last_name)),
+            ("Birth date", person.birth_date.to_string()),
+            ("Age", age),
+            ("Gender", person.gender.to_string()),
+            ("Height", person.height.format(cfg.units)),
+            ("Units", cfg.units.to_string()),
+        ]
+        .into_iter()
+        .map(|(name, value)| widgets::Row::new(vec![
+                widgets::Cell::from(name).style(Style::default().fg(Color::Green)),
+                widgets::Cell::from(value),
+        ]))
+        .collect()
+    }
+}
+
 impl Component for PageAccount {
-    fn draw(&self, _f: &mut core_ui::render::RenderFrame, _area: tui::layout::Rect) {
+    fn draw(&self, f: &mut core_ui::render::RenderFrame, area: tui::layout::Rect) {
         if !self.is_visible {
             return;
         }
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(8), Constraint::Length(9), Constraint::Min(4)].as_ref())
+            .split(area);
+
+        let top = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(chunks[0]);
+
+        let table = widgets::Table::new(self.profile_rows())
+            .block(
+                widgets::Block::default()
+                .borders(widgets::Borders::ALL)
+                .title("Account"),
+                )
+            .widths(&[Constraint::Length(15), Constraint::Min(20)]);
+        f.render_widget(table, top[0]);
+
+        let consistency = widgets::Table::new(self.consistency_rows())
+            .block(
+                widgets::Block::default()
+                .borders(widgets::Borders::ALL)
+                .title("Consistency"),
+                )
+            .widths(&[Constraint::Length(15), Constraint::Min(20)]);
+        f.render_widget(consistency, top[1]);
+
+        let middle = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(chunks[1]);
+        let (calculators, zones) = self.calculator_rows();
+        let calculators = widgets::Table::new(calculators)
+            .block(
+                widgets::Block::default()
+                .borders(widgets::Borders::ALL)
+                .title("Calculators"),
+                )
+            .widths(&[Constraint::Length(15), Constraint::Min(20)]);
+        f.render_widget(calculators, middle[0]);
+
+        let person_id = self.controller.cfg().account().id;
+        let db = self.controller.db();
+        let title = if db.measurements().get_all().any(|m| m.person_id == person_id && m.resting_heart_rate.is_some()) {
+            "Heart rate zones (Karvonen)"
+        } else {
+            "Heart rate zones (age-predicted)"
+        };
+        let zones = widgets::Table::new(zones)
+            .block(
+                widgets::Block::default()
+                .borders(widgets::Borders::ALL)
+                .title(title),
+                )
+            .widths(&[Constraint::Length(15), Constraint::Min(20)]);
+        f.render_widget(zones, middle[1]);
+
+        let goals = widgets::Table::new(self.goals_rows())
+            .header(
+                widgets::Row::new(vec!["Goal", "Target", "Current", "Progress", "Deadline", "Projected"])
+                .style(Style::default().fg(Color::Red))
+                .bottom_margin(1),
+                )
+            .block(
+                widgets::Block::default()
+                .borders(widgets::Borders::ALL)
+                .title("Goals"),
+                )
+            .widths(&[
+                    Constraint::Length(30),
+                    Constraint::Length(12),
+                    Constraint::Length(12),
+                    Constraint::Length(18),
+                    Constraint::Length(12),
+                    Constraint::Length(12),
+            ]);
+        f.render_widget(goals, chunks[2]);
     }
 
     fn is_visible(&self) -> bool { self.is_visible }
@@ -26,17 +280,43 @@ impl Component for PageAccount {
     fn set_visible(&mut self, value: bool) { self.is_visible = value; }
 }
 
-impl TermEventDispatcher for PageAccount {}
+/// Item after `current`, the first one after the last
+fn next<T: Copy + PartialEq>(items: &[T], current: T) -> T {
+    let index = items.iter().position(|i| *i == current).unwrap_or_default();
+    items[(index + 1) % items.len()]
+}
+
+impl TermEventDispatcher for PageAccount {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::Account))
+    }
+
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Reload => self.reload(),
+            Action::BmrFormula => self.bmr_formula = next(&BmrFormula::ALL, self.bmr_formula),
+            Action::ActivityLevel => self.activity = next(&ActivityLevel::ALL, self.activity),
+            Action::Backup => self.export_backup(),
+            Action::Restore => {
+                let mut popup = Box::new(UiBackupRestore::new(self.controller.clone(), self.backup().file_name()));
+                popup.set_ui_events_manager(self.ui_event_sender.ui_events_manager());
+                self.ui_event_sender.send_add_layer_event(popup);
+            }
+            _ => return false,
+        }
+        true
+    }
+}
 impl KeyEventDispatcher for PageAccount {}
 
 impl EventComponent for PageAccount {
-    fn focus(&mut self, value: bool) { 
+    fn focus(&mut self, value: bool) {
         self.is_focused = value;
     }
 
     fn on_focus(&self) -> bool { self.is_focused }
 
-    fn on_term_event(&mut self, event: &core_ui::component::TerminalEvent) -> bool { 
+    fn on_term_event(&mut self, event: &core_ui::component::TerminalEvent) -> bool {
         if self.on_focus() {
             return self.dispatch_term_event(event);
         }
@@ -47,14 +327,18 @@ impl EventComponent for PageAccount {
 impl Layer for PageAccount { }
 
 impl Page for PageAccount {
-    fn new(_controller: Rc<controller::Controller>, _ui_events_manager: SharedUiEvents) -> Self {
-        Self { 
+    fn new(controller: Rc<controller::Controller>, ui_events_manager: SharedUiEvents) -> Self {
+        Self {
+            ui_event_sender: UiEventSender::new(ui_events_manager),
+            bmr_formula: controller.cfg().bmr_formula,
+            activity: controller.cfg().activity,
+            controller,
             is_visible: true,
             is_focused: false,
-        } 
+        }
     }
 
-    fn commands_help(&self) -> &str {
-        ""
+    fn commands_help(&self) -> String {
+        self.controller.key_map().help(Context::Account)
     }
 }
@@ -0,0 +1,174 @@
+use std::rc::Rc;
+
+use chrono::Local;
+use core_ui::{
+    components::{Component, EventComponent},
+    event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
+    layer::Layer,
+    message_box::UiMessageBox,
+    render::RenderFrame,
+    theme::UiTheme,
+};
+use sport_core::{balance::{self, Status}, controller, keys::{Action, Context, KeyMap}};
+use tui::{layout::{Constraint, Direction, Layout, Rect}, style::Style, widgets};
+
+use crate::{menu::Page, ui_events::{SharedUiEvents, UiEventSender}};
+
+pub struct PageBalance {
+    controller: Rc<controller::Controller>,
+    ui_event_sender: UiEventSender,
+    theme: UiTheme,
+    is_visible: bool,
+    is_focused: bool,
+}
+
+impl PageBalance {
+    fn reload(&mut self) {
+        let results = [
+            ("exercises", self.controller.exercises().load_all()),
+            ("workouts", self.controller.workouts().load_all()),
+            ("workout sets", self.controller.sets().load_all()),
+        ];
+        for (name, result) in results {
+            if let Err(err) = result {
+                let err = format!("Can`t get all {}:\n{}", name, err);
+                self.ui_event_sender.send_add_layer_event(Box::new(UiMessageBox::err("Balance", err)));
+                return;
+            }
+        }
+    }
+
+    fn status_style(&self, status: Status) -> Style {
+        match status {
+            Status::Ok => self.theme.status.ok_style,
+            Status::Warning => self.theme.status.warning_style,
+            Status::Danger => self.theme.status.danger_style,
+        }
+    }
+}
+
+impl Component for PageBalance {
+    fn apply_theme(&mut self, theme: &UiTheme) {
+        self.theme = *theme;
+    }
+
+    fn draw(&self, f: &mut RenderFrame, area: Rect) {
+        if !self.is_visible {
+            return;
+        }
+        let person_id = self.controller.cfg().account().id;
+        let balance = balance::balance(&self.controller.db(), person_id, Local::now().date_naive());
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(50), Constraint::Min(20)].as_ref())
+            .split(area);
+
+        let rows: Vec<widgets::Row> = balance.groups
+            .iter()
+            .map(|g| widgets::Row::new(vec![
+                widgets::Cell::from(g.group.to_string()),
+                widgets::Cell::from(g.weekly_sets.to_string()),
+                widgets::Cell::from(format!("{:.1}", g.chronic_sets)),
+                widgets::Cell::from(g.acwr.map(|acwr| format!("{:.2}", acwr)).unwrap_or_else(|| "-".to_owned())),
+            ])
+            .style(self.status_style(g.status)))
+            .collect();
+        let table = widgets::Table::new(rows)
+            .style(self.theme.table.table_style)
+            .header(
+                widgets::Row::new(vec!["Muscle group", "Sets", "Usual", "ACWR"])
+                .style(self.theme.table.header_style)
+                .bottom_margin(1),
+                )
+            .block(
+                widgets::Block::default()
+                .borders(widgets::Borders::ALL)
+                .title("Sets in the last 7 days"),
+                )
+            .widths(&[
+                    Constraint::Length(14),
+                    Constraint::Length(6),
+                    Constraint::Length(7),
+                    Constraint::Length(6),
+            ]);
+        f.render_widget(table, chunks[0]);
+
+        let ratio_row = |name: &'static str, ratio: Option<f64>| {
+            let style = ratio.map(|r| self.status_style(balance::ratio_status(r))).unwrap_or_default();
+            widgets::Row::new(vec![name.to_owned(), ratio.map(|r| format!("{:.2}", r)).unwrap_or_else(|| "-".to_owned())])
+                .style(style)
+        };
+        let mut rows = vec![
+            ratio_row("Push/pull", balance.push_pull),
+            ratio_row("Upper/lower", balance.upper_lower),
+            widgets::Row::new(vec![""]),
+        ];
+        rows.extend(balance.warnings
+            .iter()
+            .map(|w| widgets::Row::new(vec![w.to_string()]).style(self.status_style(w.status()))));
+        if balance.warnings.is_empty() {
+            rows.push(widgets::Row::new(vec!["No warnings"]).style(self.theme.status.ok_style));
+        }
+        let warnings = widgets::Table::new(rows)
+            .block(
+                widgets::Block::default()
+                .borders(widgets::Borders::ALL)
+                .title("Ratios and warnings"),
+                )
+            .widths(&[Constraint::Min(40), Constraint::Length(8)]);
+        f.render_widget(warnings, chunks[1]);
+    }
+
+    fn is_visible(&self) -> bool { self.is_visible }
+
+    fn set_visible(&mut self, value: bool) { self.is_visible = value; }
+}
+
+impl TermEventDispatcher for PageBalance {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::Balance))
+    }
+
+    fn on_action(&mut self, action: Action) -> bool {
+        if action == Action::Reload {
+            self.reload();
+            return true;
+        }
+        false
+    }
+}
+
+impl KeyEventDispatcher for PageBalance {}
+
+impl EventComponent for PageBalance {
+    fn focus(&mut self, value: bool) {
+        self.is_focused = value;
+    }
+
+    fn on_focus(&self) -> bool { self.is_focused }
+
+    fn on_term_event(&mut self, event: &core_ui::component::TerminalEvent) -> bool {
+        if self.on_focus() {
+            return self.dispatch_term_event(event);
+        }
+        false
+    }
+}
+
+impl Layer for PageBalance { }
+
+impl Page for PageBalance {
+    fn new(controller: Rc<controller::Controller>, ui_events_manager: SharedUiEvents) -> Self {
+        Self {
+            controller,
+            ui_event_sender: UiEventSender::new(ui_events_manager),
+            theme: UiTheme::default(),
+            is_visible: true,
+            is_focused: false,
+        }
+    }
+
+    fn commands_help(&self) -> String {
+        self.controller.key_map().help(Context::Balance)
+    }
+}
@@ -60,11 +60,11 @@ impl Page for PageExercises {
         } 
     }
 
-    fn commands_help(&self) -> &str {
+    fn commands_help(&self) -> String {
         if self.exercises_table.on_focus() {
             self.exercises_table.commands_help()
         } else {
-            ""
+            String::new()
         }
     }
 }
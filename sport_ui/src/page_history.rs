@@ -0,0 +1,194 @@
+use std::{cell::RefCell, rc::Rc};
+
+use chrono::Local;
+use core_ui::{
+    components::{Component, EventComponent, UiCalendar},
+    event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
+    layer::Layer,
+    message_box::UiMessageBox,
+    render::RenderFrame,
+};
+use sport_core::{analytics::{self, Period}, controller, keys::{Action, Context, KeyMap}};
+use sport_core_db::{entity, model::Table, units::Quantity};
+use tui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets,
+};
+
+use crate::{menu::Page, ui_events::{SharedUiEvents, UiEventSender}};
+
+pub struct PageHistory {
+    controller: Rc<controller::Controller>,
+    ui_event_sender: UiEventSender,
+    calendar: RefCell<UiCalendar>,
+    is_visible: bool,
+    is_focused: bool,
+}
+
+impl PageHistory {
+    fn reload(&mut self) {
+        let results = [
+            ("exercises", self.controller.exercises().load_all()),
+            ("workouts", self.controller.workouts().load_all()),
+            ("workout sets", self.controller.sets().load_all()),
+        ];
+        for (name, result) in results {
+            if let Err(err) = result {
+                let err = format!("Can`t get all {}:\n{}", name, err);
+                self.ui_event_sender.send_add_layer_event(Box::new(UiMessageBox::err("History", err)));
+                return;
+            }
+        }
+    }
+
+    fn set_line(&self, set: &entity::WorkoutSet) -> Spans<'static> {
+        let units = self.controller.cfg().units;
+        let db = self.controller.db();
+        let exercise = db.exercises().get_one(set.exercise_id).map(|e| e.name.clone()).unwrap_or_default();
+        let mut parts = vec![];
+        if set.reps > 0 {
+            parts.push(format!("{} reps", set.reps));
+        }
+        if !set.weight.is_zero() {
+            parts.push(set.weight.format(units));
+        }
+        if !set.distance.is_zero() {
+            parts.push(set.distance.format(units));
+        }
+        if !set.duration.is_zero() {
+            parts.push(set.duration.format(units));
+        }
+        if let Some(rpe) = set.rpe {
+            parts.push(format!("RPE {}", rpe));
+        }
+        let is_record = db.records().get_all().any(|r| r.set_id == set.id && r.is_improvement());
+        Spans::from(vec![
+            Span::raw(format!("  {:>2}. {} ", set.order, exercise)),
+            Span::styled(parts.join(" x "), Style::default().fg(Color::Gray)),
+            Span::styled(if is_record { " PR" } else { "" }, Style::default().fg(Color::Yellow)),
+        ])
+    }
+
+    fn day_lines(&self) -> Vec<Spans<'static>> {
+        let units = self.controller.cfg().units;
+        let person_id = self.controller.cfg().account().id;
+        let day = self.calendar.borrow().selected();
+        let db = self.controller.db();
+        let workouts = analytics::workouts_in(&db, person_id, Period::new(day, day));
+        if workouts.is_empty() {
+            return vec![Spans::from("No workouts")];
+        }
+        let mut lines = vec![];
+        for workout in workouts {
+            let volume = analytics::workout_volume(&db, workout.id);
+            lines.push(Spans::from(vec![
+                Span::styled(workout.started_at.format("%H:%M ").to_string(), Style::default().fg(Color::Green)),
+                Span::styled(workout.name.to_owned(), Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(format!(
+                    "  {}  {} sets  {}",
+                    workout.duration.format(units),
+                    volume.sets,
+                    volume.tonnage.format(units),
+                )),
+            ]));
+            let mut sets: Vec<&entity::WorkoutSet> = db.sets().get_all().filter(|s| s.workout_id == workout.id).collect();
+            sets.sort_by_key(|s| s.order);
+            lines.extend(sets.into_iter().map(|s| self.set_line(s)));
+            lines.push(Spans::from(""));
+        }
+        lines
+    }
+}
+
+impl Component for PageHistory {
+    fn draw(&self, f: &mut RenderFrame, area: Rect) {
+        if !self.is_visible {
+            return;
+        }
+        {
+            let person_id = self.controller.cfg().account().id;
+            let db = self.controller.db();
+            let mut calendar = self.calendar.borrow_mut();
+            calendar.set_today(Local::now().date_naive());
+            calendar.set_marked(db.workouts().get_all().filter(|w| w.person_id == person_id).map(|w| w.date()));
+        }
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(25), Constraint::Min(20)].as_ref())
+            .split(area);
+        let calendar_area = Rect { height: chunks[0].height.min(10), ..chunks[0] };
+        self.calendar.borrow().draw(f, calendar_area);
+
+        let title = self.calendar.borrow().selected().format("%A, %Y-%m-%d").to_string();
+        let day = widgets::Paragraph::new(self.day_lines())
+            .block(
+                widgets::Block::default()
+                .borders(widgets::Borders::ALL)
+                .title(title),
+                )
+            .wrap(widgets::Wrap { trim: false });
+        f.render_widget(day, chunks[1]);
+    }
+
+    fn is_visible(&self) -> bool { self.is_visible }
+
+    fn set_visible(&mut self, value: bool) { self.is_visible = value; }
+}
+
+impl TermEventDispatcher for PageHistory {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::History))
+    }
+
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Reload => {
+                self.reload();
+                true
+            }
+            Action::Today => {
+                self.calendar.borrow_mut().go_today();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl KeyEventDispatcher for PageHistory {}
+
+impl EventComponent for PageHistory {
+    fn focus(&mut self, value: bool) {
+        self.is_focused = value;
+        self.calendar.borrow_mut().focus(value);
+    }
+
+    fn on_focus(&self) -> bool { self.is_focused }
+
+    fn on_term_event(&mut self, event: &core_ui::component::TerminalEvent) -> bool {
+        if self.on_focus() {
+            return self.dispatch_term_event(event) || self.calendar.borrow_mut().on_term_event(event);
+        }
+        false
+    }
+}
+
+impl Layer for PageHistory { }
+
+impl Page for PageHistory {
+    fn new(controller: Rc<controller::Controller>, ui_events_manager: SharedUiEvents) -> Self {
+        Self {
+            controller,
+            ui_event_sender: UiEventSender::new(ui_events_manager),
+            calendar: RefCell::new(UiCalendar::new(Local::now().date_naive())),
+            is_visible: true,
+            is_focused: false,
+        }
+    }
+
+    fn commands_help(&self) -> String {
+        format!("Day[arrows] Month[PgUp/PgDn] {}", self.controller.key_map().help(Context::History))
+    }
+}
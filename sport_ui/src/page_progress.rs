@@ -0,0 +1,357 @@
+use std::{cell::RefCell, rc::Rc};
+
+use chrono::{Duration, Local, NaiveDate};
+use core_ui::{
+    components::{Component, EventComponent, UiTable},
+    event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
+    layer::Layer,
+    message_box::UiMessageBox,
+    render::RenderFrame,
+};
+use sport_core::{analytics::{self, Period}, controller, goals::MetricPoint, keys::{Action, Context, KeyMap}};
+use sport_core_db::{entity, model::Table, units::{format_number, Mass, Quantity}};
+use tui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols,
+    text::{Span, Spans},
+    widgets,
+};
+
+use crate::{menu::Page, ui_events::{SharedUiEvents, UiEventSender}};
+
+const Y_AXIS_LABELS: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Series {
+    OneRepMax,
+    Tonnage,
+    BodyWeight,
+}
+
+impl Series {
+    const ALL: [Series; 3] = [Series::OneRepMax, Series::Tonnage, Series::BodyWeight];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Series::OneRepMax => "Estimated 1RM",
+            Series::Tonnage => "Volume",
+            Series::BodyWeight => "Body weight",
+        }
+    }
+
+    fn index(&self) -> usize {
+        Series::ALL.iter().position(|s| s == self).unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TimeRange {
+    FourWeeks,
+    ThreeMonths,
+    Year,
+    All,
+}
+
+impl TimeRange {
+    fn name(&self) -> &'static str {
+        match self {
+            TimeRange::FourWeeks => "4 weeks",
+            TimeRange::ThreeMonths => "3 months",
+            TimeRange::Year => "year",
+            TimeRange::All => "all",
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            TimeRange::FourWeeks => TimeRange::ThreeMonths,
+            TimeRange::ThreeMonths => TimeRange::Year,
+            TimeRange::Year => TimeRange::All,
+            TimeRange::All => TimeRange::FourWeeks,
+        }
+    }
+
+    fn period(&self, today: NaiveDate) -> Period {
+        match self {
+            TimeRange::FourWeeks => Period::last_days(28, today),
+            TimeRange::ThreeMonths => Period::last_days(91, today),
+            TimeRange::Year => Period::last_days(365, today),
+            TimeRange::All => Period::until(today),
+        }
+    }
+}
+
+pub struct PageProgress {
+    controller: Rc<controller::Controller>,
+    ui_event_sender: UiEventSender,
+    exercises_table: RefCell<UiTable<entity::ID>>,
+    series: Series,
+    range: TimeRange,
+    is_visible: bool,
+    is_focused: bool,
+}
+
+impl PageProgress {
+    fn reload(&mut self) {
+        let results = [
+            ("exercises", self.controller.exercises().load_all()),
+            ("workouts", self.controller.workouts().load_all()),
+            ("workout sets", self.controller.sets().load_all()),
+            ("measurements", self.controller.measurements().load_all()),
+        ];
+        for (name, result) in results {
+            if let Err(err) = result {
+                let err = format!("Can`t get all {}:\n{}", name, err);
+                self.ui_event_sender.send_add_layer_event(Box::new(UiMessageBox::err("Progress", err)));
+                return;
+            }
+        }
+    }
+
+    fn select_series(&mut self, offset: usize) {
+        let index = (self.series.index() + offset) % Series::ALL.len();
+        self.series = Series::ALL[index];
+    }
+
+    /// Points of the current series in the user's units
+    fn points(&self, period: Period) -> Vec<MetricPoint> {
+        let cfg = self.controller.cfg();
+        let db = self.controller.db();
+        let person_id = cfg.account().id;
+        let exercise_id = self.exercises_table.borrow().get_value().copied();
+        let points = match (self.series, exercise_id) {
+            (Series::OneRepMax, Some(id)) => analytics::one_rep_max_history(&db, person_id, id, period, cfg.one_rep_max),
+            (Series::OneRepMax, None) => vec![],
+            (Series::Tonnage, id) => analytics::tonnage_history(&db, person_id, id, period),
+            (Series::BodyWeight, _) => analytics::body_weight_history(&db, person_id, period),
+        };
+        points
+            .into_iter()
+            .map(|p| MetricPoint::new(p.date, Mass::from_kilograms(p.value).value_in(cfg.units)))
+            .collect()
+    }
+
+    fn draw_exercises(&self, f: &mut RenderFrame, area: Rect) {
+        let db = self.controller.db();
+        let person_id = self.controller.cfg().account().id;
+        let trained: Vec<entity::ID> = analytics::volume_by_exercise(&db, person_id, Period::until(NaiveDate::MAX))
+            .into_keys()
+            .collect();
+        let mut exercises: Vec<&entity::Exercise> = trained.iter().filter_map(|id| db.exercises().get_one(*id)).collect();
+        exercises.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let rows: Vec<widgets::Row> = exercises
+            .iter()
+            .map(|e| widgets::Row::new(vec![e.name.to_owned()]))
+            .collect();
+        self.exercises_table.borrow_mut().set_values(exercises.iter().map(|e| e.id).collect());
+        if self.exercises_table.borrow().selected_row().is_none() && !exercises.is_empty() {
+            self.exercises_table.borrow_mut().select_row(0);
+        }
+
+        let theme = *self.exercises_table.borrow().theme();
+        let table = widgets::Table::new(rows)
+            .style(theme.table_style)
+            .block(
+                widgets::Block::default()
+                .borders(widgets::Borders::ALL)
+                .title("Exercises"),
+                )
+            .widths(&[Constraint::Min(10)])
+            .highlight_style(theme.highlight_style)
+            .highlight_symbol(">");
+        f.render_stateful_widget(table, area, self.exercises_table.borrow_mut().state());
+    }
+
+    fn draw_series_tabs(&self, f: &mut RenderFrame, area: Rect) {
+        let titles = Series::ALL.iter().map(|s| Spans::from(s.name())).collect();
+        let tabs = widgets::Tabs::new(titles)
+            .block(
+                widgets::Block::default()
+                .borders(widgets::Borders::ALL)
+                .title(format!("Range: {}", self.range.name())),
+                )
+            .select(self.series.index())
+            .highlight_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+        f.render_widget(tabs, area);
+    }
+
+    fn draw_chart(&self, f: &mut RenderFrame, area: Rect, period: Period) {
+        let units = self.controller.cfg().units;
+        let points = self.points(period);
+        let title = match self.series {
+            Series::BodyWeight => self.series.name().to_owned(),
+            _ => {
+                let db = self.controller.db();
+                let exercise = self.exercises_table.borrow().get_value().and_then(|id| db.exercises().get_one(*id)).map(|e| e.name.clone());
+                format!("{} {}", self.series.name(), exercise.unwrap_or_else(|| "all exercises".to_owned()))
+            }
+        };
+        let block = widgets::Block::default().borders(widgets::Borders::ALL).title(title);
+        if points.is_empty() {
+            f.render_widget(widgets::Paragraph::new("No data in the range").block(block), area);
+            return;
+        }
+
+        let start = match self.range {
+            TimeRange::All => points[0].date,
+            _ => period.start,
+        };
+        let days = ((period.end - start).num_days() as f64).max(1.0);
+        let data: Vec<(f64, f64)> = points.iter().map(|p| ((p.date - start).num_days() as f64, p.value)).collect();
+        let min = points.iter().map(|p| p.value).fold(f64::INFINITY, f64::min);
+        let max = points.iter().map(|p| p.value).fold(f64::NEG_INFINITY, f64::max);
+        let margin = ((max - min) * 0.1).max(1.0);
+        let (low, high) = ((min - margin).max(0.0), max + margin);
+
+        let x_labels = [0.0, 0.5, 1.0]
+            .iter()
+            .map(|part| Span::from((start + Duration::days((days * part).round() as i64)).format("%Y-%m-%d").to_string()))
+            .collect();
+        let y_labels = (0..Y_AXIS_LABELS)
+            .map(|i| Span::from(format_number(low + (high - low) * i as f64 / (Y_AXIS_LABELS - 1) as f64, 0)))
+            .collect();
+
+        let datasets = vec![
+            widgets::Dataset::default()
+                .name(self.series.name())
+                .marker(symbols::Marker::Braille)
+                .graph_type(widgets::GraphType::Line)
+                .style(Style::default().fg(Color::Cyan))
+                .data(&data),
+        ];
+        let chart = widgets::Chart::new(datasets)
+            .block(block)
+            .x_axis(
+                widgets::Axis::default()
+                .title("Date")
+                .style(Style::default().fg(Color::Gray))
+                .bounds([0.0, days])
+                .labels(x_labels),
+                )
+            .y_axis(
+                widgets::Axis::default()
+                .title(Mass::unit_symbol(units))
+                .style(Style::default().fg(Color::Gray))
+                .bounds([low, high])
+                .labels(y_labels),
+                );
+        f.render_widget(chart, area);
+    }
+
+    fn draw_sessions(&self, f: &mut RenderFrame, area: Rect, period: Period) {
+        let db = self.controller.db();
+        let weeks = analytics::weekly_volume(&db, self.controller.cfg().account().id, period);
+        let sessions: Vec<u64> = weeks.iter().map(|w| w.sessions as u64).collect();
+        let sparkline = widgets::Sparkline::default()
+            .block(
+                widgets::Block::default()
+                .borders(widgets::Borders::ALL)
+                .title("Sessions per week"),
+                )
+            .data(&sessions)
+            .style(Style::default().fg(Color::Green));
+        f.render_widget(sparkline, area);
+    }
+}
+
+impl Component for PageProgress {
+    fn draw(&self, f: &mut RenderFrame, area: Rect) {
+        if !self.is_visible {
+            return;
+        }
+        let period = self.range.period(Local::now().date_naive());
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(30), Constraint::Min(20)].as_ref())
+            .split(area);
+        self.draw_exercises(f, chunks[0]);
+
+        let right = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(8), Constraint::Length(5)].as_ref())
+            .split(chunks[1]);
+        self.draw_series_tabs(f, right[0]);
+        self.draw_chart(f, right[1], period);
+        self.draw_sessions(f, right[2], period);
+    }
+
+    fn is_visible(&self) -> bool { self.is_visible }
+
+    fn set_visible(&mut self, value: bool) { self.is_visible = value; }
+}
+
+impl TermEventDispatcher for PageProgress {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::Progress))
+    }
+
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Reload => {
+                self.reload();
+                true
+            }
+            Action::Range => {
+                self.range = self.range.next();
+                true
+            }
+            Action::NextExercise => {
+                self.exercises_table.borrow_mut().next();
+                true
+            }
+            Action::PreviousExercise => {
+                self.exercises_table.borrow_mut().previous();
+                true
+            }
+            Action::NextSeries => {
+                self.select_series(1);
+                true
+            }
+            Action::PreviousSeries => {
+                self.select_series(Series::ALL.len() - 1);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl KeyEventDispatcher for PageProgress {}
+
+impl EventComponent for PageProgress {
+    fn focus(&mut self, value: bool) {
+        self.is_focused = value;
+        self.exercises_table.borrow_mut().focus(value);
+    }
+
+    fn on_focus(&self) -> bool { self.is_focused }
+
+    fn on_term_event(&mut self, event: &core_ui::component::TerminalEvent) -> bool {
+        if self.on_focus() {
+            return self.dispatch_term_event(event);
+        }
+        false
+    }
+}
+
+impl Layer for PageProgress { }
+
+impl Page for PageProgress {
+    fn new(controller: Rc<controller::Controller>, ui_events_manager: SharedUiEvents) -> Self {
+        Self {
+            controller,
+            ui_event_sender: UiEventSender::new(ui_events_manager),
+            exercises_table: RefCell::new(UiTable::default()),
+            series: Series::OneRepMax,
+            range: TimeRange::ThreeMonths,
+            is_visible: true,
+            is_focused: false,
+        }
+    }
+
+    fn commands_help(&self) -> String {
+        self.controller.key_map().help(Context::Progress)
+    }
+}
@@ -0,0 +1,201 @@
+use std::{path::Path, rc::Rc};
+
+use chrono::{Local, NaiveDate};
+use core_ui::{
+    components::{Component, EventComponent},
+    event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
+    layer::Layer,
+    message_box::UiMessageBox,
+    render::RenderFrame,
+    theme::UiTableTheme,
+};
+use sport_core::{controller, reports::{self, Report, ReportFormat, ReportKind}, keys::{Action, Context, KeyMap}};
+use tui::{layout::{Constraint, Direction, Layout, Rect}, widgets};
+
+use crate::{menu::Page, ui_events::{SharedUiEvents, UiEventSender}};
+
+pub struct PageReports {
+    controller: Rc<controller::Controller>,
+    ui_event_sender: UiEventSender,
+    kind: ReportKind,
+    /// Any day of the reported period
+    date: NaiveDate,
+    theme: UiTableTheme,
+    is_visible: bool,
+    is_focused: bool,
+}
+
+impl PageReports {
+    fn reload(&mut self) {
+        let results = [
+            ("exercises", self.controller.exercises().load_all()),
+            ("workouts", self.controller.workouts().load_all()),
+            ("workout sets", self.controller.sets().load_all()),
+        ];
+        for (name, result) in results {
+            if let Err(err) = result {
+                let err = format!("Can`t get all {}:\n{}", name, err);
+                self.ui_event_sender.send_add_layer_event(Box::new(UiMessageBox::err("Reports", err)));
+                return;
+            }
+        }
+    }
+
+    fn report(&self) -> Report {
+        let person_id = self.controller.cfg().account().id;
+        reports::generate(&self.controller.db(), person_id, self.kind, self.date)
+    }
+
+    fn export(&mut self, format: ReportFormat) {
+        let units = self.controller.cfg().units;
+        let popup = match self.report().export(Path::new("."), format, units) {
+            Ok(path) => UiMessageBox::info("Reports", format!("Report saved to {}", path.display())),
+            Err(err) => UiMessageBox::err("Reports", format!("Can`t save report:\n{}", err)),
+        };
+        self.ui_event_sender.send_add_layer_event(Box::new(popup));
+    }
+
+    fn table<const N: usize>(&self, title: &str, header: [&'static str; N], rows: Vec<[String; N]>, widths: &'static [Constraint]) -> widgets::Table<'static> {
+        widgets::Table::new(rows.into_iter().map(widgets::Row::new))
+            .style(self.theme.table_style)
+            .header(
+                widgets::Row::new(header)
+                .style(self.theme.header_style)
+                .bottom_margin(1),
+                )
+            .block(
+                widgets::Block::default()
+                .borders(widgets::Borders::ALL)
+                .title(title.to_owned()),
+                )
+            .widths(widths)
+    }
+}
+
+impl Component for PageReports {
+    fn draw(&self, f: &mut RenderFrame, area: Rect) {
+        if !self.is_visible {
+            return;
+        }
+        let units = self.controller.cfg().units;
+        let report = self.report();
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(8), Constraint::Min(5)].as_ref())
+            .split(area);
+        let bottom = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(40), Constraint::Min(20)].as_ref())
+            .split(chunks[1]);
+
+        const SUMMARY_WIDTHS: [Constraint; 4] = [Constraint::Length(10), Constraint::Length(14), Constraint::Length(14), Constraint::Length(8)];
+        const GROUP_WIDTHS: [Constraint; 4] = [Constraint::Length(12), Constraint::Length(6), Constraint::Length(9), Constraint::Length(8)];
+        const RECORD_WIDTHS: [Constraint; 5] = [
+            Constraint::Length(10),
+            Constraint::Length(20),
+            Constraint::Length(16),
+            Constraint::Length(10),
+            Constraint::Length(10),
+        ];
+        let summary = self.table(&report.title(), ["", "This period", "Previous", "Change"], report.summary_rows(units), &SUMMARY_WIDTHS);
+        f.render_widget(summary, chunks[0]);
+        let groups = self.table("Sets per muscle group", ["Group", "Sets", "Previous", "Change"], report.muscle_group_rows(), &GROUP_WIDTHS);
+        f.render_widget(groups, bottom[0]);
+        let records = self.table(
+            "Personal records",
+            ["Date", "Exercise", "Record", "Value", "Previous"],
+            report.record_rows(units),
+            &RECORD_WIDTHS,
+        );
+        f.render_widget(records, bottom[1]);
+    }
+
+    fn is_visible(&self) -> bool { self.is_visible }
+
+    fn set_visible(&mut self, value: bool) { self.is_visible = value; }
+}
+
+impl TermEventDispatcher for PageReports {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::Reports))
+    }
+
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Reload => {
+                self.reload();
+                true
+            }
+            Action::Weekly => {
+                self.kind = ReportKind::Weekly;
+                true
+            }
+            Action::Monthly => {
+                self.kind = ReportKind::Monthly;
+                true
+            }
+            Action::Today => {
+                self.date = Local::now().date_naive();
+                true
+            }
+            Action::Markdown => {
+                self.export(ReportFormat::Markdown);
+                true
+            }
+            Action::Html => {
+                self.export(ReportFormat::Html);
+                true
+            }
+            Action::PreviousPeriod => {
+                if let Some(date) = self.kind.period(self.date).start.pred_opt() {
+                    self.date = date;
+                }
+                true
+            }
+            Action::NextPeriod => {
+                if let Some(date) = self.kind.period(self.date).end.succ_opt() {
+                    self.date = date;
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl KeyEventDispatcher for PageReports {}
+
+impl EventComponent for PageReports {
+    fn focus(&mut self, value: bool) {
+        self.is_focused = value;
+    }
+
+    fn on_focus(&self) -> bool { self.is_focused }
+
+    fn on_term_event(&mut self, event: &core_ui::component::TerminalEvent) -> bool {
+        if self.on_focus() {
+            return self.dispatch_term_event(event);
+        }
+        false
+    }
+}
+
+impl Layer for PageReports { }
+
+impl Page for PageReports {
+    fn new(controller: Rc<controller::Controller>, ui_events_manager: SharedUiEvents) -> Self {
+        Self {
+            controller,
+            ui_event_sender: UiEventSender::new(ui_events_manager),
+            kind: ReportKind::Weekly,
+            date: Local::now().date_naive(),
+            theme: UiTableTheme::default(),
+            is_visible: true,
+            is_focused: false,
+        }
+    }
+
+    fn commands_help(&self) -> String {
+        self.controller.key_map().help(Context::Reports)
+    }
+}
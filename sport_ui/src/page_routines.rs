@@ -0,0 +1,291 @@
+use std::{cell::RefCell, path::Path, rc::Rc};
+
+use chrono::{Local, Utc};
+use core_ui::{
+    components::{Component, EventComponent, UiTable},
+    event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
+    layer::Layer,
+    message_box::UiMessageBox,
+    render::RenderFrame,
+};
+use sport_core::{controller, ics, progression, keys::{Action, Context, KeyMap}};
+use sport_core_db::{entity, model::Table, units::Quantity};
+use tui::{layout::{Constraint, Direction, Layout, Rect}, widgets, style::{Color, Style}};
+
+use crate::{
+    menu::Page,
+    routine_editor::UiRoutineEditor,
+    routine_exercise_editor::UiRoutineExerciseEditor,
+    ui_events::{SharedUiEvents, UiEvent, UiEventSender},
+};
+
+pub struct PageRoutines {
+    controller: Rc<controller::Controller>,
+    ui_event_sender: UiEventSender,
+    routines_table: RefCell<UiTable<entity::ID>>,
+    is_visible: bool,
+    is_focused: bool,
+}
+
+impl PageRoutines {
+    fn selected_routine(&self) -> Option<entity::Routine> {
+        let id = self.routines_table.borrow().get_value().copied()?;
+        self.controller.db().routines().get_one(id).cloned()
+    }
+
+    fn reload(&mut self) {
+        let results = [
+            ("exercises", self.controller.exercises().load_all()),
+            ("routines", self.controller.routines().load_all()),
+            ("workouts", self.controller.workouts().load_all()),
+            ("workout sets", self.controller.sets().load_all()),
+        ];
+        for (name, result) in results {
+            if let Err(err) = result {
+                let err = format!("Can`t get all {}:\n{}", name, err);
+                self.ui_event_sender.send_add_layer_event(Box::new(UiMessageBox::err("Routines", err)));
+                return;
+            }
+        }
+    }
+
+    fn update_routine(&self, routine: entity::Routine) {
+        if let Err(err) = self.controller.routines().update(routine) {
+            let err = format!("Can`t save routine:\n{}", err);
+            self.ui_event_sender.ui_events_manager().add_event(UiEvent::AddLayer(Box::new(UiMessageBox::err("Routines", err))));
+        }
+    }
+
+    fn draw_routines(&self, f: &mut RenderFrame, area: Rect) {
+        let db = self.controller.db();
+        let mut routines: Vec<&entity::Routine> = db.routines()
+            .get_all()
+            .filter(|r| r.person_id == self.controller.cfg().account().id)
+            .collect();
+        routines.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let rows: Vec<widgets::Row> = routines
+            .iter()
+            .map(|r| widgets::Row::new(vec![
+                widgets::Cell::from(r.name.to_owned()),
+                widgets::Cell::from(r.exercises.len().to_string()),
+            ]))
+            .collect();
+        self.routines_table.borrow_mut().set_values(routines.iter().map(|r| r.id).collect());
+
+        let theme = *self.routines_table.borrow().theme();
+        let table = widgets::Table::new(rows)
+            .style(theme.table_style)
+            .header(
+                widgets::Row::new(vec!["Name", "Exercises"])
+                .style(theme.header_style)
+                .bottom_margin(1),
+                )
+            .block(
+                widgets::Block::default()
+                .borders(widgets::Borders::ALL)
+                .title("Routines"),
+                )
+            .widths(&[
+                    Constraint::Length(25),
+                    Constraint::Length(9),
+            ])
+            .highlight_style(theme.highlight_style)
+            .highlight_symbol(">");
+        f.render_stateful_widget(table, area, self.routines_table.borrow_mut().state());
+    }
+
+    fn draw_exercises(&self, f: &mut RenderFrame, area: Rect) {
+        let routine = self.selected_routine();
+        let db = self.controller.db();
+        let (units, one_rep_max) = (self.controller.cfg().units, self.controller.cfg().one_rep_max);
+        let now = Local::now().naive_local();
+        let rows: Vec<widgets::Row> = routine
+            .iter()
+            .flat_map(|r| r.exercises.iter().enumerate().map(move |(i, e)| (r, i, e)))
+            .map(|(routine, i, e)| {
+                let exercise = db.exercises().get_one(e.exercise_id).map(|e| e.name.clone()).unwrap_or_default();
+                let history = progression::exercise_history(&db, routine.person_id, e.exercise_id, now);
+                let next = progression::rule_for(e.scheme, one_rep_max)
+                    .suggest(e, &history)
+                    .map(|s| format!("{}x{} @ {}", s.sets, s.reps, s.weight.format(units)))
+                    .unwrap_or_default();
+                widgets::Row::new(vec![
+                    widgets::Cell::from((i + 1).to_string()).style(Style::default().fg(Color::Green)),
+                    widgets::Cell::from(exercise),
+                    widgets::Cell::from(e.sets.to_string()),
+                    widgets::Cell::from(format!("{}-{}", e.min_reps, e.max_reps)),
+                    widgets::Cell::from(e.increment.format(units)),
+                    widgets::Cell::from(e.scheme.to_string()),
+                    widgets::Cell::from(next),
+                ])
+            })
+            .collect();
+
+        let theme = *self.routines_table.borrow().theme();
+        let table = widgets::Table::new(rows)
+            .style(theme.table_style)
+            .header(
+                widgets::Row::new(vec!["#", "Exercise", "Sets", "Reps", "Increment", "Progression", "Next"])
+                .style(theme.header_style)
+                .bottom_margin(1),
+                )
+            .block(
+                widgets::Block::default()
+                .borders(widgets::Borders::ALL)
+                .title("Exercises"),
+                )
+            .widths(&[
+                    Constraint::Length(3),
+                    Constraint::Length(25),
+                    Constraint::Length(4),
+                    Constraint::Length(6),
+                    Constraint::Length(10),
+                    Constraint::Length(18),
+                    Constraint::Length(18),
+            ]);
+        f.render_widget(table, area);
+    }
+}
+
+impl Component for PageRoutines {
+    fn draw(&self, f: &mut RenderFrame, area: Rect) {
+        if !self.is_visible {
+            return;
+        }
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
+            .split(area);
+        self.draw_routines(f, chunks[0]);
+        self.draw_exercises(f, chunks[1]);
+    }
+
+    fn is_visible(&self) -> bool { self.is_visible }
+
+    fn set_visible(&mut self, value: bool) { self.is_visible = value; }
+}
+
+impl TermEventDispatcher for PageRoutines {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::Routines))
+    }
+
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Reload => {
+                self.reload();
+                true
+            }
+            Action::Add => {
+                let mut popup = Box::new(UiRoutineEditor::new(self.controller.clone()));
+                popup.set_ui_events_manager(self.ui_event_sender.ui_events_manager());
+                self.ui_event_sender.send_add_layer_event(popup);
+                true
+            }
+            Action::Update => {
+                if let Some(data) = self.selected_routine() {
+                    let mut popup = Box::new(UiRoutineEditor::new(self.controller.clone()));
+                    popup.set_ui_events_manager(self.ui_event_sender.ui_events_manager());
+                    popup.load_data(&data);
+                    self.ui_event_sender.send_add_layer_event(popup);
+                    return true;
+                }
+                false
+            }
+            Action::AddExercise => {
+                if let Some(data) = self.selected_routine() {
+                    let mut popup = Box::new(UiRoutineExerciseEditor::new(self.controller.clone(), data));
+                    popup.set_ui_events_manager(self.ui_event_sender.ui_events_manager());
+                    self.ui_event_sender.send_add_layer_event(popup);
+                    return true;
+                }
+                false
+            }
+            Action::Calendar => {
+                let cfg = self.controller.cfg();
+                let sessions = ics::planned_sessions(&self.controller.db(), cfg.account().id, &cfg.calendar, Local::now().naive_local());
+                let popup = match ics::export_to(&sessions, Utc::now(), Path::new(".")) {
+                    Ok(path) => UiMessageBox::info("Routines", format!("{} sessions saved to {}", sessions.len(), path.display())),
+                    Err(err) => UiMessageBox::err("Routines", format!("Can`t save calendar:\n{}", err)),
+                };
+                self.ui_event_sender.send_add_layer_event(Box::new(popup));
+                true
+            }
+            Action::RemoveExercise => {
+                if let Some(mut data) = self.selected_routine() {
+                    if data.exercises.pop().is_some() {
+                        self.update_routine(data);
+                        return true;
+                    }
+                }
+                false
+            }
+            Action::Delete => {
+                if let Some(data) = self.selected_routine() {
+                    let ui_events_manager = self.ui_event_sender.ui_events_manager();
+                    let controller = self.controller.clone();
+                    let msg = format!("Are you sure you want to delete the routine `{}`", data.name);
+                    let mut popup = Box::new(UiMessageBox::warn("Routines", msg));
+                    popup.set_on_accept(Box::new(move || {
+                        if let Err(err) = controller.routines().remove(data.id) {
+                            let err = format!("Can`t remove routine:\n{}", err);
+                            let msgbox = Box::new(UiMessageBox::err("Routines", err));
+                            ui_events_manager.add_event(UiEvent::AddLayer(msgbox));
+                        }
+                    }));
+                    self.ui_event_sender.send_add_layer_event(popup);
+                    return true;
+                }
+                false
+            }
+            _ => false,
+        }
+    }
+}
+
+impl KeyEventDispatcher for PageRoutines {
+    fn on_down(&mut self) -> bool {
+        self.routines_table.borrow_mut().next();
+        true
+    }
+
+    fn on_up(&mut self) -> bool {
+        self.routines_table.borrow_mut().previous();
+        true
+    }
+}
+
+impl EventComponent for PageRoutines {
+    fn focus(&mut self, value: bool) {
+        self.is_focused = value;
+        self.routines_table.borrow_mut().focus(value);
+    }
+
+    fn on_focus(&self) -> bool { self.is_focused }
+
+    fn on_term_event(&mut self, event: &core_ui::component::TerminalEvent) -> bool {
+        if self.on_focus() {
+            return self.dispatch_term_event(event);
+        }
+        false
+    }
+}
+
+impl Layer for PageRoutines { }
+
+impl Page for PageRoutines {
+    fn new(controller: Rc<controller::Controller>, ui_events_manager: SharedUiEvents) -> Self {
+        Self {
+            controller,
+            ui_event_sender: UiEventSender::new(ui_events_manager),
+            routines_table: RefCell::new(UiTable::default()),
+            is_visible: true,
+            is_focused: false,
+        }
+    }
+
+    fn commands_help(&self) -> String {
+        self.controller.key_map().help(Context::Routines)
+    }
+}
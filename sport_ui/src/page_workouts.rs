@@ -0,0 +1,377 @@
+use std::{cell::RefCell, rc::Rc};
+
+use core_ui::{
+    components::{Component, EventComponent, TextEdit, UiTable},
+    event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
+    layer::Layer,
+    message_box::UiMessageBox,
+    render::RenderFrame,
+};
+use crossterm::event::Event;
+use sport_core::{controller, quick_entry, keys::{Action, Context, KeyMap}};
+use sport_core_db::{entity, model::Table, units::Quantity};
+use tui::{layout::{Constraint, Direction, Layout, Rect}, widgets, style::{Color, Style}};
+
+use crate::{
+    menu::Page,
+    set_editor::{on_set_logged, UiSetEditor},
+    timer_editor::UiTimerEditor,
+    track_import::UiTrackImport,
+    ui_events::{SharedUiEvents, UiEvent, UiEventSender},
+    workout_editor::UiWorkoutEditor,
+    workout_import::UiWorkoutImport,
+};
+
+pub struct PageWorkouts {
+    controller: Rc<controller::Controller>,
+    ui_event_sender: UiEventSender,
+    workouts_table: RefCell<UiTable<entity::ID>>,
+    /// Sets in the `bench 3x8@80kg` notation for the selected workout
+    quick_entry: TextEdit,
+    is_visible: bool,
+    is_focused: bool,
+}
+
+impl PageWorkouts {
+    fn selected_workout(&self) -> Option<entity::ID> {
+        self.workouts_table.borrow().get_value().copied()
+    }
+
+    /// Parses the quick entry line and adds its sets to the selected workout
+    fn submit_quick_entry(&mut self) {
+        let Some(workout_id) = self.selected_workout() else {
+            return;
+        };
+        let input = self.quick_entry.text.clone();
+        let (entry, order) = {
+            let db = self.controller.db();
+            let mut sets: Vec<&entity::WorkoutSet> = db.sets().get_all().filter(|s| s.workout_id == workout_id).collect();
+            sets.sort_by_key(|s| s.order);
+            let last_exercise = sets.last().map(|s| s.exercise_id);
+            let entry = quick_entry::parse(&db, &input, self.controller.cfg().units, last_exercise);
+            (entry, sets.last().map(|s| s.order).unwrap_or_default() + 1)
+        };
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                let err = format!("{}\n\n{}", err, err.pointer(&input));
+                self.ui_event_sender.send_add_layer_event(Box::new(UiMessageBox::err("Quick entry", err)));
+                return;
+            }
+        };
+        let sets = entry.into_sets(workout_id, order);
+        let mut records = vec![];
+        let mut inserted = 0;
+        let mut failure = None;
+        for set in &sets {
+            match self.controller.sets().insert(set) {
+                Ok((_, set_records)) => {
+                    records.extend(set_records);
+                    inserted += 1;
+                }
+                Err(err) => {
+                    failure = Some(err);
+                    break;
+                }
+            }
+        }
+        // The logged sets are gone from the input so that submitting it again can't repeat them
+        if inserted > 0 {
+            self.quick_entry.text.clear();
+            on_set_logged(&self.controller, &mut self.ui_event_sender, &records);
+        }
+        if let Some(err) = failure {
+            let err = format!("Can`t insert set {} of {}:\n{}", inserted + 1, sets.len(), err);
+            self.ui_event_sender.send_add_layer_event(Box::new(UiMessageBox::err("Quick entry", err)));
+        }
+    }
+
+    fn reload(&mut self) {
+        let results = [
+            ("exercises", self.controller.exercises().load_all()),
+            ("routines", self.controller.routines().load_all()),
+            ("workouts", self.controller.workouts().load_all()),
+            ("workout sets", self.controller.sets().load_all()),
+        ];
+        for (name, result) in results {
+            if let Err(err) = result {
+                let err = format!("Can`t get all {}:\n{}", name, err);
+                self.ui_event_sender.send_add_layer_event(Box::new(UiMessageBox::err("Workouts", err)));
+                return;
+            }
+        }
+    }
+
+    fn draw_workouts(&self, f: &mut RenderFrame, area: Rect) {
+        let db = self.controller.db();
+        let mut workouts: Vec<&entity::Workout> = db.workouts()
+            .get_all()
+            .filter(|w| w.person_id == self.controller.cfg().account().id)
+            .collect();
+        workouts.sort_by_key(|w| std::cmp::Reverse(w.started_at));
+
+        let units = self.controller.cfg().units;
+        let rows: Vec<widgets::Row> = workouts
+            .iter()
+            .map(|w| widgets::Row::new(vec![
+                widgets::Cell::from(w.started_at.format("%Y-%m-%d %H:%M").to_string()).style(Style::default().fg(Color::Green)),
+                widgets::Cell::from(w.name.to_owned()),
+                widgets::Cell::from(w.duration.format(units)),
+            ]))
+            .collect();
+        self.workouts_table.borrow_mut().set_values(workouts.iter().map(|w| w.id).collect());
+
+        let theme = *self.workouts_table.borrow().theme();
+        let table = widgets::Table::new(rows)
+            .style(theme.table_style)
+            .header(
+                widgets::Row::new(vec!["Date", "Name", "Duration"])
+                .style(theme.header_style)
+                .bottom_margin(1),
+                )
+            .block(
+                widgets::Block::default()
+                .borders(widgets::Borders::ALL)
+                .title("Workouts"),
+                )
+            .widths(&[
+                    Constraint::Length(17),
+                    Constraint::Length(20),
+                    Constraint::Length(9),
+            ])
+            .highlight_style(theme.highlight_style)
+            .highlight_symbol(">");
+        f.render_stateful_widget(table, area, self.workouts_table.borrow_mut().state());
+    }
+
+    fn draw_sets(&self, f: &mut RenderFrame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref())
+            .split(area);
+        self.quick_entry.draw(f, chunks[1]);
+        let area = chunks[0];
+
+        let db = self.controller.db();
+        let units = self.controller.cfg().units;
+        let mut sets: Vec<&entity::WorkoutSet> = match self.selected_workout() {
+            Some(id) => db.sets().get_all().filter(|s| s.workout_id == id).collect(),
+            None => vec![],
+        };
+        sets.sort_by_key(|s| s.order);
+
+        let rows: Vec<widgets::Row> = sets
+            .iter()
+            .map(|s| {
+                let exercise = db.exercises().get_one(s.exercise_id).map(|e| e.name.clone()).unwrap_or_default();
+                let is_record = db.records().get_all().any(|r| r.set_id == s.id && r.is_improvement());
+                let quantity = |is_zero: bool, text: String| if is_zero { "".to_owned() } else { text };
+                widgets::Row::new(vec![
+                    widgets::Cell::from(s.order.to_string()).style(Style::default().fg(Color::Green)),
+                    widgets::Cell::from(exercise),
+                    widgets::Cell::from(quantity(s.reps == 0, s.reps.to_string())),
+                    widgets::Cell::from(quantity(s.weight.is_zero(), s.weight.format(units))),
+                    widgets::Cell::from(quantity(s.distance.is_zero(), s.distance.format(units))),
+                    widgets::Cell::from(quantity(s.duration.is_zero(), s.duration.format(units))),
+                    widgets::Cell::from(s.rpe.map(|rpe| rpe.to_string()).unwrap_or_default()),
+                    widgets::Cell::from(if is_record { "PR" } else { "" }).style(Style::default().fg(Color::Yellow)),
+                ])
+            })
+            .collect();
+
+        let theme = *self.workouts_table.borrow().theme();
+        let table = widgets::Table::new(rows)
+            .style(theme.table_style)
+            .header(
+                widgets::Row::new(vec!["#", "Exercise", "Reps", "Weight", "Distance", "Time", "RPE", ""])
+                .style(theme.header_style)
+                .bottom_margin(1),
+                )
+            .block(
+                widgets::Block::default()
+                .borders(widgets::Borders::ALL)
+                .title("Sets"),
+                )
+            .widths(&[
+                    Constraint::Length(3),
+                    Constraint::Length(25),
+                    Constraint::Length(5),
+                    Constraint::Length(10),
+                    Constraint::Length(10),
+                    Constraint::Length(8),
+                    Constraint::Length(4),
+                    Constraint::Length(3),
+            ]);
+        f.render_widget(table, area);
+    }
+}
+
+impl Component for PageWorkouts {
+    fn draw(&self, f: &mut RenderFrame, area: Rect) {
+        if !self.is_visible {
+            return;
+        }
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
+            .split(area);
+        self.draw_workouts(f, chunks[0]);
+        self.draw_sets(f, chunks[1]);
+    }
+
+    fn is_visible(&self) -> bool { self.is_visible }
+
+    fn set_visible(&mut self, value: bool) { self.is_visible = value; }
+}
+
+impl TermEventDispatcher for PageWorkouts {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::Workouts))
+    }
+
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Reload => {
+                self.reload();
+                true
+            }
+            Action::Add => {
+                let mut popup = Box::new(UiWorkoutEditor::new(self.controller.clone()));
+                popup.set_ui_events_manager(self.ui_event_sender.ui_events_manager());
+                self.ui_event_sender.send_add_layer_event(popup);
+                true
+            }
+            Action::Update => {
+                if let Some(data) = self.selected_workout().and_then(|id| self.controller.db().workouts().get_one(id).cloned()) {
+                    let mut popup = Box::new(UiWorkoutEditor::new(self.controller.clone()));
+                    popup.set_ui_events_manager(self.ui_event_sender.ui_events_manager());
+                    popup.load_data(&data);
+                    self.ui_event_sender.send_add_layer_event(popup);
+                    return true;
+                }
+                false
+            }
+            Action::AddSet => {
+                if let Some(id) = self.selected_workout() {
+                    let mut popup = Box::new(UiSetEditor::new(self.controller.clone(), id));
+                    popup.set_ui_events_manager(self.ui_event_sender.ui_events_manager());
+                    self.ui_event_sender.send_add_layer_event(popup);
+                    return true;
+                }
+                false
+            }
+            Action::QuickEntry => {
+                if self.selected_workout().is_some() {
+                    self.quick_entry.focus(true);
+                    return true;
+                }
+                false
+            }
+            Action::Import => {
+                let mut popup = Box::new(UiWorkoutImport::new(self.controller.clone()));
+                popup.set_ui_events_manager(self.ui_event_sender.ui_events_manager());
+                self.ui_event_sender.send_add_layer_event(popup);
+                true
+            }
+            Action::ImportTrack => {
+                let mut popup = Box::new(UiTrackImport::new(self.controller.clone()));
+                popup.set_ui_events_manager(self.ui_event_sender.ui_events_manager());
+                self.ui_event_sender.send_add_layer_event(popup);
+                true
+            }
+            Action::Timer => {
+                let mut popup = Box::new(UiTimerEditor::new(self.controller.clone()));
+                popup.set_ui_events_manager(self.ui_event_sender.ui_events_manager());
+                self.ui_event_sender.send_add_layer_event(popup);
+                true
+            }
+            Action::Delete => {
+                if let Some(data) = self.selected_workout().and_then(|id| self.controller.db().workouts().get_one(id).cloned()) {
+                    let ui_events_manager = self.ui_event_sender.ui_events_manager();
+                    let controller = self.controller.clone();
+                    let msg = format!("Are you sure you want to delete the workout `{}` at {}", data.name, data.started_at);
+                    let mut popup = Box::new(UiMessageBox::warn("Workouts", msg));
+                    popup.set_on_accept(Box::new(move || {
+                        if let Err(err) = controller.workouts().remove(data.id) {
+                            let err = format!("Can`t remove workout:\n{}", err);
+                            let msgbox = Box::new(UiMessageBox::err("Workouts", err));
+                            ui_events_manager.add_event(UiEvent::AddLayer(msgbox));
+                        }
+                    }));
+                    self.ui_event_sender.send_add_layer_event(popup);
+                    return true;
+                }
+                false
+            }
+            _ => false,
+        }
+    }
+}
+
+impl KeyEventDispatcher for PageWorkouts {
+    fn on_down(&mut self) -> bool {
+        self.workouts_table.borrow_mut().next();
+        true
+    }
+
+    fn on_up(&mut self) -> bool {
+        self.workouts_table.borrow_mut().previous();
+        true
+    }
+}
+
+impl EventComponent for PageWorkouts {
+    fn focus(&mut self, value: bool) {
+        self.is_focused = value;
+        self.workouts_table.borrow_mut().focus(value);
+        if !value {
+            self.quick_entry.focus(false);
+        }
+    }
+
+    fn on_focus(&self) -> bool { self.is_focused }
+
+    fn on_term_event(&mut self, event: &core_ui::component::TerminalEvent) -> bool {
+        if self.on_focus() {
+            if self.quick_entry.on_focus() {
+                if let Event::Key(key) = event {
+                    if self.controller.key_map().is(Action::Accept, key) {
+                        self.submit_quick_entry();
+                        return true;
+                    }
+                }
+                self.quick_entry.on_term_event(event);
+                return true;
+            }
+            return self.dispatch_term_event(event);
+        }
+        false
+    }
+}
+
+impl Layer for PageWorkouts { }
+
+impl Page for PageWorkouts {
+    fn new(controller: Rc<controller::Controller>, ui_events_manager: SharedUiEvents) -> Self {
+        let title = format!(
+            "Quick entry [{}]: bench 3x8@80kg, squat 120x5,5,4 rpe8, run 5km 25:30",
+            controller.key_map().binding(Action::QuickEntry),
+        );
+        Self {
+            ui_event_sender: UiEventSender::new(ui_events_manager),
+            workouts_table: RefCell::new(UiTable::default()),
+            quick_entry: TextEdit::new(&title, String::new(), Default::default()),
+            controller,
+            is_visible: true,
+            is_focused: false,
+        }
+    }
+
+    fn commands_help(&self) -> String {
+        self.controller.key_map().help(Context::Workouts)
+    }
+
+    fn is_text_input(&self) -> bool {
+        self.quick_entry.on_focus()
+    }
+}
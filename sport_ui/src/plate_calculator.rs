@@ -0,0 +1,146 @@
+use std::rc::Rc;
+
+use core_ui::{
+    render::RenderFrame,
+    components::{TextEdit, Component, EventComponent},
+    utils,
+    event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
+    layer::Layer,
+    validators::QuantityValidator
+};
+use crossterm::event::Event;
+use sport_core::{controller, plates, keys::{Action, Context, KeyMap}};
+use sport_core_db::units::{Mass, Quantity};
+use tui::{layout::{Rect, Constraint, Direction, Layout}, style::{Color, Style}, widgets};
+
+/// Plates per side and warm-up ramp for a weight
+pub struct UiPlateCalculator {
+    controller: Rc<controller::Controller>,
+    weight_editor: TextEdit<QuantityValidator<Mass>>,
+    is_focused: bool,
+    is_visible: bool,
+    is_removing_needed: bool,
+}
+
+impl UiPlateCalculator {
+    pub fn new(controller: Rc<controller::Controller>, weight: &str) -> Self {
+        let units = controller.cfg().units;
+        Self {
+            weight_editor: TextEdit::new(&format!("Weight ({}):", Mass::unit_symbol(units)), weight.to_owned(), QuantityValidator::new(units)),
+            controller,
+            is_focused: true,
+            is_visible: true,
+            is_removing_needed: false,
+        }
+    }
+
+    fn close(&mut self) {
+        self.set_visible(false);
+        self.is_removing_needed = true;
+    }
+
+    fn rows(&self) -> Vec<widgets::Row<'static>> {
+        let cfg = self.controller.cfg();
+        let weight = match Mass::parse(&self.weight_editor.text, cfg.units) {
+            Ok(weight) if !weight.is_zero() => weight,
+            _ => return vec![],
+        };
+        let load = cfg.plates.load(weight);
+        let total = if load.is_exact() {
+            load.total.format(cfg.units)
+        } else {
+            format!("{} (nearest, {:+.2})", load.total.format(cfg.units), load.difference().value_in(cfg.units))
+        };
+        let unit = Mass::unit_symbol(cfg.units);
+        let mut rows = vec![
+            widgets::Row::new(vec!["Bar".to_owned(), cfg.plates.bar.format(cfg.units)]),
+            widgets::Row::new(vec!["Loaded".to_owned(), total])
+                .style(Style::default().fg(if load.is_exact() { Color::Green } else { Color::Yellow })),
+            widgets::Row::new(vec![format!("Per side ({})", unit), load.describe(cfg.units)]),
+            widgets::Row::new(vec![""]),
+            widgets::Row::new(vec!["Warm-up".to_owned(), format!("Plates per side ({})", unit)]).style(Style::default().fg(Color::Red)),
+        ];
+        rows.extend(plates::warm_up(&cfg.plates, load.total)
+            .into_iter()
+            .map(|s| widgets::Row::new(vec![
+                    format!("{} x {}", s.load.total.format(cfg.units), s.reps),
+                    s.load.describe(cfg.units),
+            ])));
+        rows
+    }
+}
+
+impl Component for UiPlateCalculator {
+    fn draw(&self, f: &mut RenderFrame, area: Rect) {
+        if !self.is_visible() {
+            return;
+        }
+        let area = utils::centered_rect(50, 60, area);
+        let key_map = self.controller.key_map();
+        let block = widgets::Block::default()
+            .title(format!("Plates;  -> Commands({})", key_map.commands(&[("Exit", Action::Reject), ("Done", Action::Accept)])))
+            .borders(widgets::Borders::ALL);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(block, area);
+
+        let l = Layout::default()
+            .direction(Direction::Horizontal)
+            .margin(1)
+            .constraints([Constraint::Length(1), Constraint::Min(0), Constraint::Length(1)].as_ref())
+            .split(area);
+        let l = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .split(l[1]);
+        self.weight_editor.draw(f, l[0]);
+        let table = widgets::Table::new(self.rows())
+            .widths(&[Constraint::Length(22), Constraint::Min(20)]);
+        f.render_widget(table, l[1]);
+    }
+
+    fn is_visible(&self) -> bool { self.is_visible }
+    fn set_visible(&mut self, value: bool) { self.is_visible = value; }
+}
+
+impl KeyEventDispatcher for UiPlateCalculator {}
+
+impl TermEventDispatcher for UiPlateCalculator {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::Dialog))
+    }
+
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Accept | Action::Reject => {
+                self.close();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl EventComponent for UiPlateCalculator {
+    fn focus(&mut self, value: bool) {
+        self.is_focused = value;
+        self.weight_editor.focus(value);
+    }
+
+    fn on_focus(&self) -> bool { self.is_focused }
+
+    fn on_term_event(&mut self, event: &Event) -> bool {
+        if self.is_focused {
+            if self.weight_editor.on_term_event(event) {
+                return true;
+            }
+            return self.dispatch_term_event(event);
+        }
+        false
+    }
+}
+
+impl Layer for UiPlateCalculator {
+    fn is_modal(&self) -> bool { true }
+
+    fn is_remove_requested(&self) -> bool { self.is_removing_needed }
+}
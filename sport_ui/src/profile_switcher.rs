@@ -0,0 +1,147 @@
+use std::rc::Rc;
+
+use core_ui::{
+    render::RenderFrame,
+    components::{Component, EventComponent},
+    utils,
+    event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
+    layer::Layer,
+};
+use crossterm::event::Event;
+use sport_core::{controller, keys::{Action, Context, KeyMap}};
+use tui::{layout::Rect, widgets, style::{Style, Modifier}};
+
+use crate::ui_events::{UiEventSender, SharedUiEvents};
+
+/// Picks another profile of the config, the UI starts over with its server
+pub struct UiProfileSwitcher {
+    controller: Rc<controller::Controller>,
+    names: Vec<String>,
+    active: String,
+    selected: usize,
+    ui_event_sender: Option<UiEventSender>,
+    is_focused: bool,
+    is_visible: bool,
+    is_removing_needed: bool,
+}
+
+impl UiProfileSwitcher {
+    pub fn new(controller: Rc<controller::Controller>) -> Self {
+        let cfg = controller.cfg();
+        let names: Vec<String> = cfg.profile_names().into_iter().map(str::to_owned).collect();
+        let active = cfg.active_profile().to_owned();
+        let selected = names.iter().position(|name| *name == active).unwrap_or_default();
+        Self {
+            controller,
+            names,
+            active,
+            selected,
+            ui_event_sender: None,
+            is_focused: true,
+            is_visible: true,
+            is_removing_needed: false,
+        }
+    }
+
+    pub fn set_ui_events_manager(&mut self, ui_events_manager: SharedUiEvents) {
+        self.ui_event_sender = Some(UiEventSender::new(ui_events_manager));
+    }
+
+    fn close(&mut self) {
+        self.set_visible(false);
+        self.is_removing_needed = true;
+    }
+}
+
+impl Component for UiProfileSwitcher {
+    fn draw(&self, f: &mut RenderFrame, area: Rect) {
+        if !self.is_visible() {
+            return;
+        }
+        let area = utils::centered_rect(40, 40, area);
+        let key_map = self.controller.key_map();
+        let items: Vec<widgets::ListItem> = self
+            .names
+            .iter()
+            .map(|name| {
+                let mark = if *name == self.active { " (active)" } else { "" };
+                widgets::ListItem::new(format!("{}{}", name, mark))
+            })
+            .collect();
+        let list = widgets::List::new(items)
+            .block(
+                widgets::Block::default()
+                .title(format!("Profile;  -> Commands({})", key_map.commands(&[("Exit", Action::Reject), ("Switch", Action::Accept)])))
+                .borders(widgets::Borders::ALL),
+                )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut state = widgets::ListState::default();
+        state.select(Some(self.selected));
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_stateful_widget(list, area, &mut state);
+    }
+
+    fn is_visible(&self) -> bool { self.is_visible }
+    fn set_visible(&mut self, value: bool) { self.is_visible = value; }
+}
+
+impl KeyEventDispatcher for UiProfileSwitcher {
+    fn on_up(&mut self) -> bool {
+        self.selected = self.selected.saturating_sub(1);
+        true
+    }
+
+    fn on_down(&mut self) -> bool {
+        if self.selected + 1 < self.names.len() {
+            self.selected += 1;
+        }
+        true
+    }
+}
+
+impl TermEventDispatcher for UiProfileSwitcher {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::Dialog))
+    }
+
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Accept => {
+                let name = self.names[self.selected].clone();
+                if name != self.active {
+                    if let Some(sender) = &mut self.ui_event_sender {
+                        sender.send_switch_profile_event(name);
+                    }
+                }
+                self.close();
+                true
+            }
+            Action::Reject => {
+                self.close();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl EventComponent for UiProfileSwitcher {
+    fn focus(&mut self, value: bool) {
+        self.is_focused = value;
+    }
+
+    fn on_focus(&self) -> bool { self.is_focused }
+
+    fn on_term_event(&mut self, event: &Event) -> bool {
+        if !self.is_focused {
+            return false;
+        }
+        self.dispatch_term_event(event)
+    }
+}
+
+impl Layer for UiProfileSwitcher {
+    fn is_modal(&self) -> bool { true }
+
+    fn is_remove_requested(&self) -> bool { self.is_removing_needed }
+}
@@ -0,0 +1,165 @@
+use std::rc::Rc;
+
+use core_ui::{
+    render::RenderFrame,
+    components::{TextEdit, Component, EventComponent},
+    utils,
+    event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
+    message_box::UiMessageBox, layer::Layer,
+    validators::StrValidator
+};
+use crossterm::event::Event;
+use sport_core::{controller, keys::{Action, Context, KeyMap}};
+use sport_core_db::entity;
+use tui::{layout::{Rect, Constraint, Direction, Layout}, widgets};
+
+use crate::ui_events::{UiEventSender, SharedUiEvents};
+
+pub struct UiRoutineEditor {
+    controller: Rc<controller::Controller>,
+    data: entity::Routine,
+    name_editor: TextEdit<StrValidator>,
+    to_insert: bool,
+    ui_event_sender: Option<UiEventSender>,
+    is_focused: bool,
+    is_visible: bool,
+    is_removing_needed: bool,
+}
+
+impl UiRoutineEditor {
+    pub fn new(controller: Rc<controller::Controller>) -> Self {
+        Self {
+            data: entity::Routine {
+                person_id: controller.cfg().account().id,
+                ..entity::Routine::default()
+            },
+            controller,
+            name_editor: TextEdit::new("Name:", "".to_owned(), StrValidator::new(1, 100)),
+            to_insert: true,
+            ui_event_sender: None,
+            is_focused: true,
+            is_visible: true,
+            is_removing_needed: false,
+        }
+    }
+
+    pub fn data(&self) -> entity::Routine {
+        entity::Routine {
+            name: self.name_editor.text.trim().to_owned(),
+            ..self.data.clone()
+        }
+    }
+
+    pub fn load_data(&mut self, data: &entity::Routine) {
+        self.to_insert = false;
+        self.data = data.clone();
+        self.name_editor.text = data.name.to_owned();
+    }
+
+    pub fn set_ui_events_manager(&mut self, ui_events_manager: SharedUiEvents) {
+        self.ui_event_sender = Some(UiEventSender::new(ui_events_manager));
+    }
+
+    fn close(&mut self) {
+        self.set_visible(false);
+        self.is_removing_needed = true;
+    }
+}
+
+impl Component for UiRoutineEditor {
+    fn draw(&self, f: &mut RenderFrame, area: Rect) {
+        if !self.is_visible() {
+            return;
+        }
+        let area = utils::centered_rect(50, 30, area);
+        let key_map = self.controller.key_map();
+        let block = widgets::Block::default()
+            .title(format!("Routine;  -> Commands({})", key_map.commands(&[("Exit", Action::Close), ("Reject", Action::Reject), ("Accept", Action::Accept)])))
+            .borders(widgets::Borders::ALL);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(block, area);
+
+        let l = Layout::default()
+            .direction(Direction::Horizontal)
+            .margin(1)
+            .constraints([Constraint::Length(1), Constraint::Min(0), Constraint::Length(1)].as_ref())
+            .split(area);
+        let l = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3)].as_ref())
+            .split(l[1]);
+        self.name_editor.draw(f, l[0]);
+    }
+
+    fn is_visible(&self) -> bool { self.is_visible }
+    fn set_visible(&mut self, value: bool) { self.is_visible = value; }
+}
+
+impl KeyEventDispatcher for UiRoutineEditor {
+
+    fn on_char(&mut self, _c: &char) -> bool {
+        true
+    }
+}
+
+impl TermEventDispatcher for UiRoutineEditor {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::Dialog))
+    }
+
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Accept => {
+                if !self.name_editor.is_valid() {
+                    return false;
+                }
+                let result = if self.to_insert {
+                    self.controller.routines().insert(&self.data()).map(|_| ())
+                } else {
+                    self.controller.routines().update(self.data())
+                };
+                if let Err(err) = result {
+                    if let Some(sender) = &mut self.ui_event_sender {
+                        let err = format!("Can`t save routine:\n{}", err);
+                        sender.send_add_layer_event(Box::new(UiMessageBox::err("Routines", err)));
+                        return false;
+                    }
+                } else {
+                    self.close();
+                }
+
+                true
+            }
+            Action::Reject | Action::Close => {
+                self.close();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl EventComponent for UiRoutineEditor {
+    fn focus(&mut self, value: bool) {
+        self.is_focused = value;
+        self.name_editor.focus(value);
+    }
+
+    fn on_focus(&self) -> bool { self.is_focused }
+
+    fn on_term_event(&mut self, event: &Event) -> bool {
+        if self.is_focused {
+            if self.name_editor.on_term_event(event) {
+                return true;
+            }
+            return self.dispatch_term_event(event);
+        }
+        false
+    }
+}
+
+impl Layer for UiRoutineEditor {
+    fn is_modal(&self) -> bool { true }
+
+    fn is_remove_requested(&self) -> bool { self.is_removing_needed }
+}
@@ -0,0 +1,252 @@
+use std::rc::Rc;
+
+use core_ui::{
+    render::RenderFrame,
+    components::{TextEdit, Component, EventComponent},
+    utils,
+    event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
+    message_box::UiMessageBox, layer::Layer,
+    validators::{Validator, NumberValidator, QuantityValidator}
+};
+use crossterm::event::Event;
+use sport_core::{controller, keys::{Action, Context, KeyMap}};
+use sport_core_db::{entity::{self, ProgressionScheme}, units::{Mass, Quantity}};
+use thiserror::Error;
+use tui::{layout::{Rect, Constraint, Direction, Layout}, widgets};
+
+use crate::{set_editor::ExerciseNameValidator, ui_events::{UiEventSender, SharedUiEvents}};
+
+const FIELDS_COUNT: usize = 6;
+
+#[derive(Error, Debug)]
+#[error("{0}")]
+pub struct ProgressionSchemeValidatorError(String);
+
+/// Accepts the progression scheme names
+#[derive(Default)]
+pub struct ProgressionSchemeValidator;
+
+impl Validator<str> for ProgressionSchemeValidator {
+    type Error = ProgressionSchemeValidatorError;
+
+    fn validate<'a>(&'a self, value: &'a str) -> Result<(), Self::Error> {
+        value.parse::<ProgressionScheme>().map(|_| ()).map_err(ProgressionSchemeValidatorError)
+    }
+}
+
+/// Appends a planned exercise to a routine
+pub struct UiRoutineExerciseEditor {
+    controller: Rc<controller::Controller>,
+    routine: entity::Routine,
+    exercise_editor: TextEdit<ExerciseNameValidator>,
+    sets_editor: TextEdit<NumberValidator>,
+    min_reps_editor: TextEdit<NumberValidator>,
+    max_reps_editor: TextEdit<NumberValidator>,
+    increment_editor: TextEdit<QuantityValidator<Mass>>,
+    scheme_editor: TextEdit<ProgressionSchemeValidator>,
+    focused_field: usize,
+    ui_event_sender: Option<UiEventSender>,
+    is_focused: bool,
+    is_visible: bool,
+    is_removing_needed: bool,
+}
+
+impl UiRoutineExerciseEditor {
+    pub fn new(controller: Rc<controller::Controller>, routine: entity::Routine) -> Self {
+        let units = controller.cfg().units;
+        let schemes = ProgressionScheme::ALL.map(|s| s.as_str()).join(", ");
+        Self {
+            routine,
+            exercise_editor: TextEdit::new("Exercise:", "".to_owned(), ExerciseNameValidator::new(&controller)),
+            sets_editor: TextEdit::new("Sets:", "3".to_owned(), NumberValidator::integer(1.0, 100.0)),
+            min_reps_editor: TextEdit::new("Min reps:", "8".to_owned(), NumberValidator::integer(1.0, 1000.0)),
+            max_reps_editor: TextEdit::new("Max reps:", "12".to_owned(), NumberValidator::integer(1.0, 1000.0)),
+            increment_editor: TextEdit::new(&format!("Increment ({}):", Mass::unit_symbol(units)), "".to_owned(), QuantityValidator::optional(units)),
+            scheme_editor: TextEdit::new(&format!("Progression ({}):", schemes), ProgressionScheme::default().to_string(), ProgressionSchemeValidator),
+            controller,
+            focused_field: 0,
+            ui_event_sender: None,
+            is_focused: true,
+            is_visible: true,
+            is_removing_needed: false,
+        }
+    }
+
+    pub fn data(&self) -> entity::RoutineExercise {
+        let units = self.controller.cfg().units;
+        entity::RoutineExercise {
+            exercise_id: self.exercise_editor.validator().find(&self.exercise_editor.text).unwrap_or_default(),
+            sets: self.sets_editor.text.trim().parse().unwrap_or_default(),
+            min_reps: self.min_reps_editor.text.trim().parse().unwrap_or_default(),
+            max_reps: self.max_reps_editor.text.trim().parse().unwrap_or_default(),
+            increment: Mass::parse(&self.increment_editor.text, units).unwrap_or_default(),
+            scheme: self.scheme_editor.text.parse().unwrap_or_default(),
+        }
+    }
+
+    pub fn set_ui_events_manager(&mut self, ui_events_manager: SharedUiEvents) {
+        self.ui_event_sender = Some(UiEventSender::new(ui_events_manager));
+    }
+
+    fn close(&mut self) {
+        self.set_visible(false);
+        self.is_removing_needed = true;
+    }
+
+    fn is_valid(&self) -> bool {
+        self.exercise_editor.is_valid()
+            && self.sets_editor.is_valid()
+            && self.min_reps_editor.is_valid()
+            && self.max_reps_editor.is_valid()
+            && self.increment_editor.is_valid()
+            && self.scheme_editor.is_valid()
+    }
+
+    fn fields(&self) -> [&dyn Component; FIELDS_COUNT] {
+        [
+            &self.exercise_editor,
+            &self.sets_editor,
+            &self.min_reps_editor,
+            &self.max_reps_editor,
+            &self.increment_editor,
+            &self.scheme_editor,
+        ]
+    }
+
+    fn fields_mut(&mut self) -> [&mut dyn EventComponent; FIELDS_COUNT] {
+        [
+            &mut self.exercise_editor,
+            &mut self.sets_editor,
+            &mut self.min_reps_editor,
+            &mut self.max_reps_editor,
+            &mut self.increment_editor,
+            &mut self.scheme_editor,
+        ]
+    }
+
+    fn focus_field(&mut self, index: usize) {
+        self.focused_field = index % FIELDS_COUNT;
+        let focused_field = self.focused_field;
+        self.fields_mut()
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, f)| f.focus(i == focused_field));
+    }
+
+    fn show_error(&mut self, err: String) {
+        if let Some(sender) = &mut self.ui_event_sender {
+            sender.send_add_layer_event(Box::new(UiMessageBox::err("Routines", err)));
+        }
+    }
+}
+
+impl Component for UiRoutineExerciseEditor {
+    fn draw(&self, f: &mut RenderFrame, area: Rect) {
+        if !self.is_visible() {
+            return;
+        }
+        let area = utils::centered_rect(50, 80, area);
+        let key_map = self.controller.key_map();
+        let title = format!("{};  -> Commands({})", self.routine.name, key_map.commands(&[("Exit", Action::Close), ("Reject", Action::Reject), ("Accept", Action::Accept)]));
+        let block = widgets::Block::default()
+            .title(title)
+            .borders(widgets::Borders::ALL);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(block, area);
+
+        let l = Layout::default()
+            .direction(Direction::Horizontal)
+            .margin(1)
+            .constraints([Constraint::Length(1), Constraint::Min(0), Constraint::Length(1)].as_ref())
+            .split(area);
+        let l = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3); FIELDS_COUNT].as_ref())
+            .split(l[1]);
+        self.fields()
+            .iter()
+            .zip(l)
+            .for_each(|(field, area)| field.draw(f, area));
+    }
+
+    fn is_visible(&self) -> bool { self.is_visible }
+    fn set_visible(&mut self, value: bool) { self.is_visible = value; }
+}
+
+impl KeyEventDispatcher for UiRoutineExerciseEditor {
+    fn on_tab(&mut self) -> bool {
+        self.focus_field(self.focused_field + 1);
+        true
+    }
+
+    fn on_back_tab(&mut self) -> bool {
+        self.focus_field(self.focused_field + FIELDS_COUNT - 1);
+        true
+    }
+
+    fn on_char(&mut self, _c: &char) -> bool {
+        true
+    }
+}
+
+impl TermEventDispatcher for UiRoutineExerciseEditor {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::Dialog))
+    }
+
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Accept => {
+                if !self.is_valid() {
+                    return false;
+                }
+                let data = self.data();
+                if data.min_reps > data.max_reps {
+                    self.show_error("Min reps can`t be greater than max reps".to_owned());
+                    return false;
+                }
+                let mut routine = self.routine.clone();
+                routine.exercises.push(data);
+                if let Err(err) = self.controller.routines().update(routine) {
+                    self.show_error(format!("Can`t save routine:\n{}", err));
+                    return false;
+                }
+                self.close();
+                true
+            }
+            Action::Reject | Action::Close => {
+                self.close();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl EventComponent for UiRoutineExerciseEditor {
+    fn focus(&mut self, value: bool) {
+        self.is_focused = value;
+        if value {
+            self.focus_field(0);
+        }
+    }
+
+    fn on_focus(&self) -> bool { self.is_focused }
+
+    fn on_term_event(&mut self, event: &Event) -> bool {
+        if self.is_focused {
+            let focused_field = self.focused_field;
+            if self.fields_mut()[focused_field].on_term_event(event) {
+                return true;
+            }
+            return self.dispatch_term_event(event);
+        }
+        false
+    }
+}
+
+impl Layer for UiRoutineExerciseEditor {
+    fn is_modal(&self) -> bool { true }
+
+    fn is_remove_requested(&self) -> bool { self.is_removing_needed }
+}
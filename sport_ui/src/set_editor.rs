@@ -0,0 +1,374 @@
+use std::rc::Rc;
+
+use core_ui::{
+    render::RenderFrame,
+    components::{TextEdit, Component, EventComponent},
+    utils,
+    event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
+    message_box::UiMessageBox, layer::Layer,
+    validators::{Validator, NumberValidator, QuantityValidator}
+};
+use crossterm::event::Event;
+use sport_core::{controller, progression::{self, Suggestion}, keys::{Action, Context, KeyMap}};
+use sport_core_db::{entity, model::Table, units::{Distance, Duration, Mass, Quantity}};
+use thiserror::Error;
+use tui::{layout::{Rect, Constraint, Direction, Layout}, style::{Color, Style}, widgets};
+
+use crate::{plate_calculator::UiPlateCalculator, timer_overlay::UiTimerOverlay, ui_events::{UiEventSender, SharedUiEvents}};
+
+const FIELDS_COUNT: usize = 6;
+
+#[derive(Error, Debug)]
+pub enum ExerciseNameValidatorError {
+    #[error("Unknown exercise")]
+    Unknown,
+}
+
+/// Accepts names of the known exercises, case insensitive
+#[derive(Default)]
+pub struct ExerciseNameValidator {
+    names: Vec<(String, entity::ID)>,
+}
+
+impl ExerciseNameValidator {
+    pub fn new(controller: &controller::Controller) -> Self {
+        let names = controller.db()
+            .exercises()
+            .get_all()
+            .map(|e| (e.name.to_lowercase(), e.id))
+            .collect();
+        Self { names }
+    }
+
+    pub fn find(&self, name: &str) -> Option<entity::ID> {
+        let name = name.trim().to_lowercase();
+        self.names.iter().find(|(n, _)| *n == name).map(|(_, id)| *id)
+    }
+}
+
+impl Validator<str> for ExerciseNameValidator {
+    type Error = ExerciseNameValidatorError;
+
+    fn validate<'a>(&'a self, value: &'a str) -> Result<(), Self::Error> {
+        self.find(value).map(|_| ()).ok_or(ExerciseNameValidatorError::Unknown)
+    }
+}
+
+pub struct UiSetEditor {
+    controller: Rc<controller::Controller>,
+    data: entity::WorkoutSet,
+    /// Workout and its routine, the source of the suggestions
+    workout: Option<entity::Workout>,
+    routine: Option<entity::Routine>,
+    exercise_editor: TextEdit<ExerciseNameValidator>,
+    reps_editor: TextEdit<NumberValidator>,
+    weight_editor: TextEdit<QuantityValidator<Mass>>,
+    distance_editor: TextEdit<QuantityValidator<Distance>>,
+    duration_editor: TextEdit<QuantityValidator<Duration>>,
+    rpe_editor: TextEdit<NumberValidator>,
+    focused_field: usize,
+    ui_event_sender: Option<UiEventSender>,
+    is_focused: bool,
+    is_visible: bool,
+    is_removing_needed: bool,
+}
+
+impl UiSetEditor {
+    pub fn new(controller: Rc<controller::Controller>, workout_id: entity::ID) -> Self {
+        let units = controller.cfg().units;
+        let order = controller.db().sets().get_all().filter(|s| s.workout_id == workout_id).count() as u32 + 1;
+        let workout = controller.db().workouts().get_one(workout_id).cloned();
+        let routine = workout
+            .as_ref()
+            .and_then(|w| w.routine_id)
+            .and_then(|id| controller.db().routines().get_one(id).cloned());
+        let mut s = Self {
+            data: entity::WorkoutSet { workout_id, order, ..entity::WorkoutSet::default() },
+            workout,
+            routine,
+            exercise_editor: TextEdit::new("Exercise:", "".to_owned(), ExerciseNameValidator::new(&controller)),
+            reps_editor: TextEdit::new("Reps:", "".to_owned(), NumberValidator::integer(0.0, 10000.0).optional()),
+            weight_editor: TextEdit::new(&format!("Weight ({}):", Mass::unit_symbol(units)), "".to_owned(), QuantityValidator::optional(units)),
+            distance_editor: TextEdit::new(&format!("Distance ({}):", Distance::unit_symbol(units)), "".to_owned(), QuantityValidator::optional(units)),
+            duration_editor: TextEdit::new("Duration (m:ss):", "".to_owned(), QuantityValidator::optional(units)),
+            rpe_editor: TextEdit::new("RPE:", "".to_owned(), NumberValidator::new(1.0, 10.0).optional()),
+            controller,
+            focused_field: 0,
+            ui_event_sender: None,
+            is_focused: true,
+            is_visible: true,
+            is_removing_needed: false,
+        };
+        s.prefill();
+        s
+    }
+
+    /// Routine exercise with sets left in the workout, the first one of the routine otherwise
+    fn next_routine_exercise(&self) -> Option<&entity::RoutineExercise> {
+        let routine = self.routine.as_ref()?;
+        let db = self.controller.db();
+        let done = |exercise_id| db.sets()
+            .get_all()
+            .filter(|s| s.workout_id == self.data.workout_id && s.exercise_id == exercise_id)
+            .count() as u32;
+        routine.exercises
+            .iter()
+            .find(|e| done(e.exercise_id) < e.sets)
+            .or_else(|| routine.exercises.first())
+    }
+
+    /// Fills the fields with the suggestion for the next routine exercise
+    fn prefill(&mut self) {
+        let units = self.controller.cfg().units;
+        let Some(target) = self.next_routine_exercise().cloned() else {
+            return;
+        };
+        self.exercise_editor.text = self.controller.db()
+            .exercises()
+            .get_one(target.exercise_id)
+            .map(|e| e.name.clone())
+            .unwrap_or_default();
+        if let Some(suggestion) = self.suggestion() {
+            self.reps_editor.text = suggestion.reps.to_string();
+            if !suggestion.weight.is_zero() {
+                self.weight_editor.text = suggestion.weight.format(units);
+            }
+        } else {
+            self.reps_editor.text = target.max_reps.to_string();
+        }
+    }
+
+    /// Progression targets of the entered exercise if the workout follows a routine with it
+    fn suggestion(&self) -> Option<Suggestion> {
+        let workout = self.workout.as_ref()?;
+        let exercise_id = self.exercise_editor.validator().find(&self.exercise_editor.text)?;
+        let target = self.routine.as_ref()?.exercises.iter().find(|e| e.exercise_id == exercise_id)?;
+        progression::suggest(&self.controller.db(), workout, target, self.controller.cfg().one_rep_max)
+    }
+
+    pub fn data(&self) -> entity::WorkoutSet {
+        let units = self.controller.cfg().units;
+        entity::WorkoutSet {
+            exercise_id: self.exercise_editor.validator().find(&self.exercise_editor.text).unwrap_or_default(),
+            reps: self.reps_editor.text.trim().parse().unwrap_or_default(),
+            weight: Mass::parse(&self.weight_editor.text, units).unwrap_or_default(),
+            distance: Distance::parse(&self.distance_editor.text, units).unwrap_or_default(),
+            duration: Duration::parse(&self.duration_editor.text, units).unwrap_or_default(),
+            rpe: self.rpe_editor.text.trim().replace(',', ".").parse().ok(),
+            ..self.data.clone()
+        }
+    }
+
+    pub fn set_ui_events_manager(&mut self, ui_events_manager: SharedUiEvents) {
+        self.ui_event_sender = Some(UiEventSender::new(ui_events_manager));
+    }
+
+    fn close(&mut self) {
+        self.set_visible(false);
+        self.is_removing_needed = true;
+    }
+
+    fn is_valid(&self) -> bool {
+        self.exercise_editor.is_valid()
+            && self.reps_editor.is_valid()
+            && self.weight_editor.is_valid()
+            && self.distance_editor.is_valid()
+            && self.duration_editor.is_valid()
+            && self.rpe_editor.is_valid()
+    }
+
+    fn fields(&self) -> [&dyn Component; FIELDS_COUNT] {
+        [
+            &self.exercise_editor,
+            &self.reps_editor,
+            &self.weight_editor,
+            &self.distance_editor,
+            &self.duration_editor,
+            &self.rpe_editor,
+        ]
+    }
+
+    fn fields_mut(&mut self) -> [&mut dyn EventComponent; FIELDS_COUNT] {
+        [
+            &mut self.exercise_editor,
+            &mut self.reps_editor,
+            &mut self.weight_editor,
+            &mut self.distance_editor,
+            &mut self.duration_editor,
+            &mut self.rpe_editor,
+        ]
+    }
+
+    fn focus_field(&mut self, index: usize) {
+        self.focused_field = index % FIELDS_COUNT;
+        let focused_field = self.focused_field;
+        self.fields_mut()
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, f)| f.focus(i == focused_field));
+    }
+}
+
+/// Announces the records of a logged set and starts the rest countdown before the next one
+pub(crate) fn on_set_logged(controller: &controller::Controller, sender: &mut UiEventSender, records: &[entity::PersonalRecord]) {
+    announce_records(controller, sender, records);
+    let timer = controller.cfg().timer;
+    if timer.auto_rest {
+        sender.send_add_layer_event(Box::new(UiTimerOverlay::new(timer.rest(), timer.bell)));
+    }
+}
+
+fn announce_records(controller: &controller::Controller, sender: &mut UiEventSender, records: &[entity::PersonalRecord]) {
+    let Some(first) = records.first() else {
+        return;
+    };
+    let units = controller.cfg().units;
+    let db = controller.db();
+    let exercise = db.exercises()
+        .get_one(first.exercise_id)
+        .map(|e| e.name.clone())
+        .unwrap_or_default();
+    let text = records
+        .iter()
+        .map(|r| {
+            let previous = r.previous.map(|v| r.kind.format_value(v, units)).unwrap_or_default();
+            format!("{}: {} (was {})", r.kind.name(), r.kind.format_value(r.value, units), previous)
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    let title = format!("New personal record on {}", exercise);
+    sender.send_add_layer_event(Box::new(UiMessageBox::info(&title, text)));
+}
+
+impl Component for UiSetEditor {
+    fn draw(&self, f: &mut RenderFrame, area: Rect) {
+        if !self.is_visible() {
+            return;
+        }
+        let area = utils::centered_rect(50, 90, area);
+        let key_map = self.controller.key_map();
+        let block = widgets::Block::default()
+            .title(format!("Set;  -> Commands({})", key_map.commands(&[("Exit", Action::Close), ("Reject", Action::Reject), ("Accept", Action::Accept), ("Plates", Action::Plates)])))
+            .borders(widgets::Borders::ALL);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(block, area);
+
+        let l = Layout::default()
+            .direction(Direction::Horizontal)
+            .margin(1)
+            .constraints([Constraint::Length(1), Constraint::Min(0), Constraint::Length(1)].as_ref())
+            .split(area);
+        let l = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(2), Constraint::Min(0)].as_ref())
+            .split(l[1]);
+        if let Some(suggestion) = self.suggestion() {
+            let units = self.controller.cfg().units;
+            let text = format!(
+                "Suggested: {}x{} @ {} ({})",
+                suggestion.sets, suggestion.reps, suggestion.weight.format(units), suggestion.reason,
+                );
+            f.render_widget(widgets::Paragraph::new(text).style(Style::default().fg(Color::Yellow)), l[0]);
+        }
+        let l = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3); FIELDS_COUNT].as_ref())
+            .split(l[1]);
+        self.fields()
+            .iter()
+            .zip(l)
+            .for_each(|(field, area)| field.draw(f, area));
+    }
+
+    fn is_visible(&self) -> bool { self.is_visible }
+    fn set_visible(&mut self, value: bool) { self.is_visible = value; }
+}
+
+impl KeyEventDispatcher for UiSetEditor {
+    fn on_tab(&mut self) -> bool {
+        self.focus_field(self.focused_field + 1);
+        true
+    }
+
+    fn on_back_tab(&mut self) -> bool {
+        self.focus_field(self.focused_field + FIELDS_COUNT - 1);
+        true
+    }
+
+    fn on_char(&mut self, _c: &char) -> bool {
+        true
+    }
+}
+
+impl TermEventDispatcher for UiSetEditor {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::Dialog))
+    }
+
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Accept => {
+                if !self.is_valid() {
+                    return false;
+                }
+                match self.controller.sets().insert(&self.data()) {
+                    Ok((_, records)) => {
+                        if let Some(sender) = &mut self.ui_event_sender {
+                            on_set_logged(&self.controller, sender, &records);
+                        }
+                        self.close();
+                    }
+                    Err(err) => {
+                        if let Some(sender) = &mut self.ui_event_sender {
+                            let err = format!("Can`t insert set:\n{}", err);
+                            sender.send_add_layer_event(Box::new(UiMessageBox::err("Workouts", err)));
+                            return false;
+                        }
+                    }
+                }
+                true
+            }
+            Action::Reject | Action::Close => {
+                self.close();
+                true
+            }
+            Action::Plates => {
+                let popup = Box::new(UiPlateCalculator::new(self.controller.clone(), &self.weight_editor.text));
+                if let Some(sender) = &mut self.ui_event_sender {
+                    sender.send_add_layer_event(popup);
+                    return true;
+                }
+                false
+            }
+            _ => false,
+        }
+    }
+}
+
+impl EventComponent for UiSetEditor {
+    fn focus(&mut self, value: bool) {
+        self.is_focused = value;
+        if value {
+            self.focus_field(0);
+        }
+    }
+
+    fn on_focus(&self) -> bool { self.is_focused }
+
+    fn on_term_event(&mut self, event: &Event) -> bool {
+        if self.is_focused {
+            let focused_field = self.focused_field;
+            if self.fields_mut()[focused_field].on_term_event(event) {
+                return true;
+            }
+            return self.dispatch_term_event(event);
+        }
+        false
+    }
+}
+
+impl Layer for UiSetEditor {
+    fn is_modal(&self) -> bool { true }
+
+    fn is_remove_requested(&self) -> bool { self.is_removing_needed }
+}
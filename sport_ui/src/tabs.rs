@@ -31,6 +31,7 @@ impl UiTab {
 }
 
 pub struct UiTabs {
+    title: String,
     tabs: Vec<UiTab>,
     current_index: usize,
     is_focused: bool,
@@ -41,6 +42,7 @@ impl UiTabs {
     pub fn new(ui_tabs: Vec<UiTab>) -> Self {
         let mut s = Self {
             ui_event_sender: None,
+            title: "Menu".to_owned(),
             tabs: ui_tabs,
             current_index: 1,
             is_focused: false,
@@ -49,12 +51,16 @@ impl UiTabs {
         s
     }
 
+    pub fn set_title(&mut self, title: String) {
+        self.title = title;
+    }
+
     pub fn tab(&self, index: usize) -> Option<&UiTab> {
         self.tabs.get(index)
     }
 
     pub fn current_tab(&self) -> &UiTab {
-        &self.tabs[self.current_index as usize]
+        &self.tabs[self.current_index]
     }
 
     pub fn current_index(&self) -> usize {
@@ -82,7 +88,7 @@ impl UiTabs {
         self.tabs.len()
     }
 
-    pub fn next(&mut self) -> usize {
+    pub fn select_next(&mut self) -> usize {
         let current_index = self.current_index();
         if current_index < self.tabs_count() {
             self.set_current_tab(current_index + 1);
@@ -90,7 +96,7 @@ impl UiTabs {
         self.current_index
     }
 
-    pub fn prev(&mut self) -> usize {
+    pub fn select_prev(&mut self) -> usize {
         let current_index = self.current_index();
         if current_index > 0 {
             self.set_current_tab(current_index - 1);
@@ -111,9 +117,9 @@ impl Component for UiTabs {
             .block(
                 widgets::Block::default()
                 .borders(widgets::Borders::ALL)
-                .title("Menu"),
+                .title(self.title.as_str()),
                 )
-            .select(self.current_index() as usize)
+            .select(self.current_index())
             .style(Style::default().fg(Color::Cyan))
             .highlight_style(
                 Style::default()
@@ -0,0 +1,148 @@
+use std::rc::Rc;
+
+use core_ui::{
+    render::RenderFrame,
+    components::{TextEdit, Component, EventComponent},
+    utils,
+    event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
+    layer::Layer,
+    validators::Validator
+};
+use crossterm::event::Event;
+use sport_core::{controller, timer::{TimerMode, TimerModeError}, keys::{Action, Context, KeyMap}};
+use tui::{layout::{Rect, Constraint, Direction, Layout}, widgets};
+
+use crate::{timer_overlay::UiTimerOverlay, ui_events::{UiEventSender, SharedUiEvents}};
+
+/// Accepts the timer descriptions like `tabata 20 10 x8`
+#[derive(Default)]
+pub struct TimerModeValidator;
+
+impl Validator<str> for TimerModeValidator {
+    type Error = TimerModeError;
+
+    fn validate<'a>(&'a self, value: &'a str) -> Result<(), Self::Error> {
+        value.parse::<TimerMode>().map(|_| ())
+    }
+}
+
+/// Starts a timer described in one line
+pub struct UiTimerEditor {
+    controller: Rc<controller::Controller>,
+    mode_editor: TextEdit<TimerModeValidator>,
+    ui_event_sender: Option<UiEventSender>,
+    is_focused: bool,
+    is_visible: bool,
+    is_removing_needed: bool,
+}
+
+impl UiTimerEditor {
+    pub fn new(controller: Rc<controller::Controller>) -> Self {
+        let mode = controller.cfg().timer.rest().to_string();
+        Self {
+            controller,
+            mode_editor: TextEdit::new("Timer (rest 1:30, emom 1:00 x10, amrap 12m, tabata 20 10 x8, custom work 40, rest 20):", mode, TimerModeValidator),
+            ui_event_sender: None,
+            is_focused: true,
+            is_visible: true,
+            is_removing_needed: false,
+        }
+    }
+
+    pub fn set_ui_events_manager(&mut self, ui_events_manager: SharedUiEvents) {
+        self.ui_event_sender = Some(UiEventSender::new(ui_events_manager));
+    }
+
+    fn close(&mut self) {
+        self.set_visible(false);
+        self.is_removing_needed = true;
+    }
+}
+
+impl Component for UiTimerEditor {
+    fn draw(&self, f: &mut RenderFrame, area: Rect) {
+        if !self.is_visible() {
+            return;
+        }
+        let area = utils::centered_rect(70, 30, area);
+        let key_map = self.controller.key_map();
+        let block = widgets::Block::default()
+            .title(format!("Timer;  -> Commands({})", key_map.commands(&[("Exit", Action::Close), ("Reject", Action::Reject), ("Start", Action::Accept)])))
+            .borders(widgets::Borders::ALL);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(block, area);
+
+        let l = Layout::default()
+            .direction(Direction::Horizontal)
+            .margin(1)
+            .constraints([Constraint::Length(1), Constraint::Min(0), Constraint::Length(1)].as_ref())
+            .split(area);
+        let l = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3)].as_ref())
+            .split(l[1]);
+        self.mode_editor.draw(f, l[0]);
+    }
+
+    fn is_visible(&self) -> bool { self.is_visible }
+    fn set_visible(&mut self, value: bool) { self.is_visible = value; }
+}
+
+impl KeyEventDispatcher for UiTimerEditor {
+
+    fn on_char(&mut self, _c: &char) -> bool {
+        true
+    }
+}
+
+impl TermEventDispatcher for UiTimerEditor {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::Dialog))
+    }
+
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Accept => {
+                let Ok(mode) = self.mode_editor.text.parse::<TimerMode>() else {
+                    return false;
+                };
+                let bell = self.controller.cfg().timer.bell;
+                if let Some(sender) = &mut self.ui_event_sender {
+                    sender.send_add_layer_event(Box::new(UiTimerOverlay::new(mode, bell)));
+                }
+                self.close();
+                true
+            }
+            Action::Reject | Action::Close => {
+                self.close();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl EventComponent for UiTimerEditor {
+    fn focus(&mut self, value: bool) {
+        self.is_focused = value;
+        self.mode_editor.focus(value);
+    }
+
+    fn on_focus(&self) -> bool { self.is_focused }
+
+    fn on_term_event(&mut self, event: &Event) -> bool {
+        if self.is_focused {
+            if self.mode_editor.on_term_event(event) {
+                return true;
+            }
+            return self.dispatch_term_event(event);
+        }
+        false
+    }
+}
+
+impl Layer for UiTimerEditor {
+    fn is_modal(&self) -> bool { true }
+
+    fn is_remove_requested(&self) -> bool { self.is_removing_needed }
+}
@@ -0,0 +1,161 @@
+use std::io::Write;
+
+use core_ui::{
+    render::RenderFrame,
+    components::{Component, EventComponent},
+    event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
+    layer::Layer,
+};
+use sport_core::{keys::{Action, Context, KeyMap}, timer::{self, PhaseKind, SystemClock, Timer, TimerEvent, TimerMode}};
+use tui::{layout::{Alignment, Rect}, style::{Color, Modifier, Style}, widgets};
+
+const WIDTH: u16 = 40;
+const HEIGHT: u16 = 11;
+/// Rows of the large digits, `0`-`9` then `:`
+const DIGIT_ROWS: usize = 5;
+const FONT: [[&str; DIGIT_ROWS]; 11] = [
+    ["███", "█ █", "█ █", "█ █", "███"],
+    ["  █", "  █", "  █", "  █", "  █"],
+    ["███", "  █", "███", "█  ", "███"],
+    ["███", "  █", "███", "  █", "███"],
+    ["█ █", "█ █", "███", "  █", "  █"],
+    ["███", "█  ", "███", "  █", "███"],
+    ["███", "█  ", "███", "█ █", "███"],
+    ["███", "  █", "  █", "  █", "  █"],
+    ["███", "█ █", "███", "█ █", "███"],
+    ["███", "█ █", "███", "  █", "███"],
+    [" ", "█", " ", "█", " "],
+];
+
+/// `text` of digits and colons in the large font
+fn large_text(text: &str) -> Vec<String> {
+    (0..DIGIT_ROWS)
+        .map(|row| {
+            text.chars()
+                .filter_map(|c| match c {
+                    ':' => Some(FONT[10][row]),
+                    c => c.to_digit(10).map(|d| FONT[d as usize][row]),
+                })
+                .collect::<Vec<&str>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+/// Countdown of a running timer in the top right corner, closed when the timer finishes
+pub struct UiTimerOverlay {
+    timer: Timer<SystemClock>,
+    bell: bool,
+    key_map: KeyMap,
+    is_focused: bool,
+    is_visible: bool,
+    is_removing_needed: bool,
+}
+
+impl UiTimerOverlay {
+    pub fn new(mode: TimerMode, bell: bool) -> Self {
+        let mut timer = Timer::new(mode, SystemClock);
+        timer.start();
+        Self { timer, bell, key_map: KeyMap::default(), is_focused: true, is_visible: true, is_removing_needed: false }
+    }
+
+    fn close(&mut self) {
+        self.set_visible(false);
+        self.is_removing_needed = true;
+    }
+
+    fn handle(&mut self, events: Vec<TimerEvent>) {
+        if events.is_empty() {
+            return;
+        }
+        if self.bell {
+            let mut stdout = std::io::stdout();
+            let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+        }
+        if events.contains(&TimerEvent::Finished) {
+            self.close();
+        }
+    }
+}
+
+impl Component for UiTimerOverlay {
+    fn draw(&self, f: &mut RenderFrame, area: Rect) {
+        if !self.is_visible() {
+            return;
+        }
+        let area = Rect::new(
+            area.x + area.width.saturating_sub(WIDTH + 1),
+            area.y + 1,
+            WIDTH.min(area.width),
+            HEIGHT.min(area.height.saturating_sub(1)),
+        );
+        let (label, color) = match self.timer.current_phase() {
+            Some((_, phase)) if phase.kind == PhaseKind::Work => (phase.label.clone(), Color::Green),
+            Some((_, phase)) => (phase.label.clone(), Color::Blue),
+            None => ("Done".to_owned(), Color::White),
+        };
+        let color = if self.timer.is_paused() { Color::Yellow } else { color };
+        let state = if self.timer.is_paused() { " (paused)" } else { "" };
+        let title = format!("{}: {}{}", self.timer.mode().name(), label, state);
+
+        // round the countdown up so that zero shows up only at the end
+        let remaining = self.timer.remaining() + std::time::Duration::from_millis(999);
+        let mut lines = large_text(&timer::format_duration(remaining));
+        lines.push("".to_owned());
+        lines.push(format!("Total left {}", timer::format_duration(self.timer.total_remaining())));
+        lines.push(self.key_map.help(Context::Timer));
+        let text: Vec<tui::text::Spans> = lines.into_iter().map(tui::text::Spans::from).collect();
+
+        let paragraph = widgets::Paragraph::new(text)
+            .style(Style::default().fg(color).add_modifier(Modifier::BOLD))
+            .alignment(Alignment::Center)
+            .block(widgets::Block::default().borders(widgets::Borders::ALL).title(title));
+        f.render_widget(widgets::Clear, area);
+        f.render_widget(paragraph, area);
+    }
+
+    fn is_visible(&self) -> bool { self.is_visible }
+    fn set_visible(&mut self, value: bool) { self.is_visible = value; }
+}
+
+impl KeyEventDispatcher for UiTimerOverlay {}
+
+impl TermEventDispatcher for UiTimerOverlay {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((&self.key_map, Context::Timer))
+    }
+
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Pause => self.timer.toggle_pause(),
+            Action::Skip => {
+                let events = self.timer.skip();
+                self.handle(events);
+            }
+            Action::StopTimer => self.close(),
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl EventComponent for UiTimerOverlay {
+    fn focus(&mut self, value: bool) {
+        self.is_focused = value;
+    }
+
+    fn on_focus(&self) -> bool { self.is_focused }
+}
+
+impl Layer for UiTimerOverlay {
+    fn is_remove_requested(&self) -> bool { self.is_removing_needed }
+
+    fn on_tick(&mut self) {
+        let events = self.timer.tick();
+        self.handle(events);
+    }
+
+    fn set_key_map(&mut self, key_map: &KeyMap) {
+        self.key_map = key_map.clone();
+    }
+}
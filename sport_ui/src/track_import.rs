@@ -0,0 +1,229 @@
+use std::{path::Path, rc::Rc};
+
+use core_ui::{
+    render::RenderFrame,
+    components::{TextEdit, Component, EventComponent},
+    utils,
+    event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
+    message_box::UiMessageBox,
+    layer::Layer,
+};
+use crossterm::event::Event;
+use sport_core::{controller, tracks::{Track, TrackFormat, TrackSummary}, keys::{Action, Context, KeyMap}};
+use sport_core_db::{entity, model::Table};
+use tui::{layout::{Rect, Constraint, Direction, Layout}, style::{Color, Style}, widgets};
+
+use crate::ui_events::{UiEventSender, SharedUiEvents};
+
+/// Summary of a GPX, TCX or FIT recording, saved as a cardio workout of the account person
+pub struct UiTrackImport {
+    controller: Rc<controller::Controller>,
+    path_editor: TextEdit,
+    summary: Option<(TrackFormat, TrackSummary)>,
+    /// Exercises to pick from, sorted by name
+    exercises: Vec<(entity::ID, String)>,
+    exercise: usize,
+    ui_event_sender: Option<UiEventSender>,
+    is_focused: bool,
+    is_visible: bool,
+    is_removing_needed: bool,
+}
+
+impl UiTrackImport {
+    pub fn new(controller: Rc<controller::Controller>) -> Self {
+        let mut exercises: Vec<(entity::ID, String)> = controller.db().exercises().get_all().map(|e| (e.id, e.name.clone())).collect();
+        exercises.sort_by_key(|(_, name)| name.to_lowercase());
+        Self {
+            controller,
+            path_editor: TextEdit::new("GPX, TCX or FIT file: [ENTER] to read", String::new(), Default::default()),
+            summary: None,
+            exercises,
+            exercise: 0,
+            ui_event_sender: None,
+            is_focused: true,
+            is_visible: true,
+            is_removing_needed: false,
+        }
+    }
+
+    pub fn set_ui_events_manager(&mut self, ui_events_manager: SharedUiEvents) {
+        self.ui_event_sender = Some(UiEventSender::new(ui_events_manager));
+    }
+
+    fn close(&mut self) {
+        self.set_visible(false);
+        self.is_removing_needed = true;
+    }
+
+    fn show(&mut self, msgbox: UiMessageBox) {
+        if let Some(sender) = &mut self.ui_event_sender {
+            sender.send_add_layer_event(Box::new(msgbox));
+        }
+    }
+
+    fn load(&mut self) {
+        let path = self.path_editor.text.trim().to_owned();
+        let summary = Track::load(Path::new(&path)).and_then(|track| Ok((track.format, track.summary()?)));
+        match summary {
+            Ok((format, summary)) => {
+                let guess = summary.guess_exercise(&self.controller.db());
+                self.exercise = self.exercises.iter().position(|(id, _)| Some(*id) == guess).unwrap_or_default();
+                self.summary = Some((format, summary));
+                self.path_editor.focus(false);
+            }
+            Err(err) => self.show(UiMessageBox::err("Track", format!("Can`t read {}:\n{}", path, err))),
+        }
+    }
+
+    fn import(&mut self) {
+        let (Some((_, summary)), Some((exercise_id, _))) = (&self.summary, self.exercises.get(self.exercise)) else {
+            return;
+        };
+        let cfg = self.controller.cfg();
+        let msgbox = match summary.import(cfg.account().id, *exercise_id, cfg.units, self.controller.as_ref()) {
+            Ok(Some(_)) => UiMessageBox::info("Track", format!("Workout `{}` saved", summary.name)),
+            Ok(None) => UiMessageBox::warn("Track", "A workout starting at the same time is already there".to_owned()),
+            Err(err) => UiMessageBox::err("Track", format!("Can`t save workout:\n{}", err)),
+        };
+        self.close();
+        self.show(msgbox);
+    }
+
+    fn draw_summary(&self, f: &mut RenderFrame, area: Rect) {
+        let Some((format, summary)) = &self.summary else {
+            return;
+        };
+        let l = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(10), Constraint::Min(0)].as_ref())
+            .split(area);
+        let exercise = self.exercises.get(self.exercise).map(|(_, name)| name.as_str()).unwrap_or("-");
+        let started_at = summary.started_at.with_timezone(&chrono::Local);
+        let text = format!(
+            "{} {}\nStarted: {}\n{}\n\nExercise: < {} >",
+            summary.name, summary.sport, started_at.format("%Y-%m-%d %H:%M"), summary.describe(self.controller.cfg().units), exercise,
+        );
+        let paragraph = widgets::Paragraph::new(text)
+            .block(widgets::Block::default().borders(widgets::Borders::ALL).title(format!("{} track: Exercise[LEFT/RIGHT]", format.name())));
+        f.render_widget(paragraph, l[0]);
+
+        let samples: Vec<u64> = summary.heart_rate.iter().map(|(_, bpm)| *bpm as u64).collect();
+        let sparkline = widgets::Sparkline::default()
+            .block(widgets::Block::default().borders(widgets::Borders::ALL).title("Heart rate"))
+            .data(&samples)
+            .style(Style::default().fg(Color::Red));
+        f.render_widget(sparkline, l[1]);
+    }
+}
+
+impl Component for UiTrackImport {
+    fn draw(&self, f: &mut RenderFrame, area: Rect) {
+        if !self.is_visible() {
+            return;
+        }
+        let area = utils::centered_rect(60, 70, area);
+        let key_map = self.controller.key_map();
+        let block = widgets::Block::default()
+            .title(format!(
+                "Import track;  -> Commands({}, File: [TAB], {})",
+                key_map.commands(&[("Exit", Action::Reject)]), key_map.commands(&[("Save", Action::Accept)]),
+            ))
+            .borders(widgets::Borders::ALL);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(block, area);
+
+        let l = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .split(area);
+        self.path_editor.draw(f, l[0]);
+        self.draw_summary(f, l[1]);
+    }
+
+    fn is_visible(&self) -> bool { self.is_visible }
+    fn set_visible(&mut self, value: bool) { self.is_visible = value; }
+}
+
+impl KeyEventDispatcher for UiTrackImport {
+    fn on_tab(&mut self) -> bool {
+        self.path_editor.focus(true);
+        true
+    }
+
+    fn on_left(&mut self) -> bool {
+        if !self.exercises.is_empty() {
+            self.exercise = (self.exercise + self.exercises.len() - 1) % self.exercises.len();
+        }
+        true
+    }
+
+    fn on_right(&mut self) -> bool {
+        if !self.exercises.is_empty() {
+            self.exercise = (self.exercise + 1) % self.exercises.len();
+        }
+        true
+    }
+
+    fn on_char(&mut self, _c: &char) -> bool {
+        true
+    }
+}
+
+impl TermEventDispatcher for UiTrackImport {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::Dialog))
+    }
+
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Accept => {
+                if self.summary.is_none() {
+                    return false;
+                }
+                self.import();
+                true
+            }
+            Action::Reject | Action::Close => {
+                self.close();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl EventComponent for UiTrackImport {
+    fn focus(&mut self, value: bool) {
+        self.is_focused = value;
+        self.path_editor.focus(value);
+    }
+
+    fn on_focus(&self) -> bool { self.is_focused }
+
+    fn on_term_event(&mut self, event: &Event) -> bool {
+        if !self.is_focused {
+            return false;
+        }
+        if self.path_editor.on_focus() {
+            if let Event::Key(key) = event {
+                if self.controller.key_map().is(Action::Accept, key) {
+                    self.load();
+                    return true;
+                }
+                if self.controller.key_map().is(Action::Reject, key) {
+                    self.close();
+                    return true;
+                }
+            }
+            return self.path_editor.on_term_event(event);
+        }
+        self.dispatch_term_event(event)
+    }
+}
+
+impl Layer for UiTrackImport {
+    fn is_modal(&self) -> bool { true }
+
+    fn is_remove_requested(&self) -> bool { self.is_removing_needed }
+}
@@ -1,12 +1,13 @@
 pub use std::rc::Rc;
 
-use core_ui::{component::TerminalEvent, layer::{UiLayers, Layer}, render::Render, theme::UiTheme};
-use sport_core::controller;
+use core_ui::{component::TerminalEvent, layer::{UiLayers, Layer}, message_box::UiMessageBox, render::Render, theme::UiTheme};
+use sport_core::{controller, keys::KeyMap};
 use tui::{widgets, style::{Style, Color}};
 
 use crate::{ui_events::{UiEvents, UiEvent, SharedUiEvents}, main_ui_layer::MainUiLayer};
 
 pub struct Ui {
+    controller: Rc<controller::Controller>,
     need_stop: bool,
     ui_events_manager: SharedUiEvents,
     layers: UiLayers,
@@ -17,6 +18,7 @@ impl Ui {
     pub fn new(controller: Rc<controller::Controller>) -> Self {
         let ui_events_manager = Rc::new(UiEvents::default());
         let mut s = Self {
+            controller: controller.clone(),
             ui_events_manager: ui_events_manager.clone(),
             need_stop: false,
             layers: UiLayers::new(),
@@ -27,6 +29,11 @@ impl Ui {
         s
     }
 
+    /// Keys of the profile in use
+    pub fn key_map(&self) -> &KeyMap {
+        self.controller.key_map()
+    }
+
     pub fn stop(&mut self) {
         self.need_stop = true;
     }
@@ -52,9 +59,22 @@ impl Ui {
         for ui_event in ui_events {
             match ui_event {
                 UiEvent::AddLayer(layer) => self.add_layer(layer),
-                // UiEvent::MenuSwitched(_) => { self.send_event(ui_event); },
-                _ => {},
+                UiEvent::SwitchProfile(name) => self.switch_profile(&name),
+                _ => (),
+            }
+        }
+    }
+
+    /// All layers are built again with the controller of the profile
+    fn switch_profile(&mut self, name: &str) {
+        match self.controller.switch_profile(name) {
+            Ok(controller) => {
+                self.controller = Rc::new(controller);
+                self.layers = UiLayers::new();
+                let main_layer = Box::new(MainUiLayer::new(self.controller.clone(), self.ui_events_manager.clone()));
+                self.add_layer(main_layer);
             }
+            Err(err) => self.add_layer(Box::new(UiMessageBox::err("Profile", format!("Can`t switch to {}:\n{}", name, err)))),
         }
     }
 
@@ -63,6 +83,7 @@ impl Ui {
     }
 
     pub fn add_layer(&mut self, mut layer: Box<dyn Layer>) {
+        layer.set_key_map(self.controller.key_map());
         layer.focus(true);
         layer.set_visible(true);
         self.layers.push(layer);
@@ -82,6 +103,10 @@ impl Ui {
         false
     }
 
+    pub fn on_tick(&mut self) {
+        self.layers.get_all_mut().for_each(|l| l.on_tick());
+    }
+
     pub fn apply_theme(&mut self, theme: &UiTheme) {
         self.layers.get_all_mut().for_each(|l| l.apply_theme(theme));
     }
@@ -8,6 +8,8 @@ pub enum UiEvent {
     TermEvent(Event),
     AddLayer(Box<dyn Layer>),
     MenuSwitched(usize),
+    /// Start over with the profile of this name
+    SwitchProfile(String),
 }
 pub type UiEvents = events::Events<UiEvent>;
 pub type SharedUiEvents = Rc<UiEvents>;
@@ -29,6 +31,10 @@ impl UiEventSender {
         self.ui_events_manager.add_event(UiEvent::MenuSwitched(index));
     }
 
+    pub fn send_switch_profile_event(&mut self, name: String) {
+        self.ui_events_manager.add_event(UiEvent::SwitchProfile(name));
+    }
+
     pub fn ui_events_manager(&self) -> SharedUiEvents {
         self.ui_events_manager.clone()
     }
@@ -36,7 +42,7 @@ impl UiEventSender {
 
 pub trait UiEventDispatcher: TermEventDispatcher {
     fn dispatch_ui_event(&mut self, event: &UiEvent) -> bool {
-        return match event {
+        match event {
             UiEvent::TermEvent(event) => self.dispatch_term_event(event),
             UiEvent::MenuSwitched(index) => self.on_menu_switched(*index),
             _ => false,
@@ -0,0 +1,269 @@
+use std::rc::Rc;
+
+use chrono::{Local, NaiveDateTime};
+use core_ui::{
+    render::RenderFrame,
+    components::{TextEdit, Component, EventComponent},
+    utils,
+    event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
+    message_box::UiMessageBox, layer::Layer,
+    validators::{Validator, StrValidator, DateTimeValidator, QuantityValidator}
+};
+use crossterm::event::Event;
+use sport_core::{controller, keys::{Action, Context, KeyMap}};
+use sport_core_db::{entity, model::Table, units::{Duration, Quantity}};
+use thiserror::Error;
+use tui::{layout::{Rect, Constraint, Direction, Layout}, widgets};
+
+use crate::ui_events::{UiEventSender, SharedUiEvents};
+
+const FIELDS_COUNT: usize = 4;
+
+#[derive(Error, Debug)]
+pub enum RoutineNameValidatorError {
+    #[error("Unknown routine")]
+    Unknown,
+}
+
+/// Accepts names of the person's routines, case insensitive, or nothing
+#[derive(Default)]
+pub struct RoutineNameValidator {
+    names: Vec<(String, entity::ID)>,
+}
+
+impl RoutineNameValidator {
+    pub fn new(controller: &controller::Controller) -> Self {
+        let person_id = controller.cfg().account().id;
+        let names = controller.db()
+            .routines()
+            .get_all()
+            .filter(|r| r.person_id == person_id)
+            .map(|r| (r.name.to_lowercase(), r.id))
+            .collect();
+        Self { names }
+    }
+
+    pub fn find(&self, name: &str) -> Option<entity::ID> {
+        let name = name.trim().to_lowercase();
+        self.names.iter().find(|(n, _)| *n == name).map(|(_, id)| *id)
+    }
+}
+
+impl Validator<str> for RoutineNameValidator {
+    type Error = RoutineNameValidatorError;
+
+    fn validate<'a>(&'a self, value: &'a str) -> Result<(), Self::Error> {
+        if value.trim().is_empty() || self.find(value).is_some() {
+            Ok(())
+        } else {
+            Err(RoutineNameValidatorError::Unknown)
+        }
+    }
+}
+
+pub struct UiWorkoutEditor {
+    controller: Rc<controller::Controller>,
+    data: entity::Workout,
+    name_editor: TextEdit<StrValidator>,
+    started_at_editor: TextEdit<DateTimeValidator>,
+    duration_editor: TextEdit<QuantityValidator<Duration>>,
+    routine_editor: TextEdit<RoutineNameValidator>,
+    focused_field: usize,
+    to_insert: bool,
+    ui_event_sender: Option<UiEventSender>,
+    is_focused: bool,
+    is_visible: bool,
+    is_removing_needed: bool,
+}
+
+impl UiWorkoutEditor {
+    pub fn new(controller: Rc<controller::Controller>) -> Self {
+        let units = controller.cfg().units;
+        let now = Local::now().naive_local().format(DateTimeValidator::DATE_TIME).to_string();
+        let routine_validator = RoutineNameValidator::new(&controller);
+        Self {
+            data: entity::Workout {
+                person_id: controller.cfg().account().id,
+                ..entity::Workout::default()
+            },
+            controller,
+            name_editor: TextEdit::new("Name:", "Workout".to_owned(), StrValidator::new(0, 100)),
+            started_at_editor: TextEdit::new("Started at (YYYY-MM-DD HH:MM):", now, DateTimeValidator::date_time()),
+            duration_editor: TextEdit::new("Duration (h:mm:ss):", "".to_owned(), QuantityValidator::optional(units)),
+            routine_editor: TextEdit::new("Routine (optional):", "".to_owned(), routine_validator),
+            focused_field: 0,
+            to_insert: true,
+            ui_event_sender: None,
+            is_focused: true,
+            is_visible: true,
+            is_removing_needed: false,
+        }
+    }
+
+    pub fn data(&self) -> entity::Workout {
+        let units = self.controller.cfg().units;
+        let started_at = NaiveDateTime::parse_from_str(self.started_at_editor.text.trim(), DateTimeValidator::DATE_TIME)
+            .unwrap_or_default();
+        entity::Workout {
+            name: self.name_editor.text.clone(),
+            started_at,
+            duration: Duration::parse(&self.duration_editor.text, units).unwrap_or_default(),
+            routine_id: self.routine_editor.validator().find(&self.routine_editor.text),
+            ..self.data.clone()
+        }
+    }
+
+    pub fn load_data(&mut self, data: &entity::Workout) {
+        let units = self.controller.cfg().units;
+        self.to_insert = false;
+        self.data = data.clone();
+        self.name_editor.text = data.name.to_owned();
+        self.started_at_editor.text = data.started_at.format(DateTimeValidator::DATE_TIME).to_string();
+        self.duration_editor.text = if data.duration.is_zero() { "".to_owned() } else { data.duration.format(units) };
+        self.routine_editor.text = data.routine_id
+            .and_then(|id| self.controller.db().routines().get_one(id).map(|r| r.name.clone()))
+            .unwrap_or_default();
+    }
+
+    pub fn set_ui_events_manager(&mut self, ui_events_manager: SharedUiEvents) {
+        self.ui_event_sender = Some(UiEventSender::new(ui_events_manager));
+    }
+
+    fn close(&mut self) {
+        self.set_visible(false);
+        self.is_removing_needed = true;
+    }
+
+    fn is_valid(&self) -> bool {
+        self.name_editor.is_valid()
+            && self.started_at_editor.is_valid()
+            && self.duration_editor.is_valid()
+            && self.routine_editor.is_valid()
+    }
+
+    fn fields_mut(&mut self) -> [&mut dyn EventComponent; FIELDS_COUNT] {
+        [&mut self.name_editor, &mut self.started_at_editor, &mut self.duration_editor, &mut self.routine_editor]
+    }
+
+    fn focus_field(&mut self, index: usize) {
+        self.focused_field = index % FIELDS_COUNT;
+        let focused_field = self.focused_field;
+        self.fields_mut()
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, f)| f.focus(i == focused_field));
+    }
+}
+
+impl Component for UiWorkoutEditor {
+    fn draw(&self, f: &mut RenderFrame, area: Rect) {
+        if !self.is_visible() {
+            return;
+        }
+        let area = utils::centered_rect(50, 60, area);
+        let key_map = self.controller.key_map();
+        let block = widgets::Block::default()
+            .title(format!("Workout;  -> Commands({})", key_map.commands(&[("Exit", Action::Close), ("Reject", Action::Reject), ("Accept", Action::Accept)])))
+            .borders(widgets::Borders::ALL);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(block, area);
+
+        let l = Layout::default()
+            .direction(Direction::Horizontal)
+            .margin(1)
+            .constraints([Constraint::Length(1), Constraint::Min(0), Constraint::Length(1)].as_ref())
+            .split(area);
+        let l = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3); FIELDS_COUNT].as_ref())
+            .split(l[1]);
+        self.name_editor.draw(f, l[0]);
+        self.started_at_editor.draw(f, l[1]);
+        self.duration_editor.draw(f, l[2]);
+        self.routine_editor.draw(f, l[3]);
+    }
+
+    fn is_visible(&self) -> bool { self.is_visible }
+    fn set_visible(&mut self, value: bool) { self.is_visible = value; }
+}
+
+impl KeyEventDispatcher for UiWorkoutEditor {
+    fn on_tab(&mut self) -> bool {
+        self.focus_field(self.focused_field + 1);
+        true
+    }
+
+    fn on_back_tab(&mut self) -> bool {
+        self.focus_field(self.focused_field + FIELDS_COUNT - 1);
+        true
+    }
+
+    fn on_char(&mut self, _c: &char) -> bool {
+        true
+    }
+}
+
+impl TermEventDispatcher for UiWorkoutEditor {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::Dialog))
+    }
+
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Accept => {
+                if !self.is_valid() {
+                    return false;
+                }
+                let result = if self.to_insert {
+                    self.controller.workouts().insert(&self.data()).map(|_| ())
+                } else {
+                    self.controller.workouts().update(self.data())
+                };
+                if let Err(err) = result {
+                    if let Some(sender) = &mut self.ui_event_sender {
+                        let err = format!("Can`t save workout:\n{}", err);
+                        sender.send_add_layer_event(Box::new(UiMessageBox::err("Workouts", err)));
+                        return false;
+                    }
+                } else {
+                    self.close();
+                }
+
+                true
+            }
+            Action::Reject | Action::Close => {
+                self.close();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl EventComponent for UiWorkoutEditor {
+    fn focus(&mut self, value: bool) {
+        self.is_focused = value;
+        if value {
+            self.focus_field(0);
+        }
+    }
+
+    fn on_focus(&self) -> bool { self.is_focused }
+
+    fn on_term_event(&mut self, event: &Event) -> bool {
+        if self.is_focused {
+            let focused_field = self.focused_field;
+            if self.fields_mut()[focused_field].on_term_event(event) {
+                return true;
+            }
+            return self.dispatch_term_event(event);
+        }
+        false
+    }
+}
+
+impl Layer for UiWorkoutEditor {
+    fn is_modal(&self) -> bool { true }
+
+    fn is_remove_requested(&self) -> bool { self.is_removing_needed }
+}
@@ -0,0 +1,281 @@
+use std::{cell::RefCell, fs, rc::Rc};
+
+use core_ui::{
+    render::RenderFrame,
+    components::{TextEdit, Component, EventComponent, UiTable},
+    utils,
+    event_dispatcher::{KeyEventDispatcher, TermEventDispatcher},
+    message_box::UiMessageBox,
+    layer::Layer,
+};
+use crossterm::event::Event;
+use sport_core::{controller, workout_import::{self, ExerciseMapping, Resolution, WorkoutImport}, keys::{Action, Context, KeyMap}};
+use sport_core_db::{entity, model::Table};
+use tui::{layout::{Rect, Constraint, Direction, Layout}, style::{Color, Style}, widgets};
+
+use crate::ui_events::{UiEventSender, SharedUiEvents};
+
+/// Workouts from a Strong or Hevy CSV export, with the exercise names resolved before importing
+pub struct UiWorkoutImport {
+    controller: Rc<controller::Controller>,
+    path_editor: TextEdit,
+    import: Option<WorkoutImport>,
+    mappings: Vec<ExerciseMapping>,
+    /// Exercises to pick from, sorted by name
+    exercises: Vec<(entity::ID, String)>,
+    mappings_table: RefCell<UiTable<usize>>,
+    ui_event_sender: Option<UiEventSender>,
+    is_focused: bool,
+    is_visible: bool,
+    is_removing_needed: bool,
+}
+
+impl UiWorkoutImport {
+    pub fn new(controller: Rc<controller::Controller>) -> Self {
+        let mut exercises: Vec<(entity::ID, String)> = controller.db().exercises().get_all().map(|e| (e.id, e.name.clone())).collect();
+        exercises.sort_by_key(|(_, name)| name.to_lowercase());
+        Self {
+            controller,
+            path_editor: TextEdit::new("File: [ENTER] to read", "workouts.csv".to_owned(), Default::default()),
+            import: None,
+            mappings: vec![],
+            exercises,
+            mappings_table: RefCell::new(UiTable::default()),
+            ui_event_sender: None,
+            is_focused: true,
+            is_visible: true,
+            is_removing_needed: false,
+        }
+    }
+
+    pub fn set_ui_events_manager(&mut self, ui_events_manager: SharedUiEvents) {
+        self.ui_event_sender = Some(UiEventSender::new(ui_events_manager));
+    }
+
+    fn close(&mut self) {
+        self.set_visible(false);
+        self.is_removing_needed = true;
+    }
+
+    fn show(&mut self, msgbox: UiMessageBox) {
+        if let Some(sender) = &mut self.ui_event_sender {
+            sender.send_add_layer_event(Box::new(msgbox));
+        }
+    }
+
+    fn load(&mut self) {
+        let path = self.path_editor.text.trim().to_owned();
+        let read = fs::read_to_string(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|text| WorkoutImport::read(&text, self.controller.cfg().units).map_err(|err| err.to_string()));
+        match read {
+            Ok(import) => {
+                self.mappings = workout_import::map_exercises(&self.controller.db(), &import.exercise_names());
+                let mut table = self.mappings_table.borrow_mut();
+                table.set_values((0..self.mappings.len()).collect());
+                table.focus(true);
+                table.select_row(0);
+                drop(table);
+                self.import = Some(import);
+                self.path_editor.focus(false);
+            }
+            Err(err) => self.show(UiMessageBox::err("Import", format!("Can`t read {}:\n{}", path, err))),
+        }
+    }
+
+    /// Skip, create, then the exercises in order
+    fn change_resolution(&mut self, forward: bool) {
+        let Some(mapping) = self.mappings_table.borrow().get_value().and_then(|i| self.mappings.get_mut(*i)) else {
+            return;
+        };
+        let mut choices = vec![Resolution::Skip, Resolution::Create];
+        choices.extend(self.exercises.iter().map(|(id, _)| Resolution::Existing(*id)));
+        let current = choices.iter().position(|r| *r == mapping.resolution).unwrap_or_default();
+        let next = if forward { current + 1 } else { current + choices.len() - 1 };
+        mapping.resolution = choices[next % choices.len()];
+        mapping.is_guess = false;
+    }
+
+    fn import(&mut self) {
+        let Some(import) = &self.import else {
+            return;
+        };
+        let person_id = self.controller.cfg().account().id;
+        let msgbox = match workout_import::import(import, &self.mappings, person_id, self.controller.as_ref()) {
+            Ok(report) => UiMessageBox::info("Import", format!("Imported from {}\n{}", import.format.name(), report.describe())),
+            Err(err) => UiMessageBox::err("Import", format!("Import stopped:\n{}", err)),
+        };
+        self.close();
+        self.show(msgbox);
+    }
+
+    fn resolution_name(&self, resolution: Resolution) -> String {
+        match resolution {
+            Resolution::Existing(id) => self.exercises
+                .iter()
+                .find(|(e, _)| *e == id)
+                .map(|(_, name)| name.clone())
+                .unwrap_or_else(|| id.to_string()),
+            Resolution::Create => "Create new".to_owned(),
+            Resolution::Skip => "Skip".to_owned(),
+        }
+    }
+
+    fn draw_mappings(&self, f: &mut RenderFrame, area: Rect) {
+        let rows: Vec<widgets::Row> = self.mappings
+            .iter()
+            .map(|mapping| {
+                let color = match mapping.resolution {
+                    Resolution::Existing(_) if mapping.is_guess => Color::Yellow,
+                    Resolution::Existing(_) => Color::Green,
+                    Resolution::Create => Color::Cyan,
+                    Resolution::Skip => Color::Red,
+                };
+                let name = self.resolution_name(mapping.resolution);
+                let name = if mapping.is_guess { format!("{} (guess)", name) } else { name };
+                widgets::Row::new(vec![
+                    widgets::Cell::from(mapping.name.clone()),
+                    widgets::Cell::from(name).style(Style::default().fg(color)),
+                ])
+            })
+            .collect();
+        let title = match &self.import {
+            Some(import) => format!(
+                "{} export, {} workouts, {} sets: Change[LEFT/RIGHT]",
+                import.format.name(), import.workouts.len(), import.sets_count(),
+            ),
+            None => "Exercises".to_owned(),
+        };
+        let theme = *self.mappings_table.borrow().theme();
+        let table = widgets::Table::new(rows)
+            .style(theme.table_style)
+            .header(
+                widgets::Row::new(vec!["Exercise in file", "Imported as"])
+                .style(theme.header_style)
+                .bottom_margin(1),
+                )
+            .block(widgets::Block::default().borders(widgets::Borders::ALL).title(title))
+            .widths(&[Constraint::Percentage(50), Constraint::Percentage(50)])
+            .highlight_style(theme.highlight_style)
+            .highlight_symbol(">");
+        f.render_stateful_widget(table, area, self.mappings_table.borrow_mut().state());
+    }
+}
+
+impl Component for UiWorkoutImport {
+    fn draw(&self, f: &mut RenderFrame, area: Rect) {
+        if !self.is_visible() {
+            return;
+        }
+        let area = utils::centered_rect(70, 80, area);
+        let key_map = self.controller.key_map();
+        let block = widgets::Block::default()
+            .title(format!(
+                "Import workouts;  -> Commands({}, File: [TAB], {})",
+                key_map.commands(&[("Exit", Action::Reject)]), key_map.commands(&[("Import", Action::Accept)]),
+            ))
+            .borders(widgets::Borders::ALL);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(block, area);
+
+        let l = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .split(area);
+        self.path_editor.draw(f, l[0]);
+        self.draw_mappings(f, l[1]);
+    }
+
+    fn is_visible(&self) -> bool { self.is_visible }
+    fn set_visible(&mut self, value: bool) { self.is_visible = value; }
+}
+
+impl KeyEventDispatcher for UiWorkoutImport {
+    fn on_tab(&mut self) -> bool {
+        self.path_editor.focus(true);
+        true
+    }
+
+    fn on_left(&mut self) -> bool {
+        self.change_resolution(false);
+        true
+    }
+
+    fn on_right(&mut self) -> bool {
+        self.change_resolution(true);
+        true
+    }
+
+    fn on_up(&mut self) -> bool {
+        self.mappings_table.borrow_mut().previous();
+        true
+    }
+
+    fn on_down(&mut self) -> bool {
+        self.mappings_table.borrow_mut().next();
+        true
+    }
+
+    fn on_char(&mut self, _c: &char) -> bool {
+        true
+    }
+}
+
+impl TermEventDispatcher for UiWorkoutImport {
+    fn key_bindings(&self) -> Option<(&KeyMap, Context)> {
+        Some((self.controller.key_map(), Context::Dialog))
+    }
+
+    fn on_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Accept => {
+                if self.import.is_none() {
+                    return false;
+                }
+                self.import();
+                true
+            }
+            Action::Reject | Action::Close => {
+                self.close();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl EventComponent for UiWorkoutImport {
+    fn focus(&mut self, value: bool) {
+        self.is_focused = value;
+        self.path_editor.focus(value);
+    }
+
+    fn on_focus(&self) -> bool { self.is_focused }
+
+    fn on_term_event(&mut self, event: &Event) -> bool {
+        if !self.is_focused {
+            return false;
+        }
+        if self.path_editor.on_focus() {
+            if let Event::Key(key) = event {
+                if self.controller.key_map().is(Action::Accept, key) {
+                    self.load();
+                    return true;
+                }
+                if self.controller.key_map().is(Action::Reject, key) {
+                    self.close();
+                    return true;
+                }
+            }
+            return self.path_editor.on_term_event(event);
+        }
+        self.dispatch_term_event(event)
+    }
+}
+
+impl Layer for UiWorkoutImport {
+    fn is_modal(&self) -> bool { true }
+
+    fn is_remove_requested(&self) -> bool { self.is_removing_needed }
+}